@@ -0,0 +1,108 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Benchmarks of the URL conversions,
+//! which the deriver runs many times per evaluation.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use projvar::environment::Environment;
+use projvar::tools::git::TransferProtocol;
+use projvar::value_conversions;
+
+/// Web URLs of repos on different kinds of hosting platforms.
+const WEB_URLS: &[&str] = &[
+    "https://github.com/hoijui/projvar",
+    "https://gitlab.com/hoijui/kicad-text-injector",
+    "https://bitbucket.org/Aouatef/master_arbeit",
+    "https://git.sr.ht/~sircmpwn/sourcehut",
+    "https://codeberg.org/forgejo/forgejo",
+    "https://repo.or.cz/girocco.git",
+];
+
+/// Clone URLs of repos, in all the supported protocols.
+const CLONE_URLS: &[&str] = &[
+    "https://github.com/hoijui/projvar.git",
+    "git@github.com:hoijui/projvar.git",
+    "ssh://git@gitlab.com/hoijui/kicad-text-injector.git",
+    "git://repo.or.cz/girocco.git",
+    "https://hoijui@bitbucket.org/Aouatef/master_arbeit.git",
+];
+
+fn bench_web_url(c: &mut Criterion) {
+    let environment = Environment::stub();
+    let mut group = c.benchmark_group("web_url");
+    group.bench_function(BenchmarkId::from_parameter("clone_url_ssh"), |b| {
+        b.iter(|| {
+            for web_url in WEB_URLS {
+                value_conversions::web_url_to_clone_url(
+                    &environment,
+                    black_box(web_url),
+                    TransferProtocol::Ssh,
+                )
+                .ok();
+            }
+        });
+    });
+    group.bench_function(BenchmarkId::from_parameter("issues_url"), |b| {
+        b.iter(|| {
+            for web_url in WEB_URLS {
+                value_conversions::web_url_to_issues_url(&environment, black_box(web_url)).ok();
+            }
+        });
+    });
+    group.bench_function(BenchmarkId::from_parameter("build_hosting_url"), |b| {
+        b.iter(|| {
+            for web_url in WEB_URLS {
+                value_conversions::web_url_to_build_hosting_url(&environment, black_box(web_url))
+                    .ok();
+            }
+        });
+    });
+    group.bench_function(BenchmarkId::from_parameter("slug"), |b| {
+        b.iter(|| {
+            for web_url in WEB_URLS {
+                value_conversions::web_url_to_slug(&environment, black_box(web_url)).ok();
+            }
+        });
+    });
+    group.finish();
+}
+
+fn bench_clone_url(c: &mut Criterion) {
+    let environment = Environment::stub();
+    let mut group = c.benchmark_group("clone_url");
+    group.bench_function(BenchmarkId::from_parameter("web_url"), |b| {
+        b.iter(|| {
+            for clone_url in CLONE_URLS {
+                value_conversions::clone_url_to_web_url(&environment, black_box(clone_url)).ok();
+            }
+        });
+    });
+    for protocol in [
+        TransferProtocol::Https,
+        TransferProtocol::Ssh,
+        TransferProtocol::Git,
+    ] {
+        group.bench_with_input(
+            BenchmarkId::new("conversion", protocol.scheme_str()),
+            &protocol,
+            |b, &protocol| {
+                b.iter(|| {
+                    for clone_url in CLONE_URLS {
+                        value_conversions::clone_url_conversion(
+                            black_box(clone_url),
+                            &environment,
+                            protocol,
+                        )
+                        .ok();
+                    }
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_web_url, bench_clone_url);
+criterion_main!(benches);
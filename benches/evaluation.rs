@@ -0,0 +1,126 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Benchmarks of a whole evaluation on a synthetic repo,
+//! and of the parts of it that work on the results:
+//! rendering the storage tables and detecting licenses.
+
+use std::path::Path;
+
+use assert_fs::TempDir;
+use cmd_lib::run_cmd;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use projvar::environment::Environment;
+use projvar::settings::{FailOn, Settings, TableFormat, STUB};
+use projvar::sources::{self, VarSource};
+use projvar::var::Key;
+
+/// How many tagged commits the synthetic repo has.
+const NUM_RELEASES: usize = 30;
+
+/// Creates a repo with a history of [`NUM_RELEASES`] tagged commits,
+/// a license file, REUSE licenses and a remote,
+/// similar to the one used in the integration tests.
+fn create_repo(repo_dir: &Path) {
+    let license_text = include_str!("../LICENSE.txt");
+    run_cmd! (
+        cd "$repo_dir";
+        git init;
+        git config user.email "elui.alawi@email.com";
+        git config user.name "Joe Doe";
+
+        // The final call to awk is just to hide the output, without using '>',
+        // as it is not supported by lib_cmd
+        echo "$license_text" | tee "LICENSE.txt" | awk -e "{}";
+        echo "# Bench Project" | tee "README.md" | awk -e "{}";
+        mkdir -p "LICENSES";
+        touch "LICENSES/AGPL-3.0-or-later.txt";
+        touch "LICENSES/CC0-1.0.txt";
+        git add -A;
+        git commit -m "Initial commit";
+
+        git remote add origin "https://github.com/hoijui/projvar.git";
+    )
+    .expect("Failed to create the benchmark repo");
+    for release in 0..NUM_RELEASES {
+        let file = format!("file_{release}.txt");
+        let tag = format!("0.{release}.0");
+        run_cmd! (
+            cd "$repo_dir";
+            touch "$file";
+            git add "$file";
+            git commit -m "Release $tag";
+            git tag -a -m "This is release $tag" "$tag";
+        )
+        .expect("Failed to create a release in the benchmark repo");
+    }
+}
+
+fn settings(repo_path: &Path) -> Settings {
+    let mut settings = STUB.clone();
+    settings.repo_path = Some(repo_path.to_owned());
+    settings.fail_on = FailOn::Error;
+    settings
+}
+
+/// Runs a complete evaluation, without writing the results anywhere (no sinks),
+/// returning the evaluated environment and the sources used.
+fn evaluate(repo_path: &Path) -> (Environment, Vec<Box<dyn VarSource>>) {
+    let mut environment = Environment::new(settings(repo_path));
+    let result = projvar::process::run(&mut environment, sources::default_list(repo_path), vec![]);
+    black_box(result).ok();
+    (environment, sources::default_list(repo_path))
+}
+
+fn bench_evaluation(c: &mut Criterion) {
+    let repo = TempDir::new().unwrap();
+    create_repo(repo.path());
+    let repo_path = repo.path().to_owned();
+
+    c.bench_function("evaluate", |b| b.iter(|| evaluate(black_box(&repo_path))));
+    bench_tables(c, &repo_path);
+    bench_licenses(c, &repo_path);
+}
+
+/// Benchmarks rendering the storage of an evaluation as a table, in all the formats.
+fn bench_tables(c: &mut Criterion, repo_path: &Path) {
+    let (environment, sources) = evaluate(repo_path);
+    let mut group = c.benchmark_group("table");
+    for format in [
+        TableFormat::Markdown,
+        TableFormat::Html,
+        TableFormat::Json,
+        TableFormat::Csv,
+    ] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{format:?}")),
+            &format,
+            |b, &format| b.iter(|| environment.output.to_table(&environment, &sources, format)),
+        );
+    }
+    group.finish();
+}
+
+/// Benchmarks detecting the license(s) of the repo.
+fn bench_licenses(c: &mut Criterion, repo_path: &Path) {
+    let mut group = c.benchmark_group("licenses");
+    for key in [Key::License, Key::Licenses] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{key:?}")),
+            &key,
+            |b, &key| {
+                b.iter(|| {
+                    let mut environment = Environment::new(settings(repo_path));
+                    sources::fs::VarSource
+                        .retrieve(&mut environment, key)
+                        .unwrap()
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_evaluation);
+criterion_main!(benches);
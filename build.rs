@@ -23,9 +23,14 @@ fn download_licenses_cache() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // This allows us to report the target triple we were compiled for at runtime,
+    // see `sources::fs::build_host_platform_triple`.
+    println!("cargo:rustc-env=PROJVAR_TARGET={}", env::var("TARGET")?);
     // NOTE As <https://docs.rs> does not allow the build process to use the network,
     //      we have to disable downloading the licenses.
-    if std::env::var("DOCS_RS").is_ok() {
+    //      Without the "license-detection" feature, we do not need them at all.
+    if std::env::var("DOCS_RS").is_ok() || std::env::var("CARGO_FEATURE_LICENSE_DETECTION").is_err()
+    {
         Ok(())
     } else {
         download_licenses_cache()
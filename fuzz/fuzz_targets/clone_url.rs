@@ -0,0 +1,15 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Parses arbitrary strings as git clone URLs,
+//! as supplied by CI environment variables and git remotes.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use projvar::tools::git_clone_url::PartsRef;
+
+fuzz_target!(|data: &str| {
+    let _ = PartsRef::parse(data);
+});
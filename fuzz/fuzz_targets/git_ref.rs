@@ -0,0 +1,17 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Extracts branch, tag and pull request names from arbitrary strings,
+//! as supplied by CI environment variables like `GITHUB_REF`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use projvar::sources;
+
+fuzz_target!(|data: &str| {
+    let _ = sources::ref_extract_branch(data);
+    let _ = sources::ref_extract_tag(data);
+    let _ = sources::ref_extract_pull_request(data);
+});
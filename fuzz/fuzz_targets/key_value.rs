@@ -0,0 +1,15 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Parses arbitrary strings as "KEY=VALUE" pairs,
+//! as supplied on the command-line (see `--variable`).
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use projvar::var;
+
+fuzz_target!(|data: &str| {
+    let _ = var::parse_key_value_str(data);
+});
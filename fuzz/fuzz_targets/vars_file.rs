@@ -0,0 +1,15 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Parses arbitrary bytes as a vars file (see `--variables-file`),
+//! as written e.g. to `$GITHUB_ENV` by CI jobs.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use projvar::var;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = var::parse_vars_file_reader(data);
+});
@@ -2,22 +2,45 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use crate::{environment::Environment, sources::ConfVal};
+use crate::{
+    environment::Environment,
+    settings::{LengthLimit, LengthPolicy},
+    sources::ConfVal,
+    validator,
+    var::AnyKey,
+};
 use lazy_static::lazy_static;
 use regex::Regex;
+use unicode_normalization::{char::is_combining_mark, is_nfc, UnicodeNormalization};
 
-pub fn version(_environment: &mut Environment, value: &str) -> Option<String> {
+/// Cleans up a version string for human consumption,
+/// which currently means to remove a prefix like "v" or "release-",
+/// as commonly found in git tags and thus in `git describe` output.
+/// The prefixes are configurable (see `--version-strip-prefix`),
+/// are matched case-insensitively,
+/// and the first one that matches is removed,
+/// together with a directly following '.' and white-space (e.g. "V. 1.2.3").
+/// Returns `None` if there was nothing to clean up.
+pub fn version(environment: &mut Environment, value: &str) -> Option<String> {
     lazy_static! {
-        static ref R_V_PREFIX: Regex = Regex::new(r"^[vV][.]?[ \t]*").unwrap();
-    }
-    let stripped_value = R_V_PREFIX.replace(value, "");
-    if stripped_value == value {
-        None
-    } else {
-        Some(stripped_value.into_owned())
+        static ref R_PREFIX_SEPARATOR: Regex = Regex::new(r"^[.]?[ \t]*").unwrap();
     }
+    environment
+        .settings
+        .version_strip_prefixes
+        .iter()
+        .filter(|prefix| !prefix.is_empty())
+        .find_map(|prefix| {
+            value
+                .split_at_checked(prefix.len())
+                .filter(|(value_start, _rest)| value_start.eq_ignore_ascii_case(prefix))
+                .map(|(_value_start, rest)| rest)
+        })
+        .map(|rest| R_PREFIX_SEPARATOR.replace(rest, "").into_owned())
 }
 
+/// Like [`version`], but operating on a value with a confidence,
+/// and returning the original if there was nothing to clean up.
 pub fn conf_version(environment: &mut Environment, conf_val: ConfVal) -> ConfVal {
     match version(environment, &conf_val.1) {
         Some(cleaner_val) => (conf_val.0, cleaner_val),
@@ -25,8 +48,160 @@ pub fn conf_version(environment: &mut Environment, conf_val: ConfVal) -> ConfVal
     }
 }
 
+/// Whether a character is a control character we remove from values.
+/// Line breaks and tabs are kept,
+/// as they may legitimately be part of multi-line values.
+fn is_stripped_control(chr: char) -> bool {
+    chr.is_control() && chr != '\n' && chr != '\t'
+}
+
+/// Cleans up the encoding of a value,
+/// which means to normalize it to Unicode Normalization Form C (NFC),
+/// and to remove control characters (except for line breaks and tabs).
+/// Returns `None` if there was nothing to clean up.
+#[must_use]
+pub fn unicode(value: &str) -> Option<String> {
+    let has_controls = value.contains(is_stripped_control);
+    if !has_controls && is_nfc(value) {
+        return None;
+    }
+    Some(
+        value
+            .nfc()
+            .filter(|chr| !is_stripped_control(*chr))
+            .collect(),
+    )
+}
+
+/// Like [`unicode`], but operating on a value with a confidence,
+/// and returning the original if there was nothing to clean up.
+/// Values containing the Unicode replacement character (U+FFFD)
+/// are flagged with a warning,
+/// as it indicates content that was not valid UTF-8 in the first place
+/// (e.g. git author names or tag messages in a legacy encoding),
+/// and got replaced while decoding.
+pub fn conf_unicode(key: AnyKey, conf_val: ConfVal) -> ConfVal {
+    if conf_val.1.contains(char::REPLACEMENT_CHARACTER) {
+        log::warn!(
+            "The value of {key} contains content that was not valid UTF-8, \
+            replaced by '{}': '{}'",
+            char::REPLACEMENT_CHARACTER,
+            conf_val.1
+        );
+    }
+    match unicode(&conf_val.1) {
+        Some(cleaner_val) => {
+            log::debug!(
+                "Cleaned up the encoding of the value of {key}: {:?} -> {cleaner_val:?}",
+                conf_val.1
+            );
+            (conf_val.0, cleaner_val)
+        }
+        None => conf_val,
+    }
+}
+
+/// Latin letters that do not decompose into an ASCII letter
+/// and combining marks (see [`ascii`]), with their usual transliteration.
+const LATIN_SPECIALS: &[(char, &str)] = &[
+    ('ß', "ss"),
+    ('ẞ', "SS"),
+    ('Æ', "AE"),
+    ('æ', "ae"),
+    ('Œ', "OE"),
+    ('œ', "oe"),
+    ('Ø', "O"),
+    ('ø', "o"),
+    ('Đ', "D"),
+    ('đ', "d"),
+    ('Ð', "D"),
+    ('ð', "d"),
+    ('Ł', "L"),
+    ('ł', "l"),
+    ('Þ', "Th"),
+    ('þ', "th"),
+    ('ı', "i"),
+];
+
+/// Transliterates a value to ASCII, as far as that is easily possible:
+/// Characters are decomposed (Unicode Normalization Form KD),
+/// combining marks are dropped (e.g. "ü" becomes "u"),
+/// and a few special Latin letters are replaced (e.g. "ß" becomes "ss").
+/// All other non-ASCII characters (e.g. of non-Latin scripts) are kept.
+#[must_use]
+pub fn ascii(value: &str) -> String {
+    let mut ascii = String::with_capacity(value.len());
+    for chr in value.nfkd().filter(|chr| !is_combining_mark(*chr)) {
+        match LATIN_SPECIALS.iter().find(|(special, _)| *special == chr) {
+            Some((_, replacement)) => ascii.push_str(replacement),
+            None => ascii.push(chr),
+        }
+    }
+    ascii
+}
+
+/// Applies a length limit to a value (see [`LengthLimit`]),
+/// counting and truncating in characters,
+/// so a truncated value is still valid UTF-8.
+/// Returns the value - truncated, if required by the limits policy -
+/// and whether it was truncated.
+///
+/// # Errors
+///
+/// If the value is too long,
+/// and the limits policy is [`LengthPolicy::Error`].
+pub fn length(
+    key: AnyKey,
+    limit: LengthLimit,
+    value: String,
+) -> Result<(String, bool), validator::Error> {
+    let length = value.chars().count();
+    if length <= limit.max {
+        return Ok((value, false));
+    }
+    let max = limit.max;
+    match limit.policy {
+        LengthPolicy::Warn => {
+            log::warn!(
+                "The value of {key} is {length} characters long, \
+                which is more than the maximum of {max}"
+            );
+            Ok((value, false))
+        }
+        LengthPolicy::Truncate => {
+            log::warn!(
+                "The value of {key} is {length} characters long; \
+                truncating it to the maximum of {max}"
+            );
+            Ok((value.chars().take(max).collect(), true))
+        }
+        LengthPolicy::Error => Err(validator::Error::BadValue {
+            msg: format!("It is {length} characters long, which is more than the maximum of {max}"),
+            value,
+        }),
+    }
+}
+
+/// Like [`length`], but operating on a value with a confidence,
+/// and using the length limit configured for the key
+/// (see [`crate::settings::Settings::length_limit`]).
+///
+/// # Errors
+///
+/// If the value is too long,
+/// and the limits policy is [`LengthPolicy::Error`].
+pub fn conf_length(
+    environment: &Environment,
+    key: AnyKey,
+    conf_val: ConfVal,
+) -> Result<(ConfVal, bool), validator::Error> {
+    let (confidence, value) = conf_val;
+    let limit = environment.settings.length_limit(key);
+    length(key, limit, value).map(|(value, truncated)| ((confidence, value), truncated))
+}
+
 // macro_rules! version {
-//     (environment: &mut Environment, conf_val: &(Confidence, String)) => {
+//     (environment: &mut Environment, conf_val: &(ConfidenceScore, String)) => {
 //         {match bare_version(environment, &conf_val.1) {
 //             Some(cleaner_val) => (conf_val.0, cleaner_val),
 //             None => conf_val
@@ -34,3 +209,83 @@ pub fn conf_version(environment: &mut Environment, conf_val: ConfVal) -> ConfVal
 //     }
 // }
 // pub(crate) use version;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version() {
+        let mut environment = Environment::stub();
+        assert_eq!(
+            version(&mut environment, "v1.2.3"),
+            Some("1.2.3".to_owned())
+        );
+        assert_eq!(
+            version(&mut environment, "V. 1.2.3"),
+            Some("1.2.3".to_owned())
+        );
+        assert_eq!(version(&mut environment, "1.2.3"), None);
+        // multi-byte characters where the prefix would end
+        assert_eq!(version(&mut environment, "é1.2.3"), None);
+        assert_eq!(
+            version(&mut environment, "v€1.2.3"),
+            Some("€1.2.3".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_unicode() {
+        assert_eq!(unicode("plain"), None);
+        assert_eq!(unicode("multi\n\tline"), None);
+        // "e" followed by a combining acute accent
+        assert_eq!(unicode("Cafe\u{301}"), Some("Caf\u{e9}".to_owned()));
+        assert_eq!(unicode("a\u{7}b\r\nc\u{1b}"), Some("ab\nc".to_owned()));
+        assert_eq!(
+            unicode("Jos\u{e9}\u{fffd}"),
+            None,
+            "the replacement character is only flagged"
+        );
+    }
+
+    #[test]
+    fn test_ascii() {
+        assert_eq!(ascii("projvar"), "projvar");
+        assert_eq!(ascii("Kühlschrank-Steuerung"), "Kuhlschrank-Steuerung");
+        // "e" followed by a combining acute accent
+        assert_eq!(ascii("Cafe\u{301}"), "Cafe");
+        assert_eq!(ascii("Straße Øresund"), "Strasse Oresund");
+        // compatibility decomposition
+        assert_eq!(ascii("ﬁle²"), "file2");
+        assert_eq!(ascii("日本"), "日本");
+    }
+
+    #[test]
+    fn test_length() {
+        let key = AnyKey::from(crate::var::Key::Name);
+        let limit = |policy| LengthLimit { max: 4, policy };
+        let length_res = |policy, value: &str| length(key, limit(policy), value.to_owned()).ok();
+        assert_eq!(
+            length_res(LengthPolicy::Truncate, "proj"),
+            Some(("proj".to_owned(), false))
+        );
+        assert_eq!(
+            length_res(LengthPolicy::Truncate, "projvar"),
+            Some(("proj".to_owned(), true))
+        );
+        // counts characters, not bytes
+        assert_eq!(
+            length_res(LengthPolicy::Truncate, "grüße!"),
+            Some(("grüß".to_owned(), true))
+        );
+        assert_eq!(
+            length_res(LengthPolicy::Warn, "projvar"),
+            Some(("projvar".to_owned(), false))
+        );
+        assert_eq!(length_res(LengthPolicy::Error, "projvar"), None);
+        assert_eq!(
+            length_res(LengthPolicy::Error, "proj"),
+            Some(("proj".to_owned(), false))
+        );
+    }
+}
@@ -0,0 +1,427 @@
+// SPDX-FileCopyrightText: 2021 - 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::settings::{self, LengthLimit};
+use crate::tools::git_hosting_provs::HostingType;
+use crate::validator::Policy;
+
+/// The name of the config file we look for in the project root,
+/// if none is specified explicitly.
+pub const DEFAULT_FILE_NAME: &str = ".projvar.toml";
+
+pub const PROFILE_RELEASE: &str = "release";
+pub const PROFILE_CONTAINER: &str = "container";
+pub const PROFILE_DOCS: &str = "docs";
+
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Represents all cases of `std::io::Error`.
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+
+    /// The config file is not valid TOML,
+    /// or does not conform to our structure.
+    #[error(transparent)]
+    Parse(#[from] toml::de::Error),
+
+    /// The requested profile is neither built-in,
+    /// nor defined in the config file.
+    #[error("Unknown profile: '{0}'")]
+    UnknownProfile(String),
+
+    /// A hosting type used as key in the config file is not known.
+    #[error("{0}")]
+    UnknownHostingType(String),
+
+    /// A validator policy in the config file is not a valid regex.
+    #[error("Invalid validator policy for key '{key}': {source}")]
+    InvalidValidator { key: String, source: regex::Error },
+
+    /// A length limit in the config file is not of the form `LENGTH[:POLICY]`.
+    #[error("Invalid length limit for key '{key}': {msg}")]
+    InvalidLengthLimit { key: String, msg: String },
+}
+
+/// A named preset of required keys and related settings.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    /// Keys that are required when using this profile.
+    /// These may be key names, variable keys or glob patterns,
+    /// just like the values supplied to `--require`.
+    pub require: Vec<String>,
+    /// Whether to fail if any of the required values is missing.
+    pub fail: Option<bool>,
+    /// Whether to only output the required values.
+    pub only_required: Option<bool>,
+}
+
+impl Profile {
+    fn from_keys(keys: &[&str]) -> Self {
+        Self {
+            require: keys.iter().map(|key| (*key).to_owned()).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns one of the profiles that are always available,
+    /// if `name` refers to one.
+    #[must_use]
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            PROFILE_RELEASE => Some(Self::from_keys(&[
+                "Version",
+                "VersionDate",
+                "License",
+                "RepoWebUrl",
+            ])),
+            PROFILE_CONTAINER => Some(Self::from_keys(&["NameMachineReadable", "Version"])),
+            PROFILE_DOCS => Some(Self::from_keys(&["BuildHostingUrl", "RepoWebUrl"])),
+            _ => None,
+        }
+    }
+}
+
+fn deserialize_hosting_type<'de, D>(deserializer: D) -> Result<HostingType, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let name = String::deserialize(deserializer)?;
+    settings::parse_hosting_type(&name).map_err(serde::de::Error::custom)
+}
+
+/// Settings for a custom (usually self-hosted) forge.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Hosting {
+    /// The hosting software running on this domain,
+    /// see `--hosting-type` for the possible values.
+    #[serde(rename = "type", deserialize_with = "deserialize_hosting_type")]
+    pub hosting_type: HostingType,
+}
+
+/// User supplied URL templates for a hosting type.
+///
+/// They override (or - for unknown hosting software - supply)
+/// the built-in rules to construct the repo URLs from the repo web URL.
+/// These placeholders are supported:
+/// `{web_url}`, `{host}` (including the port, if any),
+/// `{slug}` (e.g. "owner/repo"), `{owner}` (e.g. "owner",
+/// the user, organization, group or Bitbucket workspace)
+/// and `{name}` (e.g. "repo").
+/// The versioned URLs are prefixes, to which the ref and path get appended,
+/// thus they do not contain a placeholder for the ref.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct UrlTemplates {
+    /// See [`crate::var::Key::RepoIssuesUrl`]
+    pub issues: Option<String>,
+    /// See [`crate::var::Key::RepoRawVersionedPrefixUrl`]
+    pub raw: Option<String>,
+    /// See [`crate::var::Key::RepoVersionedFilePrefixUrl`]
+    pub blob: Option<String>,
+    /// See [`crate::var::Key::RepoVersionedDirPrefixUrl`]
+    pub tree: Option<String>,
+    /// See [`crate::var::Key::RepoCommitPrefixUrl`]
+    pub commit: Option<String>,
+    /// See [`crate::var::Key::BuildHostingUrl`]
+    pub pages: Option<String>,
+}
+
+/// The contents of a projvar config file.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// User-defined profiles.
+    /// These take precedence over built-in ones of the same name.
+    pub profiles: HashMap<String, Profile>,
+    /// Custom forges, by domain (optionally with port, e.g. "git.company.com:8080"),
+    /// or domain suffix starting with '.' (e.g. ".company.com").
+    /// These are used before the ones supplied with `--hosting-domain`.
+    pub hosting: HashMap<String, Hosting>,
+    /// URL templates by hosting type (see `--hosting-type`),
+    /// e.g. `[urls.unknown]` or `[urls.gitea]`.
+    pub urls: HashMap<String, UrlTemplates>,
+    /// Value policies by key name, which are checked
+    /// in addition to the built-in validators, for example
+    /// `validators.Version = "^\\d+\\.\\d+\\.\\d+$"` (values have to match)
+    /// or `validators.Name = "deny:^test"` (values must not match),
+    /// see [`crate::validator::Policy`].
+    pub validators: HashMap<String, String>,
+    /// Length limits by key name (or "*" for all other keys),
+    /// for example `lengths.Description = "255:truncate"`,
+    /// see [`crate::settings::LengthLimit`].
+    pub lengths: HashMap<String, String>,
+}
+
+impl Config {
+    /// Parses a config from a TOML string.
+    ///
+    /// # Errors
+    ///
+    /// If the string is not valid TOML,
+    /// or does not conform to the config structure.
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Reads the config from a file.
+    ///
+    /// # Errors
+    ///
+    /// If the file could not be read,
+    /// or its content is not a valid config.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        log::debug!("Loading config from '{}' ...", path.display());
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Reads the config from the default location within the project root,
+    /// or returns an empty config, if there is no such file.
+    ///
+    /// # Errors
+    ///
+    /// If the file exists, but could not be read,
+    /// or its content is not a valid config.
+    pub fn load_default(repo_path: &Path) -> Result<Self, Error> {
+        let path = repo_path.join(DEFAULT_FILE_NAME);
+        if path.is_file() {
+            Self::load(&path)
+        } else {
+            log::trace!("No config file found at '{}'.", path.display());
+            Ok(Self::default())
+        }
+    }
+
+    /// Returns the hosting type of each custom domain (lower-cased),
+    /// see [`crate::settings::Settings::hosting_domains`].
+    #[must_use]
+    pub fn hosting_domains(&self) -> HashMap<String, HostingType> {
+        self.hosting
+            .iter()
+            .map(|(domain, hosting)| (domain.to_lowercase(), hosting.hosting_type))
+            .collect()
+    }
+
+    /// Returns the URL templates by hosting type,
+    /// see [`crate::settings::Settings::url_templates`].
+    ///
+    /// # Errors
+    ///
+    /// If any of the keys is not a known hosting type.
+    pub fn url_templates(&self) -> Result<HashMap<HostingType, UrlTemplates>, Error> {
+        self.urls
+            .iter()
+            .map(|(hosting_type, templates)| {
+                settings::parse_hosting_type(hosting_type)
+                    .map(|hosting_type| (hosting_type, templates.clone()))
+                    .map_err(Error::UnknownHostingType)
+            })
+            .collect()
+    }
+
+    /// Returns the parsed value policies by key name,
+    /// see [`crate::settings::Settings::validators`].
+    ///
+    /// # Errors
+    ///
+    /// If any of the policies is not a valid regex.
+    pub fn validators(&self) -> Result<HashMap<String, Policy>, Error> {
+        self.validators
+            .iter()
+            .map(|(key, policy)| {
+                policy
+                    .parse()
+                    .map(|policy| (key.clone(), policy))
+                    .map_err(|source| Error::InvalidValidator {
+                        key: key.clone(),
+                        source,
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the parsed length limits by key name,
+    /// see [`crate::settings::Settings::length_limits`].
+    ///
+    /// # Errors
+    ///
+    /// If any of the limits is not of the form `LENGTH[:POLICY]`.
+    pub fn length_limits(&self) -> Result<HashMap<String, LengthLimit>, Error> {
+        self.lengths
+            .iter()
+            .map(|(key, limit)| {
+                limit
+                    .parse()
+                    .map(|limit| (key.clone(), limit))
+                    .map_err(|msg| Error::InvalidLengthLimit {
+                        key: key.clone(),
+                        msg,
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the profile with the given name,
+    /// preferring user-defined ones over built-in ones.
+    ///
+    /// # Errors
+    ///
+    /// If there is no profile with the given name.
+    pub fn profile(&self, name: &str) -> Result<Profile, Error> {
+        self.profiles
+            .get(name)
+            .cloned()
+            .or_else(|| Profile::builtin(name))
+            .ok_or_else(|| Error::UnknownProfile(name.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_profiles() {
+        for name in [PROFILE_RELEASE, PROFILE_CONTAINER, PROFILE_DOCS] {
+            assert!(Profile::builtin(name).is_some());
+        }
+        assert_eq!(
+            Profile::builtin(PROFILE_CONTAINER).unwrap().require,
+            vec!["NameMachineReadable", "Version"]
+        );
+        assert!(Profile::builtin("non-existent").is_none());
+    }
+
+    #[test]
+    fn test_user_profiles() -> Result<(), Error> {
+        let config = Config::parse(
+            r#"
+            [profiles.docs]
+            require = ["BuildHostingUrl"]
+            only_required = true
+
+            [profiles.mine]
+            require = ["REPO_*", "PROJECT_NAME"]
+            fail = true
+            "#,
+        )?;
+        assert_eq!(
+            config.profile(PROFILE_DOCS)?.require,
+            vec!["BuildHostingUrl"]
+        );
+        assert_eq!(config.profile(PROFILE_DOCS)?.only_required, Some(true));
+        assert_eq!(config.profile("mine")?.fail, Some(true));
+        assert_eq!(
+            config.profile(PROFILE_RELEASE)?,
+            Profile::builtin(PROFILE_RELEASE).unwrap()
+        );
+        assert!(matches!(
+            config.profile("non-existent"),
+            Err(Error::UnknownProfile(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        assert!(Config::parse("[profiles.x]\nrequires = []\n").is_err());
+    }
+
+    #[test]
+    fn test_hosting() -> Result<(), Error> {
+        let config = Config::parse(
+            r#"
+            [hosting."Git.Company.com"]
+            type = "gitlab"
+
+            [hosting.".forge.org"]
+            type = "Gitea"
+
+            [hosting."192.168.1.10:3000"]
+            type = "git-lab"
+            "#,
+        )?;
+        let hosting_domains = config.hosting_domains();
+        assert_eq!(
+            hosting_domains.get("git.company.com"),
+            Some(&HostingType::GitLab)
+        );
+        assert_eq!(hosting_domains.get(".forge.org"), Some(&HostingType::Gitea));
+        assert_eq!(
+            hosting_domains.get("192.168.1.10:3000"),
+            Some(&HostingType::GitLab)
+        );
+        assert!(Config::parse("[hosting.\"x.org\"]\ntype = \"no-forge\"\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_url_templates() -> Result<(), Error> {
+        let config = Config::parse(
+            r#"
+            [urls.unknown]
+            issues = "{web_url}/issues"
+            commit = "https://{host}/{slug}/commit"
+            "#,
+        )?;
+        let url_templates = config.url_templates()?;
+        let unknown = url_templates.get(&HostingType::Unknown).unwrap();
+        assert_eq!(unknown.issues.as_deref(), Some("{web_url}/issues"));
+        assert_eq!(unknown.raw, None);
+        assert!(matches!(
+            Config::parse("[urls.no-forge]\nissues = \"x\"\n")?.url_templates(),
+            Err(Error::UnknownHostingType(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_validators() -> Result<(), Error> {
+        let config = Config::parse(
+            r#"
+            [validators]
+            Version = "^\\d+\\.\\d+\\.\\d+$"
+            Name = "deny:^test"
+            "#,
+        )?;
+        let validators = config.validators()?;
+        let version = validators.get("Version").unwrap();
+        assert!(version.check("1.2.3").is_ok());
+        assert!(version.check("1.2").is_err());
+        let name = validators.get("Name").unwrap();
+        assert!(name.check("my-proj").is_ok());
+        assert!(name.check("test-proj").is_err());
+        assert!(matches!(
+            Config::parse("[validators]\nName = \"(\"\n")?.validators(),
+            Err(Error::InvalidValidator { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_length_limits() -> Result<(), Error> {
+        let config = Config::parse(
+            r#"
+            [lengths]
+            Description = "255:truncate"
+            "*" = "4096:warn"
+            "#,
+        )?;
+        let length_limits = config.length_limits()?;
+        assert_eq!(length_limits.get("Description").unwrap().max, 255);
+        assert_eq!(length_limits.get("*").unwrap().max, 4096);
+        assert!(matches!(
+            Config::parse("[lengths]\nName = \"63:cut\"\n")?.length_limits(),
+            Err(Error::InvalidLengthLimit { .. })
+        ));
+        Ok(())
+    }
+}
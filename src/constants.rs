@@ -2,7 +2,29 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::time::Duration;
+
 pub const DEFAULT_KEY_PREFIX: &str = "PROJECT_";
+/// The prefix of the input variables that explicitly override values,
+/// independent of the key prefix, e.g. `PROJVAR_VERSION`;
+/// see [`crate::sources::overrides`].
+pub const OVERRIDE_KEY_PREFIX: &str = "PROJVAR_";
+/// The (git glob) pattern tags have to match
+/// to be considered when describing the version of the checked-out commit.
+pub const DEFAULT_VERSION_TAG_PATTERN: &str = "*[0-9]*.[0-9]*.[0-9]*";
+/// The prefixes removed from versions by default,
+/// separated by ','.
+pub const DEFAULT_VERSION_STRIP_PREFIXES: &str = "v";
+/// The default time-out for a single network request.
+pub const DEFAULT_NETWORK_TIMEOUT: Duration = Duration::from_secs(10);
+/// The default number of times a failed network request is retried.
+pub const DEFAULT_NETWORK_RETRIES: u8 = 2;
+/// The default maximum length of a value (in characters),
+/// which is the maximum length of an environment variable value on Windows.
+pub const DEFAULT_MAX_LENGTH: usize = 32_767;
+/// The key name that stands for all keys without a length limit of their own,
+/// see [`crate::settings::Settings::length_limits`].
+pub const LENGTH_LIMIT_ALL: &str = "*";
 
 pub const D_GIT_HUB_COM: &str = "github.com";
 pub const D_GIT_HUB_COM_RAW: &str = "raw.githubusercontent.com";
@@ -27,5 +49,33 @@ pub const DS_CODE_BERG_PAGE: &str = "codeberg.page";
 pub const D_SOURCE_FORGE_NET: &str = "sourceforge.net";
 pub const DS_SOURCE_FORGE_IO: &str = "sourceforge.io";
 
+/// Set by GitLab CI; the domain under which GitLab pages are hosted
+/// on the GitLab instance running the CI job, e.g. "gitlab.io" or "pages.company.com".
+pub const VAR_CI_PAGES_DOMAIN: &str = "CI_PAGES_DOMAIN";
+/// Set by GitLab CI; the visibility of the project,
+/// one of "public", "internal" or "private".
+pub const VAR_CI_PROJECT_VISIBILITY: &str = "CI_PROJECT_VISIBILITY";
+/// Set by Bitbucket Pipelines; "true" if the repo is private.
+pub const VAR_BITBUCKET_REPO_IS_PRIVATE: &str = "BITBUCKET_REPO_IS_PRIVATE";
+
+pub const D_RADICLE_APP_XYZ: &str = "app.radicle.xyz";
+pub const D_RADICLE_SEED_XYZ: &str = "seed.radicle.xyz";
+pub const D_RADICLE_SEED_GARDEN: &str = "seed.radicle.garden";
+/// The scheme of native Radicle clone URLs, e.g. `rad://z3gqcJUoA1n9HaHKufZs5FCSGazv5`
+pub const SCHEME_RADICLE: &str = "rad";
+/// The prefix of a Radicle repository ID, e.g. "rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5"
+pub const RADICLE_RID_PREFIX: &str = "rad:";
+
+/// Gerrit instances with `Gitiles` as web UI, e.g. "gerrit.googlesource.com"
+pub const DS_GOOGLE_SOURCE_COM: &str = "googlesource.com";
+/// The default port of the SSH daemon built into Gerrit
+pub const GERRIT_SSH_PORT: u16 = 29418;
+
+pub const VCS_GIT: &str = "git";
+pub const VCS_HG: &str = "hg";
+pub const VCS_SVN: &str = "svn";
+pub const VCS_NONE: &str = "none";
+pub const VALID_VCSS: &[&str] = &[VCS_GIT, VCS_HG, VCS_SVN, VCS_NONE];
+
 pub const VALID_OS_FAMILIES: &[&str] = &["linux", "unix", "bsd", "osx", "windows"]; // TODO
 pub const VALID_ARCHS: &[&str] = &["x86", "x86_64", "arm", "arm64"]; // TODO
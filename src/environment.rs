@@ -2,11 +2,20 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::constants;
 use crate::settings::{Settings, STUB};
-use crate::storage::Storage;
-use crate::tools::git;
-use std::collections::HashMap;
+use crate::storage::{Storage, StorageSnapshot, UnknownKey};
+use crate::tools::{cmd, git};
+use crate::var::{self, AnyKey, DynamicKey, Key, KeyTaken};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
 use std::convert::TryFrom;
+use std::str::FromStr;
+use std::sync::Arc;
+use strum::IntoEnumIterator;
+use thiserror::Error;
+use url::Url;
 
 pub struct Environment {
     pub settings: Settings,
@@ -16,6 +25,41 @@ pub struct Environment {
     /// The output values we evaluated for the project properties we want to know.
     pub output: Storage,
     pub repo: Option<git::Repo>,
+    /// The keys registered at runtime, in addition to the built-in ones,
+    /// see [`Self::register_dynamic_key`].
+    pub dynamic_keys: Vec<&'static DynamicKey>,
+    /// Runs the external commands of all the features that shell out;
+    /// this is a [`cmd::DryRun`] in dry-run mode,
+    /// and may be replaced with a [`cmd::Fake`] in tests.
+    pub cmd_runner: Arc<dyn cmd::Runner>,
+    /// The names of the input variables read through [`crate::sources::var`],
+    /// if we are recording them,
+    /// see [`Self::start_recording_var_reads`].
+    var_reads: RefCell<Option<Vec<String>>>,
+}
+
+/// The complete state of an evaluation,
+/// which may be persisted (e.g. as JSON),
+/// and later be replayed with [`Environment::from_snapshot`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub settings: Settings,
+    pub vars: HashMap<String, String>,
+    /// The descriptions of the registered dynamic keys, by name.
+    /// NOTE Their validators can not be persisted.
+    pub dynamic_keys: BTreeMap<String, String>,
+    pub output: StorageSnapshot,
+}
+
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    /// A dynamic key of the snapshot clashes with another key.
+    #[error(transparent)]
+    KeyTaken(#[from] KeyTaken),
+
+    /// The stored values of the snapshot refer to an unknown key.
+    #[error(transparent)]
+    UnknownKey(#[from] UnknownKey),
 }
 
 impl Environment {
@@ -29,6 +73,9 @@ impl Environment {
             vars,
             output,
             repo,
+            dynamic_keys: vec![],
+            cmd_runner: Arc::new(cmd::System),
+            var_reads: RefCell::new(None),
         }
     }
 
@@ -37,9 +84,213 @@ impl Environment {
         Self::new(STUB.clone())
     }
 
+    /// Captures the complete state of the evaluation so far.
+    #[must_use]
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            settings: self.settings.clone(),
+            vars: self.vars.clone(),
+            dynamic_keys: self
+                .dynamic_keys
+                .iter()
+                .map(|key| (key.name.to_owned(), key.variable.description.to_owned()))
+                .collect(),
+            output: self.output.snapshot(),
+        }
+    }
+
+    /// Recreates an environment from a snapshot,
+    /// e.g. to replay an evaluation in a test.
+    /// The repo is opened anew from [`Settings::repo_path`],
+    /// and the dynamic keys are registered without validators.
+    ///
+    /// # Errors
+    ///
+    /// If the dynamic keys clash with each other or with the built-in keys,
+    /// or if the stored values refer to unknown keys.
+    pub fn from_snapshot(snapshot: Snapshot) -> Result<Self, SnapshotError> {
+        let mut environment = Self::new(snapshot.settings);
+        environment.vars = snapshot.vars;
+        for (name, description) in &snapshot.dynamic_keys {
+            environment.register_dynamic_key(DynamicKey::create(name, description, None))?;
+        }
+        let keys = environment.keys();
+        environment.output = Storage::from_snapshot(snapshot.output, |name| {
+            keys.iter().copied().find(|key| key.name() == name)
+        })?;
+        Ok(environment)
+    }
+
+    /// Copies everything but the repo (and the recording of input variable reads),
+    /// to stand in for this environment while it is used on a worker thread,
+    /// see [`crate::settings::Settings::source_timeout`].
+    #[must_use]
+    pub fn copy_without_repo(&self) -> Self {
+        Self {
+            settings: self.settings.clone(),
+            vars: self.vars.clone(),
+            output: self.output.clone(),
+            repo: None,
+            dynamic_keys: self.dynamic_keys.clone(),
+            cmd_runner: Arc::clone(&self.cmd_runner),
+            var_reads: RefCell::new(None),
+        }
+    }
+
+    /// Opens the repo anew from [`Settings::repo_path`],
+    /// e.g. after the previous one was left behind with an abandoned worker thread.
+    pub fn reopen_repo(&mut self) {
+        self.repo = git::Repo::try_from(self.settings.repo_path.as_deref()).ok();
+    }
+
+    /// Registers a key for a project property that is not covered by [`Key`],
+    /// which will then be retrieved, validated and sunk
+    /// just like the built-in keys.
+    ///
+    /// # Errors
+    ///
+    /// If the name or the variable key of the new key
+    /// is already used by a built-in or a previously registered key.
+    pub fn register_dynamic_key(
+        &mut self,
+        dynamic_key: &'static DynamicKey,
+    ) -> Result<(), KeyTaken> {
+        let var_key = dynamic_key.variable.key_raw();
+        let taken = Key::from_str(dynamic_key.name).is_ok()
+            || Key::iter().any(|key| var::get(key).key_raw() == var_key)
+            || self
+                .dynamic_keys
+                .iter()
+                .any(|other| other.name == dynamic_key.name || other.variable.key_raw() == var_key);
+        if taken {
+            return Err(KeyTaken {
+                name: dynamic_key.name.to_owned(),
+            });
+        }
+        self.dynamic_keys.push(dynamic_key);
+        Ok(())
+    }
+
+    /// All the built-in keys, followed by the registered dynamic ones.
+    #[must_use]
+    pub fn keys(&self) -> Vec<AnyKey> {
+        Key::iter()
+            .map(AnyKey::from)
+            .chain(self.dynamic_keys.iter().copied().map(AnyKey::Dynamic))
+            .collect()
+    }
+
+    /// Why we think the project is not publicly accessible, if we do.
+    /// URLs into such a project (e.g. raw file URLs or the pages)
+    /// can likely not be accessed anonymously.
+    ///
+    /// The visibility reported by the CI is authoritative;
+    /// without it, credentials in the clone URL of the local repo
+    /// hint at a private project.
+    #[must_use]
+    pub fn private_reason(&self) -> Option<String> {
+        if let Some(visibility) = self.vars.get(constants::VAR_CI_PROJECT_VISIBILITY) {
+            return (visibility == "private" || visibility == "internal")
+                .then(|| format!("{}='{visibility}'", constants::VAR_CI_PROJECT_VISIBILITY));
+        }
+        if let Some(is_private) = self.vars.get(constants::VAR_BITBUCKET_REPO_IS_PRIVATE) {
+            return (is_private == "true")
+                .then(|| format!("{}='true'", constants::VAR_BITBUCKET_REPO_IS_PRIVATE));
+        }
+        let clone_url = self.repo.as_ref()?.remote_clone_url().ok()??;
+        Url::parse(&clone_url)
+            .ok()
+            .filter(|url| url.password().is_some())
+            .map(|_| "the clone URL of the local repo contains credentials".to_owned())
+    }
+
+    /// Starts recording the names of the input variables
+    /// the sources try to read,
+    /// whether they are set or not,
+    /// which is used to explain where a value came from
+    /// (see [`crate::settings::Settings::explain`]).
+    pub fn start_recording_var_reads(&self) {
+        *self.var_reads.borrow_mut() = Some(vec![]);
+    }
+
+    /// Stops recording the input variable reads,
+    /// and returns the recorded variable names.
+    pub fn take_var_reads(&self) -> Vec<String> {
+        self.var_reads.borrow_mut().take().unwrap_or_default()
+    }
+
+    /// Records the read of an input variable,
+    /// if we are recording them.
+    pub fn record_var_read(&self, name: &str) {
+        if let Some(var_reads) = self.var_reads.borrow_mut().as_mut() {
+            if !var_reads.iter().any(|read| read == name) {
+                var_reads.push(name.to_owned());
+            }
+        }
+    }
+
     #[must_use]
     pub const fn repo(&self) -> Option<&git::Repo> {
         // TODO DEPRECATED Just use the repo property directly, instead
         self.repo.as_ref()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::var::ConfidenceScore;
+    use strum::EnumCount;
+
+    #[test]
+    fn test_register_dynamic_key() {
+        let mut environment = Environment::stub();
+        let dynamic_key = DynamicKey::create("FirmwareVariant", "The firmware variant", None);
+        assert!(environment.register_dynamic_key(dynamic_key).is_ok());
+        assert!(environment.register_dynamic_key(dynamic_key).is_err());
+        // clashes with a built-in key
+        assert!(environment
+            .register_dynamic_key(DynamicKey::create("Version", "", None))
+            .is_err());
+        assert_eq!(environment.keys().len(), Key::COUNT + 1);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut environment = Environment::stub();
+        let dynamic_key = DynamicKey::create("BoardRevision", "The board revision", None);
+        environment.register_dynamic_key(dynamic_key).unwrap();
+        environment.vars.insert("CI".to_owned(), "true".to_owned());
+        environment
+            .output
+            .add(Key::Version, 0, ConfidenceScore::HIGH, "1.2.3".to_owned());
+        environment.output.add(
+            AnyKey::Dynamic(dynamic_key),
+            1,
+            ConfidenceScore::LOW,
+            "B".to_owned(),
+        );
+
+        let json = serde_json::to_string(&environment.snapshot()).unwrap();
+        let replayed = Environment::from_snapshot(serde_json::from_str(&json).unwrap()).unwrap();
+        assert_eq!(replayed.vars, environment.vars);
+        assert_eq!(replayed.keys().len(), Key::COUNT + 1);
+        assert_eq!(
+            replayed.output.get(Key::Version),
+            Some(&(ConfidenceScore::HIGH, "1.2.3".to_owned()))
+        );
+        assert_eq!(replayed.output.snapshot(), environment.output.snapshot());
+        assert_eq!(
+            replayed.settings.required_keys,
+            environment.settings.required_keys
+        );
+
+        // an unregistered dynamic key
+        let mut snapshot = environment.snapshot();
+        snapshot.dynamic_keys.clear();
+        assert!(matches!(
+            Environment::from_snapshot(snapshot),
+            Err(SnapshotError::UnknownKey(_))
+        ));
+    }
+}
@@ -0,0 +1,105 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use thiserror::Error;
+
+use crate::{config, sinks, sources, storage, tools, validator, var};
+
+/// This enumerates all possible errors returned by the public API of this crate,
+/// allowing library users to match on the kind of failure.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// A source failed to retrieve a value.
+    #[error(transparent)]
+    Source(#[from] sources::Error),
+
+    /// A value is missing or unusable.
+    #[error(transparent)]
+    Validation(#[from] validator::Error),
+
+    /// A sink failed to store the values.
+    #[error(transparent)]
+    Sink(#[from] sinks::Error),
+
+    /// Some values are valid, but only suboptimal,
+    /// see [`crate::settings::Settings::strict`].
+    #[error("{0} value(s) are only suboptimal, which is not accepted in strict mode")]
+    Suboptimal(usize),
+
+    /// Two sources disagree about a value,
+    /// see [`crate::settings::Settings::strict_consistency`].
+    #[error(transparent)]
+    Inconsistency(#[from] storage::Discrepancy),
+
+    /// Accessing the git repository failed.
+    #[error(transparent)]
+    Git(#[from] tools::git::Error),
+
+    /// The config file could not be loaded.
+    #[error(transparent)]
+    Config(#[from] config::Error),
+
+    /// A file containing "KEY=VALUE" lines could not be parsed.
+    #[error(transparent)]
+    VarsFile(#[from] var::ParseError),
+
+    /// Represents all other cases of `std::io::Error`.
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+}
+
+impl Error {
+    /// The exit code the CLI reports if it fails with this error.
+    #[must_use]
+    pub const fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::Validation(validator::Error::Missing(_)) => ExitCode::MissingRequired,
+            Self::Validation(
+                validator::Error::AlmostUsableValue { .. } | validator::Error::BadValue { .. },
+            )
+            | Self::Suboptimal(_) => ExitCode::Invalid,
+            Self::Inconsistency(_) => ExitCode::Inconsistent,
+            Self::Source(_)
+            | Self::Validation(validator::Error::IO(_))
+            | Self::Sink(_)
+            | Self::Git(_)
+            | Self::Config(_)
+            | Self::VarsFile(_)
+            | Self::IO(_) => ExitCode::Error,
+        }
+    }
+}
+
+/// The exit codes of the CLI,
+/// which allow scripts to distinguish the different kinds of failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ExitCode {
+    /// All values were evaluated, stored and found valid.
+    Success = 0,
+    /// Any failure not covered by a more specific exit code,
+    /// e.g. an unreadable file or an unusable git repo.
+    Error = 1,
+    /// No value was found for a required key,
+    /// see `--fail`.
+    MissingRequired = 2,
+    /// A value is invalid,
+    /// or - in strict mode - only suboptimal,
+    /// see `--strict`.
+    Invalid = 3,
+    /// Two sources disagree about a value,
+    /// see `--strict-consistency`.
+    Inconsistent = 4,
+    /// The command-line arguments are invalid;
+    /// the value of `EX_USAGE` from `sysexits.h`.
+    Usage = 64,
+}
+
+impl From<ExitCode> for std::process::ExitCode {
+    fn from(code: ExitCode) -> Self {
+        Self::from(code as u8)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
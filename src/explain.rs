@@ -0,0 +1,195 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::environment::Environment;
+use crate::sources::{deriver, Hierarchy, VarSource};
+use crate::storage;
+use crate::var::{self, ConfidenceScore, Key};
+
+/// What a single source came up with for a key that is to be explained.
+pub struct Attempt {
+    /// The index of the source in the (sorted) list of sources
+    pub source_index: usize,
+    /// Whether the source is usable in the current environment at all;
+    /// if not, it was not asked for a value
+    pub usable: bool,
+    /// The names of the input variables the source tried to read
+    pub var_reads: Vec<String>,
+    /// The value the source retrieved, if any
+    pub value: Option<(ConfidenceScore, String)>,
+    /// The error the source failed with, if any,
+    /// see [`crate::settings::Settings::keep_going`]
+    pub error: Option<String>,
+}
+
+/// Records how the values of some keys came about,
+/// so we can explain it to the user after evaluation,
+/// see [`crate::settings::Settings::explain`].
+#[derive(Default)]
+pub struct Explanations {
+    attempts: HashMap<Key, Vec<Attempt>>,
+    validity: HashMap<Key, String>,
+    verification: HashMap<Key, String>,
+}
+
+fn var_keys(environment: &Environment, derivations: &[deriver::Derivation]) -> String {
+    derivations
+        .iter()
+        .map(|derivation| var::get(derivation.from).key(environment).into_owned())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Explains why the winning source supplied the primary value,
+/// based on its position in the [`Hierarchy`].
+fn why(winner: &dyn VarSource) -> &'static str {
+    match winner.hierarchy() {
+        Hierarchy::Top => {
+            "It was derived from the values of other keys (see above), \
+            which takes precedence over all the sources."
+        }
+        Hierarchy::Override => {
+            "It was set explicitly through an override variable, \
+            which takes precedence over all the sources."
+        }
+        Hierarchy::Stub => {
+            "It is a fixed stand-in for a volatile value (see `--stub-values`), \
+            which takes precedence over all the sources except for overrides."
+        }
+        Hierarchy::EvenHigher => {
+            "The selector chose it out of the values of all the previous sources, \
+            preferring the most valid, then the most confident one."
+        }
+        Hierarchy::Low | Hierarchy::Middle | Hierarchy::High | Hierarchy::Higher => {
+            "Of all the sources that supplied a value, \
+            the one with the highest priority wins."
+        }
+    }
+}
+
+impl Explanations {
+    /// Records what a source came up with for the given key.
+    pub fn add_attempt(&mut self, key: Key, attempt: Attempt) {
+        self.attempts.entry(key).or_default().push(attempt);
+    }
+
+    /// Records the result of validating the final value of the given key.
+    pub fn set_validity(&mut self, key: Key, validity: String) {
+        self.validity.insert(key, validity);
+    }
+
+    /// Records the result of verifying the URL value of the given key,
+    /// see [`crate::verify`].
+    #[cfg(feature = "online")]
+    pub fn set_verification(&mut self, key: Key, verification: String) {
+        self.verification.insert(key, verification);
+    }
+
+    /// Renders a human-readable explanation of where a value for the key
+    /// can come from, and which source actually supplied it, and why.
+    ///
+    /// # Panics
+    ///
+    /// Never; writing to a `String` can not fail.
+    #[must_use]
+    pub fn render(
+        &self,
+        environment: &Environment,
+        sources: &[Box<dyn VarSource>],
+        key: Key,
+    ) -> String {
+        let variable = var::get(key);
+        let mut out = String::new();
+        let required = if environment.settings.required_keys.contains(&key) {
+            "required"
+        } else {
+            "optional"
+        };
+        let name: &str = key.into();
+        writeln!(
+            out,
+            "{} ({name}, {required}):\n    {}",
+            variable.key(environment),
+            variable.description
+        )
+        .unwrap();
+        let derived_from = deriver::derivations(key);
+        if !derived_from.is_empty() {
+            writeln!(
+                out,
+                "  May be derived or converted from: {}",
+                var_keys(environment, derived_from)
+            )
+            .unwrap();
+        }
+
+        writeln!(out, "  Sources, from lowest to highest priority:").unwrap();
+        let attempts = self.attempts.get(&key).map_or(&[][..], Vec::as_slice);
+        for attempt in attempts {
+            let Some(source) = sources.get(attempt.source_index) else {
+                continue;
+            };
+            let result = match (&attempt.value, &attempt.error, attempt.usable) {
+                (_, _, false) => "not usable here".to_owned(),
+                (None, Some(error), true) => format!("failed: {error}"),
+                (None, None, true) => "no value".to_owned(),
+                (Some((confidence, value)), _, true) => {
+                    format!("'{value}' (confidence {confidence})")
+                }
+            };
+            write!(
+                out,
+                "    - {}: {result}",
+                storage::source_name(source.as_ref())
+            )
+            .unwrap();
+            if !attempt.var_reads.is_empty() {
+                write!(out, "; reads: {}", attempt.var_reads.join(", ")).unwrap();
+            }
+            out.push('\n');
+        }
+
+        let winner = attempts
+            .iter()
+            .rev()
+            .find(|attempt| attempt.value.is_some());
+        match (environment.output.get(key), winner) {
+            (Some((confidence, value)), Some(winner)) => {
+                let supplied_by = attempts
+                    .iter()
+                    .filter(|attempt| {
+                        attempt
+                            .value
+                            .as_ref()
+                            .is_some_and(|(_confidence, other_value)| other_value == value)
+                    })
+                    .filter_map(|attempt| sources.get(attempt.source_index))
+                    .map(|source| storage::source_name(source.as_ref()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                writeln!(
+                    out,
+                    "  Result: '{value}' (confidence {confidence}), supplied by: {supplied_by}"
+                )
+                .unwrap();
+                if let Some(winner) = sources.get(winner.source_index) {
+                    writeln!(out, "    {}", why(winner.as_ref())).unwrap();
+                }
+            }
+            (Some(_), None) | (None, _) => {
+                writeln!(out, "  Result: no value").unwrap();
+            }
+        }
+        if let Some(validity) = self.validity.get(&key) {
+            writeln!(out, "  Validation: {validity}").unwrap();
+        }
+        if let Some(verification) = self.verification.get(&key) {
+            writeln!(out, "  Verification: {verification}").unwrap();
+        }
+        out
+    }
+}
@@ -0,0 +1,96 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Renders the graph of which keys the deriver may derive
+//! from which other keys, and with which converter,
+//! see [`crate::sources::deriver::derivations`].
+
+use std::fmt::Write;
+
+use strum::IntoEnumIterator;
+
+use crate::environment::Environment;
+use crate::settings::GraphFormat;
+use crate::sources::deriver::{self, Derivation};
+use crate::var::{self, Key};
+
+/// All the derivations, as (derived key, derivation) pairs,
+/// in the order of the keys and then of preference.
+fn edges() -> Vec<(Key, &'static Derivation)> {
+    Key::iter()
+        .flat_map(|key| {
+            deriver::derivations(key)
+                .iter()
+                .map(move |derivation| (key, derivation))
+        })
+        .collect()
+}
+
+/// Renders the key derivation graph in the given format.
+///
+/// Keys are labeled with their variable keys (e.g. `PROJECT_REPO_WEB_URL`),
+/// and edges with the name of the converter.
+/// Keys that are neither derived nor derived from are left out.
+///
+/// # Panics
+///
+/// Never; writing to a `String` can not fail.
+#[must_use]
+pub fn render(environment: &Environment, format: GraphFormat) -> String {
+    let var_key = |key: Key| var::get(key).key(environment).into_owned();
+    let mut out = String::new();
+    match format {
+        GraphFormat::Dot => {
+            writeln!(out, "digraph {} {{", env!("CARGO_PKG_NAME")).unwrap();
+            writeln!(out, "    rankdir=LR;").unwrap();
+            for (key, derivation) in edges() {
+                writeln!(
+                    out,
+                    "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                    var_key(derivation.from),
+                    var_key(key),
+                    derivation.converter
+                )
+                .unwrap();
+            }
+            writeln!(out, "}}").unwrap();
+        }
+        GraphFormat::Mermaid => {
+            writeln!(out, "flowchart LR").unwrap();
+            for (key, derivation) in edges() {
+                writeln!(
+                    out,
+                    "    {}[\"{}\"] -->|{}| {}[\"{}\"]",
+                    <&str>::from(derivation.from),
+                    var_key(derivation.from),
+                    derivation.converter,
+                    <&str>::from(key),
+                    var_key(key)
+                )
+                .unwrap();
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let environment = Environment::stub();
+        let dot = render(&environment, GraphFormat::Dot);
+        assert!(dot.starts_with("digraph projvar {\n"));
+        assert!(dot.contains(
+            "    \"PROJECT_REPO_WEB_URL\" -> \"PROJECT_REPO_SLUG\" [label=\"web_url_to_slug\"];\n"
+        ));
+        assert!(!dot.contains("PROJECT_BUILD_ARCH\""));
+        let mermaid = render(&environment, GraphFormat::Mermaid);
+        assert!(mermaid.contains(
+            "    RepoSlug[\"PROJECT_REPO_SLUG\"] -->|slug_to_owner| RepoOwner[\"PROJECT_REPO_OWNER\"]\n"
+        ));
+    }
+}
@@ -0,0 +1,273 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Reuses the values retrieved in a previous run
+//! for the sources whose inputs did not change since,
+//! see [`crate::settings::Settings::incremental`].
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::environment::{Environment, Snapshot};
+use crate::sources::{ConfVal, Inputs, VarSource};
+use crate::storage::StorageSnapshot;
+use crate::var::{AnyKey, Key};
+
+/// Keys whose values depend on the time or the machine we run on,
+/// rather than on the project;
+/// these are always retrieved anew.
+pub const ALWAYS_RETRIEVED: &[Key] = &[
+    Key::BuildArch,
+    Key::BuildDate,
+    Key::BuildDateEpoch,
+    Key::BuildHostCpuCount,
+    Key::BuildHostKernelVersion,
+    Key::BuildHostPlatformTriple,
+    Key::BuildOs,
+    Key::BuildOsFamily,
+];
+
+/// The files in the git dir that change when refs (e.g. tags) change.
+const GIT_REFS: &[&str] = &[
+    "packed-refs",
+    "refs/heads",
+    "refs/remotes",
+    "refs/tags",
+    "config",
+];
+
+/// What the inputs of the sources looked like,
+/// except for the input variables, which are part of the [`Snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Fingerprint {
+    /// The sources, sorted as they were run
+    sources: Vec<String>,
+    /// The commit checked out in the repo
+    head: Option<String>,
+    /// Whether the working tree of the repo had uncommitted changes
+    dirty: Option<bool>,
+    /// The modification times of the git refs, see [`GIT_REFS`]
+    refs: BTreeMap<String, SystemTime>,
+    /// The modification times of the entries in the project root
+    files: BTreeMap<String, SystemTime>,
+}
+
+/// What gets persisted between runs.
+#[derive(Serialize, Deserialize)]
+struct State {
+    fingerprint: Fingerprint,
+    snapshot: Snapshot,
+}
+
+/// The modification times of the given entries of `dir`,
+/// or of all its entries, if `names` is `None`.
+/// Entries that do not exist are skipped.
+fn mtimes(dir: &Path, names: Option<&[&str]>) -> BTreeMap<String, SystemTime> {
+    let names: Vec<String> = match names {
+        Some(names) => names.iter().map(|name| (*name).to_owned()).collect(),
+        None => fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .filter_map(|entry| entry.file_name().into_string().ok())
+                    .filter(|name| name != ".git")
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let modified = dir.join(&name).metadata().and_then(|meta| meta.modified());
+            modified.ok().map(|modified| (name, modified))
+        })
+        .collect()
+}
+
+/// The name of `file`, if it is located directly in `dir`.
+fn name_in(dir: &Path, file: &Path) -> Option<String> {
+    let parent = file
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    if parent.canonicalize().ok()? == dir.canonicalize().ok()? {
+        file.file_name()?.to_str().map(ToOwned::to_owned)
+    } else {
+        None
+    }
+}
+
+fn source_names(sources: &[Box<dyn VarSource>]) -> Vec<String> {
+    sources.iter().map(|source| source.display()).collect()
+}
+
+impl Fingerprint {
+    /// Fingerprints the current inputs.
+    /// `state_file` is excluded,
+    /// as it changes with each run.
+    fn new(environment: &Environment, sources: &[Box<dyn VarSource>], state_file: &Path) -> Self {
+        let repo = environment.repo.as_ref();
+        let repo_path = environment.settings.repo_path.as_deref();
+        let mut files = repo_path
+            .map(|repo_path| mtimes(repo_path, None))
+            .unwrap_or_default();
+        if let Some(name) = repo_path.and_then(|repo_path| name_in(repo_path, state_file)) {
+            files.remove(&name);
+        }
+        Self {
+            sources: source_names(sources),
+            head: repo.and_then(|repo| repo.sha().ok().flatten()),
+            dirty: repo.and_then(|repo| repo.is_dirty().ok()),
+            refs: repo
+                .map(|repo| mtimes(repo.inner().path(), Some(GIT_REFS)))
+                .unwrap_or_default(),
+            files,
+        }
+    }
+}
+
+/// The values retrieved in the previous run,
+/// and which of them may be reused.
+pub struct Previous {
+    reusable: HashSet<Inputs>,
+    values: StorageSnapshot,
+}
+
+impl Previous {
+    /// Loads the state of the previous run from `file`,
+    /// and checks which of its inputs are unchanged.
+    /// Returns `None` if there is no usable state,
+    /// e.g. because the settings or the sources changed.
+    pub fn load(
+        file: &Path,
+        environment: &Environment,
+        sources: &[Box<dyn VarSource>],
+    ) -> Option<Self> {
+        if !file.exists() {
+            log::debug!("No previous state found at '{}'", file.display());
+            return None;
+        }
+        let state: State = match File::open(file)
+            .map_err(serde_json::Error::io)
+            .and_then(|reader| serde_json::from_reader(BufReader::new(reader)))
+        {
+            Ok(state) => state,
+            Err(err) => {
+                log::warn!(
+                    "Ignoring the unreadable previous state at '{}': {err}",
+                    file.display()
+                );
+                return None;
+            }
+        };
+        // Settings can not be compared directly, because of the regexes in them
+        let settings_changed = serde_json::to_value(&state.snapshot.settings).ok()
+            != serde_json::to_value(&environment.settings).ok();
+        let dynamic_keys_changed = state
+            .snapshot
+            .dynamic_keys
+            .keys()
+            .map(String::as_str)
+            .collect::<BTreeSet<_>>()
+            != environment
+                .dynamic_keys
+                .iter()
+                .map(|key| key.name)
+                .collect();
+        let current = Fingerprint::new(environment, sources, file);
+        if settings_changed || dynamic_keys_changed || state.fingerprint.sources != current.sources
+        {
+            log::info!("The settings or sources changed; retrieving all values anew");
+            return None;
+        }
+
+        let mut reusable = HashSet::new();
+        if state.fingerprint.files == current.files {
+            reusable.insert(Inputs::Files);
+        }
+        if (
+            &state.fingerprint.head,
+            state.fingerprint.dirty,
+            &state.fingerprint.refs,
+        ) == (&current.head, current.dirty, &current.refs)
+        {
+            reusable.insert(Inputs::Repo);
+        }
+        if state.snapshot.vars == environment.vars {
+            reusable.insert(Inputs::Vars);
+        }
+        log::debug!(
+            "Reusing the values of the previous run from sources depending on: {reusable:?}"
+        );
+        Some(Self {
+            reusable,
+            values: state.snapshot.output,
+        })
+    }
+
+    /// Whether the value `source` retrieved for `key` in the previous run
+    /// may be reused.
+    pub fn get(&self, source: &dyn VarSource, source_index: usize, key: AnyKey) -> Reuse {
+        let reusable = self.reusable.contains(&source.inputs())
+            && !key
+                .builtin()
+                .is_some_and(|key| ALWAYS_RETRIEVED.contains(&key));
+        if reusable {
+            Reuse::Unchanged(
+                self.values
+                    .values
+                    .get(key.name())
+                    .and_then(|values| values.get(&source_index))
+                    .cloned(),
+            )
+        } else {
+            Reuse::Recompute
+        }
+    }
+}
+
+/// Whether the value a source retrieved for a key in the previous run
+/// may be reused, see [`Previous::get`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reuse {
+    /// The inputs of the source did not change,
+    /// so it would retrieve this (possibly missing) value again.
+    Unchanged(Option<ConfVal>),
+    /// The value has to be retrieved anew.
+    Recompute,
+}
+
+/// Stores the state of this run to `file`,
+/// for the next run to reuse.
+/// `retrieved` are the values as they were retrieved from the sources,
+/// before any were discarded during validation.
+///
+/// This should be called at the very end of the run,
+/// so the files written by the sinks count as unchanged inputs in the next one.
+///
+/// # Errors
+///
+/// If writing the file fails.
+pub fn store(
+    file: &Path,
+    environment: &Environment,
+    sources: &[Box<dyn VarSource>],
+    retrieved: StorageSnapshot,
+) -> io::Result<()> {
+    let state = State {
+        fingerprint: Fingerprint::new(environment, sources, file),
+        snapshot: Snapshot {
+            output: retrieved,
+            ..environment.snapshot()
+        },
+    };
+    log::trace!("Storing the state of this run to '{}' ...", file.display());
+    serde_json::to_writer(BufWriter::new(File::create(file)?), &state)?;
+    Ok(())
+}
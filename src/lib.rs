@@ -3,19 +3,31 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 mod cleanup;
+pub mod config;
 mod constants;
 pub mod environment;
+pub mod error;
+mod explain;
+pub mod graph;
+mod incremental;
+#[cfg(feature = "license-detection")]
 mod license;
 pub mod process;
+mod reuse;
 pub mod settings;
 pub mod sinks;
 pub mod sources;
 mod std_error;
-mod storage;
+pub mod storage;
 pub mod tools;
+mod ui;
 pub mod validator;
 pub mod value_conversions;
 pub mod var;
+#[cfg(feature = "online")]
+mod verify;
+
+pub use error::{Error, Result};
 
 use git_version::git_version;
 
@@ -2,11 +2,17 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+#[cfg(not(docsrs))]
+use std::env;
 use std::fmt;
 
 use askalono::{Store, TextData};
-use lazy_static::lazy_static;
-use std::{ffi::OsStr, fs};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::tools::git_attributes;
 
 const LICENSE_FILE_PREFIXES: [&str; 3] = ["LICENSE", "LICENCE", "COPYING"];
 
@@ -85,13 +91,107 @@ pub fn validate_spdx_expr(expr: &str) -> Result<(), Error> {
     Ok(())
 }
 
-pub fn get_licenses(dir: &str) -> Result<Vec<String>, std::io::Error> {
-    lazy_static! {
-        static ref DIR_LICENSES_EXTRACTOR: Detector = Detector::new();
-    }
+/// This enumerates the errors of detecting licenses from license texts.
+#[derive(thiserror::Error, Debug)]
+pub enum DetectionError {
+    /// The store of known license texts could not be loaded.
+    #[error("Failed to load the license texts cache: {0}")]
+    StoreLoad(String),
+
+    /// A directory could not be searched for license files.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
 
-    log::trace!("Fetching licenses from (REUSE-dir) '{}' OUTSIDE ...", dir);
-    DIR_LICENSES_EXTRACTOR.get_licenses(dir)
+/// Returns the SPDX license identifiers found in LICENSE files,
+/// each with the file it was found in,
+/// in the given directory and its sub-directories
+/// down to a depth of `max_depth` (0 meaning only the given directory).
+///
+/// The store of known license texts is only loaded on the first call,
+/// so runs that never detect licenses do not pay for it.
+///
+/// # Errors
+///
+/// If the store of known license texts could not be loaded,
+/// or a directory could not be read.
+pub fn get_licenses(
+    dir: &Path,
+    max_depth: usize,
+) -> Result<Vec<(String, PathBuf)>, DetectionError> {
+    static DIR_LICENSES_EXTRACTOR: OnceLock<Result<Detector, String>> = OnceLock::new();
+
+    log::trace!(
+        "Fetching licenses from (REUSE-dir) '{}' OUTSIDE ...",
+        dir.display()
+    );
+    let detector = DIR_LICENSES_EXTRACTOR
+        .get_or_init(Detector::load)
+        .as_ref()
+        .map_err(|err| DetectionError::StoreLoad(err.clone()))?;
+    Ok(detector.get_licenses(dir, max_depth)?)
+}
+
+/// Returns the location of the decompressed license texts store,
+/// which is "$XDG_CACHE_HOME/projvar/licenses-cache-<version>-<size>.msgpack",
+/// falling back to "$HOME/.cache/projvar/...".
+/// The version of projvar and the size of the embedded store
+/// make sure it is not reused after an update.
+#[cfg(not(docsrs))]
+fn cache_file() -> Option<PathBuf> {
+    env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .map(|cache_dir| {
+            cache_dir.join(env!("CARGO_PKG_NAME")).join(format!(
+                "licenses-cache-{}-{}.msgpack",
+                env!("CARGO_PKG_VERSION"),
+                CACHE_DATA.len()
+            ))
+        })
+}
+
+/// Reads the decompressed license texts store,
+/// if it exists and is valid.
+#[cfg(not(docsrs))]
+fn read_cache(path: &Path) -> Option<Store> {
+    let file = fs::File::open(path).ok()?;
+    rmp_serde::decode::from_read(io::BufReader::new(file))
+        .inspect_err(|err| {
+            log::debug!(
+                "Ignoring the invalid license texts cache '{}': {err}",
+                path.display()
+            );
+        })
+        .ok()
+}
+
+/// Writes the decompressed license texts store,
+/// so the next run can skip decompressing the embedded one.
+/// Failing to do so is not an error,
+/// as it only makes the next run slower.
+#[cfg(not(docsrs))]
+fn write_cache(path: &Path, store: &Store) {
+    let write = || -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // NOTE We write to a temporary file first,
+        //      so concurrent runs never see a partially written cache.
+        let tmp_path = path.with_extension(format!("tmp-{}", std::process::id()));
+        let mut writer = io::BufWriter::new(fs::File::create(&tmp_path)?);
+        rmp_serde::encode::write(&mut writer, store).map_err(io::Error::other)?;
+        writer.flush()?;
+        fs::rename(&tmp_path, path)
+    };
+    match write() {
+        Ok(()) => log::debug!("Wrote the license texts cache '{}'", path.display()),
+        Err(err) => log::debug!(
+            "Failed to write the license texts cache '{}': {err}",
+            path.display()
+        ),
+    }
 }
 
 /// A basic wrapper around the askalono library;
@@ -103,59 +203,58 @@ struct Detector {
 }
 
 impl Detector {
+    /// Loads the store of known license texts,
+    /// preferably from the decompressed on-disk cache (see [`cache_file`]),
+    /// falling back to the compressed one embedded into the binary,
+    /// which is then used to create the on-disk cache.
     #[cfg(not(docsrs))]
-    pub fn new() -> Self {
-        match Store::from_cache(CACHE_DATA) {
-            Ok(store) => Self { store },
-            Err(err) => {
-                log::error!("Failed to load licenses info cache: {err}");
-                panic!("Failed to load licenses info cache: {err}");
-            }
+    fn load() -> Result<Self, String> {
+        let cache_file = cache_file();
+        if let Some(store) = cache_file.as_deref().and_then(read_cache) {
+            return Ok(Self { store });
         }
+        let store = Store::from_cache(CACHE_DATA).map_err(|err| err.to_string())?;
+        if let Some(cache_file) = &cache_file {
+            write_cache(cache_file, &store);
+        }
+        Ok(Self { store })
     }
     #[cfg(docsrs)]
-    pub fn new() -> Self {
-        // This will never be called; if it will anyway,
-        // the dev of this code did something wrong
-        log::error!("No licenses cache available if `cfg(docsrs)` is set");
-        panic!("No licenses cache available if `cfg(docsrs)` is set");
+    fn load() -> Result<Self, String> {
+        Err("No licenses cache available if `cfg(docsrs)` is set".to_owned())
     }
 
-    /// Returns a list of SPDX license identifiers;
-    /// one for each LICENSE file found in the given directory.
-    pub fn get_licenses(&self, dir: &str) -> Result<Vec<String>, std::io::Error> {
-        fn is_license_file<S: AsRef<str>>(file_name: S) -> bool {
+    /// Returns a list of SPDX license identifiers,
+    /// each with the LICENSE file it was found in,
+    /// searching the given directory and its sub-directories
+    /// down to a depth of `max_depth`,
+    /// and leaving out the files excluded by their git attributes
+    /// (e.g. vendored ones, see [`git_attributes::Attributes::is_excluded`]).
+    pub fn get_licenses(
+        &self,
+        dir: &Path,
+        max_depth: usize,
+    ) -> Result<Vec<(String, PathBuf)>, std::io::Error> {
+        fn is_license_file(file_name: &str) -> bool {
             LICENSE_FILE_PREFIXES
                 .iter()
-                .any(|&lf_prefix| file_name.as_ref().starts_with(lf_prefix))
+                .any(|&lf_prefix| file_name.starts_with(lf_prefix))
         }
 
-        log::trace!("Looking for license files in '{}' ...", dir);
-        let mut output = fs::read_dir(dir)?
-            .filter_map(std::result::Result::ok)
-            .map(|entry| entry.path())
-            .filter(|entry| {
-                entry.is_file()
-                    && entry
-                        .file_name()
-                        .map(OsStr::to_string_lossy)
-                        .is_some_and(is_license_file)
-            })
+        log::trace!("Looking for license files in '{}' ...", dir.display());
+        let output = git_attributes::find_files(dir, max_depth, is_license_file)?
+            .into_iter()
             .filter_map(|entry| {
                 let contents = fs::read_to_string(entry.as_path()).unwrap_or_default(); // TODO Not too clean; we should possibly fail the function instead of silently skipping the file on error
-                let evaluated_license_opt = self.analyze(&contents);
-                if let Some(evaluated_license) = &evaluated_license_opt {
-                    log::trace!(
-                        "Found (non-REUSE) license {evaluated_license} in file {}.",
-                        entry.display()
-                    );
-                }
-                evaluated_license_opt
+                let evaluated_license = self.analyze(&contents)?;
+                log::trace!(
+                    "Found (non-REUSE) license {evaluated_license} in file {}.",
+                    entry.display()
+                );
+                Some((evaluated_license, entry))
             })
             .collect::<Vec<_>>();
 
-        output.sort();
-        output.dedup();
         log::trace!("Fetching licenses - found {}.", output.len());
         Ok(output)
     }
@@ -2,9 +2,13 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::io;
+use std::cell::Cell;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 
-use crate::settings::Verbosity;
+use crate::settings::{LogFormat, Verbosity};
 use cli_utils::BoxResult;
 use tracing::metadata::LevelFilter;
 use tracing_subscriber::{
@@ -25,34 +29,179 @@ const fn verbosity_to_level(verbosity: Verbosity) -> LevelFilter {
     }
 }
 
+/// The (optional) log file.
+/// It is only known after parsing the CLI arguments,
+/// while logging is set up before that,
+/// so it starts out as `None`,
+/// in which case everything written to it is discarded.
+#[derive(Clone, Default)]
+struct LogFile(Arc<Mutex<Option<File>>>);
+
+impl Write for LogFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.0.lock() {
+            Ok(mut file) => match file.as_mut() {
+                Some(file) => file.write(buf),
+                None => Ok(buf.len()),
+            },
+            Err(_poisoned) => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.0.lock() {
+            Ok(mut file) => file.as_mut().map_or(Ok(()), Write::flush),
+            Err(_poisoned) => Ok(()),
+        }
+    }
+}
+
+/// Allows to change the logging setup after its initialization.
+///
+/// There is one layer for each combination of target (stderr, file)
+/// and format (text, JSON), each with its own level filter;
+/// the filters of the layers with the format not in use are kept "off".
+pub struct Logger {
+    stderr_text_filter: Handle<LevelFilter, Registry>,
+    stderr_json_filter: Handle<LevelFilter, Registry>,
+    file_text_filter: Handle<LevelFilter, Registry>,
+    file_json_filter: Handle<LevelFilter, Registry>,
+    stderr_level: Cell<LevelFilter>,
+    file_level: Cell<LevelFilter>,
+    format: Cell<LogFormat>,
+    file: LogFile,
+}
+
 /// Sets up logging, with a way to change the log level later on,
 /// and with all output going to stderr,
-/// as suggested by <https://clig.dev/>.
+/// as suggested by <https://clig.dev/>,
+/// plus optionally to a log file with its own log level,
+/// see [`Logger::set_log_file`].
 ///
 /// # Errors
 ///
 /// If initializing the registry (logger) failed.
-pub fn setup_logging() -> BoxResult<Handle<LevelFilter, Registry>> {
+pub fn setup_logging() -> BoxResult<Logger> {
     // NOTE It is crucial to first set the lowest log level,
     //      as apparently, any level that is lower then this one
     //      will be ignored when trying to set it later on.
     //      Later though, the level can be changed up and down as desired.
-    let level_filter = LevelFilter::TRACE;
-    let (filter, reload_handle_filter) = reload::Layer::new(level_filter);
+    let (stderr_text_filter, stderr_text_filter_handle) = reload::Layer::new(LevelFilter::TRACE);
+    let (stderr_json_filter, stderr_json_filter_handle) = reload::Layer::new(LevelFilter::OFF);
+    // The log file is disabled until it is set
+    let (file_text_filter, file_text_filter_handle) = reload::Layer::new(LevelFilter::OFF);
+    let (file_json_filter, file_json_filter_handle) = reload::Layer::new(LevelFilter::OFF);
+    let file = LogFile::default();
 
-    let l_stderr = fmt::layer().map_writer(move |_| io::stderr);
+    let l_stderr_text = fmt::layer()
+        .map_writer(move |_| io::stderr)
+        .with_filter(stderr_text_filter);
+    let l_stderr_json = fmt::layer()
+        .json()
+        .flatten_event(true)
+        .with_current_span(true)
+        .with_span_list(false)
+        .map_writer(move |_| io::stderr)
+        .with_filter(stderr_json_filter);
+    let file_writer = file.clone();
+    let l_file_text = fmt::layer()
+        .with_ansi(false)
+        .with_writer(move || file_writer.clone())
+        .with_filter(file_text_filter);
+    let file_writer = file.clone();
+    let l_file_json = fmt::layer()
+        .json()
+        .flatten_event(true)
+        .with_current_span(true)
+        .with_span_list(false)
+        .with_writer(move || file_writer.clone())
+        .with_filter(file_json_filter);
 
-    let registry = tracing_subscriber::registry().with(filter).with(l_stderr);
+    // NOTE Combined with `and_then`, the level hints of the filtered layers
+    //      get lost, which silences all logging; a `Vec` keeps them.
+    let registry = tracing_subscriber::registry().with(vec![
+        l_stderr_text.boxed(),
+        l_stderr_json.boxed(),
+        l_file_text.boxed(),
+        l_file_json.boxed(),
+    ]);
     registry.try_init()?;
 
-    Ok(reload_handle_filter)
+    Ok(Logger {
+        stderr_text_filter: stderr_text_filter_handle,
+        stderr_json_filter: stderr_json_filter_handle,
+        file_text_filter: file_text_filter_handle,
+        file_json_filter: file_json_filter_handle,
+        stderr_level: Cell::new(LevelFilter::TRACE),
+        file_level: Cell::new(LevelFilter::OFF),
+        format: Cell::new(LogFormat::Text),
+        file,
+    })
 }
 
-pub fn set_log_level(
-    reload_handle: &Handle<LevelFilter, Registry>,
-    verbosity: Verbosity,
-) -> BoxResult<()> {
-    let level_filter = verbosity_to_level(verbosity);
-    reload_handle.modify(|filter| *filter = level_filter)?;
-    Ok(())
+impl Logger {
+    /// Applies the current levels and format to the layer filters.
+    fn apply(&self) -> BoxResult<()> {
+        let (text_active, json_active) = match self.format.get() {
+            LogFormat::Text => (true, false),
+            LogFormat::Json => (false, true),
+        };
+        let level_if = |active: bool, level: LevelFilter| {
+            if active {
+                level
+            } else {
+                LevelFilter::OFF
+            }
+        };
+        let stderr_level = self.stderr_level.get();
+        let file_level = self.file_level.get();
+        self.stderr_text_filter
+            .modify(|filter| *filter = level_if(text_active, stderr_level))?;
+        self.stderr_json_filter
+            .modify(|filter| *filter = level_if(json_active, stderr_level))?;
+        self.file_text_filter
+            .modify(|filter| *filter = level_if(text_active, file_level))?;
+        self.file_json_filter
+            .modify(|filter| *filter = level_if(json_active, file_level))?;
+        Ok(())
+    }
+
+    /// Sets the log level for the output to stderr.
+    ///
+    /// # Errors
+    ///
+    /// If the logging system is gone already.
+    pub fn set_log_level(&self, verbosity: Verbosity) -> BoxResult<()> {
+        self.stderr_level.set(verbosity_to_level(verbosity));
+        self.apply()
+    }
+
+    /// Sets the format of the log output,
+    /// both to stderr and to the log file.
+    ///
+    /// # Errors
+    ///
+    /// If the logging system is gone already.
+    pub fn set_log_format(&self, format: LogFormat) -> BoxResult<()> {
+        self.format.set(format);
+        self.apply()
+    }
+
+    /// Additionally logs to the given file, with its own log level,
+    /// which is independent of the one for stderr.
+    /// The file is truncated if it exists already.
+    ///
+    /// # Errors
+    ///
+    /// If the file could not be created,
+    /// or the logging system is gone already.
+    pub fn set_log_file(&self, path: &Path, verbosity: Verbosity) -> BoxResult<()> {
+        let file = File::create(path)?;
+        match self.file.0.lock() {
+            Ok(mut log_file) => *log_file = Some(file),
+            Err(_poisoned) => return Err("The log file lock is poisoned".into()),
+        }
+        self.file_level.set(verbosity_to_level(verbosity));
+        self.apply()
+    }
 }
@@ -10,32 +10,48 @@ extern crate remain;
 extern crate url;
 
 use clap::builder::ValueParser;
+use clap::parser::ValueSource;
 use clap::{command, value_parser, Arg, ArgAction, ArgMatches, Command, ValueHint};
 use cli_utils::BoxResult;
 use const_format::formatcp;
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
 use strum::IntoEnumIterator;
 
 mod cleanup;
+mod config;
 mod constants;
 mod environment;
+mod error;
+mod explain;
+mod graph;
+mod incremental;
+#[cfg(feature = "license-detection")]
 mod license;
 mod logger;
 mod process;
+mod reuse;
 pub mod settings;
 pub mod sinks;
 pub mod sources;
 mod std_error;
 mod storage;
 pub mod tools;
+mod ui;
 mod validator;
 mod value_conversions;
 mod var;
+#[cfg(feature = "online")]
+mod verify;
 
+use crate::config::{Config, Profile};
 use crate::environment::Environment;
+use crate::error::ExitCode;
 use crate::settings::{Settings, Verbosity};
 use crate::sinks::VarSink;
 use crate::tools::git_hosting_provs::{self, HostingType};
@@ -50,22 +66,38 @@ const A_S_VARIABLE: char = 'D';
 const A_L_VARIABLE: &str = "variable";
 const A_S_VARIABLES_FILE: char = 'I';
 const A_L_VARIABLES_FILE: &str = "variables-file";
+const A_L_INTERPOLATE: &str = "interpolate";
 const A_S_NO_ENV_IN: char = 'x';
 const A_L_NO_ENV_IN: &str = "no-env-in";
+const A_L_ENV_ALLOW: &str = "env-allow";
+const A_L_ENV_DENY: &str = "env-deny";
 const A_S_ENV_OUT: char = 'e';
 const A_L_ENV_OUT: &str = "env-out";
+const A_L_EXPORT_SCRIPT: &str = "export-script";
+const A_L_DIRENV: &str = "direnv";
 const A_S_FILE_OUT: char = 'O';
 const A_L_FILE_OUT: &str = "file-out";
+// const A_S_OCI_LABELS: char = '?';
+const A_L_OCI_LABELS: &str = "oci-labels";
 const A_S_HOSTING_TYPE: char = 't';
 const A_L_HOSTING_TYPE: &str = "hosting-type";
+const A_L_HOSTING_DOMAIN: &str = "hosting-domain";
+const A_L_HOSTING_BASE_PATH: &str = "hosting-base-path";
 const A_S_VERBOSE: char = 'v';
 const A_L_VERBOSE: &str = "verbose";
 const A_S_LOG_LEVEL: char = 'F';
 const A_L_LOG_LEVEL: &str = "log-level";
+// const A_S_LOG_FORMAT: char = '?';
+const A_L_LOG_FORMAT: &str = "log-format";
+// const A_S_LOG_FILE: char = '?';
+const A_L_LOG_FILE: &str = "log-file";
+// const A_S_LOG_FILE_LEVEL: char = '?';
+const A_L_LOG_FILE_LEVEL: &str = "log-file-level";
 const A_S_QUIET: char = 'q';
 const A_L_QUIET: &str = "quiet";
 const A_S_FAIL_ON_MISSING_VALUE: char = 'f';
 const A_L_FAIL_ON_MISSING_VALUE: &str = "fail";
+const A_L_STRICT: &str = "strict";
 const A_S_REQUIRE_NONE: char = 'n';
 const A_L_REQUIRE_NONE: &str = "none";
 const A_S_REQUIRE_ALL: char = 'a';
@@ -78,18 +110,54 @@ const A_L_REQUIRE_NOT: &str = "require-not";
 const A_L_ONLY_REQUIRED: &str = "only-required";
 // const A_S_KEY_PREFIX: char = '?';
 const A_L_KEY_PREFIX: &str = "key-prefix";
+const A_L_KEY_CASE: &str = "key-case";
 const A_S_DRY: char = 'd';
 const A_L_DRY: &str = "dry";
+const A_L_DRY_OUT: &str = "dry-out";
 const A_S_OVERWRITE: char = 'o';
 const A_L_OVERWRITE: &str = "overwrite";
+const A_L_MERGE: &str = "merge";
+const A_L_MULTILINE_STYLE: &str = "multiline-style";
+const A_L_MAX_LENGTH: &str = "max-length";
 const A_S_LIST: char = 'l';
 const A_L_LIST: &str = "list";
+const A_L_GRAPH: &str = "graph";
+const A_L_EXPLAIN: &str = "explain";
 const A_S_DATE_FORMAT: char = 'T';
 const A_L_DATE_FORMAT: &str = "date-format";
+// const A_S_DATE_TIMEZONE: char = '?';
+const A_L_BUILD_DATE_FORMAT: &str = "build-date-format";
+const A_L_VERSION_DATE_FORMAT: &str = "version-date-format";
+const A_L_DATE_TIMEZONE: &str = "date-timezone";
 const A_S_SHOW_ALL_RETRIEVED: char = 'A';
 const A_L_SHOW_ALL_RETRIEVED: &str = "show-all-retrieved";
 const A_S_SHOW_PRIMARY_RETRIEVED: char = 'P';
 const A_L_SHOW_PRIMARY_RETRIEVED: &str = "show-primary-retrieved";
+const A_L_RETRIEVED_FORMAT: &str = "retrieved-format";
+// const A_S_CONFIG: char = '?';
+const A_L_CONFIG: &str = "config";
+// const A_S_PROFILE: char = '?';
+const A_L_PROFILE: &str = "profile";
+// const A_S_ARCHIVE: char = '?';
+const A_L_ARCHIVE: &str = "archive";
+const A_L_PARENT_PROJECT: &str = "parent-project";
+const A_L_ONLINE: &str = "online";
+const A_L_OFFLINE: &str = "offline";
+const A_L_NETWORK_TIMEOUT: &str = "network-timeout";
+const A_L_NETWORK_RETRIES: &str = "network-retries";
+const A_L_CA_BUNDLE: &str = "ca-bundle";
+const A_L_CREDENTIALS_FILE: &str = "credentials-file";
+const A_L_STRICT_CONSISTENCY: &str = "strict-consistency";
+const A_L_KEEP_GOING: &str = "keep-going";
+const A_L_SOURCE_TIMEOUT: &str = "source-timeout";
+const A_L_VERIFY_URLS: &str = "verify-urls";
+const A_L_VERSION_TAG_PATTERN: &str = "version-tag-pattern";
+const A_L_VERSION_STRIP_PREFIX: &str = "version-strip-prefix";
+const A_L_LICENSE_SCAN_DEPTH: &str = "license-scan-depth";
+const A_L_STUB_VALUES: &str = "stub-values";
+const A_L_SOURCE_DATE_EPOCH_OUT: &str = "source-date-epoch-out";
+const A_L_PLAIN_MACHINE_NAMES: &str = "plain-machine-names";
+const A_L_INCREMENTAL: &str = "incremental";
 
 fn arg_version() -> Arg {
     Arg::new(A_L_VERSION)
@@ -168,6 +236,25 @@ fn arg_variables_file() -> Arg {
         .default_missing_value("-")
 }
 
+fn arg_interpolate() -> Arg {
+    Arg::new(A_L_INTERPOLATE)
+        .help("Resolve ${VAR} references in the values of input variables")
+        .long_help(formatcp!(
+            "Resolve ${{VAR}} references in the values supplied with \
+            -{A_S_VARIABLE},--{A_L_VARIABLE} and -{A_S_VARIABLES_FILE},--{A_L_VARIABLES_FILE}, \
+            against the variables collected before \
+            (from the environment, earlier variables files and earlier -{A_S_VARIABLE}) \
+            and those in the same file. \
+            A reference to the variable itself resolves to its previous value \
+            (e.g. PATH=${{PATH}}:/my/bin). \
+            Use $$ for a literal $. \
+            Unknown references are an error.",
+        ))
+        .action(ArgAction::SetTrue)
+        .long(A_L_INTERPOLATE)
+        .required(false)
+}
+
 fn arg_no_env_in() -> Arg {
     Arg::new(A_L_NO_ENV_IN)
         .help("Do not read environment variables")
@@ -178,23 +265,118 @@ fn arg_no_env_in() -> Arg {
         .required(false)
 }
 
+fn arg_env_allow() -> Arg {
+    Arg::new(A_L_ENV_ALLOW)
+        .help("Only read environment variables with names matching this pattern")
+        .long_help(formatcp!(
+            "Only read environment variables with names matching this pattern. \
+            It is a simple glob, where '*' matches any number of characters \
+            and '?' matches exactly one (e.g. 'CI_*'), \
+            or a regex, if prefixed with '{}' (e.g. 'regex:(CI|GITHUB)_.*'). \
+            If given multiple times, a name has to match any of them. \
+            This does not affect the variables supplied with -{A_S_VARIABLE},--{A_L_VARIABLE} \
+            or -{A_S_VARIABLES_FILE},--{A_L_VARIABLES_FILE}. \
+            See --{A_L_ENV_DENY}.",
+            var::VAR_NAME_PATTERN_PREFIX_REGEX,
+        ))
+        .num_args(1)
+        .value_parser(var::parse_var_name_pattern)
+        .value_name("PATTERN")
+        .value_hint(ValueHint::Other)
+        .long(A_L_ENV_ALLOW)
+        .action(ArgAction::Append)
+        .required(false)
+}
+
+fn arg_env_deny() -> Arg {
+    Arg::new(A_L_ENV_DENY)
+        .help("Do not read environment variables with names matching this pattern")
+        .long_help(formatcp!(
+            "Do not read environment variables with names matching this pattern, \
+            e.g. to keep secrets out of the evaluation ('*_TOKEN'). \
+            The pattern is the same as for --{A_L_ENV_ALLOW}, \
+            and this takes precedence over it. \
+            May be given multiple times.",
+        ))
+        .num_args(1)
+        .value_parser(var::parse_var_name_pattern)
+        .value_name("PATTERN")
+        .value_hint(ValueHint::Other)
+        .long(A_L_ENV_DENY)
+        .action(ArgAction::Append)
+        .required(false)
+}
+
 fn arg_env_out() -> Arg {
     Arg::new(A_L_ENV_OUT)
-        .help("Write resulting values directy into the environment") // TODO Check: is that even possible? As in, the values remaining in the environment after the ned of the process?
+        .help("Write resulting values into the environment of this process")
+        .long_help(formatcp!(
+            "Write resulting values into the environment of this process. \
+            As a process can not modify the environment of its parent, \
+            the values are gone once this tool exits; \
+            this is only useful when using this crate as a library. \
+            To get the values into the environment of your shell, \
+            use --{A_L_EXPORT_SCRIPT}.",
+        ))
         .action(ArgAction::SetTrue)
         .short(A_S_ENV_OUT)
         .long(A_L_ENV_OUT)
         .required(false)
 }
 
+fn arg_export_script() -> Arg {
+    Arg::new(A_L_EXPORT_SCRIPT)
+        .help("Write resulting values to stdout, as a shell script to be eval'ed")
+        .long_help(formatcp!(
+            "Write resulting values to stdout, \
+            as a POSIX shell script of \"export KEY='VALUE'\" lines. \
+            This is the way to get the values into the environment of your shell: \
+            eval \"$(projvar --{A_L_EXPORT_SCRIPT})\" \
+            All logging goes to stderr, so it does not interfere. \
+            Unless --{A_L_FILE_OUT} is given too, \
+            no file is written when using this.",
+        ))
+        .action(ArgAction::SetTrue)
+        .long(A_L_EXPORT_SCRIPT)
+        .required(false)
+        .conflicts_with(A_L_DIRENV)
+}
+
+fn arg_direnv() -> Arg {
+    Arg::new(A_L_DIRENV)
+        .help("Write resulting values to stdout, as a snippet for a direnv .envrc")
+        .long_help(formatcp!(
+            "Write resulting values to stdout, \
+            as a snippet for a direnv (https://direnv.net) .envrc file. \
+            This is the same as --{A_L_EXPORT_SCRIPT}, \
+            but preceded by \"watch_file\" lines for the files \
+            whose change likely changes the values \
+            (e.g. the git HEAD and index, package manifests and the config file), \
+            so direnv re-evaluates them when required. \
+            Use it in your .envrc like this: \
+            eval \"$(projvar --{A_L_DIRENV})\"",
+        ))
+        .action(ArgAction::SetTrue)
+        .long(A_L_DIRENV)
+        .required(false)
+}
+
 fn arg_out_file() -> Arg {
     Arg::new(A_L_FILE_OUT)
-        .help("Write variables into this file; .env or .json")
+        .help("Write variables into this file; .env, .json, .toml, .nix or .scm")
         .long_help(
             "Write evaluated values into a file. \
-            Two file formats are supported: \
+            These file formats are supported: \
             * ENV: one KEY=VALUE pair per line (BASH syntax) \
             * JSON: a dictionary of KEY: \"value\" \
+            * TOML: KEY = \"value\" pairs in the [env] table, \
+            as used by mise (https://mise.jdx.dev); \
+            the rest of the file is preserved \
+            * Nix: an attribute set for packaging, with the values under vars \
+            * Scheme: definitions for a Guix package, with the values under projvar-vars \
+            * OKH: the name, version, license and repo fields \
+            of an Open Know-How manifest (okh.toml or *.okh.toml); \
+            the rest of the file is preserved \
             You can choose which format is used by the file-extension.
             Note that \"-\" has no special meaning here; \
             it does not mean stdout, but rather the file \"./-\".",
@@ -210,6 +392,31 @@ fn arg_out_file() -> Arg {
         .required(false)
 }
 
+fn arg_oci_labels() -> Arg {
+    Arg::new(A_L_OCI_LABELS)
+        .help("Write OCI image labels into this file; \"-\" for stdout")
+        .long_help(
+            "Write evaluated values as OCI container image labels \
+            (org.opencontainers.image.*: created, licenses, revision, source, title, url, version) \
+            into a file. \
+            If the file name contains \"Dockerfile\" or \"Containerfile\" \
+            (e.g. \"labels.Dockerfile\"), \
+            a LABEL instruction is written, to be included into a Dockerfile; \
+            otherwise, one \"--label=KEY=VALUE\" argument per line, \
+            to be passed to `docker build`. \
+            If FILE is omitted or \"-\", the arguments are written to stdout. \
+            This does not replace the other outputs (see --file-out).",
+        )
+        .num_args(0..=1)
+        .value_parser(value_parser!(std::path::PathBuf))
+        .value_name("FILE")
+        .value_hint(ValueHint::FilePath)
+        .long(A_L_OCI_LABELS)
+        .action(ArgAction::Append)
+        .default_missing_value("-")
+        .required(false)
+}
+
 fn arg_hosting_type() -> Arg {
     Arg::new(A_L_HOSTING_TYPE)
         .help("Overrides the hosting type of the primary remote")
@@ -229,6 +436,47 @@ fn arg_hosting_type() -> Arg {
         .required(false)
 }
 
+fn arg_hosting_domain() -> Arg {
+    Arg::new(A_L_HOSTING_DOMAIN)
+        .help("Maps a custom (self-hosted) domain to a hosting type")
+        .long_help(formatcp!(
+            "Maps a custom domain to the hosting software it runs, \
+            e.g. 'git.company.com=gitlab'. \
+            The domain may also be an IP, and may include a port, \
+            e.g. '192.168.1.10:3000=gitea'; \
+            a mapping with a port takes precedence over one without. \
+            The possible types are the same as for --{A_L_HOSTING_TYPE}, \
+            which - if given - overrides these mappings. \
+            A domain starting with '.' matches all its sub-domains. \
+            These mappings may also be set in the config file (see --{A_L_CONFIG}).",
+        ))
+        .num_args(1)
+        .value_parser(settings::parse_hosting_domain)
+        .value_name("DOMAIN=TYPE")
+        .value_hint(ValueHint::Other)
+        .long(A_L_HOSTING_DOMAIN)
+        .action(ArgAction::Append)
+        .required(false)
+}
+
+fn arg_hosting_base_path() -> Arg {
+    Arg::new(A_L_HOSTING_BASE_PATH)
+        .help("The path under which a self-hosted instance is served")
+        .long_help(
+            "The path under which a self-hosted instance is served, \
+            e.g. 'gitlab' for a GitLab instance with the relative URL root '/gitlab', \
+            which serves projects under 'https://example.com/gitlab/group/project'. \
+            It is part of web and HTTP(S) clone URLs, but not of SSH clone URLs, \
+            and it is ignored for the well-known public hosting sites.",
+        )
+        .num_args(1)
+        .value_name("PATH")
+        .value_hint(ValueHint::Other)
+        .long(A_L_HOSTING_BASE_PATH)
+        .action(ArgAction::Set)
+        .required(false)
+}
+
 fn arg_verbose() -> Arg {
     Arg::new(A_L_VERBOSE)
         .help("More verbose log output")
@@ -254,6 +502,60 @@ fn arg_log_level() -> Arg {
         .conflicts_with(A_L_QUIET)
 }
 
+fn arg_log_format() -> Arg {
+    Arg::new(A_L_LOG_FORMAT)
+        .help("Set the format of the log output")
+        .long_help(
+            "Set the format of the log output, both to stderr and to the log-file. \
+            'json' emits one JSON object per line, \
+            containing the level, target and message, \
+            and while evaluating, also the key and source concerned, \
+            which makes it easy to process by CI log processors.",
+        )
+        .value_parser(value_parser!(settings::LogFormat))
+        .value_name("FORMAT")
+        .long(A_L_LOG_FORMAT)
+        .action(ArgAction::Set)
+        .default_value("text")
+        .required(false)
+}
+
+fn arg_log_file() -> Arg {
+    Arg::new(A_L_LOG_FILE)
+        .help("Additionally write the log to this file")
+        .long_help(formatcp!(
+            "Additionally write the log to this file, \
+            which is truncated if it exists already. \
+            It has its own log level, \
+            see --{A_L_LOG_FILE_LEVEL}, \
+            and is not affected by -{A_S_QUIET},--{A_L_QUIET}. \
+            This is useful for archiving a full trace in CI, \
+            without polluting the job output.",
+        ))
+        .num_args(1)
+        .value_parser(value_parser!(std::path::PathBuf))
+        .value_name("FILE")
+        .value_hint(ValueHint::FilePath)
+        .long(A_L_LOG_FILE)
+        .action(ArgAction::Set)
+        .required(false)
+}
+
+fn arg_log_file_level() -> Arg {
+    Arg::new(A_L_LOG_FILE_LEVEL)
+        .help("Set the log-level for the log-file")
+        .long_help(formatcp!(
+            "Set the log-level for the log-file (see --{A_L_LOG_FILE}), \
+            independently of the one for stderr.",
+        ))
+        .value_parser(value_parser!(settings::Verbosity))
+        .long(A_L_LOG_FILE_LEVEL)
+        .action(ArgAction::Set)
+        .default_value("trace")
+        .required(false)
+        .requires(A_L_LOG_FILE)
+}
+
 fn arg_quiet() -> Arg {
     Arg::new(A_L_QUIET)
         .help("Minimize or suppress output to stdout")
@@ -283,6 +585,21 @@ fn arg_fail() -> Arg {
         .required(false)
 }
 
+fn arg_strict() -> Arg {
+    Arg::new(A_L_STRICT)
+        .help("Fail if a value is only suboptimal")
+        .long_help(formatcp!(
+            "Fail if any of the evaluated values is only suboptimal, \
+            for example a URL that works, but is not in its canonical form. \
+            Without this, such values are used as they are, with a warning. \
+            Invalid values always make us fail. \
+            See --{A_L_FAIL_ON_MISSING_VALUE}, --{A_L_STRICT_CONSISTENCY}.",
+        ))
+        .action(ArgAction::SetTrue)
+        .long(A_L_STRICT)
+        .required(false)
+}
+
 fn arg_require_all() -> Arg {
     Arg::new(A_L_REQUIRE_ALL)
         .help("Marks all properties as required")
@@ -323,12 +640,15 @@ fn arg_require() -> Arg {
             You may use the property name (e.g. "Name") \
             or the variable key (e.g. "PROJECT_NAME"); \
             See --{A_L_LIST} for all possible keys. \
+            Simple glob patterns are supported too, \
+            where '*' matches any number of characters \
+            and '?' matches exactly one (e.g. "REPO_*" or "Build*"). \
             If at least one such option is present, \
             the default required values list is cleared. \
             See --{A_L_FAIL_ON_MISSING_VALUE}, --{A_L_REQUIRE_ALL}, --{A_L_REQUIRE_NONE}, --{A_L_REQUIRE_NOT}."#,
         ))
         .num_args(1)
-        .value_parser(clap::builder::NonEmptyStringValueParser::new()) // TODO Maybe parse into Key directly here already?
+        .value_parser(var::parse_key_id)
         .value_name("KEY")
         .value_hint(ValueHint::Other)
         .short(A_S_REQUIRE)
@@ -346,12 +666,15 @@ fn arg_require_not() -> Arg {
         .long_help(formatcp!(
             "A key of a variable whose value is *not* required. \
             For example PROJECT_NAME (see --{A_L_LIST} for all possible keys). \
+            Simple glob patterns are supported too, \
+            where '*' matches any number of characters \
+            and '?' matches exactly one (e.g. \"REPO_*\" or \"Build*\"). \
             Can be used either on the base of the default requried list \
             or all. \
             See --{A_L_FAIL_ON_MISSING_VALUE}, --{A_L_REQUIRE_ALL}, --{A_L_REQUIRE_NONE}, --{A_L_REQUIRE}.",
         ))
         .num_args(1)
-        .value_parser(clap::builder::NonEmptyStringValueParser::new()) // TODO Maybe parse into Key directly here already?
+        .value_parser(var::parse_key_id)
         .value_name("KEY")
         .value_hint(ValueHint::Other)
         .short(A_S_REQUIRE_NOT)
@@ -394,16 +717,57 @@ fn arg_key_prefix() -> Arg {
         .required(false)
 }
 
+fn arg_key_case() -> Arg {
+    Arg::new(A_L_KEY_CASE)
+        .help("The case of the keys in the output")
+        .long_help(formatcp!(
+            "The case of the variable keys when writing out values in the sinks, \
+            applied after the prefix (see --{A_L_KEY_PREFIX}). \
+            For example \"kebab\" -> \"project-version\", \"project-name\", ...",
+        ))
+        .num_args(1)
+        .value_parser(value_parser!(settings::KeyCase))
+        .value_name("CASE")
+        .long(A_L_KEY_CASE)
+        .action(ArgAction::Set)
+        .required(false)
+}
+
 fn arg_dry() -> Arg {
     Arg::new(A_L_DRY)
         .help("Do not write any files or set any environment variables")
-        .long_help("Set Whether to skip the actual setting of environment variables.")
+        .long_help(formatcp!(
+            "Do not write any files or set any environment variables, \
+            but print what would be written to stdout instead, \
+            each output preceded by a header line naming it. \
+            See also --{A_L_DRY_OUT}.",
+        ))
         .action(ArgAction::SetTrue)
         .short(A_S_DRY)
         .long(A_L_DRY)
         .required(false)
 }
 
+fn arg_dry_out() -> Arg {
+    Arg::new(A_L_DRY_OUT)
+        .help("Write what would be written into this directory instead")
+        .long_help(formatcp!(
+            "Like --{A_L_DRY}, but writes what would be written \
+            into this directory, one file per output, \
+            named like the output file, \
+            or 'env.txt', 'export.sh' and 'envrc' for the non-file outputs. \
+            This allows to safely preview the outputs, \
+            e.g. to diff them against the current ones.",
+        ))
+        .num_args(1)
+        .value_parser(value_parser!(std::path::PathBuf))
+        .value_name("DIR")
+        .value_hint(ValueHint::DirPath)
+        .long(A_L_DRY_OUT)
+        .action(ArgAction::Set)
+        .required(false)
+}
+
 fn arg_overwrite() -> Arg {
     Arg::new(A_L_OVERWRITE)
         .help("Whether to overwrite already set values in the output.")
@@ -413,7 +777,69 @@ fn arg_overwrite() -> Arg {
         .long(A_L_OVERWRITE)
         .action(ArgAction::Set)
         .required(false)
-        .conflicts_with(A_L_DRY)
+}
+
+fn arg_merge() -> Arg {
+    Arg::new(A_L_MERGE)
+        .help("Keep the values already present in the output files")
+        .long_help(formatcp!(
+            "Keep the values already present in the output files, \
+            only adding the newly evaluated ones, \
+            or updating them according to --{A_L_OVERWRITE}. \
+            This allows multiple tools (or runs of this tool) \
+            to share one output file. \
+            Without this, output files are written from scratch. \
+            This applies to the ENV, JSON and TOML formats (see --{A_L_FILE_OUT}).",
+        ))
+        .action(ArgAction::SetTrue)
+        .long(A_L_MERGE)
+        .required(false)
+}
+
+fn arg_multiline_style() -> Arg {
+    Arg::new(A_L_MULTILINE_STYLE)
+        .help("How to write values spanning multiple lines to ENV files")
+        .long_help(formatcp!(
+            "How to write values spanning multiple lines \
+            (e.g. descriptions or lists of authors) \
+            to ENV files (see --{A_L_FILE_OUT}). \
+            Both styles are read back correctly, \
+            e.g. with --{A_L_MERGE} or --{A_L_VARIABLES_FILE}.",
+        ))
+        .num_args(1)
+        .value_parser(value_parser!(settings::MultilineStyle))
+        .value_name("STYLE")
+        .long(A_L_MULTILINE_STYLE)
+        .action(ArgAction::Set)
+        .required(false)
+}
+
+fn arg_max_length() -> Arg {
+    Arg::new(A_L_MAX_LENGTH)
+        .help("Limits the length of the values of a key")
+        .long_help(formatcp!(
+            "Limits the length (in characters) of the values of a key, \
+            e.g. 'Description=255' or 'NameMachineReadable=63:error'. \
+            The key is a property name (see --{A_L_LIST}), \
+            or '{all}' for all keys without a limit of their own. \
+            What happens with longer values depends on the policy: \
+            'warn' keeps them, 'truncate' (the default) cuts them down, \
+            and 'error' fails. \
+            Values longer than {default_max} characters \
+            (the limit for environment variable values on Windows) \
+            get a warning by default. \
+            These limits may also be set in the config file \
+            ('[lengths]', see --{A_L_CONFIG}).",
+            all = constants::LENGTH_LIMIT_ALL,
+            default_max = constants::DEFAULT_MAX_LENGTH,
+        ))
+        .num_args(1)
+        .value_parser(settings::parse_key_length_limit)
+        .value_name("KEY=LENGTH[:POLICY]")
+        .value_hint(ValueHint::Other)
+        .long(A_L_MAX_LENGTH)
+        .action(ArgAction::Append)
+        .required(false)
 }
 
 fn arg_list() -> Arg {
@@ -429,15 +855,60 @@ fn arg_list() -> Arg {
         .required(false)
 }
 
+fn arg_graph() -> Arg {
+    Arg::new(A_L_GRAPH)
+        .help("Show which properties may be derived from which others")
+        .long_help(formatcp!(
+            "Prints the graph of which properties may be derived from which others, \
+            and by which conversion, onto stdout and exits. \
+            For example, this shows which variables get derived \
+            from PROJECT_REPO_WEB_URL, if only that one is set manually. \
+            See --{A_L_LIST} for all properties.",
+        ))
+        .num_args(1)
+        .value_parser(value_parser!(settings::GraphFormat))
+        .value_name("FORMAT")
+        .long(A_L_GRAPH)
+        .action(ArgAction::Set)
+        .required(false)
+}
+
+fn arg_explain() -> Arg {
+    Arg::new(A_L_EXPLAIN)
+        .help("Explain where the value of a property comes from")
+        .long_help(formatcp!(
+            "Explains where a value for the given key may come from \
+            (which sources, which input variables, \
+            and which other keys it may be derived from), \
+            and after evaluation, which source actually supplied it and why. \
+            You may use the property name (e.g. \"RepoWebUrl\") \
+            or the variable key (e.g. \"PROJECT_REPO_WEB_URL\"); \
+            simple glob patterns are supported too. \
+            See --{A_L_LIST} for all possible keys. \
+            This implies --{A_L_DRY}.",
+        ))
+        .num_args(1)
+        .value_parser(var::parse_key_id)
+        .value_name("KEY")
+        .value_hint(ValueHint::Other)
+        .long(A_L_EXPLAIN)
+        .action(ArgAction::Append)
+        .required(false)
+}
+
 fn arg_date_format() -> Arg {
     Arg::new(A_L_DATE_FORMAT)
         .help("Date format for generated dates")
         .long_help(
-            "Date format string for generated (vs supplied) dates. \
+            "Date format string for generated (vs supplied) dates, \
+            or one of the names 'iso8601' (e.g. '2021-09-30T20:59:05+00:00'), \
+            'rfc3339' (same as 'iso8601') or 'rfc2822'. \
+            The output does not depend on the locale. \
             For details, see https://docs.rs/chrono/latest/chrono/format/strftime/index.html",
         )
         .num_args(1)
-        .value_parser(clap::builder::NonEmptyStringValueParser::new()) // TODO Maybe parse directly into a date format?
+        .value_parser(settings::parse_date_format)
+        .value_name("FORMAT")
         .value_hint(ValueHint::Other)
         .short(A_S_DATE_FORMAT)
         .long(A_L_DATE_FORMAT)
@@ -446,17 +917,448 @@ fn arg_date_format() -> Arg {
         .required(false)
 }
 
+fn arg_build_date_format() -> Arg {
+    Arg::new(A_L_BUILD_DATE_FORMAT)
+        .help("Date format for the build date")
+        .long_help(formatcp!(
+            "Date format for the build date, \
+            if it should differ from the one given by --{A_L_DATE_FORMAT}, \
+            which also describes the possible values.",
+        ))
+        .num_args(1)
+        .value_parser(settings::parse_date_format)
+        .value_name("FORMAT")
+        .value_hint(ValueHint::Other)
+        .long(A_L_BUILD_DATE_FORMAT)
+        .action(ArgAction::Set)
+        .required(false)
+}
+
+fn arg_version_date_format() -> Arg {
+    Arg::new(A_L_VERSION_DATE_FORMAT)
+        .help("Date format for the version (commit) date")
+        .long_help(formatcp!(
+            "Date format for the version (commit) date, \
+            if it should differ from the one given by --{A_L_DATE_FORMAT}, \
+            which also describes the possible values.",
+        ))
+        .num_args(1)
+        .value_parser(settings::parse_date_format)
+        .value_name("FORMAT")
+        .value_hint(ValueHint::Other)
+        .long(A_L_VERSION_DATE_FORMAT)
+        .action(ArgAction::Set)
+        .required(false)
+}
+
+fn arg_date_timezone() -> Arg {
+    Arg::new(A_L_DATE_TIMEZONE)
+        .help("Time zone for generated dates")
+        .long_help(
+            "Time zone in which generated (vs supplied) dates are represented; \
+            either 'utc', 'local' or a fixed offset like '+02:00'. \
+            This applies to both the build date and the version (commit) date.",
+        )
+        .num_args(1)
+        .value_parser(settings::DateTimezone::from_str)
+        .value_name("TIMEZONE")
+        .value_hint(ValueHint::Other)
+        .long(A_L_DATE_TIMEZONE)
+        .action(ArgAction::Set)
+        .default_value("local")
+        .required(false)
+}
+
+fn arg_archive() -> Arg {
+    Arg::new(A_L_ARCHIVE)
+        .help("Run in archive mode, deriving values without any SCM")
+        .long_help(
+            "Assume we run in an exported source archive (tarball), \
+            lacking any SCM meta-data. \
+            In this mode, we lean on package manifests (Cargo.toml, package.json, PKG-INFO), \
+            a VERSION file and .git_archival.txt instead. \
+            This mode is enabled automatically if there is no git repo, \
+            but any of these files is found in the project root.",
+        )
+        .action(ArgAction::SetTrue)
+        .long(A_L_ARCHIVE)
+        .required(false)
+}
+
+fn arg_parent_project() -> Arg {
+    Arg::new(A_L_PARENT_PROJECT)
+        .help("If in a git submodule, derive values from the superproject")
+        .long_help(
+            "If the project root is a git submodule, \
+            derive all values from its superproject (the parent project) instead. \
+            Without this, values always refer to the submodule itself.",
+        )
+        .action(ArgAction::SetTrue)
+        .long(A_L_PARENT_PROJECT)
+        .required(false)
+}
+
+fn arg_online() -> Arg {
+    Arg::new(A_L_ONLINE)
+        .help("Allow network access, e.g. to query hosting APIs")
+        .long_help(
+            "Allow network access, \
+            which is used to fetch authoritative values from the hosting APIs, \
+            that can otherwise only be guessed, \
+            e.g. the GitHub pages URL (which might use a custom domain). \
+            Without this, projvar never accesses the network.",
+        )
+        .action(ArgAction::SetTrue)
+        .long(A_L_ONLINE)
+        .required(false)
+}
+
+fn arg_offline() -> Arg {
+    Arg::new(A_L_OFFLINE)
+        .help("Disallow any network access, even if --online is given")
+        .long_help(formatcp!(
+            "Disallow any network access, \
+            even if --{A_L_ONLINE} is given. \
+            This is useful to enforce offline operation \
+            (e.g. in a sandboxed build) \
+            when calling projvar through a wrapper that enables network access.",
+        ))
+        .action(ArgAction::SetTrue)
+        .long(A_L_OFFLINE)
+        .required(false)
+}
+
+fn arg_network_timeout() -> Arg {
+    Arg::new(A_L_NETWORK_TIMEOUT)
+        .help("Time-out for a single network request, in seconds")
+        .long_help(formatcp!(
+            "Time-out for a single network request, in seconds. \
+            This only has an effect with --{A_L_ONLINE}.",
+        ))
+        .num_args(1)
+        .value_parser(value_parser!(u64).range(1..))
+        .value_name("SECONDS")
+        .value_hint(ValueHint::Other)
+        .long(A_L_NETWORK_TIMEOUT)
+        .action(ArgAction::Set)
+        .default_value(formatcp!(
+            "{}",
+            constants::DEFAULT_NETWORK_TIMEOUT.as_secs()
+        ))
+        .required(false)
+}
+
+fn arg_network_retries() -> Arg {
+    Arg::new(A_L_NETWORK_RETRIES)
+        .help("How many times to retry a failed network request")
+        .long_help(formatcp!(
+            "How many times to retry a network request \
+            that failed in a way that is likely transient \
+            (time-out, connection failure, server error or rate limiting), \
+            waiting twice as long before each further retry, \
+            starting with half a second. \
+            This only has an effect with --{A_L_ONLINE}.",
+        ))
+        .num_args(1)
+        .value_parser(value_parser!(u8))
+        .value_name("NUM")
+        .value_hint(ValueHint::Other)
+        .long(A_L_NETWORK_RETRIES)
+        .action(ArgAction::Set)
+        .default_value(formatcp!("{}", constants::DEFAULT_NETWORK_RETRIES))
+        .required(false)
+}
+
+fn arg_ca_bundle() -> Arg {
+    Arg::new(A_L_CA_BUNDLE)
+        .help("Additional CA certificates to trust for network access")
+        .long_help(formatcp!(
+            "A file containing (PEM encoded) CA certificates to trust for network access, \
+            in addition to the built-in ones; \
+            useful e.g. in company networks with TLS interception. \
+            Proxies are configured through the common environment variables \
+            HTTP_PROXY, HTTPS_PROXY, ALL_PROXY and NO_PROXY. \
+            This only has an effect with --{A_L_ONLINE}.",
+        ))
+        .num_args(1)
+        .value_parser(value_parser!(std::path::PathBuf))
+        .value_name("PEM-FILE")
+        .value_hint(ValueHint::FilePath)
+        .long(A_L_CA_BUNDLE)
+        .action(ArgAction::Set)
+        .required(false)
+}
+
+fn arg_credentials_file() -> Arg {
+    Arg::new(A_L_CREDENTIALS_FILE)
+        .help("A TOML file containing access tokens for the hosting APIs")
+        .long_help(formatcp!(
+            "A TOML file containing access tokens for the hosting APIs, \
+            one per host, e.g.: '[hosts.\"github.com\"] token = \"ghp_...\"'. \
+            Tokens in the environment (GITHUB_TOKEN, GITLAB_TOKEN and CI_JOB_TOKEN) \
+            take precedence over the ones in this file, \
+            and if compiled with the 'keyring' feature, \
+            the OS keyring is used as a last resort. \
+            The default is '$XDG_CONFIG_HOME/projvar/{}', if it exists. \
+            This only has an effect with --{A_L_ONLINE}.",
+            tools::credentials::DEFAULT_FILE_NAME,
+        ))
+        .num_args(1)
+        .value_parser(value_parser!(std::path::PathBuf))
+        .value_name("FILE")
+        .value_hint(ValueHint::FilePath)
+        .long(A_L_CREDENTIALS_FILE)
+        .action(ArgAction::Set)
+        .required(false)
+}
+
+fn arg_verify_urls() -> Arg {
+    Arg::new(A_L_VERIFY_URLS)
+        .help("Verify URL values with HTTP HEAD requests")
+        .long_help(formatcp!(
+            "Verify the values of URL keys (e.g. the repo web URL, \
+            the issues URL or the hosting URL) with HTTP HEAD requests. \
+            Values of URLs that do not exist (404 or 410) are discarded, \
+            and the confidence in values that could not be confirmed \
+            (e.g. because they require authentication) is lowered. \
+            This catches wrong derivations, \
+            e.g. a pages URL for a project without pages. \
+            The HTTP status is reported with --{A_L_EXPLAIN}. \
+            This only has an effect with --{A_L_ONLINE}.",
+        ))
+        .action(ArgAction::SetTrue)
+        .long(A_L_VERIFY_URLS)
+        .required(false)
+}
+
+fn arg_strict_consistency() -> Arg {
+    Arg::new(A_L_STRICT_CONSISTENCY)
+        .help("Fail if two sources disagree about a value")
+        .long_help(
+            "Fail if two sources disagree about a value. \
+            Without this, such discrepancies are only logged, \
+            and the value of the source highest in the hierarchy is used. \
+            Values that are mere guesses (low confidence) are not considered. \
+            Note that this also fails if a value supplied through the environment \
+            overrides a differing one found by an other source.",
+        )
+        .action(ArgAction::SetTrue)
+        .long(A_L_STRICT_CONSISTENCY)
+        .required(false)
+}
+
+fn arg_keep_going() -> Arg {
+    Arg::new(A_L_KEEP_GOING)
+        .help("Continue with the other sources if one fails")
+        .long_help(formatcp!(
+            "If a source fails to retrieve a value \
+            (e.g. because of a git repo it can not handle), \
+            log the error and continue with the other sources, \
+            instead of failing right away. \
+            The failures are counted in the summary at the end of the run, \
+            and shown with --{A_L_EXPLAIN}. \
+            The run then only fails if required values are missing \
+            (see --{A_L_FAIL_ON_MISSING_VALUE}).",
+        ))
+        .action(ArgAction::SetTrue)
+        .long(A_L_KEEP_GOING)
+        .required(false)
+}
+
+fn arg_source_timeout() -> Arg {
+    Arg::new(A_L_SOURCE_TIMEOUT)
+        .help("How long a single source may take, in seconds")
+        .long_help(
+            "How long a single source may take to retrieve all its values, \
+            in seconds, before it is treated as unusable for the remaining keys. \
+            With this set, each value is retrieved on a worker thread, \
+            which is abandoned once the time is up. \
+            This guards against pathological repositories, \
+            e.g. with a huge history. \
+            Independently of this, a source that panics (due to a bug) \
+            is treated as unusable for the remaining keys. \
+            Both are counted in the summary at the end of the run. \
+            By default, sources may take as long as they need.",
+        )
+        .num_args(1)
+        .value_parser(value_parser!(u64).range(1..))
+        .value_name("SECONDS")
+        .value_hint(ValueHint::Other)
+        .long(A_L_SOURCE_TIMEOUT)
+        .action(ArgAction::Set)
+        .required(false)
+}
+
+fn arg_version_tag_pattern() -> Arg {
+    Arg::new(A_L_VERSION_TAG_PATTERN)
+        .help("Only consider tags matching this glob pattern for the version")
+        .long_help(formatcp!(
+            "Only tags matching this (git glob) pattern are considered \
+            when describing the version of the checked-out commit. \
+            Use e.g. 'release-*' if your release tags look like 'release-2024'. \
+            If no matching tag is reachable from HEAD, \
+            the version falls back to the short SHA of HEAD, \
+            and if there is no commit either, \
+            to the content of a VERSION file or the version in a package manifest \
+            (Cargo.toml, package.json, PKG-INFO). \
+            The default is '{}'.",
+            constants::DEFAULT_VERSION_TAG_PATTERN
+        ))
+        .num_args(1)
+        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+        .value_name("GLOB")
+        .value_hint(ValueHint::Other)
+        .long(A_L_VERSION_TAG_PATTERN)
+        .action(ArgAction::Set)
+        .default_value(constants::DEFAULT_VERSION_TAG_PATTERN)
+        .required(false)
+}
+
+fn arg_version_strip_prefix() -> Arg {
+    Arg::new(A_L_VERSION_STRIP_PREFIX)
+        .help("Prefixes to remove from versions, e.g. 'v,release-'")
+        .long_help(formatcp!(
+            "Prefixes to remove from versions, as commonly found in tags, \
+            e.g. 'v' in 'v1.2.3' or 'release-' in 'release-1.2.3'. \
+            They are matched case-insensitively, and the first matching one is removed. \
+            Multiple prefixes may be given separated by ',', \
+            or by using this option multiple times. \
+            Note that this replaces the default ('{}'), \
+            so include it if you want to keep it.",
+            constants::DEFAULT_VERSION_STRIP_PREFIXES
+        ))
+        .num_args(1)
+        .value_delimiter(',')
+        .value_name("PREFIX")
+        .value_hint(ValueHint::Other)
+        .long(A_L_VERSION_STRIP_PREFIX)
+        .action(ArgAction::Append)
+        .default_value(constants::DEFAULT_VERSION_STRIP_PREFIXES)
+        .required(false)
+}
+
+fn arg_license_scan_depth() -> Arg {
+    Arg::new(A_L_LICENSE_SCAN_DEPTH)
+        .help("How many directory levels below the project root to search for licenses")
+        .long_help(
+            "How many directory levels below the project root \
+            to search for license files (LICENSE*, LICENCE*, COPYING* \
+            and REUSE LICENSES/*.txt), \
+            e.g. to find the licenses of all the crates in a workspace. \
+            The unique licenses found are combined, \
+            and the files they were found in are reported. \
+            Files excluded from source archives (export-ignore) \
+            or marked as vendored (linguist-vendored) \
+            through git attributes are skipped. \
+            The default only searches the project root, which is the fastest.",
+        )
+        .num_args(1)
+        .value_parser(value_parser!(usize))
+        .value_name("DEPTH")
+        .value_hint(ValueHint::Other)
+        .long(A_L_LICENSE_SCAN_DEPTH)
+        .action(ArgAction::Set)
+        .default_value("0")
+        .required(false)
+}
+
+fn arg_stub_values() -> Arg {
+    Arg::new(A_L_STUB_VALUES)
+        .help("Use fixed stand-ins for the values that change from build to build")
+        .long_help(formatcp!(
+            "Use fixed stand-ins for the values that change \
+            from build to build or from build host to build host, \
+            like the build date, build number, OS, architecture and host properties. \
+            The build date is taken from {epoch_var}, if set, \
+            and is the start of the Unix epoch otherwise. \
+            Only explicit overrides win over these values. \
+            This makes the output reproducible, \
+            e.g. for snapshot tests of documents that embed it. \
+            Note that with --{A_L_STRICT_CONSISTENCY}, \
+            this fails if an other source found a differing value.",
+            epoch_var = sources::env::SOURCE_DATE_EPOCH,
+        ))
+        .action(ArgAction::SetTrue)
+        .long(A_L_STUB_VALUES)
+        .required(false)
+}
+
+fn arg_source_date_epoch_out() -> Arg {
+    Arg::new(A_L_SOURCE_DATE_EPOCH_OUT)
+        .help(formatcp!(
+            "Also write the build date epoch as {epoch_var}",
+            epoch_var = sources::env::SOURCE_DATE_EPOCH,
+        ))
+        .long_help(formatcp!(
+            "Also write the build date as a Unix timestamp \
+            (the value of {epoch_key}) to {epoch_var}, \
+            without key prefix, \
+            so the tools of the following build steps produce reproducible output. \
+            As {epoch_var} is honored when evaluating the build date, \
+            all the steps then agree on it.",
+            epoch_key = var::KEY_BUILD_DATE_EPOCH,
+            epoch_var = sources::env::SOURCE_DATE_EPOCH,
+        ))
+        .action(ArgAction::SetTrue)
+        .long(A_L_SOURCE_DATE_EPOCH_OUT)
+        .required(false)
+}
+
+fn arg_plain_machine_names() -> Arg {
+    Arg::new(A_L_PLAIN_MACHINE_NAMES)
+        .help("Derive machine-readable names without transliteration")
+        .long_help(
+            "When deriving the machine-readable project name \
+            from the human-readable one, \
+            replace each character that is not an ASCII letter, digit, '-' or '_' \
+            with a '_', as older versions did \
+            (e.g. \"Kühlschrank-Steuerung\" becomes \"K_hlschrank-Steuerung\"). \
+            By default, the name is transliterated to ASCII first, \
+            and each run of remaining unsupported characters \
+            is replaced by a single '_' \
+            (e.g. \"Kühlschrank-Steuerung\" becomes \"Kuhlschrank-Steuerung\").",
+        )
+        .action(ArgAction::SetTrue)
+        .long(A_L_PLAIN_MACHINE_NAMES)
+        .required(false)
+}
+
+fn arg_incremental() -> Arg {
+    Arg::new(A_L_INCREMENTAL)
+        .help("Reuse the values of the previous run, where the inputs did not change")
+        .long_help(
+            "Loads the state of the previous run from this file (if it exists), \
+            and only retrieves values anew from the sources whose inputs changed \
+            since then; the files in the project root, \
+            the git repo (HEAD, dirty state and refs) \
+            and the environment variables are checked separately. \
+            If the settings changed, all values are retrieved anew. \
+            Values depending on the time or the build machine \
+            (e.g. the build date) are always retrieved anew. \
+            The state of this run is then stored to the same file. \
+            This speeds up repeated runs, e.g. in dozens of jobs of a large monorepo.",
+        )
+        .num_args(1)
+        .value_parser(value_parser!(std::path::PathBuf))
+        .value_name("STATE-FILE")
+        .value_hint(ValueHint::FilePath)
+        .long(A_L_INCREMENTAL)
+        .action(ArgAction::Set)
+        .required(false)
+}
+
 fn arg_show_all_retrieved() -> Arg {
     Arg::new(A_L_SHOW_ALL_RETRIEVED)
         .help("Shows a table of all values retrieved from sources")
         .long_help(
-            "Shows a table (in Markdown syntax) of all properties and the values \
+            "Shows a table (in Markdown syntax by default) of all properties and the values \
             retrieved for each from each individual source. \
-            Writes to log(Info), if no target file is given as argument.",
+            Writes to log(Info), if no target file is given as argument. \
+            See --retrieved-format.",
         )
         .num_args(0..=1)
         .value_hint(ValueHint::FilePath)
-        .value_name("MD-FILE")
+        .value_name("FILE")
         .value_parser(value_parser!(std::path::PathBuf))
         .short(A_S_SHOW_ALL_RETRIEVED)
         .long(A_L_SHOW_ALL_RETRIEVED)
@@ -468,14 +1370,15 @@ fn arg_show_primary_retrieved() -> Arg {
     Arg::new(A_L_SHOW_PRIMARY_RETRIEVED)
         .help("Shows a list of the primary values retrieved from sources")
         .long_help(
-            "Shows a list (in Markdown syntax) of all properties \
+            "Shows a list (in Markdown syntax by default) of all properties \
             and the primary values retrieved for each, \
             accumulated over the sources. \
-            Writes to log(Info), if no target file is given as argument.",
+            Writes to log(Info), if no target file is given as argument. \
+            See --retrieved-format.",
         )
         .num_args(0..=1)
         .value_hint(ValueHint::FilePath)
-        .value_name("MD-FILE")
+        .value_name("FILE")
         .value_parser(value_parser!(std::path::PathBuf))
         .short(A_S_SHOW_PRIMARY_RETRIEVED)
         .long(A_L_SHOW_PRIMARY_RETRIEVED)
@@ -484,33 +1387,156 @@ fn arg_show_primary_retrieved() -> Arg {
         .conflicts_with(A_L_SHOW_ALL_RETRIEVED)
 }
 
+fn arg_retrieved_format() -> Arg {
+    Arg::new(A_L_RETRIEVED_FORMAT)
+        .help("The format of the retrieved values overview")
+        .long_help(formatcp!(
+            "The format of the overview of the retrieved values, \
+            as created by --{A_L_SHOW_ALL_RETRIEVED} and --{A_L_SHOW_PRIMARY_RETRIEVED}. \
+            If not given, it is evaluated from the extension of the target file \
+            ('.md', '.html', '.json' or '.csv'), \
+            falling back to Markdown. \
+            If there is no target file, the default is an aligned plain-text table, \
+            colored if the terminal supports it and NO_COLOR is not set.",
+        ))
+        .num_args(1)
+        .value_parser(value_parser!(settings::TableFormat))
+        .value_name("FORMAT")
+        .long(A_L_RETRIEVED_FORMAT)
+        .action(ArgAction::Set)
+        .required(false)
+}
+
+fn arg_config() -> Arg {
+    Arg::new(A_L_CONFIG)
+        .help("The config file to use")
+        .long_help(formatcp!(
+            "A TOML config file, for example containing user-defined profiles \
+            or custom forge domains ('[hosting.\"<DOMAIN>\"]' with a 'type' field) \
+            and URL templates per hosting type ('[urls.<HOSTING-TYPE>]', \
+            with the optional fields 'issues', 'raw', 'blob', 'tree', 'commit' and 'pages', \
+            supporting the placeholders '{{web_url}}', '{{host}}', '{{slug}}', '{{owner}}' and '{{name}}'), \
+            as well as value policies per key name ('[validators]', \
+            e.g. 'Version = \"^\\\\d+\\\\.\\\\d+$\"' or 'Name = \"deny:^test\"'), \
+            checked in addition to the built-in validators, \
+            and length limits per key name ('[lengths]', \
+            e.g. 'Description = \"255:truncate\"', see --{A_L_MAX_LENGTH}). \
+            If not specified, we use '{}' in the project root, if it exists. \
+            See --{A_L_PROFILE}.",
+            config::DEFAULT_FILE_NAME,
+        ))
+        .num_args(1)
+        .value_parser(value_parser!(std::path::PathBuf))
+        .value_name("TOML-FILE")
+        .value_hint(ValueHint::FilePath)
+        // .short(A_S_CONFIG)
+        .long(A_L_CONFIG)
+        .action(ArgAction::Set)
+        .required(false)
+}
+
+fn arg_profile() -> Arg {
+    Arg::new(A_L_PROFILE)
+        .help("Use a named preset of required keys and settings")
+        .long_help(formatcp!(
+            "Use a named preset of required keys and related settings. \
+            The keys of the profile replace the default required keys list; \
+            --{A_L_REQUIRE} and --{A_L_REQUIRE_NOT} are applied on top of it. \
+            Built-in profiles: \
+            '{}' (Version, VersionDate, License, RepoWebUrl), \
+            '{}' (NameMachineReadable, Version), \
+            '{}' (BuildHostingUrl, RepoWebUrl). \
+            More can be defined in the config file (see --{A_L_CONFIG}), \
+            under '[profiles.<NAME>]', \
+            with the optional fields \
+            'require' (a list of keys), 'fail' and 'only_required' (booleans).",
+            config::PROFILE_RELEASE,
+            config::PROFILE_CONTAINER,
+            config::PROFILE_DOCS,
+        ))
+        .num_args(1)
+        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+        .value_name("NAME")
+        .value_hint(ValueHint::Other)
+        // .short(A_S_PROFILE)
+        .long(A_L_PROFILE)
+        .action(ArgAction::Set)
+        .required(false)
+        .conflicts_with(A_L_REQUIRE_ALL)
+        .conflicts_with(A_L_REQUIRE_NONE)
+}
+
 lazy_static! {
-    static ref ARGS: [Arg; 25] = [
+    static ref ARGS: Vec<Arg> = vec![
         arg_version(),
         arg_project_root(),
         arg_raw_panic(),
         arg_variable(),
         arg_variables_file(),
+        arg_interpolate(),
         arg_no_env_in(),
+        arg_env_allow(),
+        arg_env_deny(),
         arg_env_out(),
+        arg_export_script(),
+        arg_direnv(),
         arg_out_file(),
+        arg_oci_labels(),
         arg_hosting_type(),
+        arg_hosting_domain(),
+        arg_hosting_base_path(),
         arg_verbose(),
         arg_log_level(),
+        arg_log_format(),
+        arg_log_file(),
+        arg_log_file_level(),
         arg_quiet(),
         arg_fail(),
+        arg_strict(),
         arg_require_all(),
         arg_require_none(),
         arg_require(),
         arg_require_not(),
         arg_only_required(),
         arg_key_prefix(),
+        arg_key_case(),
         arg_dry(),
+        arg_dry_out(),
         arg_overwrite(),
+        arg_merge(),
+        arg_multiline_style(),
+        arg_max_length(),
         arg_list(),
+        arg_graph(),
+        arg_explain(),
         arg_date_format(),
+        arg_build_date_format(),
+        arg_version_date_format(),
+        arg_date_timezone(),
         arg_show_all_retrieved(),
         arg_show_primary_retrieved(),
+        arg_retrieved_format(),
+        arg_config(),
+        arg_profile(),
+        arg_archive(),
+        arg_parent_project(),
+        arg_online(),
+        arg_offline(),
+        arg_network_timeout(),
+        arg_network_retries(),
+        arg_ca_bundle(),
+        arg_credentials_file(),
+        arg_verify_urls(),
+        arg_strict_consistency(),
+        arg_keep_going(),
+        arg_source_timeout(),
+        arg_version_tag_pattern(),
+        arg_version_strip_prefix(),
+        arg_license_scan_depth(),
+        arg_stub_values(),
+        arg_source_date_epoch_out(),
+        arg_plain_machine_names(),
+        arg_incremental(),
     ];
 }
 
@@ -546,11 +1572,65 @@ fn arg_matcher() -> Command {
     app
 }
 
-fn hosting_type(args: &ArgMatches) -> HostingType {
-    let hosting_type = args
-        .get_one::<HostingType>(A_L_HOSTING_TYPE)
-        .copied()
-        .unwrap_or_default();
+/// Returns the value of a CLI option,
+/// falling back to our setting `name` from the git config
+/// (see [`tools::git::CONFIG_SECTION`]),
+/// and then to the default value of the option,
+/// if it was not given explicitly.
+/// Invalid values in the git config are ignored with a warning.
+fn arg_or_git_config<T, E>(
+    args: &ArgMatches,
+    git_config: Option<&git2::Config>,
+    arg: &str,
+    name: &str,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Option<T>
+where
+    T: Clone + Send + Sync + 'static,
+    E: std::fmt::Display,
+{
+    if !matches!(
+        args.value_source(arg),
+        None | Some(ValueSource::DefaultValue)
+    ) {
+        return args.get_one::<T>(arg).cloned();
+    }
+    git_config
+        .and_then(|config| tools::git::config_setting(config, name))
+        .and_then(|value| match parse(&value) {
+            Ok(parsed) => {
+                log::debug!(
+                    "Using git config {}.{name}='{value}'.",
+                    tools::git::CONFIG_SECTION
+                );
+                Some(parsed)
+            }
+            Err(err) => {
+                log::warn!(
+                    "Ignoring invalid git config {}.{name}='{value}': {err}",
+                    tools::git::CONFIG_SECTION
+                );
+                None
+            }
+        })
+        .or_else(|| args.get_one::<T>(arg).cloned())
+}
+
+/// Parses a git config value that is used as-is.
+#[allow(clippy::unnecessary_wraps)]
+fn parse_verbatim(value: &str) -> Result<String, Infallible> {
+    Ok(value.to_owned())
+}
+
+fn hosting_type(args: &ArgMatches, git_config: Option<&git2::Config>) -> HostingType {
+    let hosting_type = arg_or_git_config(
+        args,
+        git_config,
+        A_L_HOSTING_TYPE,
+        "hostingType",
+        settings::parse_hosting_type,
+    )
+    .unwrap_or_default();
 
     if log::log_enabled!(log::Level::Debug) {
         let hosting_type_str: &str = hosting_type.into();
@@ -560,6 +1640,15 @@ fn hosting_type(args: &ArgMatches) -> HostingType {
     hosting_type
 }
 
+fn hosting_domains(args: &ArgMatches, config: &Config) -> HashMap<String, HostingType> {
+    let mut hosting_domains = config.hosting_domains();
+    if let Some(mappings) = args.get_many::<(String, HostingType)>(A_L_HOSTING_DOMAIN) {
+        hosting_domains.extend(mappings.cloned());
+    }
+    log::debug!("Hosting domains: {:#?}", hosting_domains);
+    hosting_domains
+}
+
 fn overwrite(args: &ArgMatches) -> settings::Overwrite {
     let overwrite = args
         .get_one::<settings::Overwrite>(A_L_OVERWRITE)
@@ -603,59 +1692,215 @@ fn repo_path(args: &ArgMatches) -> PathBuf {
         .get_one::<PathBuf>(A_L_PROJECT_ROOT)
         .cloned()
         .unwrap_or_else(PathBuf::new);
+    let repo_path = if args.get_flag(A_L_PARENT_PROJECT) {
+        if let Some(parent) = tools::git::Repo::try_from(Some(repo_path.as_path()))
+            .ok()
+            .and_then(|repo| repo.superproject())
+        {
+            parent.local_path()
+        } else {
+            log::warn!(
+                "'--{A_L_PARENT_PROJECT}' was given, \
+                but {:#?} is not a git submodule; ignoring it.",
+                &repo_path
+            );
+            repo_path
+        }
+    } else {
+        repo_path
+    };
     log::debug!("Using repo path {:#?}.", &repo_path);
     repo_path
 }
 
-fn date_format(args: &ArgMatches) -> &str {
-    let date_format = match args.get_one::<String>(A_L_DATE_FORMAT) {
-        Some(date_format) => date_format,
-        None => tools::git::DATE_FORMAT,
-    };
+fn date_format(args: &ArgMatches, git_config: Option<&git2::Config>) -> String {
+    let date_format = arg_or_git_config(
+        args,
+        git_config,
+        A_L_DATE_FORMAT,
+        "dateFormat",
+        settings::parse_date_format,
+    )
+    .unwrap_or_else(|| tools::git::DATE_FORMAT.to_owned());
     log::debug!("Using date format '{}'.", date_format);
     date_format
 }
 
+fn date_timezone(args: &ArgMatches, git_config: Option<&git2::Config>) -> settings::DateTimezone {
+    let date_timezone = arg_or_git_config(
+        args,
+        git_config,
+        A_L_DATE_TIMEZONE,
+        "dateTimezone",
+        settings::DateTimezone::from_str,
+    )
+    .unwrap_or_default();
+    log::debug!("Using date time zone '{}'.", date_timezone);
+    date_timezone
+}
+
+fn retrieved_format(
+    args: &ArgMatches,
+    show_retrieved: &settings::ShowRetrieved,
+) -> Option<settings::TableFormat> {
+    let retrieved_format = args
+        .get_one::<settings::TableFormat>(A_L_RETRIEVED_FORMAT)
+        .copied()
+        .or_else(|| match show_retrieved {
+            settings::ShowRetrieved::No => None,
+            settings::ShowRetrieved::Primary(target) | settings::ShowRetrieved::All(target) => {
+                target.as_deref().and_then(settings::TableFormat::from_path)
+            }
+        });
+    log::debug!("Using retrieved values format '{:?}'.", retrieved_format);
+    retrieved_format
+}
+
+fn version_tag_pattern(args: &ArgMatches, git_config: Option<&git2::Config>) -> String {
+    let version_tag_pattern = arg_or_git_config(
+        args,
+        git_config,
+        A_L_VERSION_TAG_PATTERN,
+        "versionTagPattern",
+        parse_verbatim,
+    )
+    .unwrap_or_else(|| constants::DEFAULT_VERSION_TAG_PATTERN.to_owned());
+    log::debug!("Using version tag pattern '{}'.", version_tag_pattern);
+    version_tag_pattern
+}
+
+fn version_strip_prefixes(args: &ArgMatches) -> Vec<String> {
+    let version_strip_prefixes: Vec<String> = args
+        .get_many::<String>(A_L_VERSION_STRIP_PREFIX)
+        .unwrap_or_default()
+        .map(|prefix| prefix.trim().to_owned())
+        .filter(|prefix| !prefix.is_empty())
+        .collect();
+    log::debug!("Using version strip prefixes {:?}.", version_strip_prefixes);
+    version_strip_prefixes
+}
+
+fn archive(args: &ArgMatches, repo_path: &std::path::Path) -> bool {
+    let archive = sources::archive::is_archive_root(repo_path, args.get_flag(A_L_ARCHIVE));
+    if archive {
+        log::warn!(
+            "No git repo found at '{}'; running in archive mode, \
+            using package manifests and archive meta-data files instead. \
+            These keys are fundamentally unavailable in this mode, \
+            unless supplied by a CI environment or otherwise: {:?}",
+            repo_path.display(),
+            sources::archive::UNAVAILABLE_KEYS
+        );
+    }
+    archive
+}
+
 fn sinks_cli(args: &ArgMatches) -> Vec<Box<dyn VarSink>> {
     let env_out = args.get_flag(A_L_ENV_OUT);
-    let dry = args.get_flag(A_L_DRY);
+    let script = if args.get_flag(A_L_EXPORT_SCRIPT) {
+        Some(sinks::Script::Export)
+    } else if args.get_flag(A_L_DIRENV) {
+        Some(sinks::Script::Direnv)
+    } else {
+        None
+    };
+    let dry = if args.contains_id(A_L_EXPLAIN) {
+        // explaining is about the evaluation, not about its output
+        sinks::DryRun::Silent
+    } else if let Some(dir) = args.get_one::<PathBuf>(A_L_DRY_OUT) {
+        sinks::DryRun::Dir(dir.clone())
+    } else if args.get_flag(A_L_DRY) {
+        sinks::DryRun::Print
+    } else {
+        sinks::DryRun::No
+    };
 
     let mut default_out_file = true;
     let mut additional_out_files = vec![];
-    if let Some(out_files) = args.get_many::<PathBuf>(A_L_FILE_OUT) {
+    if script.is_some() && args.value_source(A_L_FILE_OUT) == Some(ValueSource::DefaultValue) {
+        // the script on stdout is the output
+        default_out_file = false;
+    } else if let Some(out_files) = args.get_many::<PathBuf>(A_L_FILE_OUT) {
         for out_file in out_files {
             additional_out_files.push(out_file.into());
             default_out_file = false;
         }
+    } else {
+        // only the default output file
     }
 
-    sinks::cli_list(env_out, dry, default_out_file, additional_out_files)
+    let oci_label_files = args
+        .get_many::<PathBuf>(A_L_OCI_LABELS)
+        .map(|files| files.cloned().collect())
+        .unwrap_or_default();
+
+    sinks::cli_list(
+        env_out,
+        script,
+        dry,
+        default_out_file,
+        additional_out_files,
+        oci_label_files,
+    )
+}
+
+fn config(args: &ArgMatches, repo_path: &std::path::Path) -> BoxResult<Config> {
+    Ok(match args.get_one::<PathBuf>(A_L_CONFIG) {
+        Some(config_file) => Config::load(config_file)?,
+        None => Config::load_default(repo_path)?,
+    })
+}
+
+fn profile(
+    args: &ArgMatches,
+    config: &Config,
+    git_config: Option<&git2::Config>,
+) -> BoxResult<Option<Profile>> {
+    let Some(profile_name) =
+        arg_or_git_config(args, git_config, A_L_PROFILE, "profile", parse_verbatim)
+    else {
+        return Ok(None);
+    };
+    let profile = config.profile(&profile_name)?;
+    log::debug!("Using profile '{}': {:?}", profile_name, profile.require);
+    Ok(Some(profile))
 }
 
-fn required_keys(key_prefix: Option<String>, args: &ArgMatches) -> BoxResult<HashSet<Key>> {
+fn required_keys(
+    key_prefix: Option<String>,
+    args: &ArgMatches,
+    profile: Option<&Profile>,
+) -> BoxResult<HashSet<Key>> {
     let require_all: bool = args.get_flag(A_L_REQUIRE_ALL);
     let require_none: bool = args.get_flag(A_L_REQUIRE_NONE);
+    let r_key_prefix_str = format!("^{}", key_prefix.unwrap_or_default());
+    let r_key_prefix = Regex::new(&r_key_prefix_str).unwrap();
     let mut required_keys = if require_all {
         let mut all = HashSet::<Key>::new();
         all.extend(Key::iter());
         all
     } else if require_none {
         HashSet::<Key>::new()
+    } else if let Some(profile) = profile {
+        let mut profile_keys = HashSet::<Key>::new();
+        for require in &profile.require {
+            profile_keys.extend(Key::from_name_or_var_key_glob(&r_key_prefix, require)?);
+        }
+        profile_keys
     } else {
         var::default_keys().clone()
     };
-    let r_key_prefix_str = format!("^{}", key_prefix.unwrap_or_default());
-    let r_key_prefix = Regex::new(&r_key_prefix_str).unwrap();
     if let Some(requires) = args.get_many::<String>(A_L_REQUIRE) {
         for require in requires {
-            let key = Key::from_name_or_var_key(&r_key_prefix, require)?;
-            required_keys.insert(key);
+            let keys = Key::from_name_or_var_key_glob(&r_key_prefix, require)?;
+            required_keys.extend(keys);
         }
     }
     if let Some(require_nots) = args.get_many::<String>(A_L_REQUIRE_NOT) {
         for require_not in require_nots {
-            let key = Key::from_name_or_var_key(&r_key_prefix, require_not)?;
-            required_keys.remove(&key);
+            for key in Key::from_name_or_var_key_glob(&r_key_prefix, require_not)? {
+                required_keys.remove(&key);
+            }
         }
     }
     // make imutable
@@ -669,6 +1914,18 @@ fn required_keys(key_prefix: Option<String>, args: &ArgMatches) -> BoxResult<Has
     Ok(required_keys)
 }
 
+fn explain_keys(key_prefix: Option<String>, args: &ArgMatches) -> BoxResult<HashSet<Key>> {
+    let r_key_prefix_str = format!("^{}", key_prefix.unwrap_or_default());
+    let r_key_prefix = Regex::new(&r_key_prefix_str).unwrap();
+    let mut explain_keys = HashSet::<Key>::new();
+    if let Some(explains) = args.get_many::<String>(A_L_EXPLAIN) {
+        for explain in explains {
+            explain_keys.extend(Key::from_name_or_var_key_glob(&r_key_prefix, explain)?);
+        }
+    }
+    Ok(explain_keys)
+}
+
 fn print_version_and_exit(quiet: bool) {
     #![allow(clippy::print_stdout)]
 
@@ -679,56 +1936,59 @@ fn print_version_and_exit(quiet: bool) {
     std::process::exit(0);
 }
 
-fn main() -> BoxResult<()> {
-    let log_filter_reload_handle = logger::setup_logging()?;
-    let initial_verbosity = if cfg!(debug_assertions) {
-        Verbosity::Debug
-    } else {
-        Verbosity::Info
-    };
-    logger::set_log_level(&log_filter_reload_handle, initial_verbosity)?;
-
-    let args = arg_matcher().get_matches();
-
-    if !args.get_flag(A_L_RAW_PANIC) {
-        human_panic::setup_panic!();
-    }
-
-    let quiet = args.get_flag(A_L_QUIET);
-
-    let version = args.get_flag(A_L_VERSION);
-    if version {
-        print_version_and_exit(quiet);
+fn main() -> std::process::ExitCode {
+    #![allow(clippy::print_stderr)]
+
+    match run() {
+        Ok(()) => ExitCode::Success.into(),
+        Err(err) => {
+            if let Some(clap_err) = err.downcast_ref::<clap::Error>() {
+                // This also covers --help, which is not an error for us
+                let _ = clap_err.print();
+                return if clap_err.use_stderr() {
+                    ExitCode::Usage
+                } else {
+                    ExitCode::Success
+                }
+                .into();
+            }
+            eprintln!("Error: {err}");
+            err.downcast_ref::<error::Error>()
+                .map_or(ExitCode::Error, error::Error::exit_code)
+                .into()
+        }
     }
+}
 
-    let verbosity = verbosity(&args);
-    logger::set_log_level(&log_filter_reload_handle, verbosity)?;
+fn key_case(args: &ArgMatches, git_config: Option<&git2::Config>) -> settings::KeyCase {
+    arg_or_git_config(args, git_config, A_L_KEY_CASE, "keyCase", |value| {
+        <settings::KeyCase as clap::ValueEnum>::from_str(value, true)
+    })
+    .unwrap_or_default()
+}
 
-    if args.get_flag(A_L_LIST) {
-        let environment = Environment::stub();
-        let list = var::list_keys(&environment);
-        log::info!("{}", list);
-        return Ok(());
+fn length_limits(
+    args: &ArgMatches,
+    config: &Config,
+) -> BoxResult<HashMap<String, settings::LengthLimit>> {
+    let mut length_limits = config.length_limits()?;
+    if let Some(limits) = args.get_many::<(String, settings::LengthLimit)>(A_L_MAX_LENGTH) {
+        length_limits.extend(limits.cloned());
     }
+    Ok(length_limits)
+}
 
-    let repo_path = repo_path(&args);
-    let date_format = date_format(&args);
-
-    let overwrite = overwrite(&args);
-
-    log::trace!("Collecting sources ...");
-    let sources = sources::default_list(&repo_path);
-
-    log::trace!("Collecting sinks ...");
-    let sinks = sinks_cli(&args);
+fn network_timeout(args: &ArgMatches) -> std::time::Duration {
+    std::time::Duration::from_secs(
+        args.get_one::<u64>(A_L_NETWORK_TIMEOUT)
+            .copied()
+            .unwrap_or(constants::DEFAULT_NETWORK_TIMEOUT.as_secs()),
+    )
+}
 
-    log::trace!("Collecting more settings ...");
-    let fail_on_missing = args.get_flag(A_L_FAIL_ON_MISSING_VALUE);
-    let key_prefix = args.get_one::<String>(A_L_KEY_PREFIX).cloned();
-    log::trace!("Collecting required keys ...");
-    let required_keys = required_keys(key_prefix.clone(), &args)?;
+fn show_retrieved(args: &ArgMatches) -> settings::ShowRetrieved {
     log::trace!("Collecting setting 'show-retrieved?' ...");
-    let show_retrieved: settings::ShowRetrieved = if args.contains_id(A_L_SHOW_ALL_RETRIEVED) {
+    if args.contains_id(A_L_SHOW_ALL_RETRIEVED) {
         settings::ShowRetrieved::All(
             args.get_one::<PathBuf>(A_L_SHOW_ALL_RETRIEVED)
                 .map(std::convert::Into::into),
@@ -740,32 +2000,140 @@ fn main() -> BoxResult<()> {
         )
     } else {
         settings::ShowRetrieved::No
-    };
-    log::trace!("Collecting yet more settings ...");
-    let hosting_type = hosting_type(&args);
-    let only_required = args.get_flag(A_L_ONLY_REQUIRED);
+    }
+}
 
-    let settings = Settings {
+fn settings(
+    args: &ArgMatches,
+    repo_path: PathBuf,
+    verbosity: Verbosity,
+    log_format: settings::LogFormat,
+) -> BoxResult<Settings> {
+    let git_config = tools::git::config_snapshot(&repo_path);
+    let date_format = date_format(args, git_config.as_ref());
+    let date_timezone = date_timezone(args, git_config.as_ref());
+    let config = config(args, &repo_path)?;
+    let profile = profile(args, &config, git_config.as_ref())?;
+    let archive = archive(args, &repo_path);
+
+    let overwrite = overwrite(args);
+
+    log::trace!("Collecting more settings ...");
+    let fail_on_missing = args.get_flag(A_L_FAIL_ON_MISSING_VALUE)
+        || profile.as_ref().and_then(|prof| prof.fail).unwrap_or(false);
+    let key_prefix = arg_or_git_config(
+        args,
+        git_config.as_ref(),
+        A_L_KEY_PREFIX,
+        "keyPrefix",
+        parse_verbatim,
+    );
+    log::trace!("Collecting required keys ...");
+    let required_keys = required_keys(key_prefix.clone(), args, profile.as_ref())?;
+    let explain = explain_keys(key_prefix.clone(), args)?;
+    let show_retrieved = show_retrieved(args);
+    let retrieved_format = retrieved_format(args, &show_retrieved);
+    log::trace!("Collecting yet more settings ...");
+    let hosting_type = hosting_type(args, git_config.as_ref());
+    let hosting_domains = hosting_domains(args, &config);
+    let hosting_base_path = arg_or_git_config(
+        args,
+        git_config.as_ref(),
+        A_L_HOSTING_BASE_PATH,
+        "hostingBasePath",
+        parse_verbatim,
+    )
+    .and_then(|base_path| settings::normalize_hosting_base_path(&base_path));
+    let url_templates = config.url_templates()?;
+    let validators = config.validators()?;
+    let length_limits = length_limits(args, &config)?;
+    let only_required = args.get_flag(A_L_ONLY_REQUIRED)
+        || profile
+            .as_ref()
+            .and_then(|prof| prof.only_required)
+            .unwrap_or(false);
+
+    Ok(Settings {
         repo_path: Some(repo_path),
         required_keys,
-        date_format: date_format.to_owned(),
+        date_format,
+        build_date_format: args.get_one::<String>(A_L_BUILD_DATE_FORMAT).cloned(),
+        version_date_format: args.get_one::<String>(A_L_VERSION_DATE_FORMAT).cloned(),
+        date_timezone,
         overwrite,
+        merge: args.get_flag(A_L_MERGE),
         fail_on: settings::FailOn::from(fail_on_missing),
+        strict: args.get_flag(A_L_STRICT),
+        explain,
         show_retrieved,
+        retrieved_format,
         hosting_type,
+        hosting_domains,
+        hosting_type_cache: settings::HostingTypeCache::default(),
+        hosting_base_path,
+        url_templates,
+        validators,
+        length_limits,
         only_required,
         key_prefix,
+        key_case: key_case(args, git_config.as_ref()),
+        multiline_style: args
+            .get_one::<settings::MultilineStyle>(A_L_MULTILINE_STYLE)
+            .copied()
+            .unwrap_or_default(),
         verbosity,
-    };
-    log::trace!("Created Settings.");
-    let mut environment = Environment::new(settings);
-    log::trace!("Created Environment.");
+        log_format,
+        archive,
+        online: args.get_flag(A_L_ONLINE) && !args.get_flag(A_L_OFFLINE),
+        network_timeout: network_timeout(args),
+        network_retries: args
+            .get_one::<u8>(A_L_NETWORK_RETRIES)
+            .copied()
+            .unwrap_or(constants::DEFAULT_NETWORK_RETRIES),
+        ca_bundle: args.get_one::<PathBuf>(A_L_CA_BUNDLE).cloned(),
+        credentials_file: args.get_one::<PathBuf>(A_L_CREDENTIALS_FILE).cloned(),
+        verify_urls: args.get_flag(A_L_VERIFY_URLS),
+        strict_consistency: args.get_flag(A_L_STRICT_CONSISTENCY),
+        keep_going: args.get_flag(A_L_KEEP_GOING),
+        source_timeout: args
+            .get_one::<u64>(A_L_SOURCE_TIMEOUT)
+            .copied()
+            .map(std::time::Duration::from_secs),
+        version_tag_pattern: version_tag_pattern(args, git_config.as_ref()),
+        version_strip_prefixes: version_strip_prefixes(args),
+        license_scan_depth: args
+            .get_one::<usize>(A_L_LICENSE_SCAN_DEPTH)
+            .copied()
+            .unwrap_or_default(),
+        stub_values: args.get_flag(A_L_STUB_VALUES),
+        source_date_epoch_out: args.get_flag(A_L_SOURCE_DATE_EPOCH_OUT),
+        transliterate_names: !args.get_flag(A_L_PLAIN_MACHINE_NAMES),
+        incremental: args.get_one::<PathBuf>(A_L_INCREMENTAL).cloned(),
+    })
+}
 
+/// Fetches the input variables from the environment,
+/// the variables files and the CLI, in that order.
+fn fetch_vars(args: &ArgMatches, environment: &mut Environment) -> BoxResult<()> {
     // fetch environment variables
     if !args.get_flag(A_L_NO_ENV_IN) {
         log::trace!("Fetching variables from the environment ...");
-        repvar::tools::append_env(&mut environment.vars);
+        let mut env_vars = HashMap::new();
+        repvar::tools::append_env(&mut env_vars);
+        let filter = var::VarFilter {
+            allow: args
+                .get_many::<Regex>(A_L_ENV_ALLOW)
+                .map(|patterns| patterns.cloned().collect())
+                .unwrap_or_default(),
+            deny: args
+                .get_many::<Regex>(A_L_ENV_DENY)
+                .map(|patterns| patterns.cloned().collect())
+                .unwrap_or_default(),
+        };
+        filter.apply(&mut env_vars);
+        environment.vars.extend(env_vars);
     }
+    let interpolate = args.get_flag(A_L_INTERPOLATE);
     // fetch variables files
     if let Some(var_files) = args.get_many::<PathBuf>(A_L_VARIABLES_FILE) {
         for var_file in var_files.cloned() {
@@ -775,19 +2143,106 @@ fn main() -> BoxResult<()> {
                 log::trace!("Fetching variables from file '{}' ...", var_file.display());
             }
             let mut reader = cli_utils::create_input_reader(Some(var_file))?;
-            environment
-                .vars
-                .extend(var::parse_vars_file_reader(&mut reader)?);
+            let mut file_vars = var::parse_vars_file_reader(&mut reader)?;
+            if interpolate {
+                file_vars = var::interpolate_vars(&file_vars, &environment.vars)?;
+            }
+            environment.vars.extend(file_vars);
         }
     }
     // insert CLI supplied variables values
     if let Some(variables) = args.get_many::<(String, String)>(A_L_VARIABLE) {
         for (key, value) in variables {
-            log::trace!("Adding variable from CLI: {}='{}' ...", key, value);
-            environment.vars.insert(key.clone(), value.clone());
+            let value = if interpolate {
+                let new_var = HashMap::from([(key.clone(), value.clone())]);
+                var::interpolate_vars(&new_var, &environment.vars)?
+                    .remove(key)
+                    .unwrap_or_default()
+            } else {
+                value.clone()
+            };
+            log::trace!(
+                "Adding variable from CLI: {}='{}' ...",
+                key,
+                tools::credentials::redact_var(key, &value)
+            );
+            environment.vars.insert(key.clone(), value);
         }
     }
 
-    process::run(&mut environment, sources, sinks)
+    Ok(())
+}
+
+fn run() -> BoxResult<()> {
+    let logger = logger::setup_logging()?;
+    let initial_verbosity = if cfg!(debug_assertions) {
+        Verbosity::Debug
+    } else {
+        Verbosity::Info
+    };
+    logger.set_log_level(initial_verbosity)?;
+
+    let args = arg_matcher().try_get_matches()?;
+
+    if !args.get_flag(A_L_RAW_PANIC) {
+        human_panic::setup_panic!();
+        // panics within sources are caught and reported by us
+        process::isolate_panics();
+    }
+
+    let quiet = args.get_flag(A_L_QUIET);
+
+    let version = args.get_flag(A_L_VERSION);
+    if version {
+        print_version_and_exit(quiet);
+    }
+
+    let verbosity = verbosity(&args);
+    logger.set_log_level(verbosity)?;
+    let log_format = args
+        .get_one::<settings::LogFormat>(A_L_LOG_FORMAT)
+        .copied()
+        .unwrap_or_default();
+    logger.set_log_format(log_format)?;
+    if let Some(log_file) = args.get_one::<PathBuf>(A_L_LOG_FILE) {
+        let log_file_level = args
+            .get_one::<Verbosity>(A_L_LOG_FILE_LEVEL)
+            .copied()
+            .unwrap_or(Verbosity::Trace);
+        logger.set_log_file(log_file, log_file_level)?;
+        log::debug!("Logging to file '{}'.", log_file.display());
+    }
+
+    if args.get_flag(A_L_LIST) {
+        let environment = Environment::stub();
+        let list = var::list_keys(&environment);
+        log::info!("{}", list);
+        return Ok(());
+    }
+
+    if let Some(format) = args.get_one::<settings::GraphFormat>(A_L_GRAPH) {
+        let environment = Environment::stub();
+        log::info!("{}", graph::render(&environment, *format));
+        return Ok(());
+    }
+
+    let repo_path = repo_path(&args);
+    log::trace!("Collecting sources ...");
+    let sources = sources::default_list(&repo_path);
+
+    log::trace!("Collecting sinks ...");
+    let sinks = sinks_cli(&args);
+
+    let settings = settings(&args, repo_path, verbosity, log_format)?;
+    log::trace!("Created Settings.");
+    let mut environment = Environment::new(settings);
+    if args.get_flag(A_L_DRY) || args.contains_id(A_L_DRY_OUT) {
+        environment.cmd_runner = Arc::new(tools::cmd::DryRun);
+    }
+    log::trace!("Created Environment.");
+
+    fetch_vars(&args, &mut environment)?;
+
+    Ok(process::run(&mut environment, sources, sinks)?)
     // Ok(())
 }
@@ -2,66 +2,645 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::cleanup;
 use crate::environment::Environment;
-use crate::settings::FailOn;
+use crate::error::{Error, Result};
+use crate::explain::{Attempt, Explanations};
+use crate::incremental::{self, Previous, Reuse};
+use crate::settings::{FailOn, LogFormat, ShowRetrieved};
 use crate::sinks::VarSink;
-use crate::sources::VarSource;
+use crate::sources::{ConfVal, RetrieveRes, VarSource};
+use crate::storage::Value;
+use crate::ui;
 use crate::validator;
 use crate::validator::Validity;
-use crate::var::Key;
-use cli_utils::BoxResult;
+use crate::var::{self, AnyKey, Key};
+#[cfg(feature = "online")]
+use crate::verify;
+use std::any::Any;
+use std::cell::{Cell, RefCell};
 use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
 use std::fs;
-use strum::IntoEnumIterator;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Instant;
+
+thread_local! {
+    /// Whether code running on this thread is [`isolated`],
+    /// so a panic in it will be caught and reported by us.
+    static ISOLATED: Cell<bool> = const { Cell::new(false) };
+    /// The message of the last panic within [`isolated`] code,
+    /// as recorded by the hook installed with [`isolate_panics`].
+    static LAST_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Installs a panic hook that only records panics within [`isolated`] code.
+///
+/// These are caught and reported by [`run`],
+/// instead of being reported as crashes.
+/// All other panics are left to the previously installed hook.
+pub fn isolate_panics() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if ISOLATED.with(Cell::get) {
+            LAST_PANIC.with(|last| last.replace(Some(info.to_string())));
+        } else {
+            previous(info);
+        }
+    }));
+}
+
+/// Extracts the message of a panic from its payload.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|msg| (*msg).to_owned())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown cause".to_owned())
+}
+
+/// Runs (a part of) a source,
+/// catching a panic in it, which is returned as its message,
+/// so a bug in a single source (or a pathological repo)
+/// does not take down the whole run.
+fn isolated<T>(part: impl FnOnce() -> T) -> std::result::Result<T, String> {
+    ISOLATED.with(|isolated| isolated.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(part));
+    ISOLATED.with(|isolated| isolated.set(false));
+    result.map_err(|payload| {
+        LAST_PANIC
+            .with(RefCell::take)
+            .unwrap_or_else(|| panic_message(&*payload))
+    })
+}
+
+/// Statistics about a run,
+/// reported in a single line at its end.
+#[derive(Debug, Default)]
+struct Summary {
+    /// The number of keys with a value that is stored in the sinks
+    set: usize,
+    /// The required keys for which no (valid) value was found
+    missing: Vec<Key>,
+    /// The number of values that are only suboptimal
+    suboptimal: usize,
+    /// The number of URL values that could not be confirmed,
+    /// if they were verified at all
+    unreachable: Option<usize>,
+    /// The number of times a source failed to retrieve a value,
+    /// which we only continue after with [`crate::settings::Settings::keep_going`]
+    source_failures: usize,
+    /// The number of sources that panicked or took too long,
+    /// and were thus treated as unusable (for the remaining keys),
+    /// see [`isolated`] and [`crate::settings::Settings::source_timeout`]
+    dropped_sources: usize,
+    /// The number of keys with (at least one) value
+    /// that was cut down to its maximum length,
+    /// see [`crate::settings::Settings::length_limits`]
+    truncated: usize,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} keys set, {} missing, {} suboptimal",
+            self.set,
+            self.missing.len(),
+            self.suboptimal
+        )?;
+        if let Some(unreachable) = self.unreachable {
+            write!(f, ", {unreachable} unreachable")?;
+        }
+        if self.source_failures > 0 {
+            write!(f, ", {} source failures", self.source_failures)?;
+        }
+        if self.dropped_sources > 0 {
+            write!(f, ", {} sources dropped", self.dropped_sources)?;
+        }
+        if self.truncated > 0 {
+            write!(f, ", {} values truncated", self.truncated)?;
+        }
+        Ok(())
+    }
+}
 
 /// Reports the raw values retrieved from the sources -
 /// if requested - to the logging system.
-fn log_retrieved(environment: &Environment, sources: &[Box<dyn VarSource>]) -> BoxResult<()> {
-    let retrieved = match &environment.settings.show_retrieved {
-        crate::settings::ShowRetrieved::No => (None, None),
-        crate::settings::ShowRetrieved::Primary(target) => (
-            Some(environment.output.to_list(environment)),
+fn log_retrieved(environment: &Environment, sources: &[Box<dyn VarSource>]) -> Result<()> {
+    let format = environment.settings.retrieved_format;
+    let (retr_content, target) = match (&environment.settings.show_retrieved, format) {
+        (ShowRetrieved::No, _) => return Ok(()),
+        // human-readable output for the terminal
+        (ShowRetrieved::Primary(None), None) => (
+            ui::render_table(
+                &environment.output.primary_table(environment),
+                ui::use_color(&environment.settings),
+            ),
+            None,
+        ),
+        (ShowRetrieved::All(None), None) => (
+            ui::render_table(
+                &environment.output.sources_table(environment, sources),
+                ui::use_color(&environment.settings),
+            ),
+            None,
+        ),
+        (ShowRetrieved::Primary(target), _) => (
+            environment
+                .output
+                .to_list(environment, format.unwrap_or_default()),
             target.as_ref(),
         ),
-        crate::settings::ShowRetrieved::All(target) => (
-            Some(environment.output.to_table(environment, sources)),
+        (ShowRetrieved::All(target), _) => (
+            environment
+                .output
+                .to_table(environment, sources, format.unwrap_or_default()),
             target.as_ref(),
         ),
     };
-    if let (Some(retr_content), target) = retrieved {
-        match target {
-            None => {
-                log::info!("Raw, Retrieved values from sources:\n\n{}", retr_content,);
-            }
-            Some(path) => {
-                fs::write(path, retr_content)?;
-            }
+    match target {
+        None => {
+            log::info!("Raw, Retrieved values from sources:\n\n{}", retr_content,);
+        }
+        Some(path) => {
+            fs::write(path, retr_content)?;
         }
     }
     Ok(())
 }
 
-fn key_missing(environment: &mut Environment, key: Key) -> BoxResult<()> {
-    let required = environment.settings.required_keys.contains(&key);
-    if required {
-        log::warn!("Missing value for required key '{:?}'", key);
-        if matches!(environment.settings.fail_on, FailOn::AnyMissingValue) {
-            return Err(validator::Error::Missing(key).into());
-        }
+/// Reports where the values of the keys to be explained came from -
+/// if any - to the logging system.
+fn log_explanations(
+    environment: &Environment,
+    sources: &[Box<dyn VarSource>],
+    explanations: &Explanations,
+) {
+    let mut keys: Vec<Key> = environment.settings.explain.iter().copied().collect();
+    keys.sort_unstable();
+    for key in keys {
+        log::info!("{}", explanations.render(environment, sources, key));
+    }
+}
+
+/// Creates a tracing span, if we log in JSON format,
+/// which then adds structured context (e.g. the key)
+/// to all log events within it.
+/// In the text format, this would only clutter the output.
+fn structured_span(
+    environment: &Environment,
+    create: impl FnOnce() -> tracing::Span,
+) -> tracing::Span {
+    if environment.settings.log_format == LogFormat::Json {
+        create()
     } else {
-        log::debug!("Missing value for optional key '{:?}'", key);
+        tracing::Span::none()
+    }
+}
+
+fn key_missing(environment: &mut Environment, key: AnyKey, summary: &mut Summary) {
+    // dynamic keys can not be required
+    let required_key = key
+        .builtin()
+        .filter(|key| environment.settings.required_keys.contains(key));
+    if let Some(required_key) = required_key {
+        log::warn!("Missing value for required key '{}'", key);
+        summary.missing.push(required_key);
+    } else {
+        log::debug!("Missing value for optional key '{}'", key);
         if let Some((_confidence, value)) = environment.output.remove(key) {
             log::warn!(
-                "\tDiscarded {:?}='{}', because it was evaluated as a 'missing' value",
+                "\tDiscarded {}='{}', because it was evaluated as a 'missing' value",
                 key,
                 value
             );
         }
     }
+}
+
+/// Verifies the URL values, if requested (see [`verify::run`]),
+/// and returns how many of them were unreachable.
+#[cfg(feature = "online")]
+fn verify_urls(environment: &mut Environment, explanations: &mut Explanations) -> Option<usize> {
+    let verifications = verify::run(environment)?;
+    for verification in &verifications {
+        if environment.settings.explain.contains(&verification.key) {
+            explanations.set_verification(verification.key, verification.to_string());
+        }
+    }
+    Some(
+        verifications
+            .iter()
+            .filter(|verification| verification.verdict.is_failure())
+            .count(),
+    )
+}
+
+#[cfg(not(feature = "online"))]
+fn verify_urls(environment: &Environment, _explanations: &mut Explanations) -> Option<usize> {
+    if environment.settings.verify_urls {
+        log::warn!(
+            "Not verifying URLs, because projvar was compiled without the \"online\" feature"
+        );
+    }
+    None
+}
+
+/// Fails if there are missing required values (with [`FailOn::AnyMissingValue`]),
+/// or suboptimal ones (in strict mode).
+fn check_summary(environment: &Environment, summary: &Summary) -> Result<()> {
+    if let Some(&missing_key) = summary.missing.first() {
+        if matches!(environment.settings.fail_on, FailOn::AnyMissingValue) {
+            return Err(validator::Error::Missing(missing_key).into());
+        }
+    }
+    if environment.settings.strict && summary.suboptimal > 0 {
+        return Err(Error::Suboptimal(summary.suboptimal));
+    }
+    Ok(())
+}
+
+/// How a single attempt to retrieve a value from a source ended.
+enum Retrieval {
+    /// The source returned, with or without a value
+    Returned(RetrieveRes),
+    /// The source panicked, see [`isolated`]
+    Panicked(String),
+    /// The source took too long,
+    /// see [`crate::settings::Settings::source_timeout`]
+    TimedOut,
+}
+
+impl From<std::result::Result<RetrieveRes, String>> for Retrieval {
+    fn from(isolated: std::result::Result<RetrieveRes, String>) -> Self {
+        match isolated {
+            Ok(retrieved) => Self::Returned(retrieved),
+            Err(panic) => Self::Panicked(panic),
+        }
+    }
+}
+
+fn retrieve_now(environment: &mut Environment, source: &dyn VarSource, key: AnyKey) -> RetrieveRes {
+    match key {
+        AnyKey::Builtin(key) => source.retrieve(environment, key),
+        AnyKey::Dynamic(key) => source.retrieve_dynamic(environment, key),
+    }
+}
+
+/// A source, in a form that may be handed to a worker thread.
+struct SharedSource {
+    sources: Arc<Vec<Box<dyn VarSource>>>,
+    index: usize,
+}
+
+impl SharedSource {
+    fn get(&self) -> &dyn VarSource {
+        self.sources
+            .get(self.index)
+            .expect("the index is one of the sources")
+            .as_ref()
+    }
+}
+
+/// Retrieves the value of a single key from a source.
+/// If the time of the source is limited,
+/// this happens on a worker thread,
+/// which is abandoned once the `deadline` passed.
+/// The environment is handed to that thread,
+/// and a copy of it is used from then on if the thread is abandoned.
+fn retrieve(
+    environment: &mut Environment,
+    source: &SharedSource,
+    key: AnyKey,
+    deadline: Option<Instant>,
+) -> Retrieval {
+    let Some(deadline) = deadline else {
+        return isolated(|| retrieve_now(environment, source.get(), key)).into();
+    };
+    if Instant::now() >= deadline {
+        return Retrieval::TimedOut;
+    }
+    let stand_in = environment.copy_without_repo();
+    let mut worker_environment = mem::replace(environment, stand_in);
+    let worker_source = SharedSource {
+        sources: Arc::clone(&source.sources),
+        index: source.index,
+    };
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let retrieved =
+            isolated(|| retrieve_now(&mut worker_environment, worker_source.get(), key));
+        if sender.send((worker_environment, retrieved)).is_err() {
+            log::trace!("Discarding the value for key '{key}' of an abandoned source");
+        }
+    });
+    if let Ok((worker_environment, retrieved)) =
+        receiver.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+    {
+        *environment = worker_environment;
+        retrieved.into()
+    } else {
+        environment.reopen_repo();
+        Retrieval::TimedOut
+    }
+}
+
+/// What we gathered about the sources while fetching values from them,
+/// besides the values themselves.
+#[derive(Default)]
+struct Fetched {
+    explanations: Explanations,
+    /// See [`Summary::source_failures`]
+    source_failures: usize,
+    /// See [`Summary::dropped_sources`]
+    dropped_sources: usize,
+    /// The keys with (at least one) truncated value
+    truncated: HashSet<AnyKey>,
+}
+
+impl Fetched {
+    /// Checks whether a source is usable,
+    /// treating it as unusable if it panics while checking.
+    fn is_usable(&mut self, environment: &mut Environment, source: &dyn VarSource) -> bool {
+        match isolated(|| source.is_usable(environment)) {
+            Ok(usable) => usable,
+            Err(panic) => {
+                log::error!(
+                    "Source {} panicked while checking whether it is usable; \
+                    treating it as unusable: {}",
+                    source.display(),
+                    panic
+                );
+                self.dropped_sources += 1;
+                false
+            }
+        }
+    }
+
+    /// Evaluates the outcome of an attempt to retrieve a value,
+    /// returning the value and the error to be explained, if any.
+    /// If the source panicked or took too long,
+    /// it is marked as not `usable` (for the remaining keys).
+    ///
+    /// # Errors
+    ///
+    /// If the source failed to retrieve the value,
+    /// unless we [`crate::settings::Settings::keep_going`].
+    fn outcome(
+        &mut self,
+        environment: &Environment,
+        source: &dyn VarSource,
+        key: AnyKey,
+        retrieval: Retrieval,
+        usable: &mut bool,
+    ) -> Result<(Option<ConfVal>, Option<String>)> {
+        Ok(match retrieval {
+            Retrieval::Returned(Ok(rated_value)) => (rated_value, None),
+            Retrieval::Returned(Err(err)) if environment.settings.keep_going => {
+                log::warn!(
+                    "Source {} failed to retrieve a value for key '{}'; \
+                    continuing with the other sources: {}",
+                    source.display(),
+                    key,
+                    err
+                );
+                self.source_failures += 1;
+                (None, Some(err.to_string()))
+            }
+            Retrieval::Returned(Err(err)) => return Err(err.into()),
+            Retrieval::Panicked(panic) => {
+                log::error!(
+                    "Source {} panicked while retrieving a value for key '{}'; \
+                    treating it as unusable for the remaining keys: {}",
+                    source.display(),
+                    key,
+                    panic
+                );
+                self.dropped_sources += 1;
+                *usable = false;
+                (None, Some(format!("panicked: {panic}")))
+            }
+            Retrieval::TimedOut => {
+                log::warn!(
+                    "Source {} took longer than {} seconds; \
+                    treating it as unusable for the remaining keys",
+                    source.display(),
+                    environment
+                        .settings
+                        .source_timeout
+                        .unwrap_or_default()
+                        .as_secs()
+                );
+                self.dropped_sources += 1;
+                *usable = false;
+                (None, Some("timed out".to_owned()))
+            }
+        })
+    }
+}
+
+/// Adds a value retrieved from a source to the output.
+///
+/// # Errors
+///
+/// If the value disagrees with the one of an other source,
+/// and we require [`crate::settings::Settings::strict_consistency`].
+fn add_value(
+    environment: &mut Environment,
+    key: AnyKey,
+    source_index: usize,
+    (confidence, value): ConfVal,
+) -> Result<()> {
+    log::trace!("\tFetched {}='{}'", key, value);
+    if let Some(discrepancy) = environment.output.add(key, source_index, confidence, value) {
+        if !discrepancy.is_significant() {
+            log::debug!("{discrepancy}");
+        } else if environment.settings.strict_consistency {
+            log::error!("{discrepancy}");
+            return Err(discrepancy.into());
+        } else {
+            log::info!("{discrepancy}");
+        }
+    }
+    Ok(())
+}
+
+/// Fetches the values of all the keys from a single source.
+///
+/// # Errors
+///
+/// See [`Fetched::outcome`] and [`add_value`].
+fn fetch_from_source(
+    environment: &mut Environment,
+    shared_source: &SharedSource,
+    previous: Option<&Previous>,
+    fetched: &mut Fetched,
+) -> Result<()> {
+    let source = shared_source.get();
+    let source_index = shared_source.index;
+    let mut usable = fetched.is_usable(environment, source);
+    if usable {
+        log::trace!("Trying to fetch from source {} ...", source.display());
+    }
+    let deadline = environment
+        .settings
+        .source_timeout
+        .map(|timeout| Instant::now() + timeout);
+    for key in environment.keys() {
+        let explained = key
+            .builtin()
+            .filter(|key| environment.settings.explain.contains(key));
+        if !usable {
+            if let Some(explained) = explained {
+                fetched.explanations.add_attempt(
+                    explained,
+                    Attempt {
+                        source_index,
+                        usable,
+                        var_reads: vec![],
+                        value: None,
+                        error: None,
+                    },
+                );
+            }
+            continue;
+        }
+        let _span = structured_span(
+            environment,
+            || tracing::info_span!("retrieve", key = %key, source = %source.display()),
+        )
+        .entered();
+        if explained.is_some() {
+            environment.start_recording_var_reads();
+        }
+        let retrieval = match previous.map_or(Reuse::Recompute, |previous| {
+            previous.get(source, source_index, key)
+        }) {
+            Reuse::Unchanged(rated_value) => Retrieval::Returned(Ok(rated_value)),
+            Reuse::Recompute => retrieve(environment, shared_source, key, deadline),
+        };
+        let (rated_value, error) =
+            fetched.outcome(environment, source, key, retrieval, &mut usable)?;
+        let rated_value = match rated_value {
+            Some(rated_value) => {
+                let rated_value = cleanup::conf_unicode(key, rated_value);
+                let (rated_value, was_truncated) =
+                    cleanup::conf_length(environment, key, rated_value)?;
+                if was_truncated {
+                    fetched.truncated.insert(key);
+                }
+                Some(rated_value)
+            }
+            None => None,
+        };
+        if let Some(explained) = explained {
+            fetched.explanations.add_attempt(
+                explained,
+                Attempt {
+                    source_index,
+                    usable,
+                    var_reads: environment.take_var_reads(),
+                    value: rated_value.clone(),
+                    error,
+                },
+            );
+        }
+        if let Some(rated_value) = rated_value {
+            add_value(environment, key, source_index, rated_value)?;
+        }
+    }
+    Ok(())
+}
 
+/// Validates the value of each key,
+/// dropping the ones evaluated as missing.
+///
+/// # Errors
+///
+/// If a validator fails.
+fn validate(
+    environment: &mut Environment,
+    explanations: &mut Explanations,
+    summary: &mut Summary,
+) -> Result<()> {
+    log::trace!("Validate each variables presence and value ...");
+    let output = environment.output.clone();
+    for key in environment.keys() {
+        let _span =
+            structured_span(environment, || tracing::info_span!("validate", key = %key)).entered();
+        match output.get(key) {
+            Some((_confidence, value)) => {
+                log::trace!("Validating value for key '{}': '{}'", key, value);
+                let validation_res = validator::get(key)(environment, value);
+                match validation_res {
+                    Ok(validity) => {
+                        log::debug!("Validation result for key '{}': {:?}", key, validity);
+                        if let Some(key) = key
+                            .builtin()
+                            .filter(|key| environment.settings.explain.contains(key))
+                        {
+                            explanations.set_validity(key, format!("{validity:?}"));
+                        }
+                        match &validity {
+                            Validity::RequiresAuth { msg } => {
+                                log::warn!("Value for key '{key}' requires authentication: {msg}");
+                            }
+                            Validity::Suboptimal { msg, source: _ } => {
+                                log::warn!("Value for key '{key}' is suboptimal: {msg}");
+                                summary.suboptimal += 1;
+                            }
+                            Validity::Missing => key_missing(environment, key, summary),
+                            Validity::High { .. }
+                            | Validity::Middle { .. }
+                            | Validity::Low { .. }
+                            | Validity::Unknown => (),
+                        }
+                    }
+                    Err(err) => {
+                        log::error!("Validation result for key '{}': {:?}", key, err);
+                        return Err(err.into());
+                    }
+                }
+            }
+            None => key_missing(environment, key, summary),
+        }
+    }
     Ok(())
 }
 
+/// Reports the evaluated values -
+/// if requested - to the logging system.
+fn log_evaluated(environment: &Environment, values: &[Value]) {
+    if !log::log_enabled!(log::Level::Trace) {
+        return;
+    }
+    log::trace!("Evaluated variables ...");
+    for (key, variable, (confidence, value)) in values {
+        let sinking = if environment.settings.only_required
+            && !key
+                .builtin()
+                .is_some_and(|key| environment.settings.required_keys.contains(&key))
+        {
+            "output"
+        } else {
+            "!output"
+        };
+        log::trace!(
+            "\t{}:{}:{}:{}='{}' ",
+            key,
+            variable.key(environment),
+            confidence,
+            sinking,
+            &value
+        );
+    }
+}
+
 /// The main function of this crate,
 /// gathering data as good as it can,
 /// and making sure it is stored in the appropriate environment variables.
@@ -74,11 +653,14 @@ fn key_missing(environment: &mut Environment, key: Key) -> BoxResult<()> {
 /// for a specific key fails.
 ///
 /// Writing to the environment fails.
+///
+/// A required value is missing (with [`FailOn::AnyMissingValue`]),
+/// or a value is only suboptimal (in strict mode).
 pub fn run(
     environment: &mut Environment,
     mut sources: Vec<Box<dyn VarSource>>,
     sinks: Vec<Box<dyn VarSink>>,
-) -> BoxResult<()> {
+) -> Result<()> {
     sources.sort_unstable_by(|s1, s2| {
         let o_hierarchy = s1.hierarchy().cmp(&s2.hierarchy());
         if Ordering::Equal == o_hierarchy {
@@ -93,76 +675,83 @@ pub fn run(
             o_hierarchy
         }
     });
+    // shared with the worker threads, see `retrieve`
+    let sources = Arc::new(sources);
 
-    for (source_index, source) in sources.iter().enumerate() {
-        if source.is_usable(environment) {
-            log::trace!("Trying to fetch from source {} ...", source.display());
-            for key in Key::iter() {
-                let rated_value = source.retrieve(environment, key)?;
-                if let Some((confidence, value)) = rated_value {
-                    log::trace!("\tFetched {:?}='{}'", key, value);
-                    environment.output.add(key, source_index, confidence, value);
-                }
-            }
-        }
+    let previous = environment
+        .settings
+        .incremental
+        .as_deref()
+        .and_then(|file| Previous::load(file, environment, &sources));
+    let mut fetched = Fetched::default();
+    for source_index in 0..sources.len() {
+        let shared_source = SharedSource {
+            sources: Arc::clone(&sources),
+            index: source_index,
+        };
+        fetch_from_source(environment, &shared_source, previous.as_ref(), &mut fetched)?;
     }
+    let Fetched {
+        mut explanations,
+        source_failures,
+        dropped_sources,
+        truncated,
+    } = fetched;
 
+    let retrieved = environment
+        .settings
+        .incremental
+        .is_some()
+        .then(|| environment.output.snapshot());
     log_retrieved(environment, &sources)?;
 
-    log::trace!("Validate each variables presence and value ...");
-    let output = environment.output.clone();
-    for key in Key::iter() {
-        match output.get(key) {
-            Some((_confidence, value)) => {
-                log::trace!("Validating value for key '{:?}': '{}'", key, value);
-                let validation_res = validator::get(key)(environment, value);
-                match validation_res {
-                    Ok(validity) => {
-                        log::debug!("Validation result for key '{:?}': {:?}", key, validity);
-                        if matches!(validity, Validity::Missing) {
-                            key_missing(environment, key)?;
-                        }
-                    }
-                    Err(err) => {
-                        log::error!("Validation result for key '{:?}': {:?}", key, err);
-                        return Err(Box::new(err));
-                    }
-                }
-            }
-            None => key_missing(environment, key)?,
-        }
-    }
+    let unreachable = verify_urls(environment, &mut explanations);
+
+    let mut summary = Summary {
+        unreachable,
+        source_failures,
+        dropped_sources,
+        truncated: truncated.len(),
+        ..Summary::default()
+    };
+    validate(environment, &mut explanations, &mut summary)?;
+
+    log_explanations(environment, &sources, &explanations);
 
     let values = environment.output.get_wrapup();
-    if log::log_enabled!(log::Level::Trace) {
-        log::trace!("Evaluated variables ...");
-        for (key, variable, (confidence, value)) in &values {
-            let sinking = if environment.settings.only_required
-                && !environment.settings.required_keys.contains(key)
-            {
-                "output"
-            } else {
-                "!output"
-            };
-            log::trace!(
-                "\t{:?}:{}:{}:{}='{}' ",
-                key,
-                variable.key(environment),
-                confidence,
-                sinking,
-                &value
-            );
-        }
-    }
+    log_evaluated(environment, &values);
 
-    let sink_values = if environment.settings.only_required {
+    let mut sink_values = if environment.settings.only_required {
         values
             .into_iter()
-            .filter(|val| environment.settings.required_keys.contains(&val.0))
+            .filter(|val| {
+                val.0
+                    .builtin()
+                    .is_some_and(|key| environment.settings.required_keys.contains(&key))
+            })
             .collect()
     } else {
         values
     };
+    summary.set = sink_values.len();
+    if let Err(err) = check_summary(environment, &summary) {
+        log::info!("{summary}");
+        return Err(err);
+    }
+    if environment.settings.source_date_epoch_out {
+        if let Some(epoch) = environment.output.get(Key::BuildDateEpoch) {
+            sink_values.push((
+                Key::BuildDateEpoch.into(),
+                &var::VAR_SOURCE_DATE_EPOCH,
+                epoch,
+            ));
+        } else {
+            log::warn!(
+                "Not writing {}, as the build date is unknown",
+                var::VAR_SOURCE_DATE_EPOCH
+            );
+        }
+    }
 
     for ref sink in sinks {
         log::trace!("Checking if sink {} is usable ...", sink);
@@ -172,7 +761,78 @@ pub fn run(
         }
     }
 
+    if let (Some(file), Some(retrieved)) = (&environment.settings.incremental, retrieved) {
+        incremental::store(file, environment, &sources, retrieved)?;
+    }
+
+    log::info!("{summary}");
     log::trace!("Done.");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::STUB;
+    use crate::sources::Hierarchy;
+    use crate::var::ConfidenceScore;
+    use std::time::Duration;
+
+    /// Takes ages to retrieve the version.
+    struct Slow;
+
+    impl VarSource for Slow {
+        fn is_usable(&self, _environment: &mut Environment) -> bool {
+            true
+        }
+
+        fn hierarchy(&self) -> Hierarchy {
+            Hierarchy::High
+        }
+
+        fn type_name(&self) -> &'static str {
+            "Slow"
+        }
+
+        fn properties(&self) -> &Vec<String> {
+            static NO_PROPERTIES: Vec<String> = Vec::new();
+            &NO_PROPERTIES
+        }
+
+        fn retrieve(&self, _environment: &mut Environment, key: Key) -> RetrieveRes {
+            if key == Key::Version {
+                thread::sleep(Duration::from_secs(30));
+            }
+            Ok(Some((ConfidenceScore::HIGH, "slow".to_owned())))
+        }
+    }
+
+    #[test]
+    fn test_isolated() {
+        assert_eq!(isolated(|| 42), Ok(42));
+        let res: std::result::Result<(), String> = isolated(|| panic!("pathological repo"));
+        assert!(res.is_err_and(|msg| msg.contains("pathological repo")));
+        // not isolated anymore afterwards
+        assert!(!ISOLATED.with(Cell::get));
+    }
+
+    #[test]
+    fn test_source_timeout() {
+        let mut settings = STUB.clone();
+        settings.source_timeout = Some(Duration::from_secs(1));
+        let mut environment = Environment::new(settings);
+        let source = SharedSource {
+            sources: Arc::new(vec![Box::new(Slow)]),
+            index: 0,
+        };
+        let mut fetched = Fetched::default();
+        let started = Instant::now();
+        fetch_from_source(&mut environment, &source, None, &mut fetched).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(fetched.dropped_sources, 1);
+        // fetched before the source was abandoned
+        assert!(environment.output.get(Key::BuildArch).is_some());
+        assert!(environment.output.get(Key::Version).is_none());
+    }
+}
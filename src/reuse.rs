@@ -0,0 +1,299 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! A lightweight check for compliance with the REUSE specification
+//! (<https://reuse.software>):
+//! Every file has to come with copyright and licensing information,
+//! either in its header, in an accompanying "<file>.license" file,
+//! or through a [`FILE_DEP5`] or [`FILE_REUSE_TOML`] file.
+//!
+//! This is not a replacement for the `reuse lint` tool;
+//! most notably, it does not check whether the licenses used
+//! are present in the "LICENSES" directory,
+//! and it only considers the [`FILE_REUSE_TOML`] in the project root.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::tools::{git, git_attributes};
+
+/// The (deprecated) Debian copyright file, relative to the project root.
+const FILE_DEP5: &str = ".reuse/dep5";
+/// The file annotating files in bulk, relative to the project root.
+const FILE_REUSE_TOML: &str = "REUSE.toml";
+/// The tags marking the copyright information in a file;
+/// any one of them suffices.
+const TAGS_COPYRIGHT: &[&str] = &["SPDX-FileCopyrightText:", "Copyright", "©"];
+/// The tag marking the licensing information in a file.
+const TAG_LICENSE: &str = "SPDX-License-Identifier:";
+/// The keys of an annotation in the [`FILE_REUSE_TOML`].
+const TOML_KEY_PATH: &str = "path";
+const TOML_KEY_COPYRIGHT: &str = "SPDX-FileCopyrightText";
+const TOML_KEY_LICENSE: &str = "SPDX-License-Identifier";
+
+lazy_static! {
+    /// The files that do not need licensing information themselves,
+    /// as they contain licenses, or licensing information for other files.
+    static ref R_IGNORED: Regex = Regex::new(
+        r"(^|/)(LICEN[CS]E([-.][^/]*)?|COPYING([-.][^/]*)?|REUSE\.toml)$|^LICENSES/|^\.reuse/dep5$|\.license$"
+    ).unwrap();
+}
+
+/// How many of the files of a project are covered
+/// by copyright and licensing information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compliance {
+    pub covered: usize,
+    pub total: usize,
+}
+
+impl Compliance {
+    /// The share of covered files, in percent, rounded down,
+    /// so 100 is only reached if all the files are covered.
+    /// Returns `None` if there are no files at all.
+    #[must_use]
+    pub const fn percentage(&self) -> Option<usize> {
+        (self.covered * 100).checked_div(self.total)
+    }
+}
+
+/// Whether the text contains both copyright and licensing information.
+fn has_info(text: &str) -> bool {
+    text.contains(TAG_LICENSE) && TAGS_COPYRIGHT.iter().any(|tag| text.contains(tag))
+}
+
+/// Parses the [`FILE_DEP5`] file,
+/// returning the patterns of the files covered by
+/// both copyright and licensing information.
+fn parse_dep5(content: &str) -> Vec<Regex> {
+    let mut patterns = vec![];
+    for paragraph in content.split("\n\n") {
+        let mut files = None;
+        let mut copyright = false;
+        let mut license = false;
+        for line in paragraph.lines() {
+            if let Some(value) = line.strip_prefix("Files:") {
+                files = Some(value);
+            } else if line.starts_with("Copyright:") {
+                copyright = true;
+            } else if line.starts_with("License:") {
+                license = true;
+            } else {
+                // other fields, e.g. "Comment:", or continuation lines
+            }
+        }
+        if let (Some(files), true, true) = (files, copyright, license) {
+            patterns.extend(
+                files
+                    .split_whitespace()
+                    .filter_map(|pattern| git::glob_to_regex(pattern).ok()),
+            );
+        }
+    }
+    patterns
+}
+
+/// Parses the [`FILE_REUSE_TOML`] file,
+/// returning the patterns of the files covered by
+/// both copyright and licensing information.
+fn parse_reuse_toml(content: &str) -> Vec<Regex> {
+    let table = match content.parse::<toml::Table>() {
+        Ok(table) => table,
+        Err(err) => {
+            log::warn!("Failed to parse '{FILE_REUSE_TOML}': {err}");
+            return vec![];
+        }
+    };
+    let Some(annotations) = table.get("annotations").and_then(toml::Value::as_array) else {
+        return vec![];
+    };
+    annotations
+        .iter()
+        .filter_map(toml::Value::as_table)
+        .filter(|annotation| {
+            annotation.contains_key(TOML_KEY_COPYRIGHT) && annotation.contains_key(TOML_KEY_LICENSE)
+        })
+        .filter_map(|annotation| annotation.get(TOML_KEY_PATH))
+        .flat_map(|paths| match paths {
+            toml::Value::String(path) => vec![path.as_str()],
+            toml::Value::Array(paths) => paths.iter().filter_map(toml::Value::as_str).collect(),
+            toml::Value::Integer(_)
+            | toml::Value::Float(_)
+            | toml::Value::Boolean(_)
+            | toml::Value::Datetime(_)
+            | toml::Value::Table(_) => vec![],
+        })
+        .filter_map(|path| git_attributes::pattern_to_regex(path).ok())
+        .collect()
+}
+
+/// Lists all the files below `root`,
+/// relative to it and separated by '/',
+/// leaving out git meta-data.
+/// This is used if the project is not a git repo;
+/// otherwise we only consider the tracked files.
+///
+/// # Errors
+///
+/// If a directory could not be read.
+pub fn list_files(root: &Path) -> io::Result<Vec<String>> {
+    let mut files = vec![];
+    let mut dirs = vec![String::new()];
+    while let Some(rel_dir) = dirs.pop() {
+        for entry in fs::read_dir(root.join(&rel_dir))? {
+            let entry = entry?;
+            let Some(file_name) = entry.file_name().to_str().map(ToOwned::to_owned) else {
+                continue;
+            };
+            let rel_path = if rel_dir.is_empty() {
+                file_name.clone()
+            } else {
+                format!("{rel_dir}/{file_name}")
+            };
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                if file_name != ".git" {
+                    dirs.push(rel_path);
+                }
+            } else if file_type.is_file() {
+                files.push(rel_path);
+            } else {
+                // symlinks and other special files are skipped
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Checks which of the given files
+/// (relative to `root` and separated by '/')
+/// are covered by copyright and licensing information.
+/// Files that can not be read (e.g. because they were deleted),
+/// empty files,
+/// and files that do not need licensing information
+/// (like the license texts themselves) are not counted.
+#[must_use]
+pub fn compliance(root: &Path, files: &[String]) -> Compliance {
+    let mut bulk_patterns = fs::read_to_string(root.join(FILE_DEP5))
+        .map(|content| parse_dep5(&content))
+        .unwrap_or_default();
+    bulk_patterns.extend(
+        fs::read_to_string(root.join(FILE_REUSE_TOML))
+            .map(|content| parse_reuse_toml(&content))
+            .unwrap_or_default(),
+    );
+    let mut result = Compliance {
+        covered: 0,
+        total: 0,
+    };
+    for rel_path in files {
+        if R_IGNORED.is_match(rel_path) {
+            continue;
+        }
+        let Ok(content) = fs::read(root.join(rel_path)) else {
+            continue;
+        };
+        if content.is_empty() {
+            continue;
+        }
+        result.total += 1;
+        let covered = bulk_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(rel_path))
+            || has_info(&String::from_utf8_lossy(&content))
+            || fs::read_to_string(root.join(format!("{rel_path}.license")))
+                .is_ok_and(|sidecar| has_info(&sidecar));
+        if covered {
+            result.covered += 1;
+        } else {
+            log::debug!("No REUSE compliant copyright and licensing information for '{rel_path}'");
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignored() {
+        assert!(R_IGNORED.is_match("LICENSE"));
+        assert!(R_IGNORED.is_match("LICENSE-MIT"));
+        assert!(R_IGNORED.is_match("crates/core/COPYING.txt"));
+        assert!(R_IGNORED.is_match("LICENSES/MIT.txt"));
+        assert!(R_IGNORED.is_match("logo.png.license"));
+        assert!(R_IGNORED.is_match("REUSE.toml"));
+        assert!(R_IGNORED.is_match(".reuse/dep5"));
+        assert!(!R_IGNORED.is_match("src/license.rs"));
+        assert!(!R_IGNORED.is_match("LICENSES.md"));
+    }
+
+    #[test]
+    fn test_parse_dep5() {
+        let patterns = parse_dep5(
+            "Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/\n\
+            Upstream-Name: projvar\n\
+            \n\
+            Files: res/* *.json\n\
+            Copyright: 2021 Robin Vobruba <hoijui.quaero@gmail.com>\n\
+            License: CC0-1.0\n\
+            \n\
+            Files: docs/*\n\
+            License: CC0-1.0\n",
+        );
+        assert_eq!(patterns.len(), 2);
+        assert!(patterns
+            .iter()
+            .any(|pattern| pattern.is_match("res/img/logo.svg")));
+        assert!(patterns
+            .iter()
+            .any(|pattern| pattern.is_match("data/x.json")));
+        assert!(!patterns
+            .iter()
+            .any(|pattern| pattern.is_match("docs/index.md")));
+    }
+
+    #[test]
+    fn test_parse_reuse_toml() {
+        let patterns = parse_reuse_toml(
+            r#"version = 1
+
+[[annotations]]
+path = ["res/**", "*.json"]
+SPDX-FileCopyrightText = "2024 Robin Vobruba <hoijui.quaero@gmail.com>"
+SPDX-License-Identifier = "CC0-1.0"
+
+[[annotations]]
+path = "docs/**"
+SPDX-License-Identifier = "CC0-1.0"
+"#,
+        );
+        assert_eq!(patterns.len(), 2);
+        assert!(patterns
+            .iter()
+            .any(|pattern| pattern.is_match("res/img/logo.svg")));
+        assert!(patterns.iter().any(|pattern| pattern.is_match("data.json")));
+        assert!(!patterns
+            .iter()
+            .any(|pattern| pattern.is_match("data/x.json")));
+        assert!(!patterns
+            .iter()
+            .any(|pattern| pattern.is_match("docs/index.md")));
+    }
+
+    #[test]
+    fn test_percentage() {
+        let compliance = |covered, total| Compliance { covered, total };
+        assert_eq!(compliance(0, 0).percentage(), None);
+        assert_eq!(compliance(3, 3).percentage(), Some(100));
+        assert_eq!(compliance(199, 200).percentage(), Some(99));
+        assert_eq!(compliance(0, 7).percentage(), Some(0));
+    }
+}
@@ -2,22 +2,34 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, TimeZone, Utc};
+#[cfg(feature = "cli")]
 use clap::ValueEnum;
 use lazy_static::lazy_static;
-use std::{collections::HashSet, path::PathBuf};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Mutex,
+    time::Duration,
+};
 use strum::IntoEnumIterator;
 use strum_macros::{EnumIter, EnumString, IntoStaticStr, VariantNames};
 use url::Url;
 
 use crate::{
+    config::UrlTemplates,
     constants,
     tools::git_hosting_provs::{HostingType, PublicSite},
-    var::Key,
+    validator::Policy,
+    var::{self, AnyKey, Key},
 };
 
 #[derive(
     Debug,
-    ValueEnum,
     EnumString,
     VariantNames,
     EnumIter,
@@ -27,7 +39,10 @@ use crate::{
     PartialOrd,
     Copy,
     Clone,
+    Serialize,
+    Deserialize,
 )]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
 pub enum Verbosity {
     None,
     Errors,
@@ -79,7 +94,83 @@ impl From<bool> for Verbosity {
     }
 }
 
-#[derive(Debug, ValueEnum, EnumString, VariantNames, IntoStaticStr, Clone, Copy)]
+/// The format of the log output.
+#[derive(
+    Debug,
+    EnumString,
+    VariantNames,
+    IntoStaticStr,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+pub enum LogFormat {
+    /// Human-readable lines of text
+    #[default]
+    Text,
+    /// One JSON object per line,
+    /// including the key and source being evaluated as fields
+    Json,
+}
+
+/// The case of the variable keys written to the sinks,
+/// applied after the key prefix.
+#[derive(
+    Debug,
+    EnumString,
+    VariantNames,
+    IntoStaticStr,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+// NOTE The docs of the variants are shown verbatim in the CLI help
+#[allow(clippy::doc_markdown)]
+pub enum KeyCase {
+    /// e.g. "PROJECT_REPO_WEB_URL"
+    #[default]
+    UpperSnake,
+    /// e.g. "project_repo_web_url"
+    LowerSnake,
+    /// e.g. "ProjectRepoWebUrl"
+    Camel,
+    /// e.g. "project-repo-web-url"
+    Kebab,
+}
+
+impl KeyCase {
+    /// Converts an `"UPPER_SNAKE_CASE"` key into this case.
+    ///
+    /// ```
+    /// # use projvar::settings::KeyCase;
+    /// assert_eq!(KeyCase::UpperSnake.apply("PROJECT_WEB_URL".into()), "PROJECT_WEB_URL");
+    /// assert_eq!(KeyCase::LowerSnake.apply("PROJECT_WEB_URL".into()), "project_web_url");
+    /// assert_eq!(KeyCase::Camel.apply("PROJECT_WEB_URL".into()), "ProjectWebUrl");
+    /// assert_eq!(KeyCase::Kebab.apply("PROJECT_WEB_URL".into()), "project-web-url");
+    /// ```
+    #[must_use]
+    pub fn apply(self, key: Cow<str>) -> Cow<str> {
+        match self {
+            Self::UpperSnake => key,
+            Self::LowerSnake => Cow::Owned(key.to_lowercase()),
+            Self::Camel => Cow::Owned(var::upper_snake_to_camel_case(&key)),
+            Self::Kebab => Cow::Owned(key.to_lowercase().replace('_', "-")),
+        }
+    }
+}
+
+#[derive(Debug, EnumString, VariantNames, IntoStaticStr, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
 pub enum Overwrite {
     All,
     None,
@@ -115,7 +206,93 @@ impl Default for Overwrite {
 /*     const VARIANTS: &'static [&'static str]; */
 /* } */
 
-#[derive(Clone, Copy, Debug)]
+/// The time zone in which generated dates are represented.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DateTimezone {
+    Utc,
+    #[default]
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl FromStr for DateTimezone {
+    type Err = String;
+
+    fn from_str(tz: &str) -> Result<Self, Self::Err> {
+        match tz.to_lowercase().as_str() {
+            "utc" | "z" => Ok(Self::Utc),
+            "local" => Ok(Self::Local),
+            _ => FixedOffset::from_str(tz)
+                .map(Self::Fixed)
+                .map_err(|err| format!("Not a valid time zone (utc|local|+HH:MM): '{tz}' - {err}")),
+        }
+    }
+}
+
+/// Serializes to the same string it is parsed from,
+/// e.g. "utc" or "+02:00".
+impl Serialize for DateTimezone {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTimezone {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tz = String::deserialize(deserializer)?;
+        Self::from_str(&tz).map_err(serde::de::Error::custom)
+    }
+}
+
+impl DateTimezone {
+    /// Formats the given date in this time zone.
+    #[must_use]
+    pub fn format<Tz: TimeZone>(self, date: &DateTime<Tz>, date_format: &str) -> String {
+        match self {
+            Self::Utc => date.with_timezone(&Utc).format(date_format).to_string(),
+            Self::Local => date.with_timezone(&Local).format(date_format).to_string(),
+            Self::Fixed(offset) => date.with_timezone(&offset).format(date_format).to_string(),
+        }
+    }
+
+    /// Interprets a date without time zone as being in this time zone.
+    /// Returns `None` if it does not exist in this time zone,
+    /// e.g. because it is skipped by a daylight saving time transition.
+    #[must_use]
+    pub fn from_naive(self, naive: &NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Self::Utc => Some(naive.and_utc().fixed_offset()),
+            Self::Local => Local
+                .from_local_datetime(naive)
+                .earliest()
+                .map(|date| date.fixed_offset()),
+            Self::Fixed(offset) => offset.from_local_datetime(naive).single(),
+        }
+    }
+
+    /// The offset from UTC,
+    /// if it is the same for all dates in this time zone.
+    #[must_use]
+    pub const fn fixed_offset(self) -> Option<FixedOffset> {
+        match self {
+            Self::Utc => FixedOffset::east_opt(0),
+            Self::Local => None, // This may change with daylight saving time
+            Self::Fixed(offset) => Some(offset),
+        }
+    }
+}
+
+impl Display for DateTimezone {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Utc => write!(formatter, "utc"),
+            Self::Local => write!(formatter, "local"),
+            Self::Fixed(offset) => write!(formatter, "{offset}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum FailOn {
     AnyMissingValue,
     Error,
@@ -131,29 +308,349 @@ impl From<bool> for FailOn {
     }
 }
 
-#[derive(Clone, Debug)]
+/// The format of the overview of the retrieved values,
+/// see [`ShowRetrieved`].
+#[derive(
+    Debug,
+    EnumString,
+    VariantNames,
+    IntoStaticStr,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+pub enum TableFormat {
+    #[default]
+    Markdown,
+    Html,
+    Json,
+    Csv,
+}
+
+impl TableFormat {
+    /// Evaluates the format from the extension of a file name,
+    /// if it is one we know.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "html" | "htm" => Some(Self::Html),
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// How values spanning multiple lines are written to ENV files,
+/// see [`crate::sinks::file::VarSink`].
+/// Either way, the files are read back correctly,
+/// see [`crate::var::parse_vars_file_reader`].
+#[derive(
+    Debug,
+    EnumString,
+    VariantNames,
+    IntoStaticStr,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+pub enum MultilineStyle {
+    /// All values in double quotes, with line breaks kept as they are (BASH compatible)
+    #[default]
+    Quoted,
+    /// Values that need quoting as heredocs, all others unquoted (as in GitHub Actions `$GITHUB_ENV`)
+    Heredoc,
+}
+
+/// The format of the key derivation graph,
+/// see [`crate::graph`].
+#[derive(Debug, EnumString, VariantNames, IntoStaticStr, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+/// What to do with a value that is longer than its maximum length,
+/// see [`LengthLimit`].
+#[derive(Debug, EnumIter, IntoStaticStr, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LengthPolicy {
+    /// Keep the value as it is, but log a warning
+    Warn,
+    /// Cut the value down to the maximum length, and log a warning
+    Truncate,
+    /// Fail, just like with an invalid value
+    Error,
+}
+
+impl FromStr for LengthPolicy {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let name_normalized = name.trim().to_lowercase();
+        Self::iter()
+            .find(|&variant| <&str>::from(variant).to_lowercase() == name_normalized)
+            .ok_or_else(|| {
+                format!("Unknown length policy '{name}'; use 'warn', 'truncate' or 'error'")
+            })
+    }
+}
+
+/// The maximum length (in characters) of the values of a key,
+/// and what to do with values exceeding it,
+/// see [`Settings::length_limits`] and [`crate::cleanup::conf_length`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LengthLimit {
+    pub max: usize,
+    pub policy: LengthPolicy,
+}
+
+/// The limit for keys without one configured:
+/// Values longer than [`constants::DEFAULT_MAX_LENGTH`]
+/// can not be stored in environment variables on Windows,
+/// but they may be fine for all other sinks,
+/// so we only warn about them.
+impl Default for LengthLimit {
+    fn default() -> Self {
+        Self {
+            max: constants::DEFAULT_MAX_LENGTH,
+            policy: LengthPolicy::Warn,
+        }
+    }
+}
+
+impl FromStr for LengthLimit {
+    type Err = String;
+
+    /// Parses a `LENGTH[:POLICY]` length limit,
+    /// with `POLICY` being anything accepted by [`LengthPolicy::from_str`],
+    /// and defaulting to [`LengthPolicy::Truncate`].
+    fn from_str(limit: &str) -> Result<Self, Self::Err> {
+        let (max, policy) = limit
+            .split_once(':')
+            .map_or((limit, None), |(max, policy)| (max, Some(policy)));
+        let max = max
+            .trim()
+            .parse::<usize>()
+            .map_err(|err| format!("Invalid maximum length in '{limit}': {err}"))?;
+        let policy = policy
+            .map(LengthPolicy::from_str)
+            .transpose()?
+            .unwrap_or(LengthPolicy::Truncate);
+        Ok(Self { max, policy })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ShowRetrieved {
     No,
     Primary(Option<PathBuf>),
     All(Option<PathBuf>),
 }
 
-#[derive(Clone, Debug)]
+/// Memoizes the hosting types of hosts (including the port, if any),
+/// see [`Settings::hosting_type_from_host`].
+///
+/// Unknown hosting types are not memoized,
+/// so mappings added to [`Settings::hosting_domains`] later on still apply.
+/// A clone starts out empty,
+/// as settings are usually cloned to be modified.
+#[derive(Debug, Default)]
+pub struct HostingTypeCache(Mutex<HashMap<(String, Option<u16>), HostingType>>);
+
+impl HostingTypeCache {
+    /// Returns the cached hosting type of the host,
+    /// or evaluates and caches it, if there is none yet
+    /// and it is known.
+    fn get_or_evaluate(
+        &self,
+        host: &str,
+        port: Option<u16>,
+        evaluate: impl FnOnce() -> HostingType,
+    ) -> HostingType {
+        let key = (host.to_owned(), port);
+        if let Some(hosting_type) = self
+            .0
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&key).copied())
+        {
+            return hosting_type;
+        }
+        let hosting_type = evaluate();
+        if hosting_type != HostingType::Unknown {
+            if let Ok(mut cache) = self.0.lock() {
+                cache.insert(key, hosting_type);
+            }
+        }
+        hosting_type
+    }
+}
+
+impl Clone for HostingTypeCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Settings /*<S: ::std::hash::BuildHasher>*/ {
     // pub repo_path: Option<Box<Path>>,
     pub repo_path: Option<PathBuf>,
     pub required_keys: HashSet<Key>,
     pub overwrite: Overwrite,
+    /// Whether to keep the values already present in output files
+    /// (of the formats that support it),
+    /// only adding or updating the newly evaluated ones
+    /// according to [`Self::overwrite`].
+    /// If `false`, output files are written from scratch.
+    pub merge: bool,
+    /// The (`strftime`) format of generated dates,
+    /// unless overridden for a specific key,
+    /// see [`Self::date_format_for`].
     pub date_format: String,
+    /// The date format for [`Key::BuildDate`], if it differs from [`Self::date_format`].
+    pub build_date_format: Option<String>,
+    /// The date format for [`Key::VersionDate`], if it differs from [`Self::date_format`].
+    pub version_date_format: Option<String>,
+    pub date_timezone: DateTimezone,
     pub fail_on: FailOn,
+    /// Whether to fail if any value is only suboptimal,
+    /// see [`crate::validator::Validity::Suboptimal`].
+    pub strict: bool,
+    /// The keys for which to explain where their value came from,
+    /// after evaluation.
+    pub explain: HashSet<Key>,
     // vars: Box<HashMap<String, String, S>>,
     // #[builder(default = false)]
     // fail_on_missing: bool,
     pub show_retrieved: ShowRetrieved,
+    /// The format of the retrieved values overview;
+    /// if `None`, it is Markdown when written to a file,
+    /// and an aligned plain-text table when logged,
+    /// see [`crate::ui::render_table`].
+    pub retrieved_format: Option<TableFormat>,
+    /// The hosting type to assume for all hosts,
+    /// taking precedence over [`Self::hosting_domains`]
+    /// and the well-known public hosting sites,
+    /// unless it is [`HostingType::Unknown`];
+    /// see [`Self::hosting_type_from_host`].
     pub hosting_type: HostingType,
+    /// Maps custom hosts - optionally including a port (e.g. "192.168.1.10:3000"),
+    /// or domain suffixes starting with '.' (e.g. ".company.com") -
+    /// to the hosting software they run.
+    /// Keys are lower-case.
+    pub hosting_domains: HashMap<String, HostingType>,
+    /// The hosting types evaluated so far, by host,
+    /// see [`Self::hosting_type_from_host`].
+    /// NOTE Changing [`Self::hosting_type`] or [`Self::hosting_domains`]
+    ///      after hosts were looked up requires a new (default) cache.
+    #[serde(skip)]
+    pub hosting_type_cache: HostingTypeCache,
+    /// The path under which a self-hosted instance is served,
+    /// without leading and trailing '/',
+    /// e.g. `gitlab` for `https://example.com/gitlab/group/project`.
+    /// It is part of web and HTTP(S) clone URLs, but not of SSH clone URLs,
+    /// see [`Self::hosting_base_path_for`].
+    pub hosting_base_path: Option<String>,
+    /// User supplied URL templates by hosting type,
+    /// which take precedence over the built-in URL construction rules.
+    pub url_templates: HashMap<HostingType, UrlTemplates>,
+    /// User supplied value policies by key name,
+    /// which are checked in addition to the built-in validators.
+    pub validators: HashMap<String, Policy>,
+    /// The maximum length of values by key name,
+    /// with [`constants::LENGTH_LIMIT_ALL`] standing for all other keys,
+    /// see [`Self::length_limit`].
+    pub length_limits: HashMap<String, LengthLimit>,
     pub only_required: bool,
     pub key_prefix: Option<String>,
+    /// The case of the variable keys written to the sinks,
+    /// see [`crate::var::Variable::key_out`].
+    pub key_case: KeyCase,
+    /// How values spanning multiple lines are written to ENV files.
+    pub multiline_style: MultilineStyle,
     pub verbosity: Verbosity,
+    pub log_format: LogFormat,
+    /// Whether we run in an exported source archive (tarball),
+    /// lacking any SCM meta-data.
+    pub archive: bool,
+    /// Whether we may access the network,
+    /// e.g. to query hosting APIs for authoritative values.
+    /// All network access has to go through [`crate::tools::net`],
+    /// which enforces this.
+    pub online: bool,
+    /// The time-out for a single network request.
+    pub network_timeout: Duration,
+    /// How many times a network request is retried on transient failures.
+    pub network_retries: u8,
+    /// A file containing additional (PEM encoded) CA certificates to trust,
+    /// e.g. for corporate TLS interception.
+    pub ca_bundle: Option<PathBuf>,
+    /// A file containing access tokens for the hosting APIs,
+    /// see [`crate::tools::credentials`].
+    pub credentials_file: Option<PathBuf>,
+    /// Whether to verify URL values with HTTP HEAD requests,
+    /// discarding the ones that do not exist;
+    /// requires [`Self::online`], see [`crate::verify`].
+    pub verify_urls: bool,
+    /// Whether to fail if two sources disagree about a value,
+    /// see [`crate::storage::Discrepancy::is_significant`].
+    pub strict_consistency: bool,
+    /// Whether to continue with the other sources
+    /// if one fails to retrieve a value, instead of failing right away.
+    /// The run then only fails if required values are missing in the end
+    /// (with [`FailOn::AnyMissingValue`]).
+    pub keep_going: bool,
+    /// How long a single source may take to retrieve all its values,
+    /// before it is treated as unusable for the remaining keys.
+    /// With this set, each value is retrieved on a worker thread,
+    /// which is abandoned once the time is up.
+    pub source_timeout: Option<Duration>,
+    /// The (git glob) pattern tags have to match
+    /// to be used for describing the version of the checked-out commit.
+    pub version_tag_pattern: String,
+    /// Prefixes to remove from versions (e.g. "v" or "release-"),
+    /// as commonly found in tags; see [`crate::cleanup::version`].
+    pub version_strip_prefixes: Vec<String>,
+    /// How many directory levels below the project root
+    /// are searched for license files (0 meaning only the root),
+    /// e.g. to find the licenses of all the crates in a workspace.
+    pub license_scan_depth: usize,
+    /// Whether to use fixed stand-ins for the values
+    /// that change from build to build (like the build date),
+    /// to get reproducible output; see [`crate::sources::stub`].
+    pub stub_values: bool,
+    /// Whether to also write the value of [`crate::var::Key::BuildDateEpoch`]
+    /// to the standard `SOURCE_DATE_EPOCH` variable,
+    /// for the tools of the following build steps;
+    /// see [`crate::var::VAR_SOURCE_DATE_EPOCH`].
+    pub source_date_epoch_out: bool,
+    /// Whether to transliterate human-readable names to ASCII
+    /// when deriving machine-readable ones from them (e.g. "ü" to "u"),
+    /// and to collapse runs of unsupported characters into a single '_',
+    /// instead of replacing each unsupported character with a '_';
+    /// see [`crate::value_conversions::name_to_machine_readable_name`].
+    pub transliterate_names: bool,
+    /// The file to load the state of the previous run from,
+    /// and to store the state of this one to.
+    /// Values are only retrieved anew from sources whose inputs changed
+    /// (see [`crate::sources::Inputs`]).
+    pub incremental: Option<PathBuf>,
 }
 
 impl Settings {
@@ -164,48 +661,433 @@ impl Settings {
             repo_path: None,
             required_keys: all_keys,
             overwrite: Overwrite::All,
+            merge: false,
             date_format: crate::tools::git::DATE_FORMAT.to_string(),
+            build_date_format: None,
+            version_date_format: None,
+            date_timezone: DateTimezone::Utc,
             fail_on: FailOn::AnyMissingValue,
+            strict: false,
+            explain: HashSet::new(),
             show_retrieved: ShowRetrieved::No,
+            retrieved_format: None,
             hosting_type: HostingType::Unknown,
+            hosting_domains: HashMap::new(),
+            hosting_type_cache: HostingTypeCache::default(),
+            hosting_base_path: None,
+            url_templates: HashMap::new(),
+            validators: HashMap::new(),
+            length_limits: HashMap::new(),
             only_required: false,
             key_prefix: Some(constants::DEFAULT_KEY_PREFIX.to_owned()),
+            key_case: KeyCase::UpperSnake,
+            multiline_style: MultilineStyle::Quoted,
             verbosity: Verbosity::None,
+            log_format: LogFormat::Text,
+            archive: false,
+            online: false,
+            network_timeout: constants::DEFAULT_NETWORK_TIMEOUT,
+            network_retries: constants::DEFAULT_NETWORK_RETRIES,
+            ca_bundle: None,
+            credentials_file: None,
+            verify_urls: false,
+            strict_consistency: false,
+            keep_going: false,
+            source_timeout: None,
+            version_tag_pattern: constants::DEFAULT_VERSION_TAG_PATTERN.to_owned(),
+            version_strip_prefixes: constants::DEFAULT_VERSION_STRIP_PREFIXES
+                .split(',')
+                .map(ToOwned::to_owned)
+                .collect(),
+            license_scan_depth: 0,
+            stub_values: false,
+            source_date_epoch_out: false,
+            transliterate_names: true,
+            incremental: None,
         }
     }
 
-    /// Returns either the initially specified hosting type,
-    /// or tries to evaluate the hosting type
-    /// from the given (possible) repo hosting URL (any form of it).
+    /// Returns the length limit for the values of the given key:
+    /// the one configured for it, or the one for all keys,
+    /// or [`LengthLimit::default`].
     #[must_use]
-    pub fn hosting_type(&self, url: &Url) -> HostingType {
-        if HostingType::Unknown == self.hosting_type {
-            HostingType::from(PublicSite::from(url.host()))
-        } else {
-            self.hosting_type
-        }
+    pub fn length_limit(&self, key: impl Into<AnyKey>) -> LengthLimit {
+        self.length_limits
+            .get(key.into().name())
+            .or_else(|| self.length_limits.get(constants::LENGTH_LIMIT_ALL))
+            .copied()
+            .unwrap_or_default()
     }
 
+    /// Returns the date format to use for the given key.
     #[must_use]
-    pub fn hosting_type_from_host(&self, host: &str) -> HostingType {
-        if HostingType::Unknown == self.hosting_type {
-            let host_assumed_domain = url::Host::Domain(host);
-            HostingType::from(PublicSite::from(host_assumed_domain))
+    pub fn date_format_for(&self, key: Key) -> &str {
+        let specific = if key == Key::BuildDate {
+            self.build_date_format.as_deref()
+        } else if key == Key::VersionDate {
+            self.version_date_format.as_deref()
         } else {
-            self.hosting_type
+            None
+        };
+        specific.unwrap_or(&self.date_format)
+    }
+
+    /// Formats a date for the given key,
+    /// in the date format (see [`Self::date_format_for`])
+    /// and time zone of these settings.
+    /// All generated dates go through this function.
+    ///
+    /// NOTE The output does not depend on the locale,
+    ///      so e.g. month names are always in English.
+    #[must_use]
+    pub fn format_date<Tz: TimeZone>(&self, key: Key, date: &DateTime<Tz>) -> String {
+        self.date_timezone.format(date, self.date_format_for(key))
+    }
+
+    /// Looks up a host in the user supplied hosting domains mapping,
+    /// first including the port (if any), then without it,
+    /// and finally by the longest matching domain suffix
+    /// (keys starting with '.', e.g. ".company.com").
+    #[must_use]
+    pub fn custom_hosting_type(&self, host: &str, port: Option<u16>) -> Option<HostingType> {
+        let host = host.to_lowercase();
+        port.and_then(|port| self.hosting_domains.get(&format!("{host}:{port}")))
+            .or_else(|| self.hosting_domains.get(&host))
+            .or_else(|| {
+                self.hosting_domains
+                    .iter()
+                    .filter(|(domain, _)| {
+                        domain.starts_with('.') && host.ends_with(domain.as_str())
+                    })
+                    .max_by_key(|(domain, _)| domain.len())
+                    .map(|(_, hosting_type)| hosting_type)
+            })
+            .copied()
+    }
+
+    /// Evaluates the hosting type
+    /// from the given (possible) repo hosting URL (any form of it),
+    /// see [`Self::hosting_type_from_host`].
+    #[must_use]
+    pub fn hosting_type(&self, url: &Url) -> HostingType {
+        url.host_str().map_or(self.hosting_type, |host| {
+            self.hosting_type_from_host(host, url.port())
+        })
+    }
+
+    /// Evaluates the hosting type of a host,
+    /// in this order of precedence:
+    ///
+    /// 1. the explicitly specified [`Self::hosting_type`]
+    ///    (e.g. through `--hosting-type`), if any
+    /// 2. the user supplied mapping of the host
+    ///    (see [`Self::custom_hosting_type`]), if any
+    /// 3. the well-known public hosting sites (e.g. "github.com")
+    ///
+    /// Known results are memoized, as the same few hosts are looked up
+    /// many times per run (see [`HostingTypeCache`]).
+    #[must_use]
+    pub fn hosting_type_from_host(&self, host: &str, port: Option<u16>) -> HostingType {
+        if HostingType::Unknown != self.hosting_type {
+            return self.hosting_type;
         }
+        self.hosting_type_cache.get_or_evaluate(host, port, || {
+            self.custom_hosting_type(host, port).unwrap_or_else(|| {
+                let host_assumed_domain = url::Host::Domain(host);
+                HostingType::from(PublicSite::from(host_assumed_domain))
+            })
+        })
     }
 
     #[must_use]
     pub fn hosting_type_from_hosting_suffix(&self, url: &Url) -> HostingType {
         if HostingType::Unknown == self.hosting_type {
-            HostingType::from(PublicSite::from_hosting_domain_option(url.host().as_ref()))
+            url.host_str()
+                .and_then(|host| {
+                    self.custom_hosting_type(host, url.port()).or_else(|| {
+                        // pages hosted under a sub-domain of a custom host
+                        host.split_once('.')
+                            .and_then(|(_sub, domain)| self.custom_hosting_type(domain, None))
+                    })
+                })
+                .unwrap_or_else(|| {
+                    HostingType::from(PublicSite::from_hosting_domain_option(url.host().as_ref()))
+                })
         } else {
             self.hosting_type
         }
     }
+
+    /// Returns the hosting base path (see [`Self::hosting_base_path`]),
+    /// if it applies to the given host,
+    /// which is the case for all but the well-known public hosting sites.
+    #[must_use]
+    pub fn hosting_base_path_for(&self, host: &str) -> Option<&str> {
+        self.hosting_base_path
+            .as_deref()
+            .filter(|_| PublicSite::from(url::Host::Domain(host)) == PublicSite::Unknown)
+    }
+
+    /// Removes the hosting base path (see [`Self::hosting_base_path_for`])
+    /// from the start of a URL path, if it is there.
+    /// The result is without a leading '/'.
+    #[must_use]
+    pub fn strip_hosting_base_path<'a>(&self, host: &str, path: &'a str) -> &'a str {
+        let path = path.trim_start_matches('/');
+        self.hosting_base_path_for(host)
+            .and_then(|base_path| path.strip_prefix(base_path))
+            .and_then(|rest| {
+                if rest.is_empty() {
+                    Some(rest)
+                } else {
+                    rest.strip_prefix('/')
+                }
+            })
+            .unwrap_or(path)
+    }
+}
+
+/// The strict ISO 8601 (and RFC 3339) date format,
+/// e.g. "2021-09-30T20:59:05+00:00".
+pub const DATE_FORMAT_ISO8601: &str = "%Y-%m-%dT%H:%M:%S%:z";
+
+/// Named date formats,
+/// which may be used instead of a `strftime` format string.
+const DATE_FORMAT_NAMES: &[(&str, &str)] = &[
+    ("iso8601", DATE_FORMAT_ISO8601),
+    ("rfc3339", DATE_FORMAT_ISO8601),
+    ("rfc2822", "%a, %d %b %Y %H:%M:%S %z"),
+];
+
+/// Parses a date format, as accepted by `--date-format`.
+///
+/// It is either one of the names "iso8601", "rfc3339" or "rfc2822",
+/// or a `strftime` format string,
+/// see <https://docs.rs/chrono/latest/chrono/format/strftime/index.html>.
+///
+/// for example:
+///
+/// ```
+/// # use projvar::settings::parse_date_format;
+/// assert_eq!(parse_date_format("iso8601"), Ok("%Y-%m-%dT%H:%M:%S%:z".to_owned()));
+/// assert_eq!(parse_date_format("%Y-%m-%d"), Ok("%Y-%m-%d".to_owned()));
+/// assert!(parse_date_format("%Y-%m-%d %Q").is_err());
+/// assert!(parse_date_format("").is_err());
+/// ```
+///
+/// # Errors
+///
+/// If the format string is empty or contains invalid specifiers.
+pub fn parse_date_format(date_format: &str) -> Result<String, String> {
+    if let Some((_name, format)) = DATE_FORMAT_NAMES
+        .iter()
+        .find(|(name, _format)| name.eq_ignore_ascii_case(date_format))
+    {
+        return Ok((*format).to_owned());
+    }
+    if date_format.is_empty() {
+        return Err("The date format must not be empty".to_owned());
+    }
+    if chrono::format::StrftimeItems::new(date_format)
+        .any(|item| item == chrono::format::Item::Error)
+    {
+        return Err(format!(
+            "Invalid date format '{date_format}'; \
+            see https://docs.rs/chrono/latest/chrono/format/strftime/index.html"
+        ));
+    }
+    Ok(date_format.to_owned())
+}
+
+/// Normalizes a hosting base path, as supplied to `--hosting-base-path`,
+/// by removing leading and trailing '/'.
+/// An empty path is treated as none.
+#[must_use]
+pub fn normalize_hosting_base_path(base_path: &str) -> Option<String> {
+    let base_path = base_path.trim_matches('/');
+    (!base_path.is_empty()).then(|| base_path.to_owned())
+}
+
+/// Parses a hosting type name, as accepted by `--hosting-type`,
+/// but more lenient: case-insensitive and optionally without the dashes
+/// (e.g. "gitlab" or "GitLab" instead of "git-lab").
+///
+/// # Errors
+///
+/// If the name does not denote a known hosting type.
+pub fn parse_hosting_type(name: &str) -> Result<HostingType, String> {
+    let normalize = |name: &str| name.replace('-', "").to_lowercase();
+    let name_normalized = normalize(name.trim());
+    HostingType::iter()
+        .find(|&variant| normalize(variant.into()) == name_normalized)
+        .ok_or_else(|| format!("Unknown hosting type '{name}'"))
+}
+
+/// Parses a `DOMAIN=TYPE` mapping, as supplied to `--hosting-domain`.
+///
+/// `DOMAIN` may be a domain name or an IP, optionally followed by ":<port>",
+/// or a domain suffix starting with '.' (e.g. ".company.com"),
+/// and `TYPE` is anything accepted by [`parse_hosting_type`].
+///
+/// # Errors
+///
+/// If there is no '=', the domain is empty,
+/// or the type is not a known hosting type.
+pub fn parse_hosting_domain(mapping: &str) -> Result<(String, HostingType), String> {
+    let (domain, hosting_type) = mapping
+        .split_once('=')
+        .ok_or_else(|| format!("Expected 'DOMAIN=TYPE', but got '{mapping}'"))?;
+    let domain = domain.trim().to_lowercase();
+    if domain.is_empty() {
+        return Err(format!("Empty domain in '{mapping}'"));
+    }
+    Ok((domain, parse_hosting_type(hosting_type)?))
+}
+
+/// Parses a `KEY=LENGTH[:POLICY]` mapping, as supplied to `--max-length`.
+///
+/// `KEY` is a key name (e.g. "Description"),
+/// or [`constants::LENGTH_LIMIT_ALL`] for all other keys,
+/// and the rest is parsed with [`LengthLimit::from_str`].
+///
+/// # Errors
+///
+/// If there is no '=', the key is empty,
+/// or the length limit is invalid.
+pub fn parse_key_length_limit(mapping: &str) -> Result<(String, LengthLimit), String> {
+    let (key, limit) = mapping
+        .split_once('=')
+        .ok_or_else(|| format!("Expected 'KEY=LENGTH[:POLICY]', but got '{mapping}'"))?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(format!("Empty key in '{mapping}'"));
+    }
+    Ok((key.to_owned(), limit.parse()?))
 }
 
 lazy_static! {
     pub static ref STUB: Settings = Settings::stub();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn test_hosting_type_heuristics() {
+        let settings = Settings::stub();
+        assert_eq!(
+            settings.hosting_type(&url("https://github.com/hoijui/projvar")),
+            HostingType::GitHub
+        );
+        assert_eq!(
+            settings.hosting_type(&url("https://git.example.com/hoijui/projvar")),
+            HostingType::Unknown
+        );
+    }
+
+    #[test]
+    fn test_hosting_type_precedence() {
+        let mut settings = Settings::stub();
+        settings
+            .hosting_domains
+            .insert("github.com".to_owned(), HostingType::Gitea);
+        settings
+            .hosting_domains
+            .insert("git.example.com".to_owned(), HostingType::GitLab);
+        settings
+            .hosting_domains
+            .insert("git.example.com:3000".to_owned(), HostingType::Gitea);
+        // the per-domain mapping wins over the heuristics
+        assert_eq!(
+            settings.hosting_type(&url("https://github.com/hoijui/projvar")),
+            HostingType::Gitea
+        );
+        // the mapping including the port wins over the one without
+        assert_eq!(
+            settings.hosting_type(&url("https://git.example.com:3000/hoijui/projvar")),
+            HostingType::Gitea
+        );
+        assert_eq!(
+            settings.hosting_type(&url("https://git.example.com/hoijui/projvar")),
+            HostingType::GitLab
+        );
+
+        // the explicit hosting type wins over everything
+        settings.hosting_type = HostingType::SourceHut;
+        for host in ["github.com", "git.example.com", "unknown.example.com"] {
+            assert_eq!(
+                settings.hosting_type_from_host(host, None),
+                HostingType::SourceHut
+            );
+        }
+    }
+
+    #[test]
+    fn test_hosting_type_cache() {
+        let mut settings = Settings::stub();
+        settings
+            .hosting_domains
+            .insert("git.example.com".to_owned(), HostingType::GitLab);
+        for _ in 0..3 {
+            assert_eq!(
+                settings.hosting_type_from_host("git.example.com", None),
+                HostingType::GitLab
+            );
+        }
+        // a clone starts with an empty cache, so it sees its own mapping
+        let mut changed = settings.clone();
+        changed
+            .hosting_domains
+            .insert("git.example.com".to_owned(), HostingType::Gitea);
+        assert_eq!(
+            changed.hosting_type_from_host("git.example.com", None),
+            HostingType::Gitea
+        );
+        assert_eq!(
+            settings.hosting_type_from_host("git.example.com", None),
+            HostingType::GitLab
+        );
+    }
+
+    #[test]
+    fn test_length_limits() {
+        assert_eq!(
+            parse_key_length_limit("Description=255"),
+            Ok((
+                "Description".to_owned(),
+                LengthLimit {
+                    max: 255,
+                    policy: LengthPolicy::Truncate
+                }
+            ))
+        );
+        assert_eq!(
+            "63:Error".parse(),
+            Ok(LengthLimit {
+                max: 63,
+                policy: LengthPolicy::Error
+            })
+        );
+        assert!(parse_key_length_limit("Description").is_err());
+        assert!(parse_key_length_limit("=255").is_err());
+        assert!("255:cut".parse::<LengthLimit>().is_err());
+        assert!("many".parse::<LengthLimit>().is_err());
+
+        let mut settings = Settings::stub();
+        assert_eq!(settings.length_limit(Key::Name), LengthLimit::default());
+        settings.length_limits.insert(
+            constants::LENGTH_LIMIT_ALL.to_owned(),
+            "1000:warn".parse().unwrap(),
+        );
+        settings
+            .length_limits
+            .insert("Name".to_owned(), "63".parse().unwrap());
+        assert_eq!(settings.length_limit(Key::Name).max, 63);
+        assert_eq!(settings.length_limit(Key::Description).max, 1000);
+    }
+}
@@ -0,0 +1,116 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::config;
+use crate::environment::Environment;
+use crate::sources::archive;
+use crate::storage;
+use crate::tools::git;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::export;
+
+/// The files in the git directory whose change likely changes our output:
+/// the checked-out branch or commit, the dirty state and the tags.
+const GIT_DIR_FILES: &[&str] = &["HEAD", "index"];
+/// Like [`GIT_DIR_FILES`], but shared by all worktrees of a repo.
+const GIT_COMMON_DIR_FILES: &[&str] = &["packed-refs", "config"];
+/// The name of the file to preview the snippet into,
+/// see [`super::VarSink::preview_file_name`].
+const PREVIEW_FILE_NAME: &str = "envrc";
+
+pub struct VarSink;
+
+/// Returns the files that direnv should watch,
+/// because a change to them likely changes the values we evaluate.
+/// Files that do not exist (yet) are included too,
+/// as their creation might change the values just as well.
+fn watch_files(environment: &Environment) -> Vec<PathBuf> {
+    let project_root = environment.repo.as_ref().map_or_else(
+        || {
+            environment
+                .settings
+                .repo_path
+                .clone()
+                .unwrap_or_else(|| PathBuf::from("."))
+        },
+        git::Repo::local_path,
+    );
+    let mut files: Vec<PathBuf> = [
+        config::DEFAULT_FILE_NAME,
+        archive::FILE_CARGO_TOML,
+        archive::FILE_PACKAGE_JSON,
+    ]
+    .iter()
+    .map(|file_name| project_root.join(file_name))
+    .collect();
+    if let Some(repo) = &environment.repo {
+        let git_dir = repo.inner().path();
+        let git_common_dir = repo.common_dir();
+        files.extend(GIT_DIR_FILES.iter().map(|file| git_dir.join(file)));
+        files.extend(
+            GIT_COMMON_DIR_FILES
+                .iter()
+                .map(|file| git_common_dir.join(file)),
+        );
+    }
+    files
+}
+
+fn write_watch_file(out: &mut impl Write, file: &Path) -> io::Result<()> {
+    writeln!(out, "watch_file {}", export::quote(&file.to_string_lossy()))
+}
+
+/// Writes evaluated values (output) to stdout,
+/// as a snippet for a [direnv](https://direnv.net) `.envrc` file,
+/// which consists of `watch_file` lines for all the files
+/// whose change should trigger a re-evaluation,
+/// followed by `export KEY='VALUE'` lines (see [`export::VarSink`]).
+/// It is meant to be used in an `.envrc` like so:
+///
+/// ```shell
+/// eval "$(projvar --direnv)"
+/// ```
+impl super::VarSink for VarSink {
+    fn is_usable(&self, _environment: &Environment) -> bool {
+        true
+    }
+
+    fn store(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<(), super::Error> {
+        let snippet = self.render(environment, values)?;
+        let mut out = io::stdout().lock();
+        out.write_all(&snippet)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    fn render(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<Vec<u8>, super::Error> {
+        let mut out = Vec::new();
+        for file in watch_files(environment) {
+            write_watch_file(&mut out, &file)?;
+        }
+        export::write_exports(&mut out, environment, values)?;
+        Ok(out)
+    }
+
+    fn preview_file_name(&self) -> String {
+        PREVIEW_FILE_NAME.to_owned()
+    }
+}
+
+impl fmt::Display for VarSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", std::any::type_name::<Self>())
+    }
+}
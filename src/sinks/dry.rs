@@ -0,0 +1,91 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::environment::Environment;
+use crate::storage;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Wraps an other sink,
+/// only showing what it would store, instead of storing it.
+pub struct VarSink {
+    pub inner: Box<dyn super::VarSink>,
+    /// The directory to write the preview to;
+    /// if `None`, it is printed to stdout.
+    pub dir: Option<PathBuf>,
+}
+
+/// Wraps each of the given sinks in a [`VarSink`].
+#[must_use]
+pub fn wrap(
+    sinks: Vec<Box<dyn super::VarSink>>,
+    dir: Option<&Path>,
+) -> Vec<Box<dyn super::VarSink>> {
+    sinks
+        .into_iter()
+        .map(|inner| {
+            Box::new(VarSink {
+                inner,
+                dir: dir.map(Path::to_path_buf),
+            }) as Box<dyn super::VarSink>
+        })
+        .collect()
+}
+
+/// Previews what the wrapped sink would store,
+/// either by printing it to stdout, preceded by a header naming the sink,
+/// or by writing it into a file in [`VarSink::dir`]
+/// (see [`super::VarSink::preview_file_name`]).
+impl super::VarSink for VarSink {
+    fn is_usable(&self, environment: &Environment) -> bool {
+        self.inner.is_usable(environment)
+    }
+
+    fn store(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<(), super::Error> {
+        let content = self.inner.render(environment, values)?;
+        if let Some(dir) = &self.dir {
+            let file = dir.join(self.inner.preview_file_name());
+            log::info!(
+                "Writing preview of {} to '{}' ...",
+                self.inner,
+                file.display()
+            );
+            fs::create_dir_all(dir)?;
+            fs::write(file, content)?;
+        } else {
+            let mut out = io::stdout().lock();
+            writeln!(out, "==> {} <==", self.inner)?;
+            out.write_all(&content)?;
+            if !content.ends_with(b"\n") {
+                writeln!(out)?;
+            }
+            out.flush()?;
+        }
+        Ok(())
+    }
+
+    fn render(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<Vec<u8>, super::Error> {
+        self.inner.render(environment, values)
+    }
+
+    fn preview_file_name(&self) -> String {
+        self.inner.preview_file_name()
+    }
+}
+
+impl fmt::Display for VarSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({})", std::any::type_name::<Self>(), self.inner)
+    }
+}
@@ -2,29 +2,68 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use cli_utils::BoxResult;
-
 use crate::environment::Environment;
 use crate::storage;
+use std::borrow::Cow;
 use std::{env, fmt};
 
+use super::file;
+
+/// The name of the file to preview the variables into,
+/// see [`super::VarSink::preview_file_name`].
+const PREVIEW_FILE_NAME: &str = "env.txt";
+
 pub struct VarSink;
 
+/// Returns the variables to set, as `(key, value)` pairs,
+/// leaving out those already set in our environment
+/// unless we overwrite the main ones.
+fn to_set<'a>(
+    environment: &'a Environment,
+    values: &'a [storage::Value],
+) -> impl Iterator<Item = (Cow<'a, str>, &'a str)> {
+    values
+        .iter()
+        .map(move |(_key, var, (_confidence, value))| (var.key_out(environment), value.as_str()))
+        .filter(move |(key, _value)| {
+            environment.settings.overwrite.main() || env::var(&**key).is_err()
+        })
+}
+
 /// Stores evaluated values (output) into environment variables.
 impl super::VarSink for VarSink {
     fn is_usable(&self, _environment: &Environment) -> bool {
         true
     }
 
-    fn store(&self, environment: &Environment, values: &[storage::Value]) -> BoxResult<()> {
-        for (_key, var, (_confidence, value)) in values {
-            let key = var.key(environment);
-            if environment.settings.overwrite.main() || env::var(&*key).is_err() {
-                env::set_var(&*key, value);
-            }
+    fn store(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<(), super::Error> {
+        for (key, value) in to_set(environment, values) {
+            env::set_var(&*key, value);
         }
         Ok(())
     }
+
+    /// Renders the variables that would be set
+    /// like an ENV file (see [`file::write_entry`]).
+    fn render(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<Vec<u8>, super::Error> {
+        let mut out = Vec::new();
+        for (key, value) in to_set(environment, values) {
+            file::write_entry(&mut out, &key, value, environment.settings.multiline_style)?;
+        }
+        Ok(out)
+    }
+
+    fn preview_file_name(&self) -> String {
+        PREVIEW_FILE_NAME.to_owned()
+    }
 }
 
 impl fmt::Display for VarSink {
@@ -0,0 +1,106 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::environment::Environment;
+use crate::storage;
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::{env, fmt};
+
+/// The name of the file to preview the script into,
+/// see [`super::VarSink::preview_file_name`].
+const PREVIEW_FILE_NAME: &str = "export.sh";
+
+pub struct VarSink;
+
+/// Quotes a value for use in a POSIX shell,
+/// by enclosing it in single quotes,
+/// which leaves everything but a single quote itself uninterpreted.
+///
+/// ```
+/// # use projvar::sinks::export::quote;
+/// assert_eq!(quote("simple"), "'simple'");
+/// assert_eq!(quote("$HOME \"x\""), "'$HOME \"x\"'");
+/// assert_eq!(quote("it's"), "'it'\\''s'");
+/// ```
+#[must_use]
+pub fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Writes one `export KEY='VALUE'` line per value,
+/// sorted by key,
+/// skipping those already set in our environment
+/// unless we overwrite the main ones.
+///
+/// # Errors
+///
+/// If writing to `out` fails.
+pub fn write_exports(
+    out: &mut impl Write,
+    environment: &Environment,
+    values: &[storage::Value],
+) -> io::Result<()> {
+    let mut output_values: Vec<(Cow<str>, &str)> = values
+        .iter()
+        .map(|(_key, var, (_confidence, value))| (var.key_out(environment), value.as_str()))
+        .collect();
+    output_values.sort_unstable();
+
+    for (key, value) in output_values {
+        // The script is evaluated in the environment we inherited from the caller
+        if environment.settings.overwrite.main() || env::var(&*key).is_err() {
+            writeln!(out, "export {key}={}", quote(value))?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes evaluated values (output) to stdout,
+/// as a POSIX shell script of `export KEY='VALUE'` lines,
+/// to be evaluated by the calling shell, like so:
+///
+/// ```shell
+/// eval "$(projvar --export-script)"
+/// ```
+///
+/// This is the only way to get the values into the environment of the caller,
+/// as a process can not modify the environment of its parent.
+impl super::VarSink for VarSink {
+    fn is_usable(&self, _environment: &Environment) -> bool {
+        true
+    }
+
+    fn store(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<(), super::Error> {
+        let script = self.render(environment, values)?;
+        let mut out = io::stdout().lock();
+        out.write_all(&script)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    fn render(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<Vec<u8>, super::Error> {
+        let mut out = Vec::new();
+        write_exports(&mut out, environment, values)?;
+        Ok(out)
+    }
+
+    fn preview_file_name(&self) -> String {
+        PREVIEW_FILE_NAME.to_owned()
+    }
+}
+
+impl fmt::Display for VarSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", std::any::type_name::<Self>())
+    }
+}
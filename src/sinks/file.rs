@@ -2,60 +2,146 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use cli_utils::BoxResult;
-
 use crate::environment::Environment;
+use crate::settings::MultilineStyle;
 use crate::storage;
-use crate::var::{self, Confidence};
+use crate::var;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
-use std::fs::File;
-use std::io::LineWriter;
-use std::io::Write;
+use std::fs;
+use std::io::{self, Write};
 use std::path::PathBuf;
 
+/// The delimiter of heredoc values,
+/// extended with '_' until it does not occur as a line in the value.
+const HEREDOC_DELIMITER: &str = "PROJVAR_EOF";
+
 pub struct VarSink {
     pub file: PathBuf,
 }
 
+/// Quotes a value for use in an ENV file,
+/// by enclosing it in double quotes,
+/// and escaping the characters BASH would interpret within them.
+/// Line breaks are kept as they are.
+///
+/// ```
+/// # use projvar::sinks::file::quote;
+/// assert_eq!(quote("simple"), r#""simple""#);
+/// assert_eq!(quote(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+/// assert_eq!(quote("$HOME `ls`"), r#""\$HOME \`ls\`""#);
+/// assert_eq!(quote("l1\nl2"), "\"l1\nl2\"");
+/// ```
+#[must_use]
+pub fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for chr in value.chars() {
+        if matches!(chr, '\\' | '"' | '$' | '`') {
+            quoted.push('\\');
+        }
+        quoted.push(chr);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Whether a value would not be read back as is
+/// if written without quotes.
+fn needs_quoting(value: &str) -> bool {
+    value.contains(['\n', '\r']) || value.starts_with(['"', '\'']) || value.trim() != value
+}
+
+/// Writes a single "KEY=VALUE" entry,
+/// which spans multiple lines if the value does.
+///
+/// # Errors
+///
+/// If writing to `out` fails.
+pub fn write_entry(
+    out: &mut impl Write,
+    key: &str,
+    value: &str,
+    style: MultilineStyle,
+) -> io::Result<()> {
+    match style {
+        MultilineStyle::Quoted => writeln!(out, "{key}={}", quote(value)),
+        MultilineStyle::Heredoc if needs_quoting(value) => {
+            let mut delimiter = HEREDOC_DELIMITER.to_owned();
+            while value.lines().any(|line| line == delimiter) {
+                delimiter.push('_');
+            }
+            writeln!(out, "{key}<<{delimiter}\n{value}\n{delimiter}")
+        }
+        MultilineStyle::Heredoc => writeln!(out, "{key}={value}"),
+    }
+}
+
 /// Stores evaluated values (output) into a file
-/// in a BASH compatible way ("KEY=VALUE\n").
+/// in a BASH compatible way ("KEY=VALUE\n"),
+/// or with heredocs for multi-line values,
+/// see [`crate::settings::Settings::multiline_style`].
+/// If [`crate::settings::Settings::merge`] is set,
+/// the values already in the file are kept.
 impl super::VarSink for VarSink {
     fn is_usable(&self, _environment: &Environment) -> bool {
         true
     }
 
-    fn store(&self, environment: &Environment, values: &[storage::Value]) -> BoxResult<()> {
+    fn store(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<(), super::Error> {
+        let content = self.render(environment, values)?;
         log::trace!(
-            "Reading previous values from ENV file (if it exists): '{}' ...",
+            "Write combined vars to ENV file: '{}' ...",
             self.file.display()
         );
-        let previous_vars = if self.file.exists() {
-            var::parse_vars_file_reader(cli_utils::create_input_reader(Some(&self.file))?)?
-        } else {
-            HashMap::new()
-        };
+        fs::write(&self.file, content)?;
+        Ok(())
+    }
 
-        log::trace!("Prepare and sort new/generated values ...");
-        let mut output_values: Vec<(Cow<str>, &&(Confidence, String))> = values
-            .iter()
-            .map(|(_key, var, rated_value)| (var.key(environment), rated_value))
-            .collect();
-        output_values.sort();
+    fn render(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<Vec<u8>, super::Error> {
+        let mut output_values: HashMap<Cow<str>, Cow<str>> = HashMap::new();
+        if environment.settings.merge && self.file.exists() {
+            log::trace!(
+                "Reading previous values from ENV file: '{}' ...",
+                self.file.display()
+            );
+            let previous_vars =
+                var::parse_vars_file_reader(cli_utils::create_input_reader(Some(&self.file))?)?;
+            output_values.extend(
+                previous_vars
+                    .into_iter()
+                    .map(|(key, value)| (Cow::Owned(key), Cow::Owned(value))),
+            );
+        }
 
-        log::trace!(
-            "Combine and write combined vars to ENV file: '{}' ...",
-            self.file.display()
-        );
-        let file = File::create(self.file.as_path())?;
-        let mut file = LineWriter::new(file);
-        for (key, rated_value) in output_values {
-            if environment.settings.overwrite.main() || !previous_vars.contains_key(key.as_ref()) {
-                file.write_fmt(format_args!("{key}=\"{}\"\n", rated_value.1))?;
+        log::trace!("Combine new/generated values with previous ones (if any) ...");
+        for (_key, var, (_confidence, value)) in values {
+            let key = var.key_out(environment);
+            if environment.settings.overwrite.main() || !output_values.contains_key(&key) {
+                output_values.insert(key, Cow::Borrowed(value));
             }
         }
-        Ok(())
+        let mut output_values: Vec<(Cow<str>, Cow<str>)> = output_values.into_iter().collect();
+        output_values.sort();
+
+        let mut out = Vec::new();
+        for (key, value) in output_values {
+            write_entry(&mut out, &key, &value, environment.settings.multiline_style)?;
+        }
+        Ok(out)
+    }
+
+    fn preview_file_name(&self) -> String {
+        super::file_name(&self.file)
     }
 }
 
@@ -69,3 +155,34 @@ impl fmt::Display for VarSink {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cli_utils::BoxResult;
+
+    #[test]
+    fn test_round_trip() -> BoxResult<()> {
+        let values = [
+            ("PLAIN", "simple value"),
+            ("EMPTY", ""),
+            ("QUOTES", r#""quoted" and 'quoted'"#),
+            ("SHELL", r"$HOME `ls` \n \"),
+            ("SPACED", "  spaced "),
+            ("MULTI", "Description\nspanning\n\nmultiple lines\n"),
+            ("DELIMITER", "x\nPROJVAR_EOF\ny"),
+        ];
+        for style in [MultilineStyle::Quoted, MultilineStyle::Heredoc] {
+            let mut out = Vec::new();
+            for (key, value) in values {
+                write_entry(&mut out, key, value, style)?;
+            }
+            let vars = var::parse_vars_file_reader(out.as_slice())?;
+            assert_eq!(vars.len(), values.len());
+            for (key, value) in values {
+                assert_eq!(vars.get(key).map(String::as_str), Some(value), "{style:?}");
+            }
+        }
+        Ok(())
+    }
+}
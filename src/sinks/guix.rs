@@ -0,0 +1,139 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::environment::Environment;
+use crate::storage;
+use crate::var::Key;
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// The prefix of all the variables we define.
+const PREFIX: &str = "projvar-";
+
+pub struct VarSink {
+    pub file: PathBuf,
+}
+
+/// Quotes a value as a Scheme string.
+///
+/// ```
+/// # use projvar::sinks::guix::quote;
+/// assert_eq!(quote("simple"), r#""simple""#);
+/// assert_eq!(quote(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+/// ```
+#[must_use]
+pub fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', r"\\").replace('"', r#"\""#))
+}
+
+fn write_define(out: &mut impl Write, name: &str, value: Option<&str>) -> io::Result<()> {
+    if let Some(value) = value {
+        writeln!(out, "(define {PREFIX}{name} {})", quote(value))?;
+    }
+    Ok(())
+}
+
+/// Stores evaluated values (output) into a Guile Scheme file,
+/// for use in a [Guix](https://guix.gnu.org) package definition.
+/// It defines the most relevant values for a package
+/// (e.g. `projvar-version` and `projvar-source-commit`),
+/// as well as all values as an association list in `projvar-vars`.
+/// Load it with `(load "projvars.scm")`, and use it like this:
+///
+/// ```scheme
+/// (package
+///   (name projvar-name)
+///   (version projvar-version)
+///   (source
+///    (origin
+///      (method git-fetch)
+///      (uri (git-reference
+///            (url projvar-source-url)
+///            (commit projvar-source-commit)))
+///      (sha256 (base32 "..."))))
+///   (home-page projvar-home-page)
+///   ...)
+/// ```
+///
+/// `projvar-license` is an SPDX expression,
+/// which has to be mapped to a Guix license manually.
+impl super::VarSink for VarSink {
+    fn is_usable(&self, _environment: &Environment) -> bool {
+        true
+    }
+
+    fn store(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<(), super::Error> {
+        let content = self.render(environment, values)?;
+        log::trace!(
+            "Write vars to Guix (Scheme) file: '{}' ...",
+            self.file.display()
+        );
+        fs::write(&self.file, content)?;
+        Ok(())
+    }
+
+    fn render(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<Vec<u8>, super::Error> {
+        let value_of = |key| super::value_of(values, key);
+        let rev = super::source_rev(environment, values);
+
+        let mut vars: Vec<(Cow<str>, &str)> = values
+            .iter()
+            .map(|(_key, var, (_confidence, value))| (var.key_out(environment), value.as_str()))
+            .collect();
+        vars.sort_unstable();
+
+        let mut out = Vec::new();
+        writeln!(
+            out,
+            ";; Generated by {}; do not edit.",
+            env!("CARGO_PKG_NAME")
+        )?;
+        write_define(
+            &mut out,
+            "name",
+            value_of(Key::NameMachineReadable).or_else(|| value_of(Key::Name)),
+        )?;
+        write_define(&mut out, "version", value_of(Key::Version))?;
+        write_define(&mut out, "source-url", super::source_url(values))?;
+        write_define(&mut out, "source-commit", rev.as_deref())?;
+        write_define(&mut out, "home-page", value_of(Key::RepoWebUrl))?;
+        write_define(&mut out, "license", value_of(Key::License))?;
+        write!(out, "(define {PREFIX}vars\n  '(")?;
+        for (index, (key, value)) in vars.iter().enumerate() {
+            if index > 0 {
+                write!(out, "\n    ")?;
+            }
+            write!(out, "({} . {})", quote(key), quote(value))?;
+        }
+        writeln!(out, "))")?;
+
+        Ok(out)
+    }
+
+    fn preview_file_name(&self) -> String {
+        super::file_name(&self.file)
+    }
+}
+
+impl fmt::Display for VarSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}(file: {})",
+            std::any::type_name::<Self>(),
+            self.file.as_path().to_str().ok_or(fmt::Error {})?
+        )
+    }
+}
@@ -2,14 +2,12 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use cli_utils::BoxResult;
-
 use crate::environment::Environment;
 use crate::storage;
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::fmt;
-use std::fs::File;
-use std::io::Write;
+use std::fs;
 use std::path::PathBuf;
 
 pub struct VarSink {
@@ -21,54 +19,74 @@ pub struct VarSink {
 /// The resulting map will contain all keys of both maps.
 /// If one key appears in both maps,
 /// the value of the first one is used.
-fn extend<K: std::cmp::Eq + std::hash::Hash, V>(
-    first: HashMap<K, V>,
-    second: HashMap<K, V>,
-) -> HashMap<K, V> {
+fn extend<K: Ord, V>(first: BTreeMap<K, V>, second: BTreeMap<K, V>) -> BTreeMap<K, V> {
     second.into_iter().chain(first).collect()
 }
 
 /// Stores evaluated values (output) into a JSON file.
+/// If [`crate::settings::Settings::merge`] is set,
+/// the values already in the file are kept.
 impl super::VarSink for VarSink {
     fn is_usable(&self, _environment: &Environment) -> bool {
         true
     }
 
-    fn store(&self, environment: &Environment, values: &[storage::Value]) -> BoxResult<()> {
+    fn store(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<(), super::Error> {
+        let json = self.render(environment, values)?;
         log::trace!(
-            "Reading previous values from JSON file (if it exists): '{}' ...",
+            "Write combined vars to JSON file: '{}' ...",
             self.file.display()
         );
-        let previous_vars: HashMap<String, String> = if self.file.exists() {
-            let mut content = String::new();
-            cli_utils::create_input_reader(Some(&self.file))?.read_to_string(&mut content)?;
-            serde_json::from_str(&content)?
-        } else {
-            HashMap::new()
-        };
+        fs::write(&self.file, json)?;
+        Ok(())
+    }
+
+    fn render(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<Vec<u8>, super::Error> {
+        let previous_vars: BTreeMap<String, String> =
+            if environment.settings.merge && self.file.exists() {
+                log::trace!(
+                    "Reading previous values from JSON file: '{}' ...",
+                    self.file.display()
+                );
+                let mut content = String::new();
+                cli_utils::create_input_reader(Some(&self.file))?.read_to_string(&mut content)?;
+                serde_json::from_str(&content)?
+            } else {
+                BTreeMap::new()
+            };
 
         log::trace!("Combine new/generated vars with previous ones (if any) ...");
-        let new_values: HashMap<String, String> = values
+        let new_values: BTreeMap<String, String> = values
             .iter()
-            .map(|(_, var, (_, val))| (var.key_raw().to_string(), val.clone()))
+            .map(|(_, var, (_, val))| {
+                let key = Cow::Borrowed(var.key_raw());
+                (
+                    environment.settings.key_case.apply(key).into_owned(),
+                    val.clone(),
+                )
+            })
             .collect();
-        let combined_values: HashMap<String, String> = if environment.settings.overwrite.main() {
+        // sorted, so the output is deterministic
+        let combined_values: BTreeMap<String, String> = if environment.settings.overwrite.main() {
             extend(new_values, previous_vars)
         } else {
             extend(previous_vars, new_values)
         };
 
         log::trace!("Convert combined vars to JSON ...");
-        let json = serde_json::to_string(&combined_values)?;
-
-        log::trace!(
-            "Write combined vars to JSON file: '{}' ...",
-            self.file.display()
-        );
-        let mut file = File::create(self.file.as_path())?;
-        file.write_all(json.as_bytes())?;
+        Ok(serde_json::to_vec(&combined_values)?)
+    }
 
-        Ok(())
+    fn preview_file_name(&self) -> String {
+        super::file_name(&self.file)
     }
 }
 
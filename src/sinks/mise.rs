@@ -0,0 +1,99 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::environment::Environment;
+use crate::storage;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+/// The table in which [mise](https://mise.jdx.dev) expects
+/// the environment variables to set.
+const TABLE_ENV: &str = "env";
+
+pub struct VarSink {
+    pub file: PathBuf,
+}
+
+/// Stores evaluated values (output) into the `[env]` table of a TOML file,
+/// as used by [mise](https://mise.jdx.dev) (e.g. "mise.local.toml").
+/// The rest of the file is preserved,
+/// though comments and formatting are not;
+/// the values already in the `[env]` table are only kept
+/// if [`crate::settings::Settings::merge`] is set.
+impl super::VarSink for VarSink {
+    fn is_usable(&self, _environment: &Environment) -> bool {
+        true
+    }
+
+    fn store(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<(), super::Error> {
+        let content = self.render(environment, values)?;
+        log::trace!(
+            "Write combined vars to TOML file: '{}' ...",
+            self.file.display()
+        );
+        fs::write(&self.file, content)?;
+        Ok(())
+    }
+
+    fn render(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<Vec<u8>, super::Error> {
+        log::trace!(
+            "Reading previous content of TOML file (if it exists): '{}' ...",
+            self.file.display()
+        );
+        let mut document: toml::Table = if self.file.exists() {
+            fs::read_to_string(&self.file)?.parse()?
+        } else {
+            toml::Table::new()
+        };
+
+        log::trace!("Combine new/generated vars with previous ones (if any) ...");
+        if !environment.settings.merge {
+            document.remove(TABLE_ENV);
+        }
+        let env_table = document
+            .entry(TABLE_ENV)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        let toml::Value::Table(env_table) = env_table else {
+            return Err(super::Error::Other(
+                format!(
+                    "'{TABLE_ENV}' is not a table in the TOML file '{}'",
+                    self.file.display()
+                )
+                .into(),
+            ));
+        };
+        for (_key, var, (_confidence, value)) in values {
+            let key = var.key_out(environment);
+            if environment.settings.overwrite.main() || !env_table.contains_key(key.as_ref()) {
+                env_table.insert(key.into_owned(), toml::Value::String(value.clone()));
+            }
+        }
+
+        Ok(toml::to_string(&document)?.into_bytes())
+    }
+
+    fn preview_file_name(&self) -> String {
+        super::file_name(&self.file)
+    }
+}
+
+impl fmt::Display for VarSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}(file: {})",
+            std::any::type_name::<Self>(),
+            self.file.as_path().to_str().ok_or(fmt::Error {})?
+        )
+    }
+}
@@ -2,22 +2,84 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+pub mod direnv;
+pub mod dry;
 pub mod env;
+pub mod export;
 pub mod file;
+pub mod guix;
 pub mod json;
+pub mod mise;
+pub mod nix;
+pub mod oci;
+pub mod okh;
 
-use cli_utils::BoxResult;
+use cli_utils::BoxError;
+use thiserror::Error;
 
 use std::ffi::OsStr;
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use crate::environment::Environment;
 use crate::storage;
+use crate::var::{self, Key};
 
 pub const DEFAULT_FILE_OUT: &str = ".projvars.env.txt";
 
+/// This enumerates all possible errors returned by this module.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The previous content of an ENV file could not be parsed.
+    #[error(transparent)]
+    VarsFile(#[from] var::ParseError),
+
+    /// Represents all cases of `serde_json::Error`.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    /// The previous content of a TOML file could not be parsed.
+    #[error(transparent)]
+    TomlParse(#[from] toml::de::Error),
+
+    /// The values could not be serialized to TOML.
+    #[error(transparent)]
+    TomlSerialize(#[from] toml::ser::Error),
+
+    /// Represents all cases of `std::io::Error`.
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+
+    /// Represents all other cases of `std::error::Error`.
+    #[error(transparent)]
+    Other(#[from] BoxError),
+}
+
+/// Whether and how to only pretend to store the values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DryRun {
+    /// Actually store the values
+    No,
+    /// Store nothing, and show nothing of what would be stored
+    Silent,
+    /// Print the content each sink would store to stdout
+    Print,
+    /// Write the content each sink would store into this directory,
+    /// one file per sink (see [`VarSink::preview_file_name`])
+    Dir(PathBuf),
+}
+
+/// The kinds of scripts that may be written to stdout,
+/// to be evaluated by the calling shell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    /// See [`export::VarSink`].
+    Export,
+    /// See [`direnv::VarSink`].
+    Direnv,
+}
+
 pub trait VarSink: fmt::Display {
     /// Indicates whether this sink of variables is usable.
     /// It might not be usable if the underlying data-sink (e.g. a file) can not be written to,
@@ -36,8 +98,68 @@ pub trait VarSink: fmt::Display {
         &self,
         environment: &Environment,
         values: &[storage::Value],
-        // values: Box<dyn Iterator<Item = (Key, &Variable, &(Confidence, String))>>,
-    ) -> BoxResult<()>;
+        // values: Box<dyn Iterator<Item = (Key, &Variable, &(ConfidenceScore, String))>>,
+    ) -> Result<(), Error>;
+
+    /// Renders the content this sink would store for the given `values`,
+    /// without storing it anywhere,
+    /// e.g. to preview it in a dry run (see [`dry::VarSink`]).
+    /// Previous content (e.g. of a file to merge with) is read, though.
+    ///
+    /// # Errors
+    ///
+    /// If the previous content could not be read or parsed,
+    /// or the values could not be serialized.
+    fn render(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<Vec<u8>, Error>;
+
+    /// The name of the file to write the rendered content to,
+    /// when previewing it into a directory (see [`DryRun::Dir`]).
+    fn preview_file_name(&self) -> String;
+}
+
+/// Returns the file name of `file`,
+/// for use as [`VarSink::preview_file_name`].
+fn file_name(file: &Path) -> String {
+    file.file_name().map_or_else(
+        || DEFAULT_FILE_OUT.to_owned(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+/// Returns the value of a built-in key, if it was evaluated.
+fn value_of<'a>(values: &[storage::Value<'a>], key: Key) -> Option<&'a str> {
+    values
+        .iter()
+        .find(|(any_key, _var, _rated_value)| any_key.builtin() == Some(key))
+        .map(|(_any_key, _var, (_confidence, value))| value.as_str())
+}
+
+/// Returns the revision to fetch the sources at,
+/// for use in package definitions:
+/// the SHA of the checked-out commit, if we have access to the repo,
+/// the build tag otherwise.
+fn source_rev(environment: &Environment, values: &[storage::Value]) -> Option<String> {
+    environment
+        .repo
+        .as_ref()
+        .and_then(|repo| repo.sha().ok().flatten())
+        .or_else(|| value_of(values, Key::BuildTag).map(ToOwned::to_owned))
+}
+
+/// Returns the URL to fetch the sources from,
+/// for use in package definitions.
+fn source_url<'a>(values: &[storage::Value<'a>]) -> Option<&'a str> {
+    value_of(values, Key::RepoCloneUrlHttp).or_else(|| value_of(values, Key::RepoCloneUrl))
+}
+
+fn has_extension(file: &Path, extension: &str) -> bool {
+    file.extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
 }
 
 /// Returns a list of sinks commonly used when using this crate as CLI tool
@@ -48,14 +170,21 @@ pub trait VarSink: fmt::Display {
 #[must_use]
 pub fn cli_list(
     env_out: bool,
-    dry: bool,
+    script: Option<Script>,
+    dry: DryRun,
     default_out_file: bool,
     additional_out_files: Vec<PathBuf>,
+    oci_label_files: Vec<PathBuf>,
 ) -> Vec<Box<dyn VarSink>> {
     let mut sinks: Vec<Box<dyn VarSink>> = vec![];
     if env_out {
         sinks.push(Box::new(env::VarSink {}));
     }
+    match script {
+        Some(Script::Export) => sinks.push(Box::new(export::VarSink {})),
+        Some(Script::Direnv) => sinks.push(Box::new(direnv::VarSink {})),
+        None => (),
+    }
     if default_out_file {
         log::info!("Using the default out file: {}", DEFAULT_FILE_OUT);
         sinks.push(Box::new(file::VarSink {
@@ -70,14 +199,40 @@ pub fn cli_list(
         {
             log::trace!("Going to sink to JSON file: {}", out_file.display());
             sinks.push(Box::new(json::VarSink { file: out_file }));
+        } else if okh::is_manifest(&out_file) {
+            log::trace!("Going to sink to OKH manifest: {}", out_file.display());
+            sinks.push(Box::new(okh::VarSink { file: out_file }));
+        } else if has_extension(&out_file, "toml") {
+            log::trace!("Going to sink to mise TOML file: {}", out_file.display());
+            sinks.push(Box::new(mise::VarSink { file: out_file }));
+        } else if has_extension(&out_file, "nix") {
+            log::trace!("Going to sink to Nix file: {}", out_file.display());
+            sinks.push(Box::new(nix::VarSink { file: out_file }));
+        } else if has_extension(&out_file, "scm") {
+            log::trace!(
+                "Going to sink to Guix (Scheme) file: {}",
+                out_file.display()
+            );
+            sinks.push(Box::new(guix::VarSink { file: out_file }));
         } else {
             log::trace!("Going to sink to ENV file: {}", out_file.display());
             sinks.push(Box::new(file::VarSink { file: out_file }));
         }
     }
-    if dry {
-        sinks.clear();
-    } else if sinks.is_empty() {
+    for labels_file in oci_label_files {
+        log::trace!(
+            "Going to sink to OCI labels file: {}",
+            labels_file.display()
+        );
+        sinks.push(Box::new(oci::VarSink { file: labels_file }));
+    }
+    let sinks = match dry {
+        DryRun::No => sinks,
+        DryRun::Silent => return vec![],
+        DryRun::Print => dry::wrap(sinks, None),
+        DryRun::Dir(dir) => dry::wrap(sinks, Some(&dir)),
+    };
+    if sinks.is_empty() {
         log::warn!("No sinks registered! The results of this run will not be stored anywhere.");
     } else {
         for sink in &sinks {
@@ -0,0 +1,137 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::environment::Environment;
+use crate::storage;
+use crate::var::Key;
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+pub struct VarSink {
+    pub file: PathBuf,
+}
+
+/// Quotes a value as a Nix string,
+/// escaping everything that would otherwise be interpreted,
+/// most notably `${`, which would start an interpolation.
+///
+/// ```
+/// # use projvar::sinks::nix::quote;
+/// assert_eq!(quote("simple"), r#""simple""#);
+/// assert_eq!(quote(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+/// assert_eq!(quote("${HOME} $x"), r#""\${HOME} $x""#);
+/// assert_eq!(quote("l1\nl2"), r#""l1\nl2""#);
+/// ```
+#[must_use]
+pub fn quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace("${", r"\${")
+        .replace('\n', r"\n")
+        .replace('\r', r"\r")
+        .replace('\t', r"\t");
+    format!("\"{escaped}\"")
+}
+
+fn write_attr(
+    out: &mut impl Write,
+    indent: &str,
+    name: &str,
+    value: Option<&str>,
+) -> io::Result<()> {
+    if let Some(value) = value {
+        writeln!(out, "{indent}{name} = {};", quote(value))?;
+    }
+    Ok(())
+}
+
+/// Stores evaluated values (output) into a Nix file,
+/// as an attribute set that can be `import`ed,
+/// for example when packaging the project for Nix.
+/// It contains the most relevant values for a package definition
+/// (`pname`, `version`, `src` and `meta`),
+/// as well as all values under `vars`.
+/// `meta.license` is an SPDX expression,
+/// which may be converted with `lib.getLicenseFromSpdxId`.
+impl super::VarSink for VarSink {
+    fn is_usable(&self, _environment: &Environment) -> bool {
+        true
+    }
+
+    fn store(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<(), super::Error> {
+        let content = self.render(environment, values)?;
+        log::trace!("Write vars to Nix file: '{}' ...", self.file.display());
+        fs::write(&self.file, content)?;
+        Ok(())
+    }
+
+    fn render(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<Vec<u8>, super::Error> {
+        let value_of = |key| super::value_of(values, key);
+        let rev = super::source_rev(environment, values);
+
+        let mut vars: Vec<(Cow<str>, &str)> = values
+            .iter()
+            .map(|(_key, var, (_confidence, value))| (var.key_out(environment), value.as_str()))
+            .collect();
+        vars.sort_unstable();
+
+        let mut out = Vec::new();
+        writeln!(
+            out,
+            "# Generated by {}; do not edit.",
+            env!("CARGO_PKG_NAME")
+        )?;
+        writeln!(out, "{{")?;
+        write_attr(
+            &mut out,
+            "  ",
+            "pname",
+            value_of(Key::NameMachineReadable).or_else(|| value_of(Key::Name)),
+        )?;
+        write_attr(&mut out, "  ", "version", value_of(Key::Version))?;
+        writeln!(out, "  src = {{")?;
+        write_attr(&mut out, "    ", "url", super::source_url(values))?;
+        write_attr(&mut out, "    ", "rev", rev.as_deref())?;
+        writeln!(out, "  }};")?;
+        writeln!(out, "  meta = {{")?;
+        write_attr(&mut out, "    ", "homepage", value_of(Key::RepoWebUrl))?;
+        write_attr(&mut out, "    ", "license", value_of(Key::License))?;
+        writeln!(out, "  }};")?;
+        writeln!(out, "  vars = {{")?;
+        for (key, value) in vars {
+            write_attr(&mut out, "    ", &quote(&key), Some(value))?;
+        }
+        writeln!(out, "  }};")?;
+        writeln!(out, "}}")?;
+
+        Ok(out)
+    }
+
+    fn preview_file_name(&self) -> String {
+        super::file_name(&self.file)
+    }
+}
+
+impl fmt::Display for VarSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}(file: {})",
+            std::any::type_name::<Self>(),
+            self.file.as_path().to_str().ok_or(fmt::Error {})?
+        )
+    }
+}
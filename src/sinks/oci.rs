@@ -0,0 +1,289 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::environment::Environment;
+use crate::storage;
+use crate::value_conversions;
+use crate::var::Key;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// The namespace of the pre-defined OCI image annotations, see
+/// <https://github.com/opencontainers/image-spec/blob/main/annotations.md>.
+pub const NAMESPACE: &str = "org.opencontainers.image";
+
+/// The file name used when previewing labels that would be written to stdout
+/// (see [`super::VarSink::preview_file_name`]).
+const PREVIEW_FILE_NAME: &str = "oci-labels.txt";
+
+/// The pre-defined OCI image annotations we set
+/// directly from the value of a key.
+const LABELS: [(&str, Key); 4] = [
+    ("source", Key::RepoWebUrl),
+    ("title", Key::Name),
+    ("url", Key::RepoWebUrl),
+    ("version", Key::Version),
+];
+
+/// The formats the labels may be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// One `--label=KEY=VALUE` argument per line,
+    /// to be passed to `docker build` or `podman build`.
+    Args,
+    /// A `LABEL` instruction, to be included into a Dockerfile.
+    Dockerfile,
+}
+
+impl Format {
+    /// Chooses the format by the name of the file to write to:
+    /// [`Self::Dockerfile`] if it contains "Dockerfile" or "Containerfile"
+    /// (case insensitive), [`Self::Args`] otherwise.
+    #[must_use]
+    pub fn from_file(file: &Path) -> Self {
+        let name = file
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if name.contains("dockerfile") || name.contains("containerfile") {
+            Self::Dockerfile
+        } else {
+            Self::Args
+        }
+    }
+}
+
+pub struct VarSink {
+    /// The file to write to; "-" means stdout.
+    pub file: PathBuf,
+}
+
+/// Converts a date into RFC 3339 format, as required for the `created` annotation.
+/// The date is usually generated by us,
+/// in the date format and time zone of our settings
+/// (see [`value_conversions::parse_our_date`]).
+fn to_rfc3339(environment: &Environment, date: &str) -> Option<String> {
+    value_conversions::parse_our_date(environment, Key::BuildDate, date)
+        .map(|date_time| date_time.to_rfc3339())
+}
+
+/// Creates the SPDX license expression required for the `licenses` annotation.
+///
+/// It covers all the licenses of the project ([`Key::Licenses`]),
+/// and falls back to the main license ([`Key::License`]).
+fn licenses(values: &[storage::Value]) -> Option<String> {
+    super::value_of(values, Key::Licenses)
+        .map(|licenses| {
+            licenses
+                .split(',')
+                .map(str::trim)
+                .filter(|license| !license.is_empty())
+                .collect::<Vec<_>>()
+                .join(" AND ")
+        })
+        .filter(|licenses| !licenses.is_empty())
+        .or_else(|| super::value_of(values, Key::License).map(ToOwned::to_owned))
+}
+
+/// Maps the evaluated values to OCI image annotations,
+/// sorted by annotation key.
+fn labels(environment: &Environment, values: &[storage::Value]) -> Vec<(String, String)> {
+    let mut labels: Vec<(String, String)> = LABELS
+        .iter()
+        .filter_map(|(name, key)| {
+            super::value_of(values, *key).map(|value| (*name, value.to_owned()))
+        })
+        .map(|(name, value)| (format!("{NAMESPACE}.{name}"), value))
+        .collect();
+    if let Some(licenses) = licenses(values) {
+        labels.push((format!("{NAMESPACE}.licenses"), licenses));
+    }
+    if let Some(build_date) = super::value_of(values, Key::BuildDate) {
+        if let Some(created) = to_rfc3339(environment, build_date) {
+            labels.push((format!("{NAMESPACE}.created"), created));
+        } else {
+            log::warn!(
+                "Not setting OCI label '{NAMESPACE}.created', \
+                because the build date '{build_date}' could not be converted to RFC 3339."
+            );
+        }
+    }
+    if let Some(sha) = environment
+        .repo
+        .as_ref()
+        .and_then(|repo| repo.sha().ok().flatten())
+    {
+        labels.push((format!("{NAMESPACE}.revision"), sha));
+    }
+    labels.sort();
+    labels
+}
+
+/// Quotes a value for use in a Dockerfile instruction.
+fn quote_dockerfile(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for chr in value.chars() {
+        match chr {
+            '\\' | '"' | '$' => {
+                quoted.push('\\');
+                quoted.push(chr);
+            }
+            '\n' | '\r' => quoted.push(' '),
+            _ => quoted.push(chr),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn format_labels(labels: &[(String, String)], format: Format) -> String {
+    match format {
+        Format::Args => labels
+            .iter()
+            .map(|(key, value)| format!("--label={key}={}\n", value.replace(['\n', '\r'], " ")))
+            .collect::<Vec<_>>()
+            .concat(),
+        Format::Dockerfile => {
+            if labels.is_empty() {
+                return String::new();
+            }
+            let pairs: Vec<String> = labels
+                .iter()
+                .map(|(key, value)| format!("{key}={}", quote_dockerfile(value)))
+                .collect();
+            format!("LABEL {}\n", pairs.join(" \\\n      "))
+        }
+    }
+}
+
+/// Stores evaluated values (output) as OCI image labels
+/// (`org.opencontainers.image.*`),
+/// either as a list of arguments for `docker build`,
+/// or as a `LABEL` instruction for a Dockerfile
+/// (see [`Format`]).
+impl super::VarSink for VarSink {
+    fn is_usable(&self, _environment: &Environment) -> bool {
+        true
+    }
+
+    fn store(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<(), super::Error> {
+        let content = self.render(environment, values)?;
+        log::trace!(
+            "Write OCI labels to {} ...",
+            cli_utils::create_output_writer_description(Some(&self.file))
+        );
+        let mut out = cli_utils::create_output_writer(Some(&self.file))?;
+        out.write_all(&content)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    fn render(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<Vec<u8>, super::Error> {
+        let format = Format::from_file(&self.file);
+        Ok(format_labels(&labels(environment, values), format).into_bytes())
+    }
+
+    fn preview_file_name(&self) -> String {
+        if cli_utils::denotes_std_stream(Some(&self.file)) {
+            PREVIEW_FILE_NAME.to_owned()
+        } else {
+            super::file_name(&self.file)
+        }
+    }
+}
+
+impl fmt::Display for VarSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}(file: {})",
+            std::any::type_name::<Self>(),
+            self.file.as_path().to_str().ok_or(fmt::Error {})?
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::var::{self, ConfidenceScore};
+
+    fn labels() -> Vec<(String, String)> {
+        vec![
+            (
+                format!("{NAMESPACE}.licenses"),
+                "AGPL-3.0-or-later".to_owned(),
+            ),
+            (
+                format!("{NAMESPACE}.title"),
+                r#"My "$HOME" \ Project"#.to_owned(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_licenses() {
+        let license = (ConfidenceScore::HIGH, "AGPL-3.0-or-later".to_owned());
+        let licenses = (
+            ConfidenceScore::HIGH,
+            "AGPL-3.0-or-later, CC0-1.0, Unlicense".to_owned(),
+        );
+        let value = |key: Key, rated_value| (key.into(), var::get(key), rated_value);
+        assert_eq!(
+            super::licenses(&[
+                value(Key::License, &license),
+                value(Key::Licenses, &licenses)
+            ]),
+            Some("AGPL-3.0-or-later AND CC0-1.0 AND Unlicense".to_owned())
+        );
+        assert_eq!(
+            super::licenses(&[value(Key::License, &license)]),
+            Some("AGPL-3.0-or-later".to_owned())
+        );
+        assert_eq!(super::licenses(&[]), None);
+    }
+
+    #[test]
+    fn test_format_from_file() {
+        assert_eq!(Format::from_file(Path::new("-")), Format::Args);
+        assert_eq!(Format::from_file(Path::new("labels.txt")), Format::Args);
+        assert_eq!(
+            Format::from_file(Path::new("build/labels.Dockerfile")),
+            Format::Dockerfile
+        );
+        assert_eq!(
+            Format::from_file(Path::new("Containerfile.labels")),
+            Format::Dockerfile
+        );
+    }
+
+    #[test]
+    fn test_render_args() {
+        assert_eq!(
+            format_labels(&labels(), Format::Args),
+            "--label=org.opencontainers.image.licenses=AGPL-3.0-or-later\n\
+            --label=org.opencontainers.image.title=My \"$HOME\" \\ Project\n"
+        );
+    }
+
+    #[test]
+    fn test_render_dockerfile() {
+        assert_eq!(
+            format_labels(&labels(), Format::Dockerfile),
+            "LABEL org.opencontainers.image.licenses=\"AGPL-3.0-or-later\" \\\n      \
+            org.opencontainers.image.title=\"My \\\"\\$HOME\\\" \\\\ Project\"\n"
+        );
+        assert_eq!(format_labels(&[], Format::Dockerfile), "");
+    }
+}
@@ -0,0 +1,99 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::environment::Environment;
+use crate::sources::okh::{FIELDS_LOSH, FILE_OKH_TOML};
+use crate::storage;
+use std::ffi::OsStr;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct VarSink {
+    pub file: PathBuf,
+}
+
+/// Whether the given file is an OKH-LOSH manifest,
+/// judging by its name, which is either exactly [`FILE_OKH_TOML`],
+/// or ends in `.okh.toml` (e.g. "my-machine.okh.toml").
+///
+/// ```
+/// # use std::path::Path;
+/// # use projvar::sinks::okh::is_manifest;
+/// assert!(is_manifest(Path::new("okh.toml")));
+/// assert!(is_manifest(Path::new("hw/My-Machine.OKH.toml")));
+/// assert!(!is_manifest(Path::new("mise.toml")));
+/// assert!(!is_manifest(Path::new("notokh.toml")));
+/// ```
+#[must_use]
+pub fn is_manifest(file: &Path) -> bool {
+    file.file_name()
+        .and_then(OsStr::to_str)
+        .map(str::to_lowercase)
+        .is_some_and(|name| name == FILE_OKH_TOML || name.ends_with(&format!(".{FILE_OKH_TOML}")))
+}
+
+/// Stores evaluated values (output) into the meta-data fields
+/// of an [OKH-LOSH](https://github.com/iop-alliance/OpenKnowHow) manifest
+/// (see [`FIELDS_LOSH`]).
+/// All other fields of the manifest are preserved,
+/// though comments and formatting are not.
+/// If the file does not exist yet, it is created with only these fields.
+impl super::VarSink for VarSink {
+    fn is_usable(&self, _environment: &Environment) -> bool {
+        true
+    }
+
+    fn store(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<(), super::Error> {
+        let content = self.render(environment, values)?;
+        log::trace!("Write OKH manifest: '{}' ...", self.file.display());
+        fs::write(&self.file, content)?;
+        Ok(())
+    }
+
+    fn render(
+        &self,
+        environment: &Environment,
+        values: &[storage::Value],
+    ) -> Result<Vec<u8>, super::Error> {
+        log::trace!(
+            "Reading previous content of OKH manifest (if it exists): '{}' ...",
+            self.file.display()
+        );
+        let mut manifest: toml::Table = if self.file.exists() {
+            fs::read_to_string(&self.file)?.parse()?
+        } else {
+            toml::Table::new()
+        };
+
+        for (key, field) in FIELDS_LOSH {
+            if let Some(value) = super::value_of(values, *key) {
+                if environment.settings.overwrite.main() || !manifest.contains_key(*field) {
+                    manifest.insert((*field).to_owned(), toml::Value::String(value.to_owned()));
+                }
+            }
+        }
+
+        Ok(toml::to_string(&manifest)?.into_bytes())
+    }
+
+    fn preview_file_name(&self) -> String {
+        super::file_name(&self.file)
+    }
+}
+
+impl fmt::Display for VarSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}(file: {})",
+            std::any::type_name::<Self>(),
+            self.file.as_path().to_str().ok_or(fmt::Error {})?
+        )
+    }
+}
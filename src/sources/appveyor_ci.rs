@@ -0,0 +1,137 @@
+// SPDX-FileCopyrightText: 2021 - 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::cleanup;
+use crate::constants;
+use crate::environment::Environment;
+use crate::var::ConfidenceScore;
+use crate::var::Key;
+
+use super::var;
+use super::var_date;
+use super::Hierarchy;
+use super::RetrieveRes;
+
+/// This sources values from the environment variables set by the CI provider AppVeyor.
+/// See: <https://www.appveyor.com/docs/environment-variables/>
+pub struct VarSource;
+
+fn version_control_system(environment: &Environment) -> Option<(ConfidenceScore, String)> {
+    var(environment, "APPVEYOR_REPO_SCM", ConfidenceScore::HIGH).and_then(|(confidence, scm)| {
+        match scm.to_lowercase().as_str() {
+            "git" => Some((confidence, constants::VCS_GIT.to_owned())),
+            "mercurial" => Some((confidence, constants::VCS_HG.to_owned())),
+            _ => None,
+        }
+    })
+}
+
+fn name(environment: &Environment) -> Option<(ConfidenceScore, String)> {
+    // APPVEYOR_REPO_NAME = "owner-name/repo-name"
+    var(environment, "APPVEYOR_REPO_NAME", ConfidenceScore::HIGH).and_then(|(confidence, slug)| {
+        slug.rsplit_once('/')
+            .map(|(_owner, name)| (confidence, name.to_owned()))
+    })
+}
+
+impl super::VarSource for VarSource {
+    fn is_usable(&self, _environment: &mut Environment) -> bool {
+        true
+    }
+
+    fn hierarchy(&self) -> Hierarchy {
+        Hierarchy::High
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn properties(&self) -> &Vec<String> {
+        &super::NO_PROPS
+    }
+
+    #[remain::check]
+    fn retrieve(&self, environment: &mut Environment, key: Key) -> RetrieveRes {
+        Ok(
+            #[remain::sorted]
+            match key {
+                Key::BuildArch
+                | Key::BuildCommitShaShort
+                | Key::BuildDate
+                | Key::BuildDateEpoch
+                | Key::BuildDirty
+                | Key::BuildHostCpuCount
+                | Key::BuildHostingUrl
+                | Key::BuildHostKernelVersion
+                | Key::BuildHostPlatformTriple
+                | Key::BuildOs
+                | Key::BuildOsFamily
+                | Key::Description
+                | Key::Doi
+                | Key::FundingUrl
+                | Key::License
+                | Key::Licenses
+                | Key::LicensesCompliance
+                | Key::NameMachineReadable
+                | Key::NameSlug
+                | Key::PullRequestNumber
+                | Key::PullRequestSourceBranch
+                | Key::PullRequestTargetBranch
+                | Key::PullRequestTitle
+                | Key::PullRequestUrl
+                | Key::RepoCiUrl
+                | Key::RepoCloneUrl
+                | Key::RepoCloneUrlGit
+                | Key::RepoCloneUrlHttp
+                | Key::RepoCloneUrlPush
+                | Key::RepoCloneUrlSsh
+                | Key::RepoCommitPrefixUrl
+                | Key::RepoDefaultBranch
+                | Key::RepoForkOfUrl
+                | Key::RepoForkUrl
+                | Key::RepoIssuesUrl
+                | Key::RepoNamespace
+                | Key::RepoOwner
+                | Key::RepoRawVersionedPrefixUrl
+                | Key::RepoReleasesUrl
+                | Key::RepoVersionedDirPrefixUrl
+                | Key::RepoVersionedFilePrefixUrl
+                | Key::RepoWebUrl
+                | Key::VersionBuildMeta
+                | Key::VersionDescribe
+                | Key::VersionMajor
+                | Key::VersionMinor
+                | Key::VersionPatch
+                | Key::VersionPrerelease => None,
+                Key::BuildBranch => var(environment, "APPVEYOR_REPO_BRANCH", ConfidenceScore::HIGH),
+                Key::BuildCommitSha => {
+                    var(environment, "APPVEYOR_REPO_COMMIT", ConfidenceScore::HIGH)
+                }
+                Key::BuildNumber => {
+                    var(environment, "APPVEYOR_BUILD_NUMBER", ConfidenceScore::HIGH)
+                }
+                Key::BuildTag => var(environment, "APPVEYOR_REPO_TAG_NAME", ConfidenceScore::HIGH),
+                Key::Ci => var(environment, "CI", ConfidenceScore::HIGH)
+                    .or_else(|| Some((ConfidenceScore::LOW, "false".to_owned()))),
+                Key::Name => name(environment),
+                Key::RepoSlug => var(environment, "APPVEYOR_REPO_NAME", ConfidenceScore::HIGH),
+                Key::Version => self.version_from_build_tag(environment, key)?.or_else(|| {
+                    // NOTE This is generated from a user-configurable format,
+                    //      which defaults to "1.0.{build}",
+                    //      thus it is not necessarily related to the projects version.
+                    var(environment, "APPVEYOR_BUILD_VERSION", ConfidenceScore::LOW)
+                        .map(|conf_val| cleanup::conf_version(environment, conf_val))
+                }),
+                Key::VersionControlSystem => version_control_system(environment),
+                Key::VersionDate => var_date(
+                    environment,
+                    key,
+                    "APPVEYOR_REPO_COMMIT_TIMESTAMP",
+                    ConfidenceScore::HIGH,
+                ),
+            },
+        )
+    }
+}
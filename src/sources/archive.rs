@@ -0,0 +1,524 @@
+// SPDX-FileCopyrightText: 2021 - 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fs;
+use std::path::Path;
+
+use crate::cleanup;
+use crate::constants;
+use crate::environment::Environment;
+use crate::tools::git;
+use crate::value_conversions;
+use crate::var::{ConfidenceScore, Key};
+
+use super::{Hierarchy, Inputs, RetrieveRes};
+
+/// The file with the SCM meta-data of an archive created by `git archive`.
+///
+/// It is filled in if the repo contains it with `export-subst` set in `.gitattributes`,
+/// as popularized by `setuptools-scm`. See:
+/// <https://setuptools-scm.readthedocs.io/en/latest/usage/#git-archives>
+pub const FILE_GIT_ARCHIVAL: &str = ".git_archival.txt";
+/// The Python package meta-data file found in source distributions.
+pub const FILE_PKG_INFO: &str = "PKG-INFO";
+pub const FILE_CARGO_TOML: &str = "Cargo.toml";
+pub const FILE_PACKAGE_JSON: &str = "package.json";
+/// A plain text file containing only the version.
+pub const FILE_VERSION: &str = "VERSION";
+
+/// The files whose presence in the project root -
+/// in the absence of SCM meta-data -
+/// indicate that we are in an exported source archive.
+const ARCHIVE_INDICATOR_FILES: &[&str] = &[
+    FILE_GIT_ARCHIVAL,
+    FILE_PKG_INFO,
+    FILE_CARGO_TOML,
+    FILE_PACKAGE_JSON,
+    FILE_VERSION,
+];
+
+/// Keys we can (usually) only get from an SCM (or a CI environment).
+///
+/// There is no common way to store them in an exported source archive.
+/// The branch and tag may be available from the "ref-names"
+/// in a `.git_archival.txt` though (see [`GitArchival`]),
+/// if the archive was created from a branch or tag.
+pub const UNAVAILABLE_KEYS: &[Key] = &[
+    Key::BuildBranch,
+    Key::BuildNumber,
+    Key::BuildTag,
+    Key::RepoCloneUrl,
+];
+
+/// Whether we should run in archive mode,
+/// which is the case if there is no git repo at `repo_path`,
+/// and either it is `forced`,
+/// or we find any of the typical package manifest or archive meta-data files.
+#[must_use]
+pub fn is_archive_root(repo_path: &Path, forced: bool) -> bool {
+    !super::is_git_repo_root(Some(repo_path))
+        && (forced
+            || ARCHIVE_INDICATOR_FILES
+                .iter()
+                .any(|file_name| repo_path.join(file_name).is_file()))
+}
+
+/// Sources values from manifests and meta-data files
+/// typically found in exported source archives (tarballs),
+/// which lack any SCM meta-data.
+///
+/// This is only used in archive mode (see [`is_archive_root`]),
+/// in which case it takes the place of the git source.
+pub struct VarSource;
+
+fn read_file(repo_path: &Path, file_name: &str) -> Result<Option<String>, std::io::Error> {
+    let file = repo_path.join(file_name);
+    Ok(if file.is_file() {
+        log::trace!("Reading archive meta-data file '{}' ...", file.display());
+        Some(fs::read_to_string(file)?)
+    } else {
+        None
+    })
+}
+
+/// Extracts the value of a field from RFC 822 style content,
+/// as used in both `PKG-INFO` and `.git_archival.txt`.
+/// Only the header part (before the first empty line) is considered.
+fn header_field(content: &str, field: &str) -> Option<String> {
+    content
+        .lines()
+        .take_while(|line| !line.trim().is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _value)| name.trim().eq_ignore_ascii_case(field))
+        .map(|(_name, value)| value.trim().to_owned())
+        .filter(|value| !value.is_empty())
+}
+
+/// Whether `value` contains a place-holder of `git archive` that was not expanded.
+///
+/// See "export-subst" in `gitattributes(5)`.
+/// This happens either because the file was not created by `git archive`,
+/// or because the git version used does not support the format
+/// (e.g. `%(describe)` requires git 2.32).
+#[must_use]
+pub fn is_unexpanded(value: &str) -> bool {
+    value.contains("$Format:") || value.contains("%(")
+}
+
+/// The content of a `.git_archival.txt` file,
+/// as written by `git archive`, e.g.:
+///
+/// ```text
+/// node: 9b5ff47e6e2b1e7ab7ac0fad3b8a13aa35b3b1a3
+/// node-date: 2021-09-30T22:59:05+02:00
+/// describe-name: 0.2.0-3-g9b5ff47
+/// ref-names: HEAD -> master, tag: 0.2.0
+/// ```
+///
+/// which is created from a file in the repo like this one:
+///
+/// ```text
+/// node: $Format:%H$
+/// node-date: $Format:%cI$
+/// describe-name: $Format:%(describe:tags=true,match=*[0-9]*)$
+/// ref-names: $Format:%D$
+/// ```
+///
+/// Unexpanded place-holders (see [`is_unexpanded`]) are left out.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GitArchival {
+    /// The SHA of the archived commit
+    pub node: Option<String>,
+    /// The commit date of the archived commit, in the strict ISO 8601 format
+    pub node_date: Option<String>,
+    /// The output of `git describe` for the archived commit
+    pub describe_name: Option<String>,
+    /// The branch that was checked out when archiving, if any
+    pub branch: Option<String>,
+    /// The tags pointing to the archived commit, sorted alphabetically
+    pub tags: Vec<String>,
+}
+
+impl GitArchival {
+    /// Parses the content of a `.git_archival.txt` file.
+    #[must_use]
+    pub fn parse(content: &str) -> Self {
+        let field = |name: &str| header_field(content, name).filter(|value| !is_unexpanded(value));
+        let mut archival = Self {
+            node: field("node"),
+            node_date: field("node-date"),
+            describe_name: field("describe-name"),
+            ..Self::default()
+        };
+        // This is the output of "git log --format=%D", e.g.:
+        // "HEAD -> master, tag: 0.2.0, origin/master"
+        for ref_name in field("ref-names")
+            .iter()
+            .flat_map(|ref_names| ref_names.split(','))
+        {
+            let ref_name = ref_name.trim();
+            if let Some(tag) = ref_name.strip_prefix("tag: ") {
+                archival.tags.push(tag.to_owned());
+            } else if let Some(branch) = ref_name.strip_prefix("HEAD -> ") {
+                archival.branch = Some(branch.to_owned());
+            } else {
+                // other refs, e.g. remote tracking branches, are of no interest
+            }
+        }
+        archival.tags.sort();
+        archival
+    }
+
+    /// Reads and parses the `.git_archival.txt` file in `repo_path`,
+    /// if it exists.
+    ///
+    /// # Errors
+    ///
+    /// If reading the file failed.
+    pub fn read(repo_path: &Path) -> Result<Option<Self>, std::io::Error> {
+        Ok(read_file(repo_path, FILE_GIT_ARCHIVAL)?.map(|content| Self::parse(&content)))
+    }
+
+    /// The tag pointing to the archived commit, if any;
+    /// the alphabetically first one, like the git source does.
+    fn tag(&self) -> Option<&String> {
+        self.tags.first()
+    }
+
+    /// What `git describe --tags` would return,
+    /// falling back to the first tag pointing to the archived commit
+    /// that matches the (git glob) `tag_pattern`,
+    /// for when the git version used for archiving
+    /// did not support `%(describe)`.
+    fn describe(&self, tag_pattern: &str) -> Option<String> {
+        self.describe_name.clone().or_else(|| {
+            let r_tag_pattern = git::glob_to_regex(tag_pattern)
+                .map_err(|err| log::warn!("Invalid version tag pattern '{tag_pattern}': {err}"))
+                .ok()?;
+            self.tags
+                .iter()
+                .find(|tag| r_tag_pattern.is_match(tag))
+                .cloned()
+        })
+    }
+}
+
+fn cargo_toml_field(content: &str, field: &str) -> Option<String> {
+    match content.parse::<toml::Table>() {
+        Ok(manifest) => manifest
+            .get("package")
+            .and_then(|package| package.get(field))
+            .and_then(toml::Value::as_str)
+            .map(ToOwned::to_owned),
+        Err(err) => {
+            log::warn!("Failed to parse '{FILE_CARGO_TOML}': {err}");
+            None
+        }
+    }
+}
+
+fn package_json_field(content: &str, field: &str) -> Option<String> {
+    match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(manifest) => manifest
+            .get(field)
+            .and_then(serde_json::Value::as_str)
+            .map(ToOwned::to_owned),
+        Err(err) => {
+            log::warn!("Failed to parse '{FILE_PACKAGE_JSON}': {err}");
+            None
+        }
+    }
+}
+
+/// Extracts the funding URL from a `package.json`,
+/// whose `funding` field may be a URL,
+/// an object with a `url` field, or a list of either.
+fn package_json_funding_url(content: &str) -> Option<String> {
+    fn url(funding: &serde_json::Value) -> Option<&str> {
+        funding
+            .as_str()
+            .or_else(|| funding.get("url").and_then(serde_json::Value::as_str))
+    }
+    match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(manifest) => manifest.get("funding").and_then(|funding| {
+            funding
+                .as_array()
+                .map_or_else(|| url(funding), |fundings| fundings.iter().find_map(url))
+                .map(ToOwned::to_owned)
+        }),
+        Err(err) => {
+            log::warn!("Failed to parse '{FILE_PACKAGE_JSON}': {err}");
+            None
+        }
+    }
+}
+
+/// Extracts a URL from the `Project-URL` fields of `PKG-INFO`,
+/// which look like `Project-URL: Funding, https://example.com/donate`.
+fn pkg_info_project_url(content: &str, label: &str) -> Option<String> {
+    content
+        .lines()
+        .take_while(|line| !line.trim().is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(name, _value)| name.trim().eq_ignore_ascii_case("Project-URL"))
+        .filter_map(|(_name, value)| value.split_once(','))
+        .find(|(url_label, _url)| url_label.trim().eq_ignore_ascii_case(label))
+        .map(|(_label, url)| url.trim().to_owned())
+        .filter(|url| !url.is_empty())
+}
+
+/// Returns the funding URL as found in the package manifest,
+/// if any.
+///
+/// # Errors
+///
+/// If reading any of the manifest files failed.
+pub(super) fn manifest_funding_url(repo_path: &Path) -> RetrieveRes {
+    let mut value = read_file(repo_path, FILE_PACKAGE_JSON)?
+        .and_then(|content| package_json_funding_url(&content));
+    if value.is_none() {
+        value = read_file(repo_path, FILE_PKG_INFO)?
+            .and_then(|content| pkg_info_project_url(&content, "Funding"));
+    }
+    Ok(value.map(|val| (ConfidenceScore::MIDDLE, val)))
+}
+
+/// Tries to extract a field from any of the supported package manifests.
+/// `field` is the (lower-case) name used in the JSON and TOML manifests,
+/// `pkg_info_field` the one used in `PKG-INFO`.
+fn manifest_field(
+    repo_path: &Path,
+    field: &str,
+    pkg_info_field: &str,
+) -> Result<Option<(ConfidenceScore, String)>, std::io::Error> {
+    let mut value = read_file(repo_path, FILE_CARGO_TOML)?
+        .and_then(|content| cargo_toml_field(&content, field));
+    if value.is_none() {
+        value = read_file(repo_path, FILE_PACKAGE_JSON)?
+            .and_then(|content| package_json_field(&content, field));
+    }
+    if value.is_none() {
+        value = read_file(repo_path, FILE_PKG_INFO)?
+            .and_then(|content| header_field(&content, pkg_info_field));
+    }
+    Ok(value.map(|val| (ConfidenceScore::MIDDLE, val)))
+}
+
+fn name(repo_path: &Path) -> RetrieveRes {
+    Ok(manifest_field(repo_path, "name", "Name")?)
+}
+
+fn repo_web_url(repo_path: &Path) -> RetrieveRes {
+    Ok(manifest_field(repo_path, "repository", "Home-page")?)
+}
+
+/// Returns the version as found in the package manifest,
+/// if any.
+///
+/// # Errors
+///
+/// If reading any of the manifest files failed.
+pub(super) fn manifest_version(repo_path: &Path) -> RetrieveRes {
+    Ok(manifest_field(repo_path, "version", "Version")?)
+}
+
+fn version(environment: &mut Environment, repo_path: &Path) -> RetrieveRes {
+    let tag_pattern = &environment.settings.version_tag_pattern;
+    let describe =
+        GitArchival::read(repo_path)?.and_then(|archival| archival.describe(tag_pattern));
+    Ok(match describe {
+        Some(describe) => Some(cleanup::conf_version(
+            environment,
+            (ConfidenceScore::HIGH, describe),
+        )),
+        None => manifest_version(repo_path)?,
+    })
+}
+
+fn version_date(environment: &Environment, repo_path: &Path) -> RetrieveRes {
+    // This comes in the strict ISO 8601 format (git log format "%cI")
+    Ok(
+        match GitArchival::read(repo_path)?.and_then(|archival| archival.node_date) {
+            Some(node_date) => {
+                value_conversions::date_to_our_format(environment, Key::VersionDate, &node_date)?
+                    .map(|date| (ConfidenceScore::HIGH, date))
+            }
+            None => None,
+        },
+    )
+}
+
+impl super::VarSource for VarSource {
+    fn is_usable(&self, environment: &mut Environment) -> bool {
+        environment.settings.archive && environment.settings.repo_path.is_some()
+    }
+
+    fn hierarchy(&self) -> Hierarchy {
+        Hierarchy::Middle
+    }
+
+    fn inputs(&self) -> Inputs {
+        Inputs::Files
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn properties(&self) -> &Vec<String> {
+        &super::NO_PROPS
+    }
+
+    #[remain::check]
+    fn retrieve(&self, environment: &mut Environment, key: Key) -> RetrieveRes {
+        let Some(repo_path) = environment.settings.repo_path.clone() else {
+            return Ok(None);
+        };
+        let archival = GitArchival::read(&repo_path)?.unwrap_or_default();
+        Ok(
+            #[remain::sorted]
+            match key {
+                Key::BuildArch
+                | Key::BuildCommitShaShort
+                | Key::BuildDate
+                | Key::BuildDateEpoch
+                | Key::BuildDirty
+                | Key::BuildHostCpuCount
+                | Key::BuildHostingUrl
+                | Key::BuildHostKernelVersion
+                | Key::BuildHostPlatformTriple
+                | Key::BuildNumber
+                | Key::BuildOs
+                | Key::BuildOsFamily
+                | Key::Ci
+                | Key::Description
+                | Key::Doi
+                | Key::FundingUrl
+                | Key::License
+                | Key::Licenses
+                | Key::LicensesCompliance
+                | Key::NameMachineReadable
+                | Key::NameSlug
+                | Key::PullRequestNumber
+                | Key::PullRequestSourceBranch
+                | Key::PullRequestTargetBranch
+                | Key::PullRequestTitle
+                | Key::PullRequestUrl
+                | Key::RepoCiUrl
+                | Key::RepoCloneUrl
+                | Key::RepoCloneUrlGit
+                | Key::RepoCloneUrlHttp
+                | Key::RepoCloneUrlPush
+                | Key::RepoCloneUrlSsh
+                | Key::RepoCommitPrefixUrl
+                | Key::RepoDefaultBranch
+                | Key::RepoForkOfUrl
+                | Key::RepoForkUrl
+                | Key::RepoIssuesUrl
+                | Key::RepoNamespace
+                | Key::RepoOwner
+                | Key::RepoRawVersionedPrefixUrl
+                | Key::RepoReleasesUrl
+                | Key::RepoSlug
+                | Key::RepoVersionedDirPrefixUrl
+                | Key::RepoVersionedFilePrefixUrl
+                | Key::VersionBuildMeta
+                | Key::VersionMajor
+                | Key::VersionMinor
+                | Key::VersionPatch
+                | Key::VersionPrerelease => None,
+                Key::BuildBranch => archival.branch.map(|val| (ConfidenceScore::HIGH, val)),
+                Key::BuildCommitSha => archival.node.map(|val| (ConfidenceScore::HIGH, val)),
+                Key::BuildTag => archival
+                    .tag()
+                    .map(|val| (ConfidenceScore::HIGH, val.clone())),
+                Key::Name => name(&repo_path)?,
+                Key::RepoWebUrl => repo_web_url(&repo_path)?,
+                Key::Version => version(environment, &repo_path)?,
+                Key::VersionControlSystem => {
+                    Some((ConfidenceScore::LOW, constants::VCS_NONE.to_owned()))
+                }
+                Key::VersionDate => version_date(environment, &repo_path)?,
+                Key::VersionDescribe => archival
+                    .describe(&environment.settings.version_tag_pattern)
+                    .map(|val| (ConfidenceScore::HIGH, val)),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_field() {
+        let pkg_info = "Metadata-Version: 2.1\nName: my-proj\nVersion: 1.2.3\n\nName: body\n";
+        assert_eq!(header_field(pkg_info, "Name"), Some("my-proj".to_owned()));
+        assert_eq!(header_field(pkg_info, "version"), Some("1.2.3".to_owned()));
+        assert_eq!(header_field(pkg_info, "Home-page"), None);
+    }
+
+    #[test]
+    fn test_git_archival() {
+        let archival = GitArchival::parse(
+            "node: 9b5ff47e6e2b1e7ab7ac0fad3b8a13aa35b3b1a3\n\
+node-date: 2021-09-30T22:59:05+02:00\n\
+describe-name: %(describe:tags=true)\n\
+ref-names: HEAD -> master, tag: v0.2.0, tag: stable, origin/master\n",
+        );
+        assert_eq!(
+            archival,
+            GitArchival {
+                node: Some("9b5ff47e6e2b1e7ab7ac0fad3b8a13aa35b3b1a3".to_owned()),
+                node_date: Some("2021-09-30T22:59:05+02:00".to_owned()),
+                describe_name: None,
+                branch: Some("master".to_owned()),
+                tags: vec!["stable".to_owned(), "v0.2.0".to_owned()],
+            }
+        );
+        // falls back to the tag, as "%(describe)" was not supported
+        assert_eq!(
+            archival.describe(crate::constants::DEFAULT_VERSION_TAG_PATTERN),
+            Some("v0.2.0".to_owned())
+        );
+        assert_eq!(archival.tag(), Some(&"stable".to_owned()));
+
+        let unexpanded = GitArchival::parse("node: $Format:%H$\nref-names: $Format:%D$\n");
+        assert_eq!(unexpanded, GitArchival::default());
+    }
+
+    #[test]
+    fn test_manifest_fields() {
+        let cargo_toml = "[package]\nname = \"my-crate\"\nversion.workspace = true\n";
+        assert_eq!(
+            cargo_toml_field(cargo_toml, "name"),
+            Some("my-crate".to_owned())
+        );
+        assert_eq!(cargo_toml_field(cargo_toml, "version"), None);
+        let package_json = r#"{ "name": "my-pkg", "version": "0.1.0" }"#;
+        assert_eq!(
+            package_json_field(package_json, "version"),
+            Some("0.1.0".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_manifest_funding_url() {
+        let package_json = r#"{ "funding": [{ "type": "patreon", "url": "https://www.patreon.com/me" }, "https://example.com"] }"#;
+        assert_eq!(
+            package_json_funding_url(package_json),
+            Some("https://www.patreon.com/me".to_owned())
+        );
+        assert_eq!(
+            package_json_funding_url(r#"{ "funding": "https://example.com/donate" }"#),
+            Some("https://example.com/donate".to_owned())
+        );
+        let pkg_info = "Name: my-proj\nProject-URL: Homepage, https://example.com\nProject-URL: Funding, https://example.com/donate\n";
+        assert_eq!(
+            pkg_info_project_url(pkg_info, "funding"),
+            Some("https://example.com/donate".to_owned())
+        );
+    }
+}
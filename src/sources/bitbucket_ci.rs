@@ -2,10 +2,12 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::constants;
 use crate::environment::Environment;
+use crate::tools::git::TransferProtocol;
+use crate::value_conversions;
+use crate::var::ConfidenceScore;
 use crate::var::Key;
-use crate::var::C_HIGH;
-use crate::var::C_LOW;
 
 use super::var;
 use super::Hierarchy;
@@ -15,6 +17,80 @@ use super::RetrieveRes;
 /// [`crate::tools::git_hosting_provs::HostingType::BitBucket`].
 pub struct VarSource;
 
+fn repo_web_url(environment: &Environment) -> Option<(ConfidenceScore, String)> {
+    // This is the web URL, even though the name suggests otherwise
+    var(
+        environment,
+        "BITBUCKET_GIT_HTTP_ORIGIN",
+        ConfidenceScore::HIGH,
+    )
+    .or_else(|| {
+        // BITBUCKET_REPO_FULL_NAME = The full name of the repository
+        // (everything that comes after https://bitbucket.org/).
+        var(
+            environment,
+            "BITBUCKET_REPO_FULL_NAME",
+            ConfidenceScore::HIGH,
+        )
+        .map(|(confidence, project_slug)| {
+            (
+                confidence,
+                format!("https://{}/{project_slug}", constants::D_BIT_BUCKET_ORG),
+            )
+        })
+    })
+}
+
+/// Converts the SSH origin, which uses the SCP-like syntax
+/// (e.g. "git@bitbucket.org:workspace/repo.git"),
+/// into a proper SSH URL.
+fn repo_clone_url_ssh(environment: &Environment) -> RetrieveRes {
+    Ok(
+        match var(
+            environment,
+            "BITBUCKET_GIT_SSH_ORIGIN",
+            ConfidenceScore::HIGH,
+        ) {
+            Some((confidence, ssh_origin)) => value_conversions::clone_url_conversion(
+                &ssh_origin,
+                environment,
+                TransferProtocol::Ssh,
+            )?
+            .map(|url| (confidence, url)),
+            None => None,
+        },
+    )
+}
+
+/// Bitbucket Cloud repos may have their issue tracker disabled,
+/// which we can not tell from the environment,
+/// thus we supply the issues URL with reduced confidence only.
+/// A user supplied issues URL template (e.g. for an external tracker)
+/// is respected, see [`crate::config::UrlTemplates`].
+fn repo_issues_url(environment: &Environment) -> RetrieveRes {
+    Ok(match repo_web_url(environment) {
+        Some((_, web_url)) => value_conversions::web_url_to_issues_url(environment, &web_url)?
+            .map(|issues_url| (ConfidenceScore::LOW, issues_url)),
+        None => None,
+    })
+}
+
+/// Only in pull request pipelines,
+/// `BITBUCKET_BRANCH` is the source branch of the pull request.
+fn pull_request_source_branch(environment: &Environment) -> Option<(ConfidenceScore, String)> {
+    var(environment, "BITBUCKET_PR_ID", ConfidenceScore::HIGH)?;
+    var(environment, "BITBUCKET_BRANCH", ConfidenceScore::HIGH)
+}
+
+fn pull_request_url(environment: &Environment) -> Option<(ConfidenceScore, String)> {
+    let (_, pr_id) = var(environment, "BITBUCKET_PR_ID", ConfidenceScore::HIGH)?;
+    let (confidence, web_url) = repo_web_url(environment)?;
+    Some((
+        confidence,
+        format!("{}/pull-requests/{pr_id}", web_url.trim_end_matches('/')),
+    ))
+}
+
 impl super::VarSource for VarSource {
     fn is_usable(&self, _environment: &mut Environment) -> bool {
         true
@@ -47,33 +123,80 @@ impl super::VarSource for VarSource {
                 | Key::VersionDate
                 | Key::NameMachineReadable
                 | Key::RepoCloneUrlGit
-                | Key::RepoCloneUrlSsh
                 | Key::RepoCommitPrefixUrl
-                | Key::RepoIssuesUrl
                 | Key::RepoRawVersionedPrefixUrl
                 | Key::RepoVersionedDirPrefixUrl
+                | Key::BuildHostPlatformTriple
+                | Key::BuildHostKernelVersion
+                | Key::BuildHostCpuCount
+                | Key::RepoOwner
+                | Key::RepoNamespace
+                | Key::RepoReleasesUrl
+                | Key::RepoCiUrl
+                | Key::VersionMajor
+                | Key::VersionMinor
+                | Key::VersionPatch
+                | Key::VersionPrerelease
+                | Key::VersionBuildMeta
+                | Key::BuildDateEpoch
+                | Key::BuildCommitShaShort
+                | Key::BuildDirty
+                | Key::Description
+                | Key::Doi
+                | Key::FundingUrl
+                | Key::LicensesCompliance
+                | Key::NameSlug
+                | Key::PullRequestTitle
+                | Key::RepoCloneUrlPush
+                | Key::RepoDefaultBranch
+                | Key::RepoForkOfUrl
+                | Key::RepoForkUrl
+                | Key::VersionDescribe
                 | Key::RepoVersionedFilePrefixUrl => None,
-                Key::BuildBranch => var(environment, "BITBUCKET_BRANCH", C_HIGH),
-                Key::BuildNumber => var(environment, "BITBUCKET_BUILD_NUMBER", C_HIGH),
-                Key::BuildTag => var(environment, "BITBUCKET_TAG", C_HIGH),
-                Key::Ci => {
-                    var(environment, "CI", C_HIGH).or_else(|| Some((C_LOW, "false".to_owned())))
+                Key::BuildBranch => var(environment, "BITBUCKET_BRANCH", ConfidenceScore::HIGH),
+                Key::BuildCommitSha => var(environment, "BITBUCKET_COMMIT", ConfidenceScore::HIGH),
+                Key::BuildNumber => {
+                    var(environment, "BITBUCKET_BUILD_NUMBER", ConfidenceScore::HIGH)
                 }
-                Key::Name => var(environment, "BITBUCKET_PROJECT_KEY", C_HIGH),
-                Key::RepoCloneUrl => var(environment, "BITBUCKET_GIT_SSH_ORIGIN", C_HIGH), // NOTE This actually contains the common SSH idnetifier type "URL", which is not a valid URL, Thus we use it here, not for RepoCloneUrlSsh
-                Key::RepoCloneUrlHttp => var(environment, "BITBUCKET_GIT_HTTP_ORIGIN", C_HIGH),
-                Key::RepoWebUrl => {
-                    // BITBUCKET_REPO_FULL_NAME = The full name of the repository
-                    // (everything that comes after https://bitbucket.org/).
-                    var(environment, "BITBUCKET_REPO_FULL_NAME", C_HIGH).map(
-                        |(confidence, project_slug)| {
-                            (confidence, format!("https://bitbucket.org/{project_slug}"))
-                        },
-                    ) // TODO Maybe use a constant here? (for "https://bitbucket.org")
+                Key::BuildTag => var(environment, "BITBUCKET_TAG", ConfidenceScore::HIGH),
+                Key::Ci => var(environment, "CI", ConfidenceScore::HIGH)
+                    .or_else(|| Some((ConfidenceScore::LOW, "false".to_owned()))),
+                Key::Name => var(environment, "BITBUCKET_PROJECT_KEY", ConfidenceScore::HIGH),
+                Key::PullRequestNumber => {
+                    var(environment, "BITBUCKET_PR_ID", ConfidenceScore::HIGH)
                 }
+                Key::PullRequestSourceBranch => pull_request_source_branch(environment),
+                Key::PullRequestTargetBranch => var(
+                    environment,
+                    "BITBUCKET_PR_DESTINATION_BRANCH",
+                    ConfidenceScore::HIGH,
+                ),
+                Key::PullRequestUrl => pull_request_url(environment),
+                Key::RepoCloneUrl => var(
+                    environment,
+                    "BITBUCKET_GIT_SSH_ORIGIN",
+                    ConfidenceScore::HIGH,
+                ), // NOTE This actually contains the common SSH idnetifier type "URL", which is not a valid URL, Thus we use it here, not for RepoCloneUrlSsh
+                Key::RepoCloneUrlHttp => var(
+                    environment,
+                    "BITBUCKET_GIT_HTTP_ORIGIN",
+                    ConfidenceScore::HIGH,
+                ),
+                Key::RepoCloneUrlSsh => repo_clone_url_ssh(environment)?,
+                Key::RepoIssuesUrl => repo_issues_url(environment)?,
+                Key::RepoSlug => var(
+                    environment,
+                    "BITBUCKET_REPO_FULL_NAME",
+                    ConfidenceScore::HIGH,
+                ),
+                Key::RepoWebUrl => repo_web_url(environment),
                 Key::Version => self
                     .version_from_build_tag(environment, key)?
-                    .or_else(|| var(environment, "BITBUCKET_COMMIT", C_HIGH)),
+                    .or_else(|| var(environment, "BITBUCKET_COMMIT", ConfidenceScore::HIGH)),
+                Key::VersionControlSystem => {
+                    var(environment, "BITBUCKET_BUILD_NUMBER", ConfidenceScore::HIGH)
+                        .map(|_| (ConfidenceScore::HIGH, constants::VCS_GIT.to_owned()))
+                }
             },
         )
     }
@@ -0,0 +1,127 @@
+// SPDX-FileCopyrightText: 2021 - 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::constants;
+use crate::environment::Environment;
+use crate::var::ConfidenceScore;
+use crate::var::Key;
+
+use super::var;
+use super::Hierarchy;
+use super::RetrieveRes;
+
+/// This sources values from the environment variables set by the CI provider Buildkite.
+/// See: <https://buildkite.com/docs/pipelines/environment-variables>
+pub struct VarSource;
+
+fn build_commit_sha(environment: &Environment) -> Option<(ConfidenceScore, String)> {
+    // NOTE Before the checkout happened,
+    //      this may also be a ref like "HEAD",
+    //      which is of no use to us.
+    var(environment, "BUILDKITE_COMMIT", ConfidenceScore::HIGH).filter(|(_confidence, sha)| {
+        !sha.is_empty() && sha.chars().all(|chr| chr.is_ascii_hexdigit())
+    })
+}
+
+fn repo_ci_url(environment: &Environment) -> Option<(ConfidenceScore, String)> {
+    // BUILDKITE_BUILD_URL = "https://buildkite.com/acme-inc/my-project/builds/1514"
+    var(environment, "BUILDKITE_BUILD_URL", ConfidenceScore::HIGH).and_then(
+        |(confidence, build_url)| {
+            build_url
+                .trim_end_matches('/')
+                .rsplit_once("/builds/")
+                .map(|(pipeline_url, _build_number)| (confidence, pipeline_url.to_owned()))
+        },
+    )
+}
+
+impl super::VarSource for VarSource {
+    fn is_usable(&self, _environment: &mut Environment) -> bool {
+        true
+    }
+
+    fn hierarchy(&self) -> Hierarchy {
+        Hierarchy::High
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn properties(&self) -> &Vec<String> {
+        &super::NO_PROPS
+    }
+
+    #[remain::check]
+    fn retrieve(&self, environment: &mut Environment, key: Key) -> RetrieveRes {
+        Ok(
+            #[remain::sorted]
+            match key {
+                Key::BuildArch
+                | Key::BuildCommitShaShort
+                | Key::BuildDate
+                | Key::BuildDateEpoch
+                | Key::BuildDirty
+                | Key::BuildHostCpuCount
+                | Key::BuildHostingUrl
+                | Key::BuildHostKernelVersion
+                | Key::BuildHostPlatformTriple
+                | Key::BuildOs
+                | Key::BuildOsFamily
+                | Key::Description
+                | Key::Doi
+                | Key::FundingUrl
+                | Key::License
+                | Key::Licenses
+                | Key::LicensesCompliance
+                | Key::Name
+                | Key::NameMachineReadable
+                | Key::NameSlug
+                | Key::PullRequestNumber
+                | Key::PullRequestSourceBranch
+                | Key::PullRequestTargetBranch
+                | Key::PullRequestTitle
+                | Key::PullRequestUrl
+                | Key::RepoCloneUrlGit
+                | Key::RepoCloneUrlHttp
+                | Key::RepoCloneUrlPush
+                | Key::RepoCloneUrlSsh
+                | Key::RepoCommitPrefixUrl
+                | Key::RepoDefaultBranch
+                | Key::RepoForkOfUrl
+                | Key::RepoForkUrl
+                | Key::RepoIssuesUrl
+                | Key::RepoNamespace
+                | Key::RepoOwner
+                | Key::RepoRawVersionedPrefixUrl
+                | Key::RepoReleasesUrl
+                | Key::RepoSlug
+                | Key::RepoVersionedDirPrefixUrl
+                | Key::RepoVersionedFilePrefixUrl
+                | Key::RepoWebUrl
+                | Key::VersionBuildMeta
+                | Key::VersionDate
+                | Key::VersionDescribe
+                | Key::VersionMajor
+                | Key::VersionMinor
+                | Key::VersionPatch
+                | Key::VersionPrerelease => None,
+                Key::BuildBranch => var(environment, "BUILDKITE_BRANCH", ConfidenceScore::HIGH),
+                Key::BuildCommitSha => build_commit_sha(environment),
+                Key::BuildNumber => {
+                    var(environment, "BUILDKITE_BUILD_NUMBER", ConfidenceScore::HIGH)
+                }
+                Key::BuildTag => var(environment, "BUILDKITE_TAG", ConfidenceScore::HIGH),
+                Key::Ci => var(environment, "CI", ConfidenceScore::HIGH)
+                    .or_else(|| Some((ConfidenceScore::LOW, "false".to_owned()))),
+                Key::RepoCiUrl => repo_ci_url(environment),
+                Key::RepoCloneUrl => var(environment, "BUILDKITE_REPO", ConfidenceScore::HIGH),
+                Key::Version => self.version_from_build_tag(environment, key)?,
+                // NOTE Buildkite only supports git
+                Key::VersionControlSystem => var(environment, "BUILDKITE", ConfidenceScore::HIGH)
+                    .map(|_| (ConfidenceScore::HIGH, constants::VCS_GIT.to_owned())),
+            },
+        )
+    }
+}
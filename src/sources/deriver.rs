@@ -4,10 +4,11 @@
 
 use url::Url;
 
+use crate::constants;
 use crate::environment::Environment;
 use crate::tools::git::TransferProtocol;
 use crate::value_conversions;
-use crate::var::Key;
+use crate::var::{ConfidenceScore, Key};
 
 use super::Hierarchy;
 use super::RetrieveRes;
@@ -82,6 +83,58 @@ fn name_machine_readable(environment: &Environment) -> RetrieveRes {
     })
 }
 
+fn name_slug(environment: &Environment) -> RetrieveRes {
+    let key = Key::NameSlug;
+    let from_name = conv_val_with_env!(environment, Name, key, name_to_slug);
+    Ok(if from_name.is_some() {
+        from_name
+    } else {
+        conv_val_with_env!(environment, NameMachineReadable, key, name_to_slug)
+    })
+}
+
+fn build_date_epoch(environment: &Environment) -> RetrieveRes {
+    let key = Key::BuildDateEpoch;
+    let from_build_date =
+        conv_val_with_env!(environment, BuildDate, key, date_to_epoch, Key::BuildDate);
+    Ok(if from_build_date.is_some() {
+        from_build_date
+    } else {
+        conv_val_with_env!(
+            environment,
+            VersionDate,
+            key,
+            date_to_epoch,
+            Key::VersionDate
+        )
+    })
+}
+
+/// NOTE The slug itself is usually derived from the web URL,
+///      which in turn is usually derived from the clone URL,
+///      so we would need a third run of the deriver
+///      if we were to only derive from the slug.
+fn repo_owner(environment: &Environment) -> RetrieveRes {
+    let key = Key::RepoOwner;
+    let from_slug = conv_val_with_env!(environment, RepoSlug, key, slug_to_owner);
+    Ok(if from_slug.is_some() {
+        from_slug
+    } else {
+        conv_val_with_env!(environment, RepoWebUrl, key, web_url_to_owner)
+    })
+}
+
+/// See the note on [`repo_owner`].
+fn repo_namespace(environment: &Environment) -> RetrieveRes {
+    let key = Key::RepoNamespace;
+    let from_slug = conv_val_with_env!(environment, RepoSlug, key, slug_to_namespace);
+    Ok(if from_slug.is_some() {
+        from_slug
+    } else {
+        conv_val_with_env!(environment, RepoWebUrl, key, web_url_to_namespace)
+    })
+}
+
 fn repo_clone_url_specific(environment: &Environment, protocol: TransferProtocol) -> RetrieveRes {
     let key = protocol.to_clone_url_key();
     let from_web_url =
@@ -116,6 +169,147 @@ fn repo_clone_url_specific(environment: &Environment, protocol: TransferProtocol
     }))
 }
 
+/// As a last resort - if no other source figured it out already -
+/// we check for the meta-data directories of the supported systems.
+///
+/// NOTE We do not report "none" here if we find neither,
+///      as we do not know whether we are in the project root at all.
+fn version_control_system(environment: &Environment) -> Option<(ConfidenceScore, String)> {
+    let key = Key::VersionControlSystem;
+    overwrite_guard!(
+        environment,
+        key,
+        environment
+            .settings
+            .repo_path
+            .as_ref()
+            .and_then(|repo_path| {
+                [
+                    (".git", constants::VCS_GIT),
+                    (".hg", constants::VCS_HG),
+                    (".svn", constants::VCS_SVN),
+                ]
+                .into_iter()
+                .find(|(meta_dir, _vcs)| repo_path.join(meta_dir).exists())
+                .map(|(_meta_dir, vcs)| (ConfidenceScore::LOW, vcs.to_owned()))
+            })
+    )
+}
+
+/// One way the value of a key may be derived from the value of an other one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Derivation {
+    /// The key whose value the value is derived from
+    pub from: Key,
+    /// The name of the function in [`value_conversions`] doing the conversion,
+    /// or "copy", if the value is used as-is
+    pub converter: &'static str,
+}
+
+/// Creates a [`Derivation`] as a literal,
+/// so references to it may be promoted to `'static`.
+macro_rules! from {
+    ($from:ident, $converter:expr) => {
+        Derivation {
+            from: Key::$from,
+            converter: $converter,
+        }
+    };
+}
+
+const COPY: &str = "copy";
+
+/// Returns the ways the value of `key` may be derived from the values of other keys,
+/// in order of preference.
+#[remain::check]
+#[must_use]
+pub const fn derivations(key: Key) -> &'static [Derivation] {
+    #[remain::sorted]
+    match key {
+        Key::BuildArch
+        | Key::BuildBranch
+        | Key::BuildCommitSha
+        | Key::BuildDate
+        | Key::BuildHostCpuCount
+        | Key::BuildHostKernelVersion
+        | Key::BuildHostPlatformTriple
+        | Key::BuildNumber
+        | Key::BuildOs
+        | Key::BuildOsFamily
+        | Key::BuildTag
+        | Key::Ci
+        | Key::Description
+        | Key::Doi
+        | Key::FundingUrl
+        | Key::License
+        | Key::Licenses
+        | Key::LicensesCompliance
+        | Key::PullRequestNumber
+        | Key::PullRequestSourceBranch
+        | Key::PullRequestTargetBranch
+        | Key::PullRequestTitle
+        | Key::PullRequestUrl
+        | Key::RepoDefaultBranch
+        | Key::RepoForkOfUrl
+        | Key::RepoForkUrl
+        | Key::Version
+        | Key::VersionControlSystem
+        | Key::VersionDate
+        | Key::VersionDescribe => &[],
+        Key::BuildCommitShaShort => &[from!(BuildCommitSha, "sha_to_short_sha")],
+        Key::BuildDateEpoch => &[
+            from!(BuildDate, "date_to_epoch"),
+            from!(VersionDate, "date_to_epoch"),
+        ],
+        Key::BuildDirty => &[from!(VersionDescribe, "describe_to_dirty")],
+        Key::BuildHostingUrl => &[from!(RepoWebUrl, "web_url_to_build_hosting_url")],
+        Key::Name => &[from!(NameMachineReadable, COPY)],
+        Key::NameMachineReadable => &[
+            from!(Name, "name_to_machine_readable_name"),
+            from!(RepoWebUrl, "web_url_to_machine_readable_name"),
+        ],
+        Key::NameSlug => &[
+            from!(Name, "name_to_slug"),
+            from!(NameMachineReadable, "name_to_slug"),
+        ],
+        Key::RepoCiUrl => &[from!(RepoWebUrl, "web_url_to_ci_url")],
+        Key::RepoCloneUrl => &[from!(RepoWebUrl, "web_url_to_clone_url")],
+        Key::RepoCloneUrlGit | Key::RepoCloneUrlHttp | Key::RepoCloneUrlSsh => &[
+            from!(RepoWebUrl, "web_url_to_clone_url"),
+            from!(RepoCloneUrl, "clone_url_conversion"),
+        ],
+        Key::RepoCloneUrlPush => &[from!(RepoCloneUrlSsh, COPY)],
+        Key::RepoCommitPrefixUrl => &[from!(RepoWebUrl, "web_url_to_commit_prefix_url")],
+        Key::RepoIssuesUrl => &[from!(RepoWebUrl, "web_url_to_issues_url")],
+        Key::RepoNamespace => &[
+            from!(RepoSlug, "slug_to_namespace"),
+            from!(RepoWebUrl, "web_url_to_namespace"),
+        ],
+        Key::RepoOwner => &[
+            from!(RepoSlug, "slug_to_owner"),
+            from!(RepoWebUrl, "web_url_to_owner"),
+        ],
+        Key::RepoRawVersionedPrefixUrl => &[from!(RepoWebUrl, "web_url_to_raw_prefix_url")],
+        Key::RepoReleasesUrl => &[from!(RepoWebUrl, "web_url_to_releases_url")],
+        Key::RepoSlug => &[from!(RepoWebUrl, "web_url_to_slug")],
+        Key::RepoVersionedDirPrefixUrl => {
+            &[from!(RepoWebUrl, "web_url_to_versioned_dir_prefix_url")]
+        }
+        Key::RepoVersionedFilePrefixUrl => {
+            &[from!(RepoWebUrl, "web_url_to_versioned_file_prefix_url")]
+        }
+        Key::RepoWebUrl => &[
+            from!(RepoCloneUrl, "clone_url_to_web_url"),
+            from!(RepoCloneUrlSsh, "clone_url_to_web_url"),
+        ],
+        Key::VersionBuildMeta => &[from!(Version, "version_to_build_meta")],
+        Key::VersionMajor => &[from!(Version, "version_to_major")],
+        Key::VersionMinor => &[from!(Version, "version_to_minor")],
+        Key::VersionPatch => &[from!(Version, "version_to_patch")],
+        Key::VersionPrerelease => &[from!(Version, "version_to_prerelease")],
+    }
+}
+
 impl super::VarSource for VarSource {
     fn is_usable(&self, _environment: &mut Environment) -> bool {
         true
@@ -149,7 +343,31 @@ impl super::VarSource for VarSource {
                 | Key::License
                 | Key::Licenses
                 | Key::Version
+                | Key::BuildHostPlatformTriple
+                | Key::BuildHostKernelVersion
+                | Key::BuildHostCpuCount
+                | Key::BuildCommitSha
+                | Key::Description
+                | Key::Doi
+                | Key::FundingUrl
+                | Key::LicensesCompliance
+                | Key::PullRequestNumber
+                | Key::PullRequestSourceBranch
+                | Key::PullRequestTargetBranch
+                | Key::PullRequestTitle
+                | Key::PullRequestUrl
+                | Key::RepoDefaultBranch
+                | Key::RepoForkOfUrl
+                | Key::RepoForkUrl
+                | Key::VersionDescribe
                 | Key::VersionDate => None,
+                Key::BuildCommitShaShort => {
+                    conv_val_with_env!(environment, BuildCommitSha, key, sha_to_short_sha)
+                }
+                Key::BuildDateEpoch => build_date_epoch(environment)?,
+                Key::BuildDirty => {
+                    conv_val_with_env!(environment, VersionDescribe, key, describe_to_dirty)
+                }
                 Key::BuildHostingUrl => {
                     conv_val_with_env!(environment, RepoWebUrl, key, web_url_to_build_hosting_url)
                 }
@@ -159,6 +377,10 @@ impl super::VarSource for VarSource {
                     environment.output.get(Key::NameMachineReadable).cloned()
                 ),
                 Key::NameMachineReadable => name_machine_readable(environment)?,
+                Key::NameSlug => name_slug(environment)?,
+                Key::RepoCiUrl => {
+                    conv_val_with_env!(environment, RepoWebUrl, key, web_url_to_ci_url)
+                }
                 Key::RepoCloneUrl => conv_val_with_env!(
                     environment,
                     RepoWebUrl,
@@ -172,6 +394,11 @@ impl super::VarSource for VarSource {
                 Key::RepoCloneUrlHttp => {
                     repo_clone_url_specific(environment, TransferProtocol::Https)?
                 }
+                Key::RepoCloneUrlPush => overwrite_guard!(
+                    environment,
+                    key,
+                    environment.output.get(Key::RepoCloneUrlSsh).cloned()
+                ),
                 Key::RepoCloneUrlSsh => {
                     repo_clone_url_specific(environment, TransferProtocol::Ssh)?
                 }
@@ -181,9 +408,17 @@ impl super::VarSource for VarSource {
                 Key::RepoIssuesUrl => {
                     conv_val_with_env!(environment, RepoWebUrl, key, web_url_to_issues_url)
                 }
+                Key::RepoNamespace => repo_namespace(environment)?,
+                Key::RepoOwner => repo_owner(environment)?,
                 Key::RepoRawVersionedPrefixUrl => {
                     conv_val_with_env!(environment, RepoWebUrl, key, web_url_to_raw_prefix_url)
                 }
+                Key::RepoReleasesUrl => {
+                    conv_val_with_env!(environment, RepoWebUrl, key, web_url_to_releases_url)
+                }
+                Key::RepoSlug => {
+                    conv_val_with_env!(environment, RepoWebUrl, key, web_url_to_slug)
+                }
                 Key::RepoVersionedDirPrefixUrl => {
                     conv_val_with_env!(
                         environment,
@@ -207,6 +442,22 @@ impl super::VarSource for VarSource {
                         conv_val_with_env!(environment, RepoCloneUrlSsh, key, clone_url_to_web_url)
                     }
                 }
+                Key::VersionBuildMeta => {
+                    conv_val_with_env!(environment, Version, key, version_to_build_meta)
+                }
+                Key::VersionControlSystem => version_control_system(environment),
+                Key::VersionMajor => {
+                    conv_val_with_env!(environment, Version, key, version_to_major)
+                }
+                Key::VersionMinor => {
+                    conv_val_with_env!(environment, Version, key, version_to_minor)
+                }
+                Key::VersionPatch => {
+                    conv_val_with_env!(environment, Version, key, version_to_patch)
+                }
+                Key::VersionPrerelease => {
+                    conv_val_with_env!(environment, Version, key, version_to_prerelease)
+                }
             },
         )
     }
@@ -0,0 +1,109 @@
+// SPDX-FileCopyrightText: 2021 - 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::constants;
+use crate::environment::Environment;
+use crate::var::ConfidenceScore;
+use crate::var::Key;
+
+use super::var;
+use super::var_date;
+use super::Hierarchy;
+use super::RetrieveRes;
+
+/// This sources values from the environment variables set by the CI provider Drone.
+/// See: <https://docs.drone.io/pipeline/environment/reference/>
+pub struct VarSource;
+
+impl super::VarSource for VarSource {
+    fn is_usable(&self, _environment: &mut Environment) -> bool {
+        true
+    }
+
+    fn hierarchy(&self) -> Hierarchy {
+        Hierarchy::High
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn properties(&self) -> &Vec<String> {
+        &super::NO_PROPS
+    }
+
+    #[remain::check]
+    fn retrieve(&self, environment: &mut Environment, key: Key) -> RetrieveRes {
+        Ok(
+            #[remain::sorted]
+            match key {
+                Key::BuildArch
+                | Key::BuildCommitShaShort
+                | Key::BuildDateEpoch
+                | Key::BuildDirty
+                | Key::BuildHostCpuCount
+                | Key::BuildHostingUrl
+                | Key::BuildHostKernelVersion
+                | Key::BuildHostPlatformTriple
+                | Key::BuildOs
+                | Key::BuildOsFamily
+                | Key::Description
+                | Key::Doi
+                | Key::FundingUrl
+                | Key::License
+                | Key::Licenses
+                | Key::LicensesCompliance
+                | Key::NameMachineReadable
+                | Key::NameSlug
+                | Key::PullRequestNumber
+                | Key::PullRequestSourceBranch
+                | Key::PullRequestTargetBranch
+                | Key::PullRequestTitle
+                | Key::PullRequestUrl
+                | Key::RepoCiUrl
+                | Key::RepoCloneUrl
+                | Key::RepoCloneUrlGit
+                | Key::RepoCloneUrlHttp
+                | Key::RepoCloneUrlPush
+                | Key::RepoCloneUrlSsh
+                | Key::RepoCommitPrefixUrl
+                | Key::RepoDefaultBranch
+                | Key::RepoForkOfUrl
+                | Key::RepoForkUrl
+                | Key::RepoIssuesUrl
+                | Key::RepoNamespace
+                | Key::RepoRawVersionedPrefixUrl
+                | Key::RepoReleasesUrl
+                | Key::RepoVersionedDirPrefixUrl
+                | Key::RepoVersionedFilePrefixUrl
+                | Key::VersionBuildMeta
+                | Key::VersionDate
+                | Key::VersionDescribe
+                | Key::VersionMajor
+                | Key::VersionMinor
+                | Key::VersionPatch
+                | Key::VersionPrerelease => None,
+                Key::BuildBranch => var(environment, "DRONE_BRANCH", ConfidenceScore::HIGH),
+                Key::BuildCommitSha => var(environment, "DRONE_COMMIT", ConfidenceScore::HIGH),
+                Key::BuildDate => var_date(
+                    environment,
+                    key,
+                    "DRONE_BUILD_STARTED",
+                    ConfidenceScore::HIGH,
+                ),
+                Key::BuildNumber => var(environment, "DRONE_BUILD_NUMBER", ConfidenceScore::HIGH),
+                Key::BuildTag => var(environment, "DRONE_TAG", ConfidenceScore::HIGH),
+                Key::Ci => var(environment, "CI", ConfidenceScore::HIGH)
+                    .or_else(|| Some((ConfidenceScore::LOW, "false".to_owned()))),
+                Key::Name => var(environment, "DRONE_REPO_NAME", ConfidenceScore::HIGH),
+                Key::RepoOwner => var(environment, "DRONE_REPO_OWNER", ConfidenceScore::HIGH),
+                Key::RepoSlug => var(environment, "DRONE_REPO", ConfidenceScore::HIGH),
+                Key::RepoWebUrl => var(environment, "DRONE_REPO_LINK", ConfidenceScore::HIGH),
+                Key::Version => self.version_from_build_tag(environment, key)?,
+                Key::VersionControlSystem => var(environment, "DRONE", ConfidenceScore::HIGH)
+                    .map(|_| (ConfidenceScore::HIGH, constants::VCS_GIT.to_owned())),
+            },
+        )
+    }
+}
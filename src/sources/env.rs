@@ -3,22 +3,41 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::environment::Environment;
+use crate::value_conversions;
 use crate::var;
+use crate::var::ConfidenceScore;
+use crate::var::DynamicKey;
 use crate::var::Key;
-use crate::var::C_HIGH;
 
 use super::var;
 use super::Hierarchy;
 use super::RetrieveRes;
 
+/// The de-facto standard environment variable for reproducible builds, see:
+/// <https://reproducible-builds.org/specs/source-date-epoch/>
+pub const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+
 /// Sources from environment variables
 /// with the same names as the those used for output.
 ///
 /// We treat this as a way to (almost) preset certain output values,
 /// which is both useful for testing
 /// and streamlining the process during production use.
+///
+/// The build date keys fall back to [`SOURCE_DATE_EPOCH`], if set.
 pub struct VarSource;
 
+fn source_date_epoch(environment: &Environment, key: Key) -> RetrieveRes {
+    let epoch = var(environment, SOURCE_DATE_EPOCH, ConfidenceScore::HIGH);
+    Ok(if matches!(key, Key::BuildDateEpoch) {
+        epoch
+    } else if let (Key::BuildDate, Some((confidence, epoch))) = (key, epoch) {
+        value_conversions::epoch_to_our_format(environment, &epoch)?.map(|date| (confidence, date))
+    } else {
+        None
+    })
+}
+
 impl super::VarSource for VarSource {
     fn is_usable(&self, _environment: &mut Environment) -> bool {
         true
@@ -37,6 +56,27 @@ impl super::VarSource for VarSource {
     }
 
     fn retrieve(&self, environment: &mut Environment, key: Key) -> RetrieveRes {
-        Ok(var(environment, &var::get(key).key(environment), C_HIGH))
+        let value = var(
+            environment,
+            &var::get(key).key(environment),
+            ConfidenceScore::HIGH,
+        );
+        if value.is_some() {
+            Ok(value)
+        } else {
+            source_date_epoch(environment, key)
+        }
+    }
+
+    fn retrieve_dynamic(
+        &self,
+        environment: &mut Environment,
+        key: &'static DynamicKey,
+    ) -> RetrieveRes {
+        Ok(var(
+            environment,
+            &key.variable.key(environment),
+            ConfidenceScore::HIGH,
+        ))
     }
 }
@@ -2,14 +2,20 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use chrono::Local;
-use cli_utils::BoxError;
+use chrono::Utc;
+use lazy_static::lazy_static;
+use regex::Regex;
 
 use crate::cleanup;
 use crate::environment::Environment;
+#[cfg(feature = "license-detection")]
 use crate::license;
+use crate::reuse;
 use crate::std_error;
-use crate::var::{Confidence, Key, C_HIGH, C_LOW};
+use crate::tools::cmd;
+use crate::tools::git_attributes;
+use crate::var::{ConfidenceScore, Key};
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 use std::{env, fs};
 
@@ -41,64 +47,122 @@ fn dir_name(path: &Path) -> Result<String, std_error::Error> {
 fn file_content(path: &Path) -> RetrieveRes {
     Ok(if path.exists() && path.is_file() {
         let content = fs::read_to_string(path)?;
-        Some((C_HIGH, content))
+        Some((ConfidenceScore::HIGH, content))
     } else {
         None
     })
 }
 
-/// Returns a list of SPDX license identifiers.
-/// It looks for the REUSE "LICENSES" dir in the project root,
-/// and returns the file names of the containing "*.txt" files.
-fn licenses_from_dir(repo_path: &Path) -> Result<Option<Vec<String>>, std_error::Error> {
-    let licenses_dir = repo_path.join("LICENSES");
-    if licenses_dir.is_dir() {
-        log::trace!("Fetching (REUSE) licenses from {licenses_dir:#?} ...");
-        let mut licenses = Vec::<String>::new();
-        for file in licenses_dir.read_dir()? {
-            let file_name = file?.file_name();
-            let file_name = file_name.to_str().ok_or(std_error::Error::NotValidUtf8)?;
-            if file_name.to_lowercase().ends_with(".txt") {
-                let license_id = file_name.get(0 .. file_name.len() - 4)
-                    .ok_or("This should be impossible, as we already checked that the extension is there.")
-                    .map_err(BoxError::from)?.to_owned();
-                log::trace!("Found (REUSE) license: {license_id}");
-                licenses.push(license_id);
+/// The locations of the file that configures a custom domain
+/// for GitHub pages, relative to the project root;
+/// one for each of the supported publishing sources.
+const PAGES_CNAME_FILES: &[&str] = &["CNAME", "docs/CNAME"];
+
+/// Returns the custom domain pages URL,
+/// if the project contains a GitHub pages "CNAME" file.
+fn build_hosting_url(environment: &mut Environment) -> RetrieveRes {
+    let repo_path = repo_path(environment)?;
+    for cname_file in PAGES_CNAME_FILES {
+        if let Some((confidence, content)) = file_content(&repo_path.join(cname_file))? {
+            if let Some(domain) = content.lines().map(str::trim).find(|line| !line.is_empty()) {
+                let pages_url = if domain.contains("://") {
+                    domain.to_owned()
+                } else {
+                    format!("https://{domain}")
+                };
+                return Ok(Some((confidence, pages_url)));
             }
         }
-        Ok(Some(licenses))
-    } else {
-        Ok(None)
     }
+    Ok(None)
 }
 
+/// SPDX license identifiers, each with the file it was found in.
+type FoundLicenses = Vec<(String, PathBuf)>;
+
 /// Returns a list of SPDX license identifiers.
-/// It searches for "(LICEN[CS]E|COPYING).*"" files in the project root dir,
-/// and figures out which license it contains.
-fn licenses_from_files(repo_path: &Path) -> Result<Option<Vec<String>>, std_error::Error> {
-    let licenses = license::get_licenses(&repo_path.display().to_string())?;
-    Ok(if licenses.is_empty() {
-        None
-    } else {
-        Some(licenses)
-    })
+/// It looks for REUSE "LICENSES" dirs
+/// in the project root and - with `max_depth` > 0 - its sub-directories,
+/// and returns the file names of the containing "*.txt" files,
+/// leaving out the ones excluded by their git attributes
+/// (see [`git_attributes::Attributes::is_excluded`]).
+fn licenses_from_dir(
+    repo_path: &Path,
+    max_depth: usize,
+) -> Result<Option<FoundLicenses>, super::Error> {
+    log::trace!("Fetching (REUSE) licenses from {repo_path:#?} ...");
+    // NOTE The license files lie one level deeper than the "LICENSES" dir
+    let licenses: FoundLicenses =
+        git_attributes::find_files(repo_path, max_depth + 1, |file_name| {
+            file_name.to_lowercase().ends_with(".txt")
+        })?
+        .into_iter()
+        .filter(|file| file.parent().and_then(Path::file_name) == Some(OsStr::new("LICENSES")))
+        .filter_map(|file| {
+            let license_id = file.file_stem()?.to_str()?.to_owned();
+            log::trace!("Found (REUSE) license: {license_id}");
+            Some((license_id, file))
+        })
+        .collect();
+    Ok((!licenses.is_empty() || repo_path.join("LICENSES").is_dir()).then_some(licenses))
+}
+
+/// Returns a list of SPDX license identifiers.
+/// It searches for "(LICEN[CS]E|COPYING).*"" files
+/// in the project root dir and - with `max_depth` > 0 - its sub-directories,
+/// and figures out which license each of them contains.
+#[cfg(feature = "license-detection")]
+fn licenses_from_files(
+    repo_path: &Path,
+    max_depth: usize,
+) -> Result<Option<FoundLicenses>, super::Error> {
+    let licenses = license::get_licenses(repo_path, max_depth)?;
+    Ok((!licenses.is_empty()).then_some(licenses))
+}
+
+#[cfg(not(feature = "license-detection"))]
+#[allow(clippy::unnecessary_wraps)]
+const fn licenses_from_files(
+    _repo_path: &Path,
+    _max_depth: usize,
+) -> Result<Option<FoundLicenses>, super::Error> {
+    Ok(None)
 }
 
+/// Returns the unique SPDX license identifiers of the project,
+/// searching down to [`crate::settings::Settings::license_scan_depth`].
+/// Where each of them was found is logged,
+/// which is only reported by default when scanning sub-directories.
 fn licenses(
     environment: &mut Environment,
     files_first: bool,
-) -> Result<Option<Vec<String>>, std_error::Error> {
+) -> Result<Option<Vec<String>>, super::Error> {
+    let max_depth = environment.settings.license_scan_depth;
     let repo_path = repo_path(environment)?;
     let fetcher_functions = if files_first {
         &[licenses_from_files, licenses_from_dir]
     } else {
         &[licenses_from_dir, licenses_from_files]
     };
+    let provenance_level = if max_depth > 0 {
+        log::Level::Info
+    } else {
+        log::Level::Debug
+    };
     for lff in fetcher_functions {
-        let licenses = lff(repo_path)?;
-        if licenses.is_some() {
+        if let Some(found) = lff(repo_path, max_depth)? {
+            for (license_id, file) in &found {
+                let file = file.strip_prefix(repo_path).unwrap_or(file).display();
+                log::log!(provenance_level, "Found license {license_id} in '{file}'");
+            }
+            let mut licenses: Vec<String> = found
+                .into_iter()
+                .map(|(license_id, _file)| license_id)
+                .collect();
+            licenses.sort();
+            licenses.dedup();
             log::trace!("Found licenses: {licenses:#?}");
-            return Ok(licenses);
+            return Ok(Some(licenses));
         }
     }
     Ok(None)
@@ -106,7 +170,7 @@ fn licenses(
 
 /// Extracts a single license if there is only a single license,
 /// otherwise returns `None`.
-fn license(environment: &mut Environment) -> Result<Option<String>, std_error::Error> {
+fn license(environment: &mut Environment) -> Result<Option<String>, super::Error> {
     if let Some(licenses) = licenses(environment, true)? {
         if licenses.len() == 1 {
             return Ok(licenses.first().map(ToOwned::to_owned));
@@ -115,50 +179,484 @@ fn license(environment: &mut Environment) -> Result<Option<String>, std_error::E
     Ok(None)
 }
 
+/// The content of the `VERSION` file,
+/// falling back to the version in a package manifest
+/// (`Cargo.toml`, `package.json` or `PKG-INFO`).
+/// This is the last resort in the version fall-back chain,
+/// for when git has nothing to offer,
+/// see [`super::git`].
 fn version(environment: &mut Environment) -> RetrieveRes {
     Ok(match &environment.settings.repo_path {
         Some(repo_path) => {
-            let version_file = repo_path.join("VERSION");
-            file_content(&version_file)?
+            let version_file = repo_path.join(super::archive::FILE_VERSION);
+            match file_content(&version_file)? {
+                // e.g. "$Format:%(describe)$" in a git checkout
+                // of a file meant for `git archive` to expand
+                Some(version) if super::archive::is_unexpanded(&version.1) => {
+                    super::archive::manifest_version(repo_path)?
+                }
+                Some(version) => Some(version),
+                None => super::archive::manifest_version(repo_path)?,
+            }
         }
         _ => None,
     })
 }
 
+/// The README files we look for a title in, relative to the project root,
+/// in order of preference.
+const README_FILES: &[(&str, ReadmeFormat)] = &[
+    ("README.md", ReadmeFormat::Markdown),
+    ("README.rst", ReadmeFormat::ReStructuredText),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum ReadmeFormat {
+    Markdown,
+    ReStructuredText,
+}
+
+/// Whether the line consists of only (and at least two)
+/// repetitions of a single punctuation character,
+/// as used to underline (and overline) headings
+/// in reStructuredText and in setext style Markdown.
+fn is_adornment(line: &str, allowed: &[char]) -> bool {
+    let line = line.trim_end();
+    let mut chars = line.chars();
+    chars.next().is_some_and(|first| {
+        allowed.contains(&first) && line.len() > 1 && chars.all(|chr| chr == first)
+    })
+}
+
+/// Returns the title of a Markdown document,
+/// which is its first level 1 heading,
+/// either in ATX (`# Title`) or setext (`Title` underlined with `===`) style.
+/// Headings within fenced code blocks are ignored.
+fn markdown_title(content: &str) -> Option<String> {
+    let mut in_code_block = false;
+    let mut prev_line: Option<&str> = None;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        if indent < 4 && (trimmed.starts_with("```") || trimmed.starts_with("~~~")) {
+            in_code_block = !in_code_block;
+            prev_line = None;
+            continue;
+        }
+        if in_code_block || indent >= 4 {
+            prev_line = None;
+            continue;
+        }
+        let atx_title = trimmed
+            .strip_prefix('#')
+            .filter(|heading| heading.is_empty() || heading.starts_with([' ', '\t']))
+            .map(|heading| heading.trim().trim_end_matches('#').trim_end())
+            .filter(|title| !title.is_empty());
+        if let Some(title) = atx_title {
+            return Some(title.to_owned());
+        }
+        if is_adornment(trimmed, &['=']) {
+            if let Some(title) = prev_line {
+                return Some(title.to_owned());
+            }
+        }
+        prev_line = Some(trimmed.trim_end()).filter(|line| !line.is_empty());
+    }
+    None
+}
+
+/// The characters reStructuredText allows for section adornments.
+const RST_ADORNMENT_CHARS: &[char] = &[
+    '=', '-', '`', ':', '.', '\'', '"', '~', '^', '_', '*', '+', '#', '<', '>', '!', '$', '%', '&',
+    '(', ')', ',', '/', ';', '?', '@', '[', '\\', ']', '{', '|', '}',
+];
+
+/// Returns the title of a reStructuredText document,
+/// which is its first section title,
+/// no matter which adornment style it uses.
+fn rst_title(content: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().map(str::trim_end).collect();
+    for (idx, line) in lines.iter().enumerate() {
+        let next = lines.get(idx + 1);
+        if is_adornment(line, RST_ADORNMENT_CHARS) {
+            // overline and underline
+            if let (Some(title), Some(underline)) = (next, lines.get(idx + 2)) {
+                if underline == line && !title.trim().is_empty() {
+                    return Some(title.trim().to_owned());
+                }
+            }
+            continue;
+        }
+        if !line.trim().is_empty() && !line.starts_with([' ', '\t']) {
+            // underline only
+            if let Some(underline) = next {
+                if is_adornment(underline, RST_ADORNMENT_CHARS)
+                    && underline.chars().count() >= line.chars().count()
+                {
+                    return Some((*line).to_owned());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// The title of the projects README,
+/// which is often a prettier version of the projects name
+/// than the name of its directory.
+/// READMEs excluded by their git attributes are skipped
+/// (see [`git_attributes::Attributes::is_excluded`]).
+fn readme_title(repo_path: &Path) -> Result<Option<String>, std_error::Error> {
+    let attributes = git_attributes::Attributes::load(repo_path);
+    for (file_name, format) in README_FILES {
+        let readme = repo_path.join(file_name);
+        if !readme.is_file() || attributes.is_excluded(file_name, false) {
+            continue;
+        }
+        let content = fs::read_to_string(readme)?;
+        let title = match format {
+            ReadmeFormat::Markdown => markdown_title(&content),
+            ReadmeFormat::ReStructuredText => rst_title(&content),
+        };
+        if title.is_some() {
+            return Ok(title);
+        }
+    }
+    Ok(None)
+}
+
+/// The meta-data of a software release, as archived on Zenodo,
+/// see <https://developers.zenodo.org/#add-metadata-to-your-github-repository-release>.
+const FILE_ZENODO_JSON: &str = ".zenodo.json";
+/// The Citation File Format file, see <https://citation-file-format.github.io>.
+const FILE_CITATION_CFF: &str = "CITATION.cff";
+/// The fields of [`FILE_ZENODO_JSON`] that may contain a DOI,
+/// in order of preference;
+/// the concept DOI represents all versions of the project.
+const ZENODO_DOI_FIELDS: &[&str] = &["conceptdoi", "doi"];
+
+lazy_static! {
+    /// A DOI, possibly embedded in a longer text,
+    /// like a "https://doi.org/..." URL or a "doi:..." reference.
+    /// This leaves out parentheses, which are valid in DOIs,
+    /// but are also used around URLs in Markdown, and are rarely used in modern DOIs.
+    static ref R_DOI: Regex = Regex::new(r"10\.\d{4,9}/[-._;/:A-Za-z0-9]+").unwrap();
+    /// A DOI resolver URL, like the ones Zenodo badges link to.
+    static ref R_DOI_URL: Regex =
+        Regex::new(r"(?i)https?://(?:dx\.)?doi\.org/(10\.\d{4,9}/[-._;/:A-Za-z0-9]+)").unwrap();
+}
+
+/// Extracts the (first) DOI from a text.
+fn extract_doi(text: &str) -> Option<String> {
+    R_DOI
+        .find(text)
+        .map(|doi| doi.as_str().trim_end_matches('.').to_owned())
+}
+
+fn zenodo_json_doi(content: &str) -> Option<String> {
+    match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(meta) => ZENODO_DOI_FIELDS
+            .iter()
+            .filter_map(|field| meta.get(field).and_then(serde_json::Value::as_str))
+            .find_map(extract_doi),
+        Err(err) => {
+            log::warn!("Failed to parse '{FILE_ZENODO_JSON}': {err}");
+            None
+        }
+    }
+}
+
+/// Extracts the DOI of the project from a `CITATION.cff` file,
+/// which is either in the top-level `doi` field,
+/// or in an entry of type `doi` in the `identifiers` list.
+/// This is not a full YAML parser;
+/// it only supports the block style, as used in the CFF documentation.
+fn citation_cff_doi(content: &str) -> Option<String> {
+    let top_level = content
+        .lines()
+        .filter_map(|line| line.strip_prefix("doi:"))
+        .find_map(extract_doi);
+    if top_level.is_some() {
+        return top_level;
+    }
+
+    let mut identifiers: Vec<Vec<(&str, &str)>> = vec![];
+    for line in content
+        .lines()
+        .skip_while(|line| !line.starts_with("identifiers:"))
+        .skip(1)
+        .take_while(|line| line.starts_with([' ', '\t', '-']) || line.trim().is_empty())
+    {
+        let entry = line.trim_start();
+        let (field, new_item) = entry
+            .strip_prefix('-')
+            .map_or((entry, false), |field| (field, true));
+        if new_item {
+            identifiers.push(vec![]);
+        }
+        if let (Some(item), Some((name, value))) = (identifiers.last_mut(), field.split_once(':')) {
+            item.push((name.trim(), value.trim().trim_matches(['"', '\''])));
+        }
+    }
+    identifiers
+        .iter()
+        .filter(|item| item.contains(&("type", "doi")))
+        .filter_map(|item| item.iter().find(|(name, _value)| *name == "value"))
+        .find_map(|(_name, value)| extract_doi(value))
+}
+
+fn readme_doi(content: &str) -> Option<String> {
+    R_DOI_URL
+        .captures(content)
+        .and_then(|caps| caps.get(1))
+        .map(|doi| doi.as_str().trim_end_matches('.').to_owned())
+}
+
+/// The DOI of the project,
+/// preferably from machine-readable citation meta-data,
+/// falling back to a DOI link (e.g. a Zenodo badge) in the README.
+fn doi(environment: &mut Environment) -> RetrieveRes {
+    let repo_path = repo_path(environment)?;
+    let read = |file_name: &str| -> Result<Option<String>, std::io::Error> {
+        let file = repo_path.join(file_name);
+        Ok(if file.is_file() {
+            Some(fs::read_to_string(file)?)
+        } else {
+            None
+        })
+    };
+    if let Some(doi) = read(FILE_ZENODO_JSON)?.and_then(|content| zenodo_json_doi(&content)) {
+        return Ok(Some((ConfidenceScore::HIGH, doi)));
+    }
+    if let Some(doi) = read(FILE_CITATION_CFF)?.and_then(|content| citation_cff_doi(&content)) {
+        return Ok(Some((ConfidenceScore::HIGH, doi)));
+    }
+    for (file_name, _format) in README_FILES {
+        if let Some(doi) = read(file_name)?.and_then(|content| readme_doi(&content)) {
+            return Ok(Some((ConfidenceScore::MIDDLE, doi)));
+        }
+    }
+    Ok(None)
+}
+
+/// The file configuring the sponsor button of a repo on GitHub,
+/// relative to the project root,
+/// see <https://docs.github.com/en/repositories/managing-your-repositorys-settings-and-features/customizing-your-repository/displaying-a-sponsor-button-in-your-repository>.
+const FILE_FUNDING_YML: &str = ".github/FUNDING.yml";
+
+/// The platforms supported in [`FILE_FUNDING_YML`],
+/// with the URL prefix of their accounts;
+/// "custom" entries are URLs already.
+const FUNDING_PLATFORMS: &[(&str, &str)] = &[
+    ("github", "https://github.com/sponsors/"),
+    ("patreon", "https://www.patreon.com/"),
+    ("open_collective", "https://opencollective.com/"),
+    ("ko_fi", "https://ko-fi.com/"),
+    ("tidelift", "https://tidelift.com/funding/github/"),
+    (
+        "community_bridge",
+        "https://crowdfunding.lfx.linuxfoundation.org/projects/",
+    ),
+    ("liberapay", "https://liberapay.com/"),
+    ("issuehunt", "https://issuehunt.io/r/"),
+    (
+        "lfx_crowdfunding",
+        "https://crowdfunding.lfx.linuxfoundation.org/projects/",
+    ),
+    ("polar", "https://polar.sh/"),
+    ("buy_me_a_coffee", "https://buymeacoffee.com/"),
+    ("thanks_dev", "https://thanks.dev/"),
+    ("custom", ""),
+];
+
+fn yaml_unquote(value: &str) -> &str {
+    value.trim().trim_matches(['"', '\''])
+}
+
+/// Returns the URL of the first account listed in a `FUNDING.yml`.
+/// This is not a full YAML parser;
+/// it supports plain scalars, as well as flow and block style lists,
+/// which is what the GitHub documentation uses.
+fn funding_yml_url(content: &str) -> Option<String> {
+    let mut lines = content
+        .lines()
+        .map(|line| line.split_once('#').map_or(line, |(line, _comment)| line))
+        .peekable();
+    while let Some(line) = lines.next() {
+        let Some((platform, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let mut accounts: Vec<&str> = if value.is_empty() {
+            let mut block_list = vec![];
+            while let Some(item) = lines
+                .peek()
+                .copied()
+                .and_then(|line| line.trim_start().strip_prefix('-'))
+            {
+                block_list.push(yaml_unquote(item));
+                lines.next();
+            }
+            block_list
+        } else if let Some(flow_list) = value
+            .strip_prefix('[')
+            .and_then(|value| value.strip_suffix(']'))
+        {
+            flow_list.split(',').map(yaml_unquote).collect()
+        } else {
+            vec![yaml_unquote(value)]
+        };
+        accounts.retain(|account| !account.is_empty());
+        let Some(url_prefix) = FUNDING_PLATFORMS
+            .iter()
+            .find(|(name, _url_prefix)| *name == platform.trim())
+            .map(|(_name, url_prefix)| *url_prefix)
+        else {
+            log::warn!("Unknown platform in '{FILE_FUNDING_YML}': '{platform}'");
+            continue;
+        };
+        if let Some(account) = accounts.first() {
+            return Some(if url_prefix.is_empty() && !account.contains("://") {
+                // custom URLs may lack the scheme, like "example.com/donate"
+                format!("https://{account}")
+            } else {
+                format!("{url_prefix}{account}")
+            });
+        }
+    }
+    None
+}
+
+/// The URL to donate to the project,
+/// preferably from the GitHub sponsor button configuration,
+/// falling back to the package manifest.
+fn funding_url(environment: &mut Environment) -> RetrieveRes {
+    let repo_path = repo_path(environment)?;
+    if let Some((confidence, content)) = file_content(&repo_path.join(FILE_FUNDING_YML))? {
+        if let Some(url) = funding_yml_url(&content) {
+            return Ok(Some((confidence, url)));
+        }
+    }
+    super::archive::manifest_funding_url(repo_path)
+}
+
+/// The share of the project files that come with
+/// copyright and licensing information, in percent,
+/// see [`reuse::compliance`].
+/// Only the files tracked by git are considered, if it is a git repo.
+/// As this reads all the files,
+/// it is skipped if the value would not be output anyway.
+fn licenses_compliance(environment: &mut Environment) -> RetrieveRes {
+    if environment.settings.only_required
+        && !environment
+            .settings
+            .required_keys
+            .contains(&Key::LicensesCompliance)
+    {
+        return Ok(None);
+    }
+    let (root, files) = if let Some(repo) = environment.repo().filter(|repo| !repo.is_bare()) {
+        (repo.local_path(), repo.tracked_files()?)
+    } else {
+        let repo_path = repo_path(environment)?.clone();
+        let files = reuse::list_files(&repo_path)?;
+        (repo_path, files)
+    };
+    let compliance = reuse::compliance(&root, &files);
+    log::debug!(
+        "{} of {} files come with copyright and licensing information",
+        compliance.covered,
+        compliance.total
+    );
+    Ok(compliance
+        .percentage()
+        .map(|percentage| (ConfidenceScore::HIGH, percentage.to_string())))
+}
+
+/// The title of the README (see [`readme_title`]),
+/// falling back to the name of the project directory.
 fn name(environment: &mut Environment) -> RetrieveRes {
-    let dir_name = dir_name(repo_path(environment)?)?;
+    let repo_path = repo_path(environment)?;
+    if let Some(title) = readme_title(repo_path)? {
+        return Ok(Some((ConfidenceScore::MIDDLE, title)));
+    }
+    let dir_name = dir_name(repo_path)?;
+    // Bare repos are conventionally named like "project.git"
+    let dir_name = dir_name
+        .strip_suffix(".git")
+        .filter(|name| !name.is_empty())
+        .map_or_else(|| dir_name.clone(), ToOwned::to_owned);
     Ok(match dir_name.to_lowercase().as_str() {
         // Filter out some common directory names that are not likely to be the projects name
         "src" | "target" | "build" | "master" | "main" | "develop" | "git" | "repo" | "repos"
         | "scm" | "trunk" => None,
-        _ => Some((C_LOW, dir_name)),
+        _ => Some((ConfidenceScore::LOW, dir_name)),
     })
 }
 
 fn build_date(environment: &mut Environment) -> String {
-    let now = Local::now();
-    now.format(&environment.settings.date_format).to_string()
+    environment
+        .settings
+        .format_date(Key::BuildDate, &Utc::now())
 }
 
-fn build_os(_environment: &mut Environment) -> (Confidence, String) {
+fn build_os(_environment: &mut Environment) -> (ConfidenceScore, String) {
     // See here for possible values:
     // <https://doc.rust-lang.org/std/env/consts/constant.OS.html>
     // Most common values: "linux", "macos", "windows"
-    (C_LOW, env::consts::OS.to_owned()) // TODO Maybe move to a new source "env.rs"? AND Map to our own values!
+    (ConfidenceScore::LOW, env::consts::OS.to_owned()) // TODO Maybe move to a new source "env.rs"? AND Map to our own values!
 }
 
-fn build_os_family(_environment: &mut Environment) -> (Confidence, String) {
+fn build_os_family(_environment: &mut Environment) -> (ConfidenceScore, String) {
     // Possible values: "unix", "windows"
     // <https://doc.rust-lang.org/std/env/consts/constant.FAMILY.html>
     // format!("{}", env::consts::FAMILY)
-    (C_LOW, env::consts::FAMILY.to_owned()) // TODO Maybe move to a new source "env.rs"?
+    (ConfidenceScore::LOW, env::consts::FAMILY.to_owned()) // TODO Maybe move to a new source "env.rs"?
 }
 
-fn build_arch(_environment: &mut Environment) -> (Confidence, String) {
+fn build_arch(_environment: &mut Environment) -> (ConfidenceScore, String) {
     // See here for possible values:
     // <https://doc.rust-lang.org/std/env/consts/constant.ARCH.html>
     // Most common values: "x86", "x86_64"
-    (C_LOW, env::consts::ARCH.to_owned()) // TODO Maybe move to a new source "env.rs"?
+    (ConfidenceScore::LOW, env::consts::ARCH.to_owned()) // TODO Maybe move to a new source "env.rs"?
+}
+
+fn build_host_platform_triple(_environment: &mut Environment) -> (ConfidenceScore, String) {
+    // NOTE This is the target triple we (projvar) were compiled for,
+    //      which is the platform we are running on,
+    //      and thus (usually) the one the project is built on.
+    (ConfidenceScore::LOW, env!("PROJVAR_TARGET").to_owned())
+}
+
+/// The file the Linux kernel reports its version in.
+const FILE_KERNEL_OS_RELEASE: &str = "/proc/sys/kernel/osrelease";
+
+/// Reads the kernel version from `os_release_file`,
+/// which is only available on Linux,
+/// and falls back to asking `uname` otherwise.
+fn kernel_version(environment: &Environment, os_release_file: &Path) -> Option<String> {
+    fs::read_to_string(os_release_file)
+        .ok()
+        .or_else(|| {
+            environment
+                .cmd_runner
+                .stdout(&cmd::Cmd::new("uname").arg("-r"))
+                .map_err(|err| log::debug!("Failed to fetch the kernel version: {err}"))
+                .ok()
+        })
+        .map(|kernel_version| kernel_version.trim().to_owned())
+        .filter(|kernel_version| !kernel_version.is_empty())
+}
+
+fn build_host_kernel_version(environment: &mut Environment) -> Option<(ConfidenceScore, String)> {
+    kernel_version(environment, Path::new(FILE_KERNEL_OS_RELEASE))
+        .map(|kernel_version| (ConfidenceScore::HIGH, kernel_version))
+}
+
+fn build_host_cpu_count(_environment: &mut Environment) -> Option<(ConfidenceScore, String)> {
+    std::thread::available_parallelism()
+        .ok()
+        .map(|cpu_count| (ConfidenceScore::HIGH, cpu_count.to_string()))
 }
 
 /// This uses an alternative method to fetch certain specific variable keys values.
@@ -166,7 +664,7 @@ fn build_arch(_environment: &mut Environment) -> (Confidence, String) {
 /// Not directly fetching it from any environment variable.
 impl super::VarSource for VarSource {
     fn is_usable(&self, environment: &mut Environment) -> bool {
-        environment.repo().is_some()
+        environment.repo().is_some() || environment.settings.archive
     }
 
     fn hierarchy(&self) -> Hierarchy {
@@ -188,7 +686,6 @@ impl super::VarSource for VarSource {
             match key {
                 Key::BuildArch => Some(build_arch(environment)),
                 Key::BuildBranch
-                | Key::BuildHostingUrl
                 | Key::BuildNumber
                 | Key::BuildTag
                 | Key::Ci
@@ -203,15 +700,49 @@ impl super::VarSource for VarSource {
                 | Key::RepoVersionedFilePrefixUrl
                 | Key::RepoWebUrl
                 | Key::VersionDate
-                | Key::NameMachineReadable => None,
-                Key::BuildDate => Some((C_HIGH, build_date(environment))),
+                | Key::RepoSlug
+                | Key::RepoOwner
+                | Key::RepoNamespace
+                | Key::RepoReleasesUrl
+                | Key::RepoCiUrl
+                | Key::VersionMajor
+                | Key::VersionMinor
+                | Key::VersionPatch
+                | Key::VersionPrerelease
+                | Key::VersionBuildMeta
+                | Key::VersionControlSystem
+                | Key::BuildCommitSha
+                | Key::BuildCommitShaShort
+                | Key::BuildDirty
+                | Key::Description
+                | Key::NameSlug
+                | Key::PullRequestNumber
+                | Key::PullRequestSourceBranch
+                | Key::PullRequestTargetBranch
+                | Key::PullRequestTitle
+                | Key::PullRequestUrl
+                | Key::RepoCloneUrlPush
+                | Key::RepoDefaultBranch
+                | Key::RepoForkOfUrl
+                | Key::RepoForkUrl
+                | Key::VersionDescribe
+                | Key::NameMachineReadable
+                | Key::BuildDateEpoch => None,
+                Key::BuildDate => Some((ConfidenceScore::HIGH, build_date(environment))),
+                Key::BuildHostCpuCount => build_host_cpu_count(environment),
+                Key::BuildHostingUrl => build_hosting_url(environment)?,
+                Key::BuildHostKernelVersion => build_host_kernel_version(environment),
+                Key::BuildHostPlatformTriple => Some(build_host_platform_triple(environment)),
                 Key::BuildOs => Some(build_os(environment)),
                 Key::BuildOsFamily => Some(build_os_family(environment)),
-                Key::License => license(environment)?.map(|val| (C_HIGH, val)),
+                Key::Doi => doi(environment)?,
+                Key::FundingUrl => funding_url(environment)?,
+                Key::License => license(environment)?.map(|val| (ConfidenceScore::HIGH, val)),
                 Key::Licenses => licenses(environment, false)?.map(|mut lv| {
                     lv.sort();
-                    (C_HIGH, lv.join(", "))
+                    (ConfidenceScore::HIGH, lv.join(", "))
                 }), // TODO Later on, rather create an SPDX expressions, maybe by using OR instead of ',' to join ... but can we really?
+                Key::LicensesCompliance => licenses_compliance(environment)?,
                 Key::Name => name(environment)?,
                 Key::Version => version(environment)?
                     .map(|conf_val| cleanup::conf_version(environment, conf_val)),
@@ -219,3 +750,95 @@ impl super::VarSource for VarSource {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_kernel_version() {
+        let fake =
+            Arc::new(cmd::Fake::default().with("uname -r", cmd::Output::success("23.4.0\n")));
+        let mut environment = Environment::stub();
+        environment.cmd_runner = Arc::clone(&fake) as Arc<dyn cmd::Runner>;
+        assert_eq!(
+            kernel_version(&environment, Path::new("/no/such/osrelease")),
+            Some("23.4.0".to_owned())
+        );
+        assert_eq!(fake.runs(), vec!["uname -r"]);
+
+        environment.cmd_runner = Arc::new(cmd::DryRun);
+        assert_eq!(
+            kernel_version(&environment, Path::new("/no/such/osrelease")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_markdown_title() {
+        assert_eq!(
+            markdown_title("<!-- comment -->\n\n# My Project #\n\nText\n"),
+            Some("My Project".to_owned())
+        );
+        assert_eq!(
+            markdown_title("My Project\n==========\n\n# Other\n"),
+            Some("My Project".to_owned())
+        );
+        assert_eq!(
+            markdown_title("```shell\n# comment\n```\n\n#hashtag\n\n# Title\n"),
+            Some("Title".to_owned())
+        );
+        assert_eq!(markdown_title("## Sub-Section\n\nText\n"), None);
+    }
+
+    #[test]
+    fn test_rst_title() {
+        assert_eq!(
+            rst_title("==========\nMy Project\n==========\n\nText\n"),
+            Some("My Project".to_owned())
+        );
+        assert_eq!(
+            rst_title(".. comment\n\nMy Project\n----------\n"),
+            Some("My Project".to_owned())
+        );
+        assert_eq!(rst_title("Just text,\nno title.\n"), None);
+    }
+
+    #[test]
+    fn test_doi() {
+        assert_eq!(
+            zenodo_json_doi(r#"{ "title": "x", "conceptdoi": "10.5281/zenodo.1234567" }"#),
+            Some("10.5281/zenodo.1234567".to_owned())
+        );
+        assert_eq!(
+            citation_cff_doi("cff-version: 1.2.0\ndoi: \"https://doi.org/10.5281/zenodo.42\"\n"),
+            Some("10.5281/zenodo.42".to_owned())
+        );
+        let cff = "cff-version: 1.2.0\nidentifiers:\n  - type: url\n    value: \"https://example.com\"\n  - type: doi\n    value: 10.5281/zenodo.43\n    description: The concept DOI\ntitle: My Project\n";
+        assert_eq!(citation_cff_doi(cff), Some("10.5281/zenodo.43".to_owned()));
+        assert_eq!(
+            readme_doi("# Title\n\n[![DOI](https://zenodo.org/badge/DOI/10.5281/zenodo.44.svg)](https://doi.org/10.5281/zenodo.44)\n"),
+            Some("10.5281/zenodo.44".to_owned())
+        );
+        assert_eq!(readme_doi("# Title\n\nSee doi.org.\n"), None);
+    }
+
+    #[test]
+    fn test_funding_yml_url() {
+        assert_eq!(
+            funding_yml_url("# comment\ngithub: [hoijui, other]\npatreon: someone\n"),
+            Some("https://github.com/sponsors/hoijui".to_owned())
+        );
+        assert_eq!(
+            funding_yml_url("github: # none\nko_fi: 'me'\n"),
+            Some("https://ko-fi.com/me".to_owned())
+        );
+        assert_eq!(
+            funding_yml_url("custom:\n  - example.com/donate\n  - https://other.example.com\n"),
+            Some("https://example.com/donate".to_owned())
+        );
+        assert_eq!(funding_yml_url("github: []\n"), None);
+    }
+}
@@ -2,26 +2,85 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::cleanup;
+use crate::constants;
 use crate::environment::Environment;
-use crate::var::{Key, C_HIGH};
+use crate::var::{ConfidenceScore, Key};
 
-use super::{Hierarchy, RetrieveRes};
+use super::{ConfVal, Hierarchy, RetrieveRes};
 
 /// Sources values by querrying the `git` CLI tool.
 /// In reality, we use a git library, but the effect is the same.
 pub struct VarSource;
 
-fn version(environment: &mut Environment) -> RetrieveRes {
-    Ok(match environment.repo() {
+/// The raw output of `git describe`,
+/// only considering tags that match the version tag pattern.
+/// This is determined through a chain of fall-backs:
+///
+/// 1. the tag pointing to HEAD (which is what `git describe` returns then)
+/// 2. the output of `git describe`, e.g. "0.2.0-3-g9b5ff47"
+/// 3. the short SHA of HEAD, with low confidence
+///
+/// If there is no commit either, nothing is returned,
+/// leaving the field to the `VERSION` file or package manifest version,
+/// as supplied by the [file-system source](super::fs::VarSource).
+fn version_describe(environment: &Environment) -> Option<ConfVal> {
+    let tag_pattern = &environment.settings.version_tag_pattern;
+    match environment.repo() {
         Some(repo) => {
-            let sc_version = repo.version().or_else(|err| {
-                log::warn!("Failed to git describe (\"{}\"), using SHA instead", err);
-                repo.sha()
-                    .and_then(|v| v.ok_or_else(|| "No SHA available to serve as version".into()))
-            })?;
-            Some((C_HIGH, sc_version))
+            let describe = repo.version(tag_pattern).unwrap_or_else(|err| {
+                log::warn!("Failed to git describe (\"{err}\")");
+                None
+            });
+            if let Some(describe) = describe {
+                Some((ConfidenceScore::HIGH, describe))
+            } else {
+                log::warn!(
+                    "No tag matching '{tag_pattern}' is reachable from HEAD, \
+using the short SHA as version instead"
+                );
+                match repo.sha_short() {
+                    Ok(sha_short) => sha_short.map(|val| (ConfidenceScore::LOW, val)),
+                    Err(err) => {
+                        log::warn!(
+                            "No SHA available to serve as version either (\"{err}\"); \
+leaving it to a VERSION file or package manifest"
+                        );
+                        None
+                    }
+                }
+            }
         }
         None => None,
+    }
+}
+
+/// The cleaned up, human-facing variant of [`version_describe`].
+fn version(environment: &mut Environment) -> Option<ConfVal> {
+    version_describe(environment).map(|conf_val| cleanup::conf_version(environment, conf_val))
+}
+
+fn sha(environment: &mut Environment) -> RetrieveRes {
+    Ok(match environment.repo() {
+        Some(repo) => repo.sha()?.map(|val| (ConfidenceScore::HIGH, val)),
+        None => None,
+    })
+}
+
+fn sha_short(environment: &mut Environment) -> RetrieveRes {
+    Ok(match environment.repo() {
+        Some(repo) => repo.sha_short()?.map(|val| (ConfidenceScore::HIGH, val)),
+        None => None,
+    })
+}
+
+fn dirty(environment: &mut Environment) -> RetrieveRes {
+    Ok(match environment.repo() {
+        // A bare repo has no working tree that could be dirty
+        Some(repo) if !repo.is_bare() => {
+            Some((ConfidenceScore::HIGH, repo.is_dirty()?.to_string()))
+        }
+        Some(_) | None => None,
     })
 }
 
@@ -32,15 +91,26 @@ fn branch(environment: &mut Environment) -> RetrieveRes {
             //     log::warn!("Failed fetching git branch - {}", err);
             //     None
             // }))
-            repo.branch()?.map(|val| (C_HIGH, val))
+            repo.branch()?.map(|val| (ConfidenceScore::HIGH, val))
         }
         None => None,
     })
 }
 
+/// Synthesizes a build number from the number of commits
+/// in the currently checked out history.
+/// This increases monotonically with each commit on a branch,
+/// and serves local builds that lack a CI provided build number.
+fn build_number(environment: &mut Environment) -> RetrieveRes {
+    Ok(match environment.repo() {
+        Some(repo) => Some((ConfidenceScore::LOW, repo.commit_count()?.to_string())),
+        None => None,
+    })
+}
+
 fn tag(environment: &mut Environment) -> RetrieveRes {
     Ok(match environment.repo() {
-        Some(repo) => repo.tag()?.map(|val| (C_HIGH, val)),
+        Some(repo) => repo.tag()?.map(|val| (ConfidenceScore::HIGH, val)),
         None => None,
     })
 }
@@ -49,15 +119,28 @@ fn clone_url(environment: &mut Environment) -> RetrieveRes {
     Ok(match environment.repo() {
         Some(repo) => repo
             .remote_clone_url()?
-            .map(|remote_clone_url| (C_HIGH, remote_clone_url)),
+            .map(|remote_clone_url| (ConfidenceScore::HIGH, remote_clone_url)),
+        None => None,
+    })
+}
+
+fn push_url(environment: &mut Environment) -> RetrieveRes {
+    Ok(match environment.repo() {
+        Some(repo) => repo
+            .remote_push_url()?
+            .map(|remote_push_url| (ConfidenceScore::HIGH, remote_push_url)),
         None => None,
     })
 }
 
 fn version_date(environment: &mut Environment) -> RetrieveRes {
-    let date_format = environment.settings.date_format.clone();
     Ok(match &environment.repo() {
-        Some(repo) => Some((C_HIGH, repo.commit_date(&date_format)?)),
+        Some(repo) => Some((
+            ConfidenceScore::HIGH,
+            environment
+                .settings
+                .format_date(Key::VersionDate, &repo.commit_date()?),
+        )),
         None => None,
     })
 }
@@ -89,7 +172,6 @@ impl super::VarSource for VarSource {
             match key {
                 Key::BuildArch
                 | Key::BuildDate
-                | Key::BuildNumber
                 | Key::BuildOs
                 | Key::BuildOsFamily
                 | Key::Ci
@@ -106,14 +188,50 @@ impl super::VarSource for VarSource {
                 | Key::RepoRawVersionedPrefixUrl
                 | Key::RepoVersionedDirPrefixUrl
                 | Key::RepoVersionedFilePrefixUrl
+                | Key::BuildHostPlatformTriple
+                | Key::BuildHostKernelVersion
+                | Key::BuildHostCpuCount
+                | Key::RepoSlug
+                | Key::RepoOwner
+                | Key::RepoNamespace
+                | Key::RepoReleasesUrl
+                | Key::RepoCiUrl
+                | Key::VersionMajor
+                | Key::VersionMinor
+                | Key::VersionPatch
+                | Key::VersionPrerelease
+                | Key::VersionBuildMeta
+                | Key::BuildDateEpoch
+                | Key::Description
+                | Key::Doi
+                | Key::FundingUrl
+                | Key::LicensesCompliance
+                | Key::NameSlug
+                | Key::PullRequestNumber
+                | Key::PullRequestSourceBranch
+                | Key::PullRequestTargetBranch
+                | Key::PullRequestTitle
+                | Key::PullRequestUrl
+                | Key::RepoDefaultBranch
+                | Key::RepoForkOfUrl
+                | Key::RepoForkUrl
                 | Key::RepoWebUrl => None,
                 Key::BuildBranch => branch(environment)?,
+                Key::BuildCommitSha => sha(environment)?,
+                Key::BuildCommitShaShort => sha_short(environment)?,
+                Key::BuildDirty => dirty(environment)?,
+                Key::BuildNumber => build_number(environment)?,
                 Key::BuildTag => tag(environment)?,
                 Key::RepoCloneUrl => clone_url(environment)?
                     .map(|rated_value| rated_value.1)
-                    .map(|val| (C_HIGH, val)),
-                Key::Version => version(environment)?,
+                    .map(|val| (ConfidenceScore::HIGH, val)),
+                Key::RepoCloneUrlPush => push_url(environment)?,
+                Key::Version => version(environment),
+                Key::VersionControlSystem => {
+                    Some((ConfidenceScore::HIGH, constants::VCS_GIT.to_owned()))
+                }
                 Key::VersionDate => version_date(environment)?,
+                Key::VersionDescribe => version_describe(environment),
             },
         )
     }
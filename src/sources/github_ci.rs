@@ -2,32 +2,88 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::fs;
+
+use crate::constants;
 use crate::environment::Environment;
+#[cfg(feature = "online")]
+use crate::tools::{credentials, hosting_api};
 use crate::value_conversions::slug_to_proj_name;
-use crate::var::Confidence;
+use crate::var::ConfidenceScore;
 use crate::var::Key;
-use crate::var::C_HIGH;
-use crate::var::C_LOW;
 
 use super::var;
 use super::Hierarchy;
 use super::RetrieveRes;
 
 /// This sources values from the environment variables set by the CI in
-/// [`crate::tools::git_hosting_provs::HostingType::GitHub`].
+/// [`crate::tools::git_hosting_provs::HostingType::GitHub`],
+/// and from the payload of the event that triggered the workflow,
+/// which takes precedence where both supply a value.
 pub struct VarSource;
 
 fn build_branch(environment: &mut Environment) -> RetrieveRes {
-    var(environment, "GITHUB_REF", C_HIGH)
+    var(environment, "GITHUB_REF", ConfidenceScore::HIGH)
         .map_or_else(|| Ok(None), |refr| super::ref_extract_branch(&refr.1))
 }
 
 fn build_tag(environment: &mut Environment) -> RetrieveRes {
-    var(environment, "GITHUB_REF", C_HIGH)
+    // set for "release" events
+    if let Some(tag) = event_str(environment, "/release/tag_name") {
+        return Ok(Some(tag));
+    }
+    var(environment, "GITHUB_REF", ConfidenceScore::HIGH)
         .map_or_else(|| Ok(None), |refr| super::ref_extract_tag(&refr.1))
 }
 
-fn repo_web_url(environment: &mut Environment) -> Option<(Confidence, String)> {
+/// Fetches the pages URL from the GitHub API,
+/// if we are allowed to access the network.
+/// Failure to do so is not fatal,
+/// as the deriver will then construct the default pages URL.
+#[cfg(feature = "online")]
+fn build_hosting_url(environment: &Environment) -> Option<(ConfidenceScore, String)> {
+    if !environment.settings.online {
+        return None;
+    }
+    let slug = environment.vars.get("GITHUB_REPOSITORY")?;
+    let api_url = environment
+        .vars
+        .get("GITHUB_API_URL")
+        .map_or(hosting_api::GIT_HUB_API_URL, String::as_str);
+    let server = environment
+        .vars
+        .get("GITHUB_SERVER_URL")
+        .map_or(constants::D_GIT_HUB_COM, String::as_str);
+    let token = credentials::token(&environment.settings, &environment.vars, server)
+        .unwrap_or_else(|err| {
+            log::warn!("Failed to look up the access token for '{server}': {err}");
+            None
+        });
+    match hosting_api::git_hub_pages_url(&environment.settings, api_url, slug, token.as_ref()) {
+        Ok(pages_url) => pages_url.map(|url| (ConfidenceScore::HIGH, url)),
+        Err(err) => {
+            log::warn!("Failed to fetch the GitHub pages URL: {err}");
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "online"))]
+const fn build_hosting_url(_environment: &Environment) -> Option<(ConfidenceScore, String)> {
+    None
+}
+
+/// The SPDX identifier of the license GitHub detected for the repo.
+fn license(environment: &Environment) -> Option<(ConfidenceScore, String)> {
+    // GitHub uses this for licenses it does not know an SPDX identifier for
+    const NO_ASSERTION: &str = "NOASSERTION";
+    event_str(environment, "/repository/license/spdx_id").filter(|(_, id)| id != NO_ASSERTION)
+}
+
+fn repo_web_url(environment: &mut Environment) -> Option<(ConfidenceScore, String)> {
+    if let Some(web_url) = event_str(environment, "/repository/html_url") {
+        return Some(web_url);
+    }
     match (
         environment.vars.get("GITHUB_SERVER_URL"),
         environment.vars.get("GITHUB_REPOSITORY"),
@@ -37,12 +93,88 @@ fn repo_web_url(environment: &mut Environment) -> Option<(Confidence, String)> {
             // usually:
             // GITHUB_SERVER_URL="https://github.com/"
             // GITHUB_REPOSITORY="user/project"
-            Some((C_HIGH, format!("{server}/{repo}")))
+            Some((ConfidenceScore::HIGH, format!("{server}/{repo}")))
         }
         (_, _) => None,
     }
 }
 
+/// Reads the JSON payload of the event that triggered the workflow,
+/// see <https://docs.github.com/en/actions/writing-workflows/choosing-when-your-workflow-runs/events-that-trigger-workflows>.
+/// Failure to do so is not fatal,
+/// as most of the info is also available from environment variables.
+fn event(environment: &Environment) -> Option<serde_json::Value> {
+    let (_, event_path) = var(environment, "GITHUB_EVENT_PATH", ConfidenceScore::HIGH)?;
+    let event = fs::read_to_string(&event_path)
+        .map_err(|err| err.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|err| err.to_string()));
+    match event {
+        Ok(event) => Some(event),
+        Err(err) => {
+            log::warn!("Failed to read the GitHub event payload from '{event_path}': {err}");
+            None
+        }
+    }
+}
+
+/// The web URLs of the fork and of the upstream repo,
+/// if this is a build of a pull request from a fork.
+/// In that case, `GITHUB_REPOSITORY` refers to the upstream repo,
+/// while the changes come from the fork,
+/// which only the event payload tells us about.
+fn fork_urls(environment: &Environment) -> Option<(String, String)> {
+    let event = event(environment)?;
+    let pull_request = event.get("pull_request")?;
+    let repo_url = |side: &str| {
+        pull_request
+            .get(side)?
+            .get("repo")?
+            .get("html_url")?
+            .as_str()
+            .map(ToOwned::to_owned)
+    };
+    let fork = repo_url("head")?;
+    let upstream = repo_url("base")?;
+    (fork != upstream).then_some((fork, upstream))
+}
+
+/// A string value from the event payload,
+/// see [`event`];
+/// `pointer` is a JSON pointer, e.g. `/pull_request/title`.
+fn event_str(environment: &Environment, pointer: &str) -> Option<(ConfidenceScore, String)> {
+    event(environment)?
+        .pointer(pointer)?
+        .as_str()
+        .filter(|val| !val.is_empty())
+        .map(|val| (ConfidenceScore::HIGH, val.to_owned()))
+}
+
+fn pull_request_number(environment: &Environment) -> RetrieveRes {
+    // usually: GITHUB_REF="refs/pull/42/merge"
+    let from_ref = match var(environment, "GITHUB_REF", ConfidenceScore::HIGH) {
+        Some((_, refr)) => super::ref_extract_pull_request(&refr)?,
+        None => None,
+    };
+    Ok(from_ref.or_else(|| {
+        event(environment)?
+            .pointer("/pull_request/number")?
+            .as_u64()
+            .map(|number| (ConfidenceScore::HIGH, number.to_string()))
+    }))
+}
+
+/// The name of a branch involved in the pull request being built, if any;
+/// from the environment variable `key` if it is set and not empty,
+/// or else from the event payload at `pointer`.
+fn pull_request_branch(environment: &Environment, key: &str, pointer: &str) -> RetrieveRes {
+    if pull_request_number(environment)?.is_none() {
+        return Ok(None);
+    }
+    Ok(var(environment, key, ConfidenceScore::HIGH)
+        .filter(|(_, branch)| !branch.is_empty())
+        .or_else(|| event_str(environment, pointer)))
+}
+
 impl super::VarSource for VarSource {
     fn is_usable(&self, _environment: &mut Environment) -> bool {
         true
@@ -67,10 +199,8 @@ impl super::VarSource for VarSource {
             match key {
                 Key::BuildArch
                 | Key::BuildDate
-                | Key::BuildHostingUrl
                 | Key::BuildNumber
                 | Key::BuildOsFamily
-                | Key::License
                 | Key::Licenses
                 | Key::VersionDate
                 | Key::NameMachineReadable
@@ -82,23 +212,70 @@ impl super::VarSource for VarSource {
                 | Key::RepoIssuesUrl
                 | Key::RepoRawVersionedPrefixUrl
                 | Key::RepoVersionedDirPrefixUrl
+                | Key::BuildHostPlatformTriple
+                | Key::BuildHostKernelVersion
+                | Key::BuildHostCpuCount
+                | Key::RepoReleasesUrl
+                | Key::RepoCiUrl
+                | Key::VersionMajor
+                | Key::VersionMinor
+                | Key::VersionPatch
+                | Key::VersionPrerelease
+                | Key::VersionBuildMeta
+                | Key::BuildDateEpoch
+                | Key::BuildCommitShaShort
+                | Key::BuildDirty
+                | Key::Doi
+                | Key::FundingUrl
+                | Key::LicensesCompliance
+                | Key::NameSlug
+                | Key::RepoCloneUrlPush
+                | Key::VersionDescribe
                 | Key::RepoVersionedFilePrefixUrl => None,
                 Key::BuildBranch => build_branch(environment)?,
-                Key::BuildOs => var(environment, "RUNNER_OS", C_LOW), // TODO PRIO Not sure if this makes sense ... have to check in practise, and probably map values to our set of accepted values!
+                Key::BuildCommitSha => var(environment, "GITHUB_SHA", ConfidenceScore::HIGH),
+                Key::BuildHostingUrl => build_hosting_url(environment),
+                Key::BuildOs => var(environment, "RUNNER_OS", ConfidenceScore::LOW), // TODO PRIO Not sure if this makes sense ... have to check in practise, and probably map values to our set of accepted values!
                 Key::BuildTag => build_tag(environment)?,
-                Key::Ci => {
-                    var(environment, "CI", C_HIGH).or_else(|| Some((C_LOW, "false".to_owned())))
-                }
-                Key::Name => match var(environment, "GITHUB_REPOSITORY", C_HIGH) {
+                Key::Ci => var(environment, "CI", ConfidenceScore::HIGH)
+                    .or_else(|| Some((ConfidenceScore::LOW, "false".to_owned()))),
+                Key::Description => event_str(environment, "/repository/description"),
+                Key::License => license(environment),
+                Key::Name => match var(environment, "GITHUB_REPOSITORY", ConfidenceScore::HIGH) {
                     Some(rated_val) => {
                         slug_to_proj_name(Some(&rated_val.1))?.map(|val| (rated_val.0, val))
                     }
                     None => None,
                 }, // usually: GITHUB_REPOSITORY="user/project"
+                Key::PullRequestNumber => pull_request_number(environment)?,
+                Key::PullRequestSourceBranch => {
+                    pull_request_branch(environment, "GITHUB_HEAD_REF", "/pull_request/head/ref")?
+                }
+                Key::PullRequestTargetBranch => {
+                    pull_request_branch(environment, "GITHUB_BASE_REF", "/pull_request/base/ref")?
+                }
+                Key::PullRequestTitle => event_str(environment, "/pull_request/title"),
+                Key::PullRequestUrl => event_str(environment, "/pull_request/html_url"),
+                Key::RepoDefaultBranch => event_str(environment, "/repository/default_branch"),
+                Key::RepoForkOfUrl => fork_urls(environment)
+                    .map(|(_fork, upstream)| (ConfidenceScore::HIGH, upstream)),
+                Key::RepoForkUrl => {
+                    fork_urls(environment).map(|(fork, _upstream)| (ConfidenceScore::HIGH, fork))
+                }
+                Key::RepoNamespace | Key::RepoOwner => var(
+                    environment,
+                    "GITHUB_REPOSITORY_OWNER",
+                    ConfidenceScore::HIGH,
+                ),
+                Key::RepoSlug => var(environment, "GITHUB_REPOSITORY", ConfidenceScore::HIGH),
                 Key::RepoWebUrl => repo_web_url(environment),
                 Key::Version => self
                     .version_from_build_tag(environment, key)?
-                    .or_else(|| var(environment, "GITHUB_SHA", C_HIGH)),
+                    .or_else(|| var(environment, "GITHUB_SHA", ConfidenceScore::HIGH)),
+                Key::VersionControlSystem => {
+                    var(environment, "GITHUB_ACTIONS", ConfidenceScore::HIGH)
+                        .map(|_| (ConfidenceScore::HIGH, constants::VCS_GIT.to_owned()))
+                }
             },
         )
     }
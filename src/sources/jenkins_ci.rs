@@ -3,17 +3,64 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::cleanup;
+use crate::constants;
 use crate::environment::Environment;
+use crate::value_conversions;
+use crate::var::ConfidenceScore;
 use crate::var::Key;
-use crate::var::C_HIGH;
 
 use super::var;
+use super::var_date;
 use super::Hierarchy;
 use super::RetrieveRes;
 
 /// This sources values from the environment variables set by the CI provider Jenkins.
 pub struct VarSource;
 
+fn version_control_system(environment: &Environment) -> Option<(ConfidenceScore, String)> {
+    if var(environment, "GIT_COMMIT", ConfidenceScore::HIGH).is_some() {
+        Some((ConfidenceScore::HIGH, constants::VCS_GIT.to_owned()))
+    } else if var(environment, "SVN_REVISION", ConfidenceScore::HIGH).is_some() {
+        Some((ConfidenceScore::HIGH, constants::VCS_SVN.to_owned()))
+    } else {
+        None
+    }
+}
+
+/// The branch being built.
+/// In multi-branch pipelines, `BRANCH_NAME` is set,
+/// but it holds the tag name for tag builds,
+/// and something like "PR-42" for pull request builds.
+/// Otherwise, the Git plugin sets `GIT_BRANCH`,
+/// usually prefixed with the remote name, e.g. "origin/master".
+fn build_branch(environment: &Environment) -> Option<(ConfidenceScore, String)> {
+    if var(environment, "TAG_NAME", ConfidenceScore::HIGH).is_some()
+        || var(environment, "CHANGE_ID", ConfidenceScore::HIGH).is_some()
+    {
+        return None;
+    }
+    var(environment, "BRANCH_NAME", ConfidenceScore::HIGH).or_else(|| {
+        let (_, branch) = var(environment, "GIT_BRANCH", ConfidenceScore::MIDDLE)?;
+        let branch = branch
+            .strip_prefix("refs/heads/")
+            .or_else(|| branch.split_once('/').map(|(_remote, branch)| branch))
+            .unwrap_or(&branch)
+            .to_owned();
+        Some((ConfidenceScore::MIDDLE, branch))
+    })
+}
+
+/// The web URL of the repo, derived from the clone URL set by the Git plugin.
+fn repo_web_url(environment: &Environment) -> RetrieveRes {
+    Ok(match var(environment, "GIT_URL", ConfidenceScore::HIGH) {
+        Some((confidence, clone_url)) => {
+            value_conversions::clone_url_to_web_url(environment, &clone_url)?
+                .map(|web_url| (confidence, web_url))
+        }
+        None => None,
+    })
+}
+
 impl super::VarSource for VarSource {
     fn is_usable(&self, _environment: &mut Environment) -> bool {
         true
@@ -37,16 +84,13 @@ impl super::VarSource for VarSource {
             #[remain::sorted]
             match key {
                 Key::BuildArch
-                | Key::BuildDate
                 | Key::BuildHostingUrl
                 | Key::BuildOs
                 | Key::BuildOsFamily
-                | Key::BuildTag
                 | Key::Ci
                 | Key::License
                 | Key::Licenses
                 | Key::NameMachineReadable
-                | Key::RepoCloneUrl
                 | Key::RepoCloneUrlGit
                 | Key::RepoCloneUrlHttp
                 | Key::RepoCloneUrlSsh
@@ -55,13 +99,58 @@ impl super::VarSource for VarSource {
                 | Key::RepoRawVersionedPrefixUrl
                 | Key::RepoVersionedDirPrefixUrl
                 | Key::RepoVersionedFilePrefixUrl
-                | Key::RepoWebUrl
+                | Key::BuildHostPlatformTriple
+                | Key::BuildHostKernelVersion
+                | Key::BuildHostCpuCount
+                | Key::RepoSlug
+                | Key::RepoOwner
+                | Key::RepoNamespace
+                | Key::RepoReleasesUrl
+                | Key::RepoCiUrl
+                | Key::VersionMajor
+                | Key::VersionMinor
+                | Key::VersionPatch
+                | Key::VersionPrerelease
+                | Key::VersionBuildMeta
+                | Key::BuildDateEpoch
+                | Key::BuildCommitShaShort
+                | Key::BuildDirty
+                | Key::Description
+                | Key::Doi
+                | Key::FundingUrl
+                | Key::LicensesCompliance
+                | Key::NameSlug
+                | Key::RepoCloneUrlPush
+                | Key::RepoDefaultBranch
+                | Key::RepoForkOfUrl
+                | Key::RepoForkUrl
+                | Key::VersionDescribe
                 | Key::VersionDate => None,
-                Key::BuildBranch => var(environment, "BRANCH_NAME", C_HIGH),
-                Key::BuildNumber => var(environment, "BUILD_NUMBER", C_HIGH),
-                Key::Name => var(environment, "APP_NAME", C_HIGH),
-                Key::Version => var(environment, "VERSION", C_HIGH)
-                    .map(|conf_val| cleanup::conf_version(environment, conf_val)), // Alternatively (but makes no sense to use): var(environment, "PULL_BASE_SHA")
+                Key::BuildBranch => build_branch(environment),
+                Key::BuildCommitSha => var(environment, "GIT_COMMIT", ConfidenceScore::HIGH),
+                Key::BuildDate => {
+                    var_date(environment, key, "BUILD_TIMESTAMP", ConfidenceScore::MIDDLE)
+                } // Set by the "Build Timestamp" plugin, in a user configurable format
+                Key::BuildNumber => var(environment, "BUILD_NUMBER", ConfidenceScore::HIGH),
+                Key::BuildTag => var(environment, "TAG_NAME", ConfidenceScore::HIGH),
+                Key::Name => var(environment, "APP_NAME", ConfidenceScore::HIGH),
+                Key::PullRequestNumber => var(environment, "CHANGE_ID", ConfidenceScore::HIGH),
+                Key::PullRequestSourceBranch => {
+                    var(environment, "CHANGE_BRANCH", ConfidenceScore::HIGH)
+                }
+                Key::PullRequestTargetBranch => {
+                    var(environment, "CHANGE_TARGET", ConfidenceScore::HIGH)
+                }
+                Key::PullRequestTitle => var(environment, "CHANGE_TITLE", ConfidenceScore::HIGH),
+                Key::PullRequestUrl => var(environment, "CHANGE_URL", ConfidenceScore::HIGH),
+                Key::RepoCloneUrl => var(environment, "GIT_URL", ConfidenceScore::HIGH),
+                Key::RepoWebUrl => repo_web_url(environment)?,
+                // Alternatively (but makes no sense to use): var(environment, "PULL_BASE_SHA")
+                Key::Version => match var(environment, "VERSION", ConfidenceScore::HIGH) {
+                    Some(conf_val) => Some(cleanup::conf_version(environment, conf_val)),
+                    None => self.version_from_build_tag(environment, key)?,
+                },
+                Key::VersionControlSystem => version_control_system(environment),
             },
         )
     }
@@ -2,26 +2,36 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+pub mod appveyor_ci;
+pub mod archive;
 pub mod bitbucket_ci;
+pub mod buildkite_ci;
 pub mod deriver;
+pub mod drone_ci;
 pub mod env;
 pub mod fs;
 pub mod git;
 pub mod github_ci;
 pub mod gitlab_ci;
 pub mod jenkins_ci;
+pub mod okh;
+pub mod overrides;
 pub mod selector;
+pub mod stub;
+pub mod teamcity_ci;
 pub mod travis_ci;
 
 use std::path::Path;
 
-use cli_utils::{BoxError, BoxResult};
+use cli_utils::BoxError;
 use thiserror::Error;
 
 use lazy_static::lazy_static;
 
 use crate::environment::Environment;
-use crate::var::{Confidence, Key, C_HIGH};
+#[cfg(feature = "license-detection")]
+use crate::license;
+use crate::var::{ConfidenceScore, DynamicKey, Key};
 use crate::{cleanup, std_error, tools, validator, value_conversions};
 
 #[derive(PartialEq, Eq, PartialOrd, Ord)]
@@ -31,9 +41,31 @@ pub enum Hierarchy {
     High,
     Higher,
     EvenHigher,
+    /// Stand-ins for volatile values (see `--stub-values`),
+    /// which only explicit overrides win over
+    Stub,
+    /// Explicit overrides by the user,
+    /// which are used as the basis for derived values
+    Override,
     Top,
 }
 
+/// What the values retrieved by a source depend on,
+/// which decides whether they may be reused from a previous run,
+/// see [`crate::settings::Settings::incremental`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Inputs {
+    /// Files in the project directory, e.g. manifests
+    Files,
+    /// The git repository, e.g. the checked-out commit and the tags
+    Repo,
+    /// The input variables, see [`Environment::vars`]
+    Vars,
+    /// The values retrieved by the other sources,
+    /// see [`Environment::output`]
+    Values,
+}
+
 lazy_static! {
     static ref NO_PROPS: Vec::<String> = Vec::<String>::new();
 }
@@ -59,6 +91,11 @@ pub enum Error {
     #[error(transparent)]
     Git(#[from] tools::git::Error),
 
+    /// Represents all cases of `license::DetectionError`.
+    #[cfg(feature = "license-detection")]
+    #[error(transparent)]
+    LicenseDetection(#[from] license::DetectionError),
+
     /// Represents all other cases of `std_error::Error`.
     #[error(transparent)]
     Std(#[from] std_error::Error),
@@ -68,10 +105,10 @@ pub enum Error {
     Other(#[from] BoxError),
 }
 
-pub type ConfVal = (Confidence, String);
-pub type RetrieveRes = BoxResult<Option<ConfVal>>;
+pub type ConfVal = (ConfidenceScore, String);
+pub type RetrieveRes = Result<Option<ConfVal>, Error>;
 
-pub trait VarSource {
+pub trait VarSource: Send + Sync {
     /// Indicates whether this source of variables is usable.
     /// It might not be usable if the underlying data-source (e.g. a file) does not exist,
     /// or is not reachable (e.g. a web URL).
@@ -82,6 +119,19 @@ pub trait VarSource {
     /// This is used for sorting.
     fn hierarchy(&self) -> Hierarchy;
 
+    /// What the values retrieved by this source depend on.
+    /// By default, this is derived from [`Self::hierarchy`].
+    fn inputs(&self) -> Inputs {
+        match self.hierarchy() {
+            Hierarchy::Low => Inputs::Files,
+            Hierarchy::Middle => Inputs::Repo,
+            Hierarchy::High | Hierarchy::Higher | Hierarchy::Stub | Hierarchy::Override => {
+                Inputs::Vars
+            }
+            Hierarchy::EvenHigher | Hierarchy::Top => Inputs::Values,
+        }
+    }
+
     /// The name of this type.
     /// This is used for display and sorting.
     fn type_name(&self) -> &'static str;
@@ -107,6 +157,22 @@ pub trait VarSource {
     /// depending on the kind of the source.
     fn retrieve(&self, environment: &mut Environment, key: Key) -> RetrieveRes;
 
+    /// Tries to retrieve the value of a single key
+    /// registered at runtime (see [`crate::var::DynamicKey`]).
+    /// Most sources know nothing about these,
+    /// which is why this retrieves nothing by default.
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::retrieve`].
+    fn retrieve_dynamic(
+        &self,
+        _environment: &mut Environment,
+        _key: &'static DynamicKey,
+    ) -> RetrieveRes {
+        Ok(None)
+    }
+
     /// Uses an already found build-tag as the version field,
     /// if available.
     ///
@@ -132,14 +198,38 @@ pub trait VarSource {
 pub fn var(
     environment: &Environment,
     key: &str,
-    confidence: Confidence,
-) -> Option<(Confidence, String)> {
+    confidence: ConfidenceScore,
+) -> Option<(ConfidenceScore, String)> {
+    environment.record_var_read(key);
     environment
         .vars
         .get(key)
         .map(|val| (confidence, val.clone()))
 }
 
+/// Like [`var`], but for a date in any of the formats commonly found in CI supplied values.
+///
+/// It is converted into the date format and time zone in our settings,
+/// see [`value_conversions::date_to_our_format`].
+/// A date in an unsupported format is ignored with a warning,
+/// as other sources might still come up with a usable one.
+#[must_use]
+pub fn var_date(
+    environment: &Environment,
+    key: Key,
+    var_key: &str,
+    confidence: ConfidenceScore,
+) -> Option<(ConfidenceScore, String)> {
+    let (confidence, in_date) = var(environment, var_key, confidence)?;
+    match value_conversions::date_to_our_format(environment, key, &in_date) {
+        Ok(out_date) => out_date.map(|out_date| (confidence, out_date)),
+        Err(err) => {
+            log::warn!("Ignoring the value of {var_key}='{in_date}': {err}");
+            None
+        }
+    }
+}
+
 fn ref_ok_or_err<'t>(refr: &str, part: Option<&'t str>) -> Result<&'t str, Error> {
     part.ok_or_else(|| Error::BadLowLevelValue {
         msg: "Invalid git reference, should be 'refs/<TYPE>/<NAME>'".to_owned(),
@@ -153,7 +243,7 @@ fn ref_extract_name_if_type_matches(refr: &str, required_ref_type: &str) -> Retr
     Ok(if extracted_ref_type == required_ref_type {
         // it *is* a branch
         let branch_name = ref_ok_or_err(refr, parts.next())?;
-        Some((C_HIGH, branch_name.to_owned()))
+        Some((ConfidenceScore::HIGH, branch_name.to_owned()))
     } else {
         None
     })
@@ -189,7 +279,23 @@ pub fn ref_extract_tag(refr: &str) -> RetrieveRes {
     ref_extract_name_if_type_matches(refr, "tags")
 }
 
-fn is_git_repo_root(repo_path: Option<&Path>) -> bool {
+/// Given a git reference, returns the pull request number,
+/// if it reffers to a pull request; None otherwise.
+/// `refr` references should look like:
+/// * "refs/tags/v1.2.3"
+/// * "refs/heads/master"
+/// * "refs/pull/:prNumber/merge"
+///
+/// # Errors
+///
+/// If the given ref is ill-formatted, meaning it does not split
+/// into at least 3 parts with the '/' separator)
+pub fn ref_extract_pull_request(refr: &str) -> RetrieveRes {
+    ref_extract_name_if_type_matches(refr, "pull")
+}
+
+#[must_use]
+pub fn is_git_repo_root(repo_path: Option<&Path>) -> bool {
     tools::git::Repo::try_from(repo_path).is_ok()
 }
 
@@ -198,15 +304,24 @@ pub fn default_list(repo_path: &Path) -> Vec<Box<dyn VarSource>> {
     let mut sources: Vec<Box<dyn VarSource>> = vec![];
     if is_git_repo_root(Some(repo_path)) {
         sources.push(Box::new(git::VarSource {}));
+    } else {
+        sources.push(Box::new(archive::VarSource {}));
     }
     sources.push(Box::new(fs::VarSource {}));
+    sources.push(Box::new(okh::VarSource {}));
+    sources.push(Box::new(appveyor_ci::VarSource {}));
     sources.push(Box::new(bitbucket_ci::VarSource {}));
+    sources.push(Box::new(buildkite_ci::VarSource {}));
+    sources.push(Box::new(drone_ci::VarSource {}));
     sources.push(Box::new(github_ci::VarSource {}));
     sources.push(Box::new(gitlab_ci::VarSource {}));
     sources.push(Box::new(jenkins_ci::VarSource {}));
+    sources.push(Box::new(teamcity_ci::VarSource {}));
     sources.push(Box::new(travis_ci::VarSource {}));
     sources.push(Box::new(env::VarSource {}));
     sources.push(Box::new(selector::VarSource {}));
+    sources.push(Box::new(stub::VarSource {}));
+    sources.push(Box::new(overrides::VarSource {}));
     sources.push(Box::new(deriver::VarSource {}));
     // NOTE We add the deriver a second time,
     //      so it may derive from values created in the first run.
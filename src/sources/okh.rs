@@ -0,0 +1,266 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fs;
+use std::path::Path;
+
+use crate::cleanup;
+use crate::environment::Environment;
+use crate::var::{ConfidenceScore, Key};
+
+use super::{Hierarchy, RetrieveRes};
+
+/// The manifest file of the current
+/// [Open Know-How (OKH-LOSH)](https://github.com/iop-alliance/OpenKnowHow)
+/// standard for Open Source Hardware projects.
+pub const FILE_OKH_TOML: &str = "okh.toml";
+/// The manifest files of the older OKH v1 standard.
+pub const FILES_OKH_YAML: &[&str] = &["okh.yml", "okh.yaml"];
+
+/// The keys we read from and write to an OKH-LOSH manifest,
+/// with the names of the (top-level) fields they map to.
+pub const FIELDS_LOSH: &[(Key, &str)] = &[
+    (Key::Name, "name"),
+    (Key::Version, "version"),
+    (Key::License, "license"),
+    (Key::RepoWebUrl, "repo"),
+];
+
+/// Like [`FIELDS_LOSH`], but for the OKH v1 manifest.
+const FIELDS_V1: &[(Key, &str)] = &[
+    (Key::Name, "title"),
+    (Key::Version, "version"),
+    (Key::License, "license"),
+    (Key::RepoWebUrl, "project-link"),
+];
+
+/// In OKH v1, the license may be given separately
+/// for the hardware, the documentation and the software;
+/// we are interested in the hardware license.
+const V1_LICENSE_HARDWARE: &str = "hardware";
+
+/// Sources values from [Open Know-How (OKH)](https://openknowhow.org) manifests.
+///
+/// These are found in the project root,
+/// and describe Open Source Hardware projects.
+/// The current OKH-LOSH manifest (`okh.toml`) is preferred
+/// over the OKH v1 one (`okh.yml`).
+pub struct VarSource;
+
+fn field_name(fields: &[(Key, &'static str)], key: Key) -> Option<&'static str> {
+    fields
+        .iter()
+        .find(|(field_key, _name)| *field_key == key)
+        .map(|(_key, name)| *name)
+}
+
+fn toml_field(content: &str, field: &str) -> Option<String> {
+    match content.parse::<toml::Table>() {
+        Ok(manifest) => manifest
+            .get(field)
+            .and_then(toml::Value::as_str)
+            .map(ToOwned::to_owned),
+        Err(err) => {
+            log::warn!("Failed to parse '{FILE_OKH_TOML}': {err}");
+            None
+        }
+    }
+}
+
+fn yaml_scalar(value: &str) -> &str {
+    let value = value
+        .split_once(" #")
+        .map_or(value, |(value, _comment)| value)
+        .trim();
+    ['"', '\'']
+        .iter()
+        .find_map(|quote| {
+            value
+                .strip_prefix(*quote)
+                .and_then(|value| value.strip_suffix(*quote))
+        })
+        .unwrap_or(value)
+}
+
+/// Extracts the value of a top-level field from an OKH v1 manifest.
+/// This is not a full YAML parser;
+/// it only supports plain, single-line scalar values,
+/// and for the license,
+/// a block mapping with a [`V1_LICENSE_HARDWARE`] entry.
+fn yaml_field(content: &str, field: &str) -> Option<String> {
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if name != field {
+            continue;
+        }
+        let value = yaml_scalar(value);
+        let found = if value.is_empty() {
+            lines
+                .take_while(|line| line.starts_with([' ', '\t']))
+                .filter_map(|line| line.split_once(':'))
+                .find(|(name, _value)| name.trim() == V1_LICENSE_HARDWARE)
+                .map(|(_name, value)| yaml_scalar(value).to_owned())
+        } else {
+            Some(value.to_owned())
+        };
+        return found.filter(|value| !value.is_empty());
+    }
+    None
+}
+
+fn read_file(repo_path: &Path, file_name: &str) -> Result<Option<String>, std::io::Error> {
+    let file = repo_path.join(file_name);
+    Ok(if file.is_file() {
+        log::trace!("Reading OKH manifest '{}' ...", file.display());
+        Some(fs::read_to_string(file)?)
+    } else {
+        None
+    })
+}
+
+fn manifest_field(repo_path: &Path, key: Key) -> Result<Option<String>, std::io::Error> {
+    if let Some(field) = field_name(FIELDS_LOSH, key) {
+        if let Some(content) = read_file(repo_path, FILE_OKH_TOML)? {
+            return Ok(toml_field(&content, field));
+        }
+    }
+    if let Some(field) = field_name(FIELDS_V1, key) {
+        for file_name in FILES_OKH_YAML {
+            if let Some(content) = read_file(repo_path, file_name)? {
+                return Ok(yaml_field(&content, field));
+            }
+        }
+    }
+    Ok(None)
+}
+
+impl super::VarSource for VarSource {
+    fn is_usable(&self, environment: &mut Environment) -> bool {
+        environment
+            .settings
+            .repo_path
+            .as_ref()
+            .is_some_and(|repo_path| {
+                repo_path.join(FILE_OKH_TOML).is_file()
+                    || FILES_OKH_YAML
+                        .iter()
+                        .any(|file_name| repo_path.join(file_name).is_file())
+            })
+    }
+
+    fn hierarchy(&self) -> Hierarchy {
+        Hierarchy::Low
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn properties(&self) -> &Vec<String> {
+        &super::NO_PROPS
+    }
+
+    #[remain::check]
+    fn retrieve(&self, environment: &mut Environment, key: Key) -> RetrieveRes {
+        let Some(repo_path) = environment.settings.repo_path.clone() else {
+            return Ok(None);
+        };
+        Ok(
+            #[remain::sorted]
+            match key {
+                Key::BuildArch
+                | Key::BuildBranch
+                | Key::BuildCommitSha
+                | Key::BuildCommitShaShort
+                | Key::BuildDate
+                | Key::BuildDateEpoch
+                | Key::BuildDirty
+                | Key::BuildHostCpuCount
+                | Key::BuildHostingUrl
+                | Key::BuildHostKernelVersion
+                | Key::BuildHostPlatformTriple
+                | Key::BuildNumber
+                | Key::BuildOs
+                | Key::BuildOsFamily
+                | Key::BuildTag
+                | Key::Ci
+                | Key::Description
+                | Key::Doi
+                | Key::FundingUrl
+                | Key::Licenses
+                | Key::LicensesCompliance
+                | Key::NameMachineReadable
+                | Key::NameSlug
+                | Key::PullRequestNumber
+                | Key::PullRequestSourceBranch
+                | Key::PullRequestTargetBranch
+                | Key::PullRequestTitle
+                | Key::PullRequestUrl
+                | Key::RepoCiUrl
+                | Key::RepoCloneUrl
+                | Key::RepoCloneUrlGit
+                | Key::RepoCloneUrlHttp
+                | Key::RepoCloneUrlPush
+                | Key::RepoCloneUrlSsh
+                | Key::RepoCommitPrefixUrl
+                | Key::RepoDefaultBranch
+                | Key::RepoForkOfUrl
+                | Key::RepoForkUrl
+                | Key::RepoIssuesUrl
+                | Key::RepoNamespace
+                | Key::RepoOwner
+                | Key::RepoRawVersionedPrefixUrl
+                | Key::RepoReleasesUrl
+                | Key::RepoSlug
+                | Key::RepoVersionedDirPrefixUrl
+                | Key::RepoVersionedFilePrefixUrl
+                | Key::VersionBuildMeta
+                | Key::VersionControlSystem
+                | Key::VersionDate
+                | Key::VersionDescribe
+                | Key::VersionMajor
+                | Key::VersionMinor
+                | Key::VersionPatch
+                | Key::VersionPrerelease => None,
+                Key::License | Key::Name | Key::RepoWebUrl => {
+                    manifest_field(&repo_path, key)?.map(|val| (ConfidenceScore::MIDDLE, val))
+                }
+                Key::Version => manifest_field(&repo_path, key)?
+                    .map(|val| cleanup::conf_version(environment, (ConfidenceScore::MIDDLE, val))),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_field() {
+        let okh_v1 = "title: \"My Machine\" # the name\nversion: 1.0.2\nlicense:\n  hardware: CERN-OHL-1.2\n  documentation: CC-BY-4.0\nproject-link: https://example.com/my-machine\n";
+        assert_eq!(yaml_field(okh_v1, "title"), Some("My Machine".to_owned()));
+        assert_eq!(yaml_field(okh_v1, "version"), Some("1.0.2".to_owned()));
+        assert_eq!(
+            yaml_field(okh_v1, "license"),
+            Some("CERN-OHL-1.2".to_owned())
+        );
+        assert_eq!(
+            yaml_field(okh_v1, "project-link"),
+            Some("https://example.com/my-machine".to_owned())
+        );
+        assert_eq!(yaml_field(okh_v1, "hardware"), None);
+    }
+
+    #[test]
+    fn test_toml_field() {
+        let okh_losh =
+            "okhv = \"OKH-LOSH_0.1.0\"\nname = \"My Machine\"\nlicense = \"CERN-OHL-S-2.0\"\n";
+        assert_eq!(toml_field(okh_losh, "name"), Some("My Machine".to_owned()));
+        assert_eq!(toml_field(okh_losh, "repo"), None);
+    }
+}
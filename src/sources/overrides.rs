@@ -0,0 +1,62 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::constants;
+use crate::environment::Environment;
+use crate::var;
+use crate::var::ConfidenceScore;
+use crate::var::DynamicKey;
+use crate::var::Key;
+
+use super::var;
+use super::Hierarchy;
+use super::RetrieveRes;
+
+/// Sources from environment variables named [`constants::OVERRIDE_KEY_PREFIX`]
+/// followed by the raw variable key,
+/// e.g. `PROJVAR_VERSION` or `PROJVAR_REPO_WEB_URL`.
+///
+/// This is a documented channel to explicitly override values,
+/// independent of the configured output key prefix.
+/// These values win over the ones of all the other sources,
+/// and are used as the basis for derived values.
+pub struct VarSource;
+
+fn override_var(environment: &Environment, var_key_raw: &str) -> Option<(ConfidenceScore, String)> {
+    var(
+        environment,
+        &format!("{}{var_key_raw}", constants::OVERRIDE_KEY_PREFIX),
+        ConfidenceScore::MAX,
+    )
+}
+
+impl super::VarSource for VarSource {
+    fn is_usable(&self, _environment: &mut Environment) -> bool {
+        true
+    }
+
+    fn hierarchy(&self) -> Hierarchy {
+        Hierarchy::Override
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn properties(&self) -> &Vec<String> {
+        &super::NO_PROPS
+    }
+
+    fn retrieve(&self, environment: &mut Environment, key: Key) -> RetrieveRes {
+        Ok(override_var(environment, var::get(key).key_raw()))
+    }
+
+    fn retrieve_dynamic(
+        &self,
+        environment: &mut Environment,
+        key: &'static DynamicKey,
+    ) -> RetrieveRes {
+        Ok(override_var(environment, key.variable.key_raw()))
+    }
+}
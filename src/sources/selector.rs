@@ -4,14 +4,15 @@
 
 use crate::environment::Environment;
 use crate::validator;
-use crate::var::Confidence;
+use crate::var::AnyKey;
+use crate::var::ConfidenceScore;
+use crate::var::DynamicKey;
 use crate::var::Key;
 
+use super::ConfVal;
 use super::Hierarchy;
 use super::RetrieveRes;
 
-use std::convert::TryFrom;
-
 /// Does not source any new values,
 /// but selects out of the previously sourced ones those with highest validity
 /// (= the return of validation function),
@@ -19,38 +20,64 @@ use std::convert::TryFrom;
 /// those with higher confidence.
 pub struct VarSource;
 
-fn source_index_to_confidence(source_index: usize) -> u8 {
-    u8::try_from(source_index).unwrap_or_else(|_err| {
-        log::warn!(
-            "Sorting during value selection has a small chance to be imprecise, \
-            because more then {} sources (at least {}) are in use.",
-            u8::MAX,
-            source_index + 1
-        );
-        u8::MAX
-    })
+/// How valuable a value retrieved for a key is,
+/// which is used to sort all of them (for a single key),
+/// and thus coming up with the most valid/valuable/meaningful of them.
+/// The fields are compared in order of declaration:
+/// only if the validity of two values is equal,
+/// the confidence of their sources is compared,
+/// and only if that is equal too, their source index,
+/// which prefers the source with the highest [`Hierarchy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Valor {
+    validity: ConfidenceScore,
+    confidence: ConfidenceScore,
+    source_index: usize,
 }
 
-/// Given all the details of a `VarSource::retrieve` result,
-/// creates a vector (technically an array) of simple numbers,
-/// which serves as a hierarchical priority indication,
-/// meaning it is well suited to sort a number of such results
-/// (in practice: all of them for a single key),
-/// and thus coming up with the most valid/valuable/meaningful of the results.
-/// The first number is the most important.
-/// When comparing two vectors created by this function,
-/// we first only compare the two numbers at index 0,
-/// and only if they are equal, we continue the comparison,
-/// looking at the numbers in the second position of the two vectors.
-// TODO Maybe make this use a custom struct as return instead?
-fn valor(validity: &validator::Result, confidence: Confidence, source_index: usize) -> [u8; 4] {
-    let res_confs = validator::res_to_confidences(validity);
-    [
-        res_confs[0],
-        res_confs[1],
-        confidence,
-        source_index_to_confidence(source_index),
-    ]
+impl Valor {
+    const fn new(
+        validity: &validator::Result,
+        confidence: ConfidenceScore,
+        source_index: usize,
+    ) -> Self {
+        Self {
+            validity: validator::res_to_confidence(validity),
+            confidence,
+            source_index,
+        }
+    }
+}
+
+/// Selects the most valuable of all the values retrieved for `key` so far.
+fn select(environment: &mut Environment, key: AnyKey) -> Option<ConfVal> {
+    match &environment.output.get_all(key) {
+        Some(values) => {
+            let mut enriched_values = vec![];
+            for (src_index, (confidence, value)) in (*values).clone() {
+                let specific_validator = validator::get(key);
+                let validity = specific_validator(environment, &value);
+                enriched_values.push((src_index, (confidence, value), validity));
+            }
+            enriched_values.sort_by_cached_key(|entry| {
+                let valor = Valor::new(&entry.2, entry.1 .0, entry.0);
+                log::trace!(
+                    "Valor evaluated for {key} from source {}, value '{}' is {valor:?}.",
+                    entry.0,
+                    entry.1 .1,
+                );
+                log::trace!(
+                    "    ... evaluated from (validity, confidence, source_index): ({:?}, {}, {})",
+                    &entry.2,
+                    entry.1 .0,
+                    entry.0
+                );
+                valor
+            });
+            enriched_values.last().map(|entry| entry.1.clone())
+        }
+        None => None,
+    }
 }
 
 impl super::VarSource for VarSource {
@@ -71,30 +98,42 @@ impl super::VarSource for VarSource {
     }
 
     fn retrieve(&self, environment: &mut Environment, key: Key) -> RetrieveRes {
-        Ok(match &environment.output.get_all(key) {
-            Some(values) => {
-                let mut enriched_values = vec![];
-                for (src_index, (confidence, value)) in (*values).clone() {
-                    let specific_validator = validator::get(key);
-                    let validity = specific_validator(environment, &value);
-                    enriched_values.push((src_index, (confidence, value), validity));
-                }
-                enriched_values.sort_by_cached_key(|entry| {
-                    let valor = valor(&entry.2, entry.1.0, entry.0);
-                    log::trace!("Valor evaluated for {key:?} from source {}, value '{}' is {valor:?}.",
-                        entry.0,
-                        entry.1.1,
-                    );
-                    log::trace!("    ... evaluated from (validity, confidence, source_index): ({:?}, {}, {})",
-                        &entry.2,
-                        entry.1 .0,
-                        entry.0
-                    );
-                    valor
-                });
-                enriched_values.last().map(|entry| entry.1.clone())
-            }
-            None => None,
+        Ok(select(environment, key.into()))
+    }
+
+    fn retrieve_dynamic(
+        &self,
+        environment: &mut Environment,
+        key: &'static DynamicKey,
+    ) -> RetrieveRes {
+        Ok(select(environment, AnyKey::Dynamic(key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bad_value() -> validator::Result {
+        Err(validator::Error::BadValue {
+            msg: "bad".to_owned(),
+            value: "x".to_owned(),
         })
     }
+
+    #[test]
+    fn test_valor_prefers_validity_over_confidence() {
+        let valid = Valor::new(&Ok(validator::Validity::Unknown), ConfidenceScore::LOW, 0);
+        let invalid = Valor::new(&bad_value(), ConfidenceScore::MAX, 1);
+        assert!(valid > invalid);
+    }
+
+    #[test]
+    fn test_valor_prefers_confidence_over_source_index() {
+        let validity = Ok(validator::Validity::High { msg: None });
+        let confident = Valor::new(&validity, ConfidenceScore::HIGH, 0);
+        let late = Valor::new(&validity, ConfidenceScore::MIDDLE, 1);
+        assert!(confident > late);
+        assert!(Valor::new(&validity, ConfidenceScore::MIDDLE, 2) > late);
+    }
 }
@@ -0,0 +1,126 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::environment::Environment;
+use crate::value_conversions;
+use crate::var::ConfidenceScore;
+use crate::var::Key;
+
+use super::env::SOURCE_DATE_EPOCH;
+use super::var;
+use super::Hierarchy;
+use super::RetrieveRes;
+
+/// The build date used if [`SOURCE_DATE_EPOCH`] is not set:
+/// the start of the Unix epoch.
+const EPOCH: &str = "0";
+
+/// Sources fixed stand-ins for the values that change between builds or build hosts.
+///
+/// These are the build date, build number, OS, architecture and host properties.
+/// This source is only used if enabled with [`crate::settings::Settings::stub_values`].
+/// The build date is taken from [`SOURCE_DATE_EPOCH`], if set.
+///
+/// This makes the output reproducible,
+/// e.g. for snapshot tests of documents that embed it.
+/// These values win over the ones of all the other sources,
+/// except for explicit overrides (see [`super::overrides`]).
+pub struct VarSource;
+
+fn epoch(environment: &Environment) -> (ConfidenceScore, String) {
+    var(environment, SOURCE_DATE_EPOCH, ConfidenceScore::MAX)
+        .unwrap_or_else(|| (ConfidenceScore::MAX, EPOCH.to_owned()))
+}
+
+impl super::VarSource for VarSource {
+    fn is_usable(&self, environment: &mut Environment) -> bool {
+        environment.settings.stub_values
+    }
+
+    fn hierarchy(&self) -> Hierarchy {
+        Hierarchy::Stub
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn properties(&self) -> &Vec<String> {
+        &super::NO_PROPS
+    }
+
+    #[remain::check]
+    // NOTE Stand-ins of different keys may coincide, but we keep the arms sorted by key
+    #[allow(clippy::match_same_arms)]
+    fn retrieve(&self, environment: &mut Environment, key: Key) -> RetrieveRes {
+        let stub = |value: &str| Some((ConfidenceScore::MAX, value.to_owned()));
+        Ok(
+            #[remain::sorted]
+            match key {
+                Key::BuildArch => stub("x86_64"),
+                Key::BuildBranch
+                | Key::BuildCommitSha
+                | Key::BuildCommitShaShort
+                | Key::BuildDirty
+                | Key::BuildHostingUrl
+                | Key::BuildTag
+                | Key::Ci
+                | Key::Description
+                | Key::Doi
+                | Key::FundingUrl
+                | Key::License
+                | Key::Licenses
+                | Key::LicensesCompliance
+                | Key::Name
+                | Key::NameMachineReadable
+                | Key::NameSlug
+                | Key::PullRequestNumber
+                | Key::PullRequestSourceBranch
+                | Key::PullRequestTargetBranch
+                | Key::PullRequestTitle
+                | Key::PullRequestUrl
+                | Key::RepoCiUrl
+                | Key::RepoCloneUrl
+                | Key::RepoCloneUrlGit
+                | Key::RepoCloneUrlHttp
+                | Key::RepoCloneUrlPush
+                | Key::RepoCloneUrlSsh
+                | Key::RepoCommitPrefixUrl
+                | Key::RepoDefaultBranch
+                | Key::RepoForkOfUrl
+                | Key::RepoForkUrl
+                | Key::RepoIssuesUrl
+                | Key::RepoNamespace
+                | Key::RepoOwner
+                | Key::RepoRawVersionedPrefixUrl
+                | Key::RepoReleasesUrl
+                | Key::RepoSlug
+                | Key::RepoVersionedDirPrefixUrl
+                | Key::RepoVersionedFilePrefixUrl
+                | Key::RepoWebUrl
+                | Key::Version
+                | Key::VersionBuildMeta
+                | Key::VersionControlSystem
+                | Key::VersionDate
+                | Key::VersionDescribe
+                | Key::VersionMajor
+                | Key::VersionMinor
+                | Key::VersionPatch
+                | Key::VersionPrerelease => None,
+                Key::BuildDate => {
+                    let (confidence, epoch) = epoch(environment);
+                    value_conversions::epoch_to_our_format(environment, &epoch)?
+                        .map(|date| (confidence, date))
+                }
+                Key::BuildDateEpoch => Some(epoch(environment)),
+                Key::BuildHostCpuCount => stub("1"),
+                Key::BuildHostKernelVersion => stub("0.0.0"),
+                Key::BuildHostPlatformTriple => stub("x86_64-unknown-linux-gnu"),
+                Key::BuildNumber => stub("1"),
+                Key::BuildOs => stub("linux"),
+                Key::BuildOsFamily => stub("unix"),
+            },
+        )
+    }
+}
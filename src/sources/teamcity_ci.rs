@@ -0,0 +1,270 @@
+// SPDX-FileCopyrightText: 2021 - 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::environment::Environment;
+use crate::var::{ConfidenceScore, Key};
+
+use super::var;
+use super::Hierarchy;
+use super::RetrieveRes;
+
+/// Set by `TeamCity` in all builds; we use it to detect that we run in there,
+/// as the other variables (e.g. `BUILD_NUMBER`) are too generic.
+const VAR_TEAMCITY_VERSION: &str = "TEAMCITY_VERSION";
+/// Points to a Java properties file containing (many more)
+/// build parameters than are available as environment variables.
+const VAR_BUILD_PROPERTIES_FILE: &str = "TEAMCITY_BUILD_PROPERTIES_FILE";
+
+/// This sources values from the environment variables set by the CI provider `TeamCity`,
+/// and from the build properties file it points to.
+pub struct VarSource;
+
+/// Parses the content of a Java properties file,
+/// as written by `TeamCity` for the build parameters.
+///
+/// This supports comments (lines starting with '#' or '!'),
+/// the separators '=', ':' and white-space,
+/// escaped characters and line continuations,
+/// but not unicode escapes (`\uXXXX`).
+fn parse_properties(content: &str) -> HashMap<String, String> {
+    let mut properties = HashMap::new();
+    let mut logical_line = String::new();
+    for line in content.lines() {
+        let line = line.trim_start();
+        if logical_line.is_empty() && (line.is_empty() || line.starts_with(['#', '!'])) {
+            continue;
+        }
+        // An odd number of trailing back-slashes means the line continues on the next one
+        let trailing_slashes = line.len() - line.trim_end_matches('\\').len();
+        if trailing_slashes % 2 == 1 {
+            logical_line.push_str(line.strip_suffix('\\').unwrap_or(line));
+            continue;
+        }
+        logical_line.push_str(line);
+        let (key, value) = parse_property(&logical_line);
+        properties.insert(key, value);
+        logical_line.clear();
+    }
+    if !logical_line.is_empty() {
+        let (key, value) = parse_property(&logical_line);
+        properties.insert(key, value);
+    }
+    properties
+}
+
+/// Resolves the escape sequences in a part of a Java properties file line.
+fn unescape(escaped: &str) -> String {
+    let mut unescaped = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(chr) = chars.next() {
+        unescaped.push(if chr == '\\' {
+            match chars.next() {
+                Some('t') => '\t',
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('f') => '\u{c}',
+                Some(escaped_chr) => escaped_chr,
+                None => break,
+            }
+        } else {
+            chr
+        });
+    }
+    unescaped
+}
+
+/// Parses a single (logical) line of a Java properties file
+/// into key and value.
+fn parse_property(line: &str) -> (String, String) {
+    let mut key_end = line.len();
+    let mut value_start = line.len();
+    let mut escaped = false;
+    for (idx, chr) in line.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if chr == '\\' {
+            escaped = true;
+        } else if matches!(chr, '=' | ':') || chr.is_whitespace() {
+            key_end = idx;
+            value_start = idx + chr.len_utf8();
+            break;
+        } else {
+            // still within the key
+        }
+    }
+    // NOTE Both indices are at char boundaries
+    let (key, rest) = line.split_at(key_end);
+    let (separator, value) = rest.split_at(value_start - key_end);
+    let mut value = value.trim_start();
+    // NOTE This allows for "key = value" and "key : value"
+    if separator.trim().is_empty() {
+        if let Some(after_separator) = value.strip_prefix(['=', ':']) {
+            value = after_separator.trim_start();
+        }
+    }
+    (unescape(key), unescape(value))
+}
+
+fn build_properties(environment: &Environment) -> Result<HashMap<String, String>, std::io::Error> {
+    Ok(match environment.vars.get(VAR_BUILD_PROPERTIES_FILE) {
+        Some(file) if Path::new(file).is_file() => {
+            log::trace!("Reading TeamCity build properties file '{file}' ...");
+            parse_properties(&fs::read_to_string(file)?)
+        }
+        Some(file) => {
+            log::warn!("TeamCity build properties file '{file}' not found");
+            HashMap::new()
+        }
+        None => HashMap::new(),
+    })
+}
+
+/// Fetches a value from the build properties file.
+fn property(
+    environment: &Environment,
+    key: &str,
+) -> Result<Option<(ConfidenceScore, String)>, std::io::Error> {
+    Ok(build_properties(environment)?
+        .remove(key)
+        .filter(|value| !value.is_empty())
+        .map(|value| (ConfidenceScore::HIGH, value)))
+}
+
+impl super::VarSource for VarSource {
+    fn is_usable(&self, environment: &mut Environment) -> bool {
+        environment.vars.contains_key(VAR_TEAMCITY_VERSION)
+    }
+
+    fn hierarchy(&self) -> Hierarchy {
+        Hierarchy::High
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn properties(&self) -> &Vec<String> {
+        &super::NO_PROPS
+    }
+
+    #[remain::check]
+    fn retrieve(&self, environment: &mut Environment, key: Key) -> RetrieveRes {
+        Ok(
+            #[remain::sorted]
+            match key {
+                Key::BuildArch
+                | Key::BuildCommitShaShort
+                | Key::BuildDate
+                | Key::BuildDateEpoch
+                | Key::BuildDirty
+                | Key::BuildHostCpuCount
+                | Key::BuildHostingUrl
+                | Key::BuildHostKernelVersion
+                | Key::BuildHostPlatformTriple
+                | Key::BuildOs
+                | Key::BuildOsFamily
+                | Key::BuildTag
+                | Key::Description
+                | Key::Doi
+                | Key::FundingUrl
+                | Key::License
+                | Key::Licenses
+                | Key::LicensesCompliance
+                | Key::NameMachineReadable
+                | Key::NameSlug
+                | Key::PullRequestNumber
+                | Key::PullRequestSourceBranch
+                | Key::PullRequestTargetBranch
+                | Key::PullRequestTitle
+                | Key::PullRequestUrl
+                | Key::RepoCiUrl
+                | Key::RepoCloneUrl
+                | Key::RepoCloneUrlGit
+                | Key::RepoCloneUrlHttp
+                | Key::RepoCloneUrlPush
+                | Key::RepoCloneUrlSsh
+                | Key::RepoCommitPrefixUrl
+                | Key::RepoDefaultBranch
+                | Key::RepoForkOfUrl
+                | Key::RepoForkUrl
+                | Key::RepoIssuesUrl
+                | Key::RepoNamespace
+                | Key::RepoOwner
+                | Key::RepoRawVersionedPrefixUrl
+                | Key::RepoReleasesUrl
+                | Key::RepoSlug
+                | Key::RepoVersionedDirPrefixUrl
+                | Key::RepoVersionedFilePrefixUrl
+                | Key::RepoWebUrl
+                | Key::Version
+                | Key::VersionBuildMeta
+                | Key::VersionControlSystem
+                | Key::VersionDate
+                | Key::VersionDescribe
+                | Key::VersionMajor
+                | Key::VersionMinor
+                | Key::VersionPatch
+                | Key::VersionPrerelease => None,
+                Key::BuildBranch => property(environment, "teamcity.build.branch")?
+                    // NOTE This is what TeamCity uses if no branch specification is configured
+                    .filter(|(_confidence, branch)| branch != "<default>"),
+                Key::BuildCommitSha => {
+                    match var(environment, "BUILD_VCS_NUMBER", ConfidenceScore::HIGH) {
+                        Some(conf_val) => Some(conf_val),
+                        None => property(environment, "build.vcs.number")?,
+                    }
+                }
+                Key::BuildNumber => var(environment, "BUILD_NUMBER", ConfidenceScore::HIGH),
+                Key::Ci => Some((ConfidenceScore::HIGH, "true".to_owned())),
+                Key::Name => property(environment, "teamcity.projectName")?,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_properties() {
+        let properties = parse_properties(
+            "#TeamCity build properties without 'system.' prefix\n\
+            #Tue Oct 15 13:37:00 UTC 2024\n\
+            teamcity.projectName=My Project\n\
+            teamcity.build.branch = feature/x\n\
+            ! another comment\n\
+            build.vcs.number\\:1: 0123abc\n\
+            teamcity.build.tempDir=C\\:\\\\BuildAgent\\\\temp\n\
+            multi.line=first \\\n    second\n\
+            empty=\n",
+        );
+        assert_eq!(
+            properties.get("teamcity.projectName").map(String::as_str),
+            Some("My Project")
+        );
+        assert_eq!(
+            properties.get("teamcity.build.branch").map(String::as_str),
+            Some("feature/x")
+        );
+        assert_eq!(
+            properties.get("build.vcs.number:1").map(String::as_str),
+            Some("0123abc")
+        );
+        assert_eq!(
+            properties.get("teamcity.build.tempDir").map(String::as_str),
+            Some("C:\\BuildAgent\\temp")
+        );
+        assert_eq!(
+            properties.get("multi.line").map(String::as_str),
+            Some("first second")
+        );
+        assert_eq!(properties.get("empty").map(String::as_str), Some(""));
+        assert_eq!(properties.len(), 6);
+    }
+}
@@ -2,9 +2,10 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::constants;
 use crate::environment::Environment;
+use crate::var::ConfidenceScore;
 use crate::var::Key;
-use crate::var::C_HIGH;
 
 use super::var;
 use super::Hierarchy;
@@ -56,18 +57,52 @@ impl super::VarSource for VarSource {
                 | Key::RepoVersionedDirPrefixUrl
                 | Key::RepoVersionedFilePrefixUrl
                 | Key::RepoWebUrl
+                | Key::BuildHostPlatformTriple
+                | Key::BuildHostKernelVersion
+                | Key::BuildHostCpuCount
+                | Key::RepoOwner
+                | Key::RepoNamespace
+                | Key::RepoReleasesUrl
+                | Key::RepoCiUrl
+                | Key::VersionMajor
+                | Key::VersionMinor
+                | Key::VersionPatch
+                | Key::VersionPrerelease
+                | Key::VersionBuildMeta
+                | Key::BuildDateEpoch
+                | Key::BuildCommitShaShort
+                | Key::BuildDirty
+                | Key::Description
+                | Key::Doi
+                | Key::FundingUrl
+                | Key::LicensesCompliance
+                | Key::NameSlug
+                | Key::PullRequestNumber
+                | Key::PullRequestSourceBranch
+                | Key::PullRequestTargetBranch
+                | Key::PullRequestTitle
+                | Key::PullRequestUrl
+                | Key::RepoCloneUrlPush
+                | Key::RepoDefaultBranch
+                | Key::RepoForkOfUrl
+                | Key::RepoForkUrl
+                | Key::VersionDescribe
                 | Key::VersionDate => None,
-                Key::BuildBranch => var(environment, "TRAVIS_BRANCH", C_HIGH),
-                Key::BuildNumber => var(environment, "TRAVIS_BUILD_NUMBER", C_HIGH),
-                Key::BuildOs => var(environment, "TRAVIS_OS_NAME", C_HIGH),
-                Key::BuildTag => var(environment, "TRAVIS_TAG", C_HIGH),
+                Key::BuildBranch => var(environment, "TRAVIS_BRANCH", ConfidenceScore::HIGH),
+                Key::BuildCommitSha => var(environment, "TRAVIS_COMMIT", ConfidenceScore::HIGH),
+                Key::BuildNumber => var(environment, "TRAVIS_BUILD_NUMBER", ConfidenceScore::HIGH),
+                Key::BuildOs => var(environment, "TRAVIS_OS_NAME", ConfidenceScore::HIGH),
+                Key::BuildTag => var(environment, "TRAVIS_TAG", ConfidenceScore::HIGH),
                 Key::Name => crate::value_conversions::slug_to_proj_name(
                     environment.vars.get("TRAVIS_REPO_SLUG"),
                 )?
-                .map(|val| (C_HIGH, val)), // usually: TRAVIS_REPO_SLUG="user/project"
+                .map(|val| (ConfidenceScore::HIGH, val)), // usually: TRAVIS_REPO_SLUG="user/project"
+                Key::RepoSlug => var(environment, "TRAVIS_REPO_SLUG", ConfidenceScore::HIGH),
                 Key::Version => self
                     .version_from_build_tag(environment, key)?
-                    .or_else(|| var(environment, "TRAVIS_COMMIT", C_HIGH)),
+                    .or_else(|| var(environment, "TRAVIS_COMMIT", ConfidenceScore::HIGH)),
+                Key::VersionControlSystem => var(environment, "TRAVIS", ConfidenceScore::HIGH)
+                    .map(|_| (ConfidenceScore::HIGH, constants::VCS_GIT.to_owned())),
             },
         )
     }
@@ -2,27 +2,211 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 
-use lazy_static::lazy_static;
-use regex::Regex;
-use strum::IntoEnumIterator;
+use serde::{Deserialize, Serialize, Serializer};
+use thiserror::Error;
 
 use crate::{
     environment::Environment,
+    settings::TableFormat,
     sources::VarSource,
-    var::{self, Confidence, Key, Variable},
+    var::{AnyKey, ConfidenceScore, Variable},
 };
 
 /// Key, associated variable meta-data, confidence, primary value.
-pub type Value<'a> = (Key, &'static Variable, &'a (Confidence, String));
+/// A list of these (see [`Storage::get_wrapup`]) may be serialized as-is.
+pub type Value<'a> = (AnyKey, &'static Variable, &'a (ConfidenceScore, String));
+
+/// Two sources disagree about the value of a key.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Sources disagree about the value of {key}: '{previous_value}' (confidence {previous_confidence}) vs. '{value}' (confidence {confidence})")]
+pub struct Discrepancy {
+    pub key: AnyKey,
+    pub previous_confidence: ConfidenceScore,
+    pub previous_value: String,
+    pub confidence: ConfidenceScore,
+    pub value: String,
+}
+
+impl Discrepancy {
+    /// Whether both of the disagreeing values are more then mere guesses
+    /// (like the fall-back "false" for [`crate::var::Key::Ci`]),
+    /// which makes the disagreement worth pointing out.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.previous_confidence >= ConfidenceScore::MIDDLE
+            && self.confidence >= ConfidenceScore::MIDDLE
+    }
+}
 
 /// Stores the property values gathered from all the sources.
 #[derive(Clone)]
 pub struct Storage {
     // key_values: HashMap<Key, Vec<(usize, String)>>,
-    key_values: HashMap<Key, HashMap<usize, (Confidence, String)>>,
-    key_primary: HashMap<Key, (Confidence, String)>,
+    key_values: HashMap<AnyKey, HashMap<usize, (ConfidenceScore, String)>>,
+    key_primary: HashMap<AnyKey, (ConfidenceScore, String)>,
+}
+
+/// The serializable form of a [`Storage`],
+/// with the keys given by name, and sorted.
+/// This is what a [`Storage`] serializes to.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageSnapshot {
+    /// All the values, by key and source index
+    pub values: BTreeMap<String, BTreeMap<usize, (ConfidenceScore, String)>>,
+    /// The primary values, by key
+    pub primary: BTreeMap<String, (ConfidenceScore, String)>,
+}
+
+/// A [`StorageSnapshot`] contains a key that is neither built-in
+/// nor registered as a dynamic key.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Unknown key in storage snapshot: '{0}'")]
+pub struct UnknownKey(pub String);
+
+/// Creates a short, human-readable name for a source,
+/// e.g. `git` instead of `projvar::sources::git::VarSource[]`.
+pub fn source_name(source: &dyn VarSource) -> String {
+    let display = source.display();
+    let display = display
+        .strip_prefix("projvar::sources::")
+        .unwrap_or(&display)
+        .replacen("::VarSource", "", 1);
+    display
+        .strip_suffix("[]")
+        .map_or_else(|| display.clone(), ToOwned::to_owned)
+}
+
+/// A table of values, independent of any output format.
+pub struct Table {
+    pub header: Vec<String>,
+    /// `None` denotes a missing value,
+    /// which is distinct from an empty one.
+    pub rows: Vec<Vec<Option<String>>>,
+}
+
+/// Renders a table in the given format.
+///
+/// NOTE The [`Storage`] functions render Markdown themselves,
+///      with more care for the details.
+fn render(format: TableFormat, table: &Table) -> String {
+    match format {
+        TableFormat::Markdown => render_markdown(table),
+        TableFormat::Html => render_html(table),
+        TableFormat::Json => render_json(table),
+        TableFormat::Csv => render_csv(table),
+    }
+}
+
+/// Escapes a value for use in a Markdown table cell,
+/// which may neither contain a '|' nor a line break.
+fn escape_markdown_cell(value: &str) -> String {
+    value
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+fn render_markdown(table: &Table) -> String {
+    let mut markdown = format!("| {} |\n|", table.header.join(" | "));
+    markdown.push_str(&" --- |".repeat(table.header.len()));
+    markdown.push('\n');
+    for row in &table.rows {
+        markdown.push('|');
+        for cell in row {
+            markdown.push(' ');
+            markdown.push_str(&escape_markdown_cell(cell.as_deref().unwrap_or_default()));
+            markdown.push_str(" |");
+        }
+        markdown.push('\n');
+    }
+    markdown
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_html(table: &Table) -> String {
+    let mut html = String::from("<table>\n<thead>\n<tr>");
+    for column in &table.header {
+        html.push_str("<th>");
+        html.push_str(&escape_html(column));
+        html.push_str("</th>");
+    }
+    html.push_str("</tr>\n</thead>\n<tbody>\n");
+    for row in &table.rows {
+        html.push_str("<tr>");
+        for cell in row {
+            html.push_str("<td>");
+            html.push_str(&escape_html(cell.as_deref().unwrap_or_default()));
+            html.push_str("</td>");
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>\n");
+    html
+}
+
+/// Renders the table as a JSON array of objects,
+/// one per row, mapping the column names to the values.
+fn render_json(table: &Table) -> String {
+    let objects: Vec<serde_json::Map<String, serde_json::Value>> = table
+        .rows
+        .iter()
+        .map(|row| {
+            table
+                .header
+                .iter()
+                .zip(row)
+                .map(|(column, cell)| {
+                    (
+                        column.clone(),
+                        cell.clone()
+                            .map_or(serde_json::Value::Null, serde_json::Value::String),
+                    )
+                })
+                .collect()
+        })
+        .collect();
+    let mut json = serde_json::to_string_pretty(&objects)
+        .expect("Serializing a list of string maps to JSON never fails");
+    json.push('\n');
+    json
+}
+
+/// Quotes a CSV field as described in RFC 4180,
+/// if it contains any special characters.
+fn escape_csv(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+fn push_csv_line<'a>(csv: &mut String, cells: impl Iterator<Item = &'a str>) {
+    let cells: Vec<Cow<str>> = cells.map(escape_csv).collect();
+    csv.push_str(&cells.join(","));
+    csv.push_str("\r\n");
+}
+
+fn render_csv(table: &Table) -> String {
+    let mut csv = String::new();
+    push_csv_line(&mut csv, table.header.iter().map(String::as_str));
+    for row in &table.rows {
+        push_csv_line(
+            &mut csv,
+            row.iter().map(|cell| cell.as_deref().unwrap_or_default()),
+        );
+    }
+    csv
 }
 
 /// Double-quotes a string if it is empty,
@@ -37,6 +221,12 @@ macro_rules! quote_empty {
     };
 }
 
+impl Serialize for Storage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.snapshot().serialize(serializer)
+    }
+}
+
 impl Storage {
     /// Creates a new, empty instance of a storage.
     pub fn new() -> Self {
@@ -46,16 +236,141 @@ impl Storage {
         }
     }
 
+    /// Creates the serializable form of this storage.
+    #[must_use]
+    pub fn snapshot(&self) -> StorageSnapshot {
+        StorageSnapshot {
+            values: self
+                .key_values
+                .iter()
+                .map(|(key, values)| {
+                    (
+                        key.name().to_owned(),
+                        values
+                            .iter()
+                            .map(|(source_index, value)| (*source_index, value.clone()))
+                            .collect(),
+                    )
+                })
+                .collect(),
+            primary: self
+                .key_primary
+                .iter()
+                .map(|(key, value)| (key.name().to_owned(), value.clone()))
+                .collect(),
+        }
+    }
+
+    /// Recreates a storage from its serializable form,
+    /// using `resolve` to find the key of each name
+    /// (which is needed for dynamic keys).
+    ///
+    /// # Errors
+    ///
+    /// If `resolve` does not know one of the key names.
+    pub fn from_snapshot(
+        snapshot: StorageSnapshot,
+        resolve: impl Fn(&str) -> Option<AnyKey>,
+    ) -> Result<Self, UnknownKey> {
+        let resolve = |name: String| resolve(&name).ok_or(UnknownKey(name));
+        let mut key_values = HashMap::with_capacity(snapshot.values.len());
+        for (name, values) in snapshot.values {
+            key_values.insert(resolve(name)?, values.into_iter().collect());
+        }
+        let mut key_primary = HashMap::with_capacity(snapshot.primary.len());
+        for (name, value) in snapshot.primary {
+            key_primary.insert(resolve(name)?, value);
+        }
+        Ok(Self {
+            key_values,
+            key_primary,
+        })
+    }
+
+    /// Creates a table of all the keys (y) and all the sources (x),
+    /// containing the currently stored values,
+    /// in the given format.
+    pub fn to_table(
+        &self,
+        environment: &Environment,
+        sources: &[Box<dyn VarSource>],
+        format: TableFormat,
+    ) -> String {
+        match format {
+            TableFormat::Markdown => {
+                let source_names: Vec<String> = sources
+                    .iter()
+                    .map(|source| source_name(&**source))
+                    .collect();
+                self.to_table_markdown(environment, &source_names)
+            }
+            TableFormat::Html | TableFormat::Json | TableFormat::Csv => {
+                render(format, &self.sources_table(environment, sources))
+            }
+        }
+    }
+
+    /// Creates a table of all the keys (y) and all the sources (x),
+    /// containing the currently stored values,
+    /// plus a final column with the primary values.
+    pub fn sources_table(
+        &self,
+        environment: &Environment,
+        sources: &[Box<dyn VarSource>],
+    ) -> Table {
+        let mut header = vec!["Property".to_owned(), "Env-Key".to_owned()];
+        header.extend(sources.iter().map(|source| source_name(&**source)));
+        header.push("Final Value".to_owned());
+        let mut keys: Vec<&AnyKey> = self.key_values.keys().collect();
+        keys.sort_unstable();
+        let rows = keys
+            .into_iter()
+            .filter_map(|key| {
+                let values = self.key_values.get(key)?;
+                let mut row = vec![
+                    Some(key.name().to_owned()),
+                    Some(key.variable().key(environment).into_owned()),
+                ];
+                row.extend(
+                    (0..sources.len())
+                        .map(|source_index| values.get(&source_index).map(|(_c, v)| v.clone())),
+                );
+                row.push(self.get(*key).map(|(_c, v)| v.clone()));
+                Some(row)
+            })
+            .collect();
+        Table { header, rows }
+    }
+
+    /// Creates a table of all the keys,
+    /// containing the primary values.
+    pub fn primary_table(&self, environment: &Environment) -> Table {
+        let rows = self
+            .get_wrapup()
+            .into_iter()
+            .map(|(key, variable, (_confidence, value))| {
+                vec![
+                    Some(key.name().to_owned()),
+                    Some(variable.key(environment).into_owned()),
+                    Some(value.clone()),
+                ]
+            })
+            .collect();
+        Table {
+            header: vec![
+                "Property".to_owned(),
+                "Env-Key".to_owned(),
+                "Value".to_owned(),
+            ],
+            rows,
+        }
+    }
+
     /// Creates a table of all the keys (y) and all the sources (x),
     /// containing the currently stored values.
     /// It will be created in markdown format.
     // TODO further specify the markdown flavor in the sentence above.
-    pub fn to_table(&self, environment: &Environment, sources: &[Box<dyn VarSource>]) -> String {
-        lazy_static! {
-            static ref R_COMMON_SOURCE_PREFIX: Regex = Regex::new(r"^projvar::sources::").unwrap();
-            static ref R_COMMON_SOURCE_NAME: Regex = Regex::new(r"::VarSource").unwrap();
-            static ref R_EMPTY_PROPERTIES: Regex = Regex::new(r"\[\]$").unwrap();
-        }
+    fn to_table_markdown(&self, environment: &Environment, source_names: &[String]) -> String {
         static HEADER_PREFIX: &str = "| Property | Env-Key |";
         static HEADER_SUFFIX: &str = " Final Value |";
         static SOURCE_NAME_ESTIMATE: usize = 32;
@@ -63,21 +378,18 @@ impl Storage {
         static CONTENT_LINE_PREFIX_EST: usize = 40;
         // " `$value` |" (this will often be empty)
         static CONTENT_LINE_PART_EST: usize = 10;
+        let num_sources = source_names.len();
         // the estimated size of the table in chars
-        let table_chars_estimate = (HEADER_PREFIX.len() + (sources.len() * (3 + SOURCE_NAME_ESTIMATE)) + 1) + // header
-            (1 + (sources.len() * 6) + 1) + // header separator
-            self.key_values.len() * (CONTENT_LINE_PREFIX_EST + sources.len() * CONTENT_LINE_PART_EST) + 1; // table content
+        let table_chars_estimate = (HEADER_PREFIX.len() + (num_sources * (3 + SOURCE_NAME_ESTIMATE)) + 1) + // header
+            (1 + (num_sources * 6) + 1) + // header separator
+            self.key_values.len() * (CONTENT_LINE_PREFIX_EST + num_sources * CONTENT_LINE_PART_EST) + 1; // table content
         let mut table = String::with_capacity(table_chars_estimate);
 
         // header
         table.push_str(HEADER_PREFIX);
-        for source in sources {
-            let display = source.display();
-            let display = R_COMMON_SOURCE_PREFIX.replace(&display, "");
-            let display = R_COMMON_SOURCE_NAME.replace(&display, "");
-            let display = R_EMPTY_PROPERTIES.replace(&display, "");
+        for source_name in source_names {
             table.push(' ');
-            table.push_str(&display);
+            table.push_str(source_name);
             table.push_str(" |");
         }
         table.push_str(HEADER_SUFFIX);
@@ -85,31 +397,35 @@ impl Storage {
 
         // header separator
         table.push('|');
-        for _table_sep_index in 0..(sources.len() + 3) {
+        for _table_sep_index in 0..(num_sources + 3) {
             table.push_str(" --- |");
         }
         table.push('\n');
 
-        // table content (`Key::iter()` is sorted)
-        for key in Key::iter() {
-            if let Some(values) = self.key_values.get(&key) {
-                let variable = var::get(key);
+        // table content
+        let mut keys: Vec<&AnyKey> = self.key_values.keys().collect();
+        keys.sort_unstable();
+        for key in keys {
+            if let Some(values) = self.key_values.get(key) {
+                let variable = key.variable();
                 table.push_str("| ");
-                table.push_str(key.into());
+                table.push_str(key.name());
                 table.push_str(" | `");
                 table.push_str(&variable.key(environment));
                 table.push_str("` |");
-                for source_index in 0..sources.len() {
+                for source_index in 0..num_sources {
                     table.push(' ');
-                    table.push_str(
+                    table.push_str(&escape_markdown_cell(
                         values
                             .get(&source_index)
                             .map_or("", |(_c, v)| quote_empty!(v)),
-                    );
+                    ));
                     table.push_str(" |");
                 }
                 table.push_str(" **");
-                table.push_str(self.get(key).map_or("", |(_c, v)| v));
+                table.push_str(&escape_markdown_cell(
+                    self.get(*key).map_or("", |(_c, v)| v),
+                ));
                 table.push_str("** |");
                 table.push('\n');
             }
@@ -120,43 +436,54 @@ impl Storage {
     }
 
     /// Creates a list of all the keys,
-    /// containing the currently stored values.
-    /// It will be created in markdown format.
-    pub fn to_list(&self, environment: &Environment) -> String {
-        let values = self.get_wrapup();
-        let mut key_strs: HashMap<Key, String> = HashMap::with_capacity(values.len());
-        for (key, variable, _value) in &values {
-            let key_str = variable.key(environment);
-            key_strs.insert(*key, key_str.as_ref().to_owned());
+    /// containing the currently stored values,
+    /// in the given format.
+    /// In all formats but Markdown, this is a table with one row per key.
+    pub fn to_list(&self, environment: &Environment, format: TableFormat) -> String {
+        if format != TableFormat::Markdown {
+            return render(format, &self.primary_table(environment));
         }
+        let values = self.get_wrapup();
+        let key_strs: Vec<Cow<str>> = values
+            .iter()
+            .map(|(_key, variable, _value)| variable.key(environment))
+            .collect();
         // because the loop below adds 7 strings for each entry
-        let mut list = Vec::with_capacity(values.len() * 7);
-        for (key, _variable, (_confidence, value)) in &values {
-            list.push("* ");
-            list.push(key.into());
-            list.push(" - `");
-            list.push(quote_empty!(&key_strs[key]));
-            list.push("` - ");
-            list.push(value);
-            list.push("\n");
+        let mut list: Vec<Cow<str>> = Vec::with_capacity(values.len() * 7);
+        for ((key, _variable, (_confidence, value)), key_str) in values.iter().zip(&key_strs) {
+            list.push(Cow::Borrowed("* "));
+            list.push(Cow::Borrowed(key.name()));
+            list.push(Cow::Borrowed(" - `"));
+            list.push(Cow::Borrowed(quote_empty!(key_str.as_ref())));
+            list.push(Cow::Borrowed("` - "));
+            // continuation lines are indented, to stay part of the list item
+            list.push(if value.contains('\n') {
+                Cow::Owned(value.replace('\n', "\n  "))
+            } else {
+                Cow::Borrowed(value)
+            });
+            list.push(Cow::Borrowed("\n"));
         }
         list.concat()
     }
 
     /// Returns the primary value associated to a specific key,
     /// if it is in store.
-    pub fn get(&self, key: Key) -> Option<&(Confidence, String)> {
+    pub fn get(&self, key: impl Into<AnyKey>) -> Option<&(ConfidenceScore, String)> {
         // The last entry contains the value of the source
         // with the highest `sources::Hierarchy`
         // that provided a value at all.
-        self.key_primary.get(&key)
+        self.key_primary.get(&key.into())
         // .and_then(|entry| entry.last().map(|entry| &entry.1))
     }
 
     /// Returns all value by any source
     /// which is associated to the provided key.
-    pub fn get_all(&self, key: Key) -> Option<&HashMap<usize, (Confidence, String)>> {
-        self.key_values.get(&key)
+    pub fn get_all(
+        &self,
+        key: impl Into<AnyKey>,
+    ) -> Option<&HashMap<usize, (ConfidenceScore, String)>> {
+        self.key_values.get(&key.into())
     }
 
     /// Builds a sorted list of all the keys with associated:
@@ -167,7 +494,7 @@ impl Storage {
             .iter()
             .map(|key_value| {
                 let key = *key_value.0;
-                let variable = var::get(*key_value.0);
+                let variable = key.variable();
                 let value = key_value.1;
                 (key, variable, value)
             })
@@ -177,22 +504,60 @@ impl Storage {
     }
 
     /// Adds the value found for a specific key by a certain source.
-    pub fn add(&mut self, key: Key, source_index: usize, confidence: Confidence, value: String) {
+    ///
+    /// The confidence of the primary value is raised
+    /// for each other source that supplied the same value,
+    /// see [`ConfidenceScore::corroborate`].
+    ///
+    /// Returns the disagreement with the previous primary value,
+    /// if there was one.
+    pub fn add(
+        &mut self,
+        key: impl Into<AnyKey>,
+        source_index: usize,
+        confidence: ConfidenceScore,
+        value: String,
+    ) -> Option<Discrepancy> {
+        let key = key.into();
         // ... PUH! :O
         // This returns the Vec for key,
         // or creates, inserts and returns a new one,
         // if none is present yet.
         // See: <https://stackoverflow.com/a/41418147>
-        (*self.key_values.entry(key).or_default())
-            .insert(source_index, (confidence, value.clone()));
+        let values = self.key_values.entry(key).or_default();
+        values.insert(source_index, (confidence, value.clone()));
+        let combined_confidence = values
+            .values()
+            .filter(|(_confidence, other_value)| *other_value == value)
+            .map(|(other_confidence, _value)| *other_confidence)
+            .reduce(ConfidenceScore::corroborate)
+            .unwrap_or(confidence);
         // here, the last to add, wins (should be the source with the highest hierarchy)
-        self.key_primary.insert(key, (confidence, value));
+        self.key_primary
+            .insert(key, (combined_confidence, value.clone()))
+            .filter(|(_previous_confidence, previous_value)| *previous_value != value)
+            .map(|(previous_confidence, previous_value)| Discrepancy {
+                key,
+                previous_confidence,
+                previous_value,
+                confidence,
+                value,
+            })
+    }
+
+    /// Lowers the confidence of the primary value of the given key
+    /// to at most `confidence`, keeping the value itself.
+    pub fn downgrade(&mut self, key: impl Into<AnyKey>, confidence: ConfidenceScore) {
+        if let Some(primary) = self.key_primary.get_mut(&key.into()) {
+            primary.0 = primary.0.min(confidence);
+        }
     }
 
     /// Removes all stored values from all sources for the given key.
-    pub fn remove(&mut self, key: Key) -> Option<(Confidence, String)> {
+    pub fn remove(&mut self, key: impl Into<AnyKey>) -> Option<(ConfidenceScore, String)> {
+        let key = key.into();
         if self.key_values.remove(&key).is_some() {
-            log::info!("Removing key from storage: {key:?}");
+            log::info!("Removing key from storage: {key}");
         }
         self.key_primary.remove(&key)
     }
@@ -203,3 +568,147 @@ impl Default for Storage {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::var::{ConfidenceScore, DynamicKey, Key};
+
+    #[test]
+    fn test_corroboration() {
+        let mut storage = Storage::new();
+        assert_eq!(
+            storage.add(Key::Version, 0, ConfidenceScore::HIGH, "1.0.0".to_owned()),
+            None
+        );
+        assert_eq!(
+            storage.add(Key::Version, 1, ConfidenceScore::HIGH, "1.0.0".to_owned()),
+            None
+        );
+        assert_eq!(
+            storage.get(Key::Version),
+            Some(&(
+                ConfidenceScore::HIGH.corroborate(ConfidenceScore::HIGH),
+                "1.0.0".to_owned()
+            ))
+        );
+        // the primary value only profits from sources agreeing with it
+        let discrepancy = storage.add(Key::Version, 2, ConfidenceScore::LOW, "2.0.0".to_owned());
+        assert_eq!(
+            storage.get(Key::Version),
+            Some(&(ConfidenceScore::LOW, "2.0.0".to_owned()))
+        );
+        assert!(discrepancy.is_some_and(|discrepancy| !discrepancy.is_significant()));
+        assert_eq!(
+            storage.add(Key::Version, 3, ConfidenceScore::HIGH, "1.0.0".to_owned()),
+            Some(Discrepancy {
+                key: Key::Version.into(),
+                previous_confidence: ConfidenceScore::LOW,
+                previous_value: "2.0.0".to_owned(),
+                confidence: ConfidenceScore::HIGH,
+                value: "1.0.0".to_owned(),
+            })
+        );
+        assert_eq!(
+            storage
+                .get(Key::Version)
+                .map(|(confidence, _value)| *confidence),
+            Some(
+                ConfidenceScore::HIGH
+                    .corroborate(ConfidenceScore::HIGH)
+                    .corroborate(ConfidenceScore::HIGH)
+            )
+        );
+    }
+
+    #[test]
+    fn test_dynamic_keys() {
+        let dynamic_key = DynamicKey::create("FirmwareVariant", "The firmware variant", None);
+        let mut storage = Storage::new();
+        storage.add(
+            AnyKey::Dynamic(dynamic_key),
+            0,
+            ConfidenceScore::HIGH,
+            "lite".to_owned(),
+        );
+        storage.add(Key::Version, 0, ConfidenceScore::HIGH, "1.0.0".to_owned());
+        assert_eq!(
+            storage.get(AnyKey::Dynamic(dynamic_key)),
+            Some(&(ConfidenceScore::HIGH, "lite".to_owned()))
+        );
+        let wrapup = storage.get_wrapup();
+        // built-in keys come first
+        let [(first_key, _, _), (second_key, second_variable, _)] = wrapup.as_slice() else {
+            panic!("Expected two values, got {}", wrapup.len());
+        };
+        assert_eq!(*first_key, AnyKey::from(Key::Version));
+        assert_eq!(*second_key, AnyKey::Dynamic(dynamic_key));
+        assert_eq!(second_variable.key_raw(), "FIRMWARE_VARIANT");
+    }
+
+    #[test]
+    fn test_to_list_formats() {
+        let environment = Environment::stub();
+        let mut storage = Storage::new();
+        storage.add(
+            Key::Name,
+            0,
+            ConfidenceScore::HIGH,
+            "a, \"b\" & <c>".to_owned(),
+        );
+        storage.add(Key::Version, 0, ConfidenceScore::HIGH, "1.0.0".to_owned());
+        assert_eq!(
+            storage.to_list(&environment, TableFormat::Csv),
+            "Property,Env-Key,Value\r\n\
+Name,PROJECT_NAME,\"a, \"\"b\"\" & <c>\"\r\n\
+Version,PROJECT_VERSION,1.0.0\r\n"
+        );
+        assert!(storage.to_list(&environment, TableFormat::Html).contains(
+            "<tr><td>Name</td><td>PROJECT_NAME</td><td>a, &quot;b&quot; &amp; &lt;c&gt;</td></tr>"
+        ));
+        let json: serde_json::Value =
+            serde_json::from_str(&storage.to_list(&environment, TableFormat::Json)).unwrap();
+        assert_eq!(json[1]["Env-Key"], "PROJECT_VERSION");
+        assert_eq!(json[1]["Value"], "1.0.0");
+    }
+
+    #[test]
+    fn test_multiline_values() {
+        let environment = Environment::stub();
+        let mut storage = Storage::new();
+        let name = "Line 1\nLine | 2";
+        storage.add(Key::Name, 0, ConfidenceScore::HIGH, name.to_owned());
+        assert_eq!(
+            storage.to_list(&environment, TableFormat::Markdown),
+            "* Name - `PROJECT_NAME` - Line 1\n  Line | 2\n"
+        );
+        assert!(
+            render(TableFormat::Markdown, &storage.primary_table(&environment))
+                .ends_with("| Name | PROJECT_NAME | Line 1<br>Line \\| 2 |\n")
+        );
+        assert_eq!(
+            storage.to_list(&environment, TableFormat::Csv),
+            "Property,Env-Key,Value\r\nName,PROJECT_NAME,\"Line 1\nLine | 2\"\r\n"
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&storage.to_list(&environment, TableFormat::Json)).unwrap();
+        assert_eq!(json[0]["Value"], name);
+    }
+
+    #[test]
+    fn test_serialize() {
+        let mut storage = Storage::new();
+        storage.add(Key::Version, 0, ConfidenceScore::LOW, "1.0.0".to_owned());
+        storage.add(Key::Version, 2, ConfidenceScore::HIGH, "1.0.1".to_owned());
+        let json = serde_json::to_value(&storage).unwrap();
+        assert_eq!(json["primary"]["Version"], serde_json::json!([75, "1.0.1"]));
+        assert_eq!(
+            json["values"]["Version"]["0"],
+            serde_json::json!([25, "1.0.0"])
+        );
+        let wrapup = serde_json::to_value(storage.get_wrapup()).unwrap();
+        assert_eq!(wrapup[0][0], "Version");
+        assert_eq!(wrapup[0][1]["key"], "VERSION");
+        assert_eq!(wrapup[0][2][1], "1.0.1");
+    }
+}
@@ -0,0 +1,379 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Common functionality for running external commands,
+//! so all features that shell out behave the same
+//! regarding time-outs, captured output, logging and error messages.
+//!
+//! Commands are run through a [`Runner`],
+//! which is [`System`] in production,
+//! and may be replaced by a [`Fake`] in tests,
+//! supplying canned outputs instead of running anything.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fmt;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+/// The default time-out for a single command.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+/// How often we check whether a running command is done yet.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// This enumerates all possible errors returned by this module.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The program is not installed, or not in the `PATH`.
+    #[error("Command '{program}' not found; is it installed and in the PATH?")]
+    NotFound { program: String },
+
+    /// The command could not be started, or waited for.
+    #[error("Failed to run `{cmd}`: {source}")]
+    Io { cmd: String, source: io::Error },
+
+    /// The command did not finish in time, and was killed.
+    #[error("Command `{cmd}` timed out after {timeout:?}")]
+    Timeout { cmd: String, timeout: Duration },
+
+    /// The command exited with a non-zero exit code.
+    #[error(
+        "Command `{cmd}` failed with {}: {stderr}",
+        .code.map_or_else(|| "a signal".to_owned(), |code| format!("exit code {code}"))
+    )]
+    Failed {
+        cmd: String,
+        code: Option<i32>,
+        stderr: String,
+    },
+
+    /// The command was not run, because we are in dry-run mode.
+    #[error("Not running `{cmd}` in dry-run mode")]
+    DryRun { cmd: String },
+}
+
+/// An external command to be run by a [`Runner`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cmd {
+    pub program: String,
+    pub args: Vec<OsString>,
+    /// The working directory; the current one if `None`
+    pub dir: Option<PathBuf>,
+    /// After how long the command is killed
+    pub timeout: Duration,
+}
+
+impl Cmd {
+    /// Creates a command for running the given program without arguments,
+    /// in the current directory, with the [`DEFAULT_TIMEOUT`].
+    #[must_use]
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+            args: vec![],
+            dir: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    #[must_use]
+    pub fn arg(mut self, arg: impl Into<OsString>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    #[must_use]
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    #[must_use]
+    pub fn dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+/// Renders the command line as it could be typed into a shell,
+/// which is used for logging, in error messages,
+/// and to look up the outputs of a [`Fake`].
+impl fmt::Display for Cmd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.program)?;
+        for arg in &self.args {
+            let arg = arg.to_string_lossy();
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                write!(f, " '{arg}'")?;
+            } else {
+                write!(f, " {arg}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The captured result of a command that ran to completion.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Output {
+    /// The exit code; `None` if the command was terminated by a signal
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl Output {
+    /// Creates the output of a successful command.
+    #[must_use]
+    pub fn success(stdout: impl Into<String>) -> Self {
+        Self {
+            code: Some(0),
+            stdout: stdout.into(),
+            stderr: String::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn is_success(&self) -> bool {
+        matches!(self.code, Some(0))
+    }
+}
+
+/// Runs external commands.
+/// Runners are shared with the threads sources may run on,
+/// see [`crate::settings::Settings::source_timeout`].
+pub trait Runner: Send + Sync {
+    /// Runs the command to completion,
+    /// capturing its output.
+    ///
+    /// # Errors
+    ///
+    /// If the command could not be run,
+    /// or did not finish within its time-out.
+    fn run(&self, cmd: &Cmd) -> Result<Output, Error>;
+
+    /// Runs the command to completion,
+    /// and returns its (trimmed) standard output.
+    ///
+    /// # Errors
+    ///
+    /// If the command could not be run,
+    /// did not finish within its time-out,
+    /// or exited with a non-zero exit code.
+    fn stdout(&self, cmd: &Cmd) -> Result<String, Error> {
+        let output = self.run(cmd)?;
+        if output.is_success() {
+            Ok(output.stdout.trim().to_owned())
+        } else {
+            Err(Error::Failed {
+                cmd: cmd.to_string(),
+                code: output.code,
+                stderr: output.stderr.trim().to_owned(),
+            })
+        }
+    }
+}
+
+/// Actually runs the commands on the system.
+pub struct System;
+
+/// Reads all of a pipe in a separate thread,
+/// so a command filling up one pipe can not block us.
+fn read_in_background<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            if let Err(err) = pipe.read_to_end(&mut buffer) {
+                log::debug!("Failed to read the output of a command: {err}");
+            }
+        }
+        String::from_utf8_lossy(&buffer).into_owned()
+    })
+}
+
+impl Runner for System {
+    fn run(&self, cmd: &Cmd) -> Result<Output, Error> {
+        log::debug!("Running `{cmd}` ...");
+        let io_err = |source| Error::Io {
+            cmd: cmd.to_string(),
+            source,
+        };
+        let mut command = Command::new(&cmd.program);
+        command
+            .args(&cmd.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(dir) = &cmd.dir {
+            command.current_dir(dir);
+        }
+        let mut child = command.spawn().map_err(|source| {
+            if source.kind() == io::ErrorKind::NotFound {
+                Error::NotFound {
+                    program: cmd.program.clone(),
+                }
+            } else {
+                io_err(source)
+            }
+        })?;
+        let stdout = read_in_background(child.stdout.take());
+        let stderr = read_in_background(child.stderr.take());
+        let start = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait().map_err(io_err)? {
+                break status;
+            }
+            if start.elapsed() >= cmd.timeout {
+                if let Err(err) = child.kill().and_then(|()| child.wait().map(|_| ())) {
+                    log::warn!("Failed to kill `{cmd}`: {err}");
+                }
+                return Err(Error::Timeout {
+                    cmd: cmd.to_string(),
+                    timeout: cmd.timeout,
+                });
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+        let output = Output {
+            code: status.code(),
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        };
+        log::trace!("`{cmd}` exited with {status} after {:?}", start.elapsed());
+        Ok(output)
+    }
+}
+
+/// Only logs the commands, without running them.
+pub struct DryRun;
+
+impl Runner for DryRun {
+    fn run(&self, cmd: &Cmd) -> Result<Output, Error> {
+        log::info!("Would run `{cmd}`");
+        Err(Error::DryRun {
+            cmd: cmd.to_string(),
+        })
+    }
+}
+
+/// Supplies canned outputs instead of running anything,
+/// for testing.
+/// Commands without an output behave as if the program was not installed.
+#[derive(Default)]
+pub struct Fake {
+    /// The outputs, by the rendered command line (see [`Cmd`]s `Display`)
+    outputs: HashMap<String, Output>,
+    /// The rendered command lines of all the commands run so far
+    runs: Mutex<Vec<String>>,
+}
+
+impl Fake {
+    /// Sets the output of the command with the given command line,
+    /// e.g. "git ls-remote --tags origin".
+    #[must_use]
+    pub fn with(mut self, cmd_line: impl Into<String>, output: Output) -> Self {
+        self.outputs.insert(cmd_line.into(), output);
+        self
+    }
+
+    /// The rendered command lines of all the commands run so far.
+    #[must_use]
+    pub fn runs(&self) -> Vec<String> {
+        self.runs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl Runner for Fake {
+    fn run(&self, cmd: &Cmd) -> Result<Output, Error> {
+        let cmd_line = cmd.to_string();
+        log::debug!("Faking `{cmd_line}` ...");
+        self.runs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(cmd_line.clone());
+        self.outputs
+            .get(&cmd_line)
+            .cloned()
+            .ok_or_else(|| Error::NotFound {
+                program: cmd.program.clone(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let cmd = Cmd::new("git")
+            .args(["log", "--format=%H %s"])
+            .arg("")
+            .dir("/tmp");
+        assert_eq!(cmd.to_string(), "git log '--format=%H %s' ''");
+    }
+
+    #[test]
+    fn test_fake() {
+        let fake = Fake::default().with(
+            "git ls-remote --tags origin",
+            Output::success("abc\trefs/tags/v1.0.0\n"),
+        );
+        let cmd = Cmd::new("git").args(["ls-remote", "--tags", "origin"]);
+        assert_eq!(fake.stdout(&cmd).unwrap(), "abc\trefs/tags/v1.0.0");
+        assert!(matches!(
+            fake.run(&Cmd::new("hg")),
+            Err(Error::NotFound { .. })
+        ));
+        assert_eq!(fake.runs(), vec!["git ls-remote --tags origin", "hg"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_system() {
+        let echo = Cmd::new("sh").args(["-c", "echo out; echo err >&2; exit 3"]);
+        let output = System.run(&echo).unwrap();
+        assert_eq!(
+            output,
+            Output {
+                code: Some(3),
+                stdout: "out\n".to_owned(),
+                stderr: "err\n".to_owned(),
+            }
+        );
+        assert!(matches!(
+            System.stdout(&echo),
+            Err(Error::Failed { code: Some(3), .. })
+        ));
+
+        let sleep = Cmd::new("sleep")
+            .arg("10")
+            .timeout(Duration::from_millis(100));
+        assert!(matches!(System.run(&sleep), Err(Error::Timeout { .. })));
+
+        assert!(matches!(
+            System.run(&Cmd::new("projvar-no-such-program")),
+            Err(Error::NotFound { .. })
+        ));
+    }
+}
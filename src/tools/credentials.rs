@@ -0,0 +1,342 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Access tokens for the hosting APIs,
+//! associated with the host they are meant for.
+//!
+//! Tokens are looked up, in this order:
+//!
+//! 1. in the environment variables set by the CI
+//!    (`GITHUB_TOKEN`, `GITLAB_TOKEN` and `CI_JOB_TOKEN`),
+//! 2. in a credentials file (see [`Settings::credentials_file`]),
+//! 3. in the OS keyring, if the "keyring" feature is enabled.
+//!
+//! A [`Token`] never reveals its secret when formatted,
+//! so it can not accidentally end up in the logs.
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use thiserror::Error;
+use url::Url;
+
+use crate::settings::Settings;
+
+/// How secrets are shown in the logs.
+pub const REDACTED: &str = "***";
+
+/// The name of the credentials file we look for in the users config directory,
+/// if none is specified explicitly.
+pub const DEFAULT_FILE_NAME: &str = "credentials.toml";
+
+/// The service name under which tokens are stored in the OS keyring,
+/// with the host as the user name.
+#[cfg(feature = "keyring")]
+pub const KEYRING_SERVICE: &str = env!("CARGO_PKG_NAME");
+
+/// The environment variables that may contain an access token,
+/// together with the variable containing the host (or server URL)
+/// the token is meant for,
+/// and the host to assume if that variable is not set.
+/// Earlier entries take precedence over later ones for the same host.
+const ENV_TOKENS: &[(&str, &str, &str)] = &[
+    ("GITHUB_TOKEN", "GITHUB_SERVER_URL", "github.com"),
+    ("GITLAB_TOKEN", "CI_SERVER_HOST", "gitlab.com"),
+    ("CI_JOB_TOKEN", "CI_SERVER_HOST", "gitlab.com"),
+];
+
+/// This enumerates all possible errors returned by this module.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The credentials file could not be read.
+    #[error("Failed to read the credentials file '{path}': {source}")]
+    Read { path: PathBuf, source: io::Error },
+
+    /// The credentials file is not valid TOML,
+    /// or does not conform to our structure.
+    #[error("Failed to parse the credentials file '{path}': {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    /// Accessing the OS keyring failed.
+    #[cfg(feature = "keyring")]
+    #[error(transparent)]
+    Keyring(#[from] keyring::Error),
+}
+
+/// A secret access token.
+/// It is shown as [`REDACTED`] when formatted,
+/// use [`Self::secret`] to get the actual value.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Token(String);
+
+impl Token {
+    #[must_use]
+    pub const fn new(secret: String) -> Self {
+        Self(secret)
+    }
+
+    /// Returns the actual token,
+    /// which must never be logged.
+    #[must_use]
+    pub fn secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+/// Whether an (environment) variable contains a secret,
+/// and thus its value must not be logged.
+#[must_use]
+pub fn is_secret_var(key: &str) -> bool {
+    ENV_TOKENS.iter().any(|(token_var, _, _)| *token_var == key)
+}
+
+/// Returns the value for logging,
+/// which is [`REDACTED`] for secret variables,
+/// see [`is_secret_var`].
+#[must_use]
+pub fn redact_var<'a>(key: &str, value: &'a str) -> &'a str {
+    if is_secret_var(key) {
+        REDACTED
+    } else {
+        value
+    }
+}
+
+/// The structure of the credentials file, for example:
+///
+/// ```toml
+/// [hosts."github.com"]
+/// token = "ghp_..."
+///
+/// [hosts."gitlab.company.com"]
+/// token = "glpat-..."
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct File {
+    hosts: HashMap<String, HostEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HostEntry {
+    token: Token,
+}
+
+impl<'de> Deserialize<'de> for Token {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(Self)
+    }
+}
+
+/// Extracts the host from a value that is either a plain host name
+/// (e.g. `gitlab.com`) or a URL (e.g. `https://github.com/`).
+fn host_of(host_or_url: &str) -> String {
+    Url::parse(host_or_url)
+        .ok()
+        .and_then(|url| url.host_str().map(ToOwned::to_owned))
+        .unwrap_or_else(|| host_or_url.trim_end_matches('/').to_owned())
+        .to_lowercase()
+}
+
+/// Returns the default location of the credentials file,
+/// which is `$XDG_CONFIG_HOME/projvar/credentials.toml`,
+/// falling back to `$HOME/.config/projvar/credentials.toml`.
+fn default_file() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|config_dir| {
+            config_dir
+                .join(env!("CARGO_PKG_NAME"))
+                .join(DEFAULT_FILE_NAME)
+        })
+}
+
+/// Warns if the credentials file is readable by others than its owner.
+#[cfg(unix)]
+fn check_permissions(path: &Path, metadata: &fs::Metadata) {
+    use std::os::unix::fs::PermissionsExt;
+
+    if metadata.permissions().mode() & 0o077 != 0 {
+        log::warn!(
+            "The credentials file '{}' is accessible by other users; \
+            consider restricting it with `chmod 600`.",
+            path.display()
+        );
+    }
+}
+
+#[cfg(not(unix))]
+const fn check_permissions(_path: &Path, _metadata: &fs::Metadata) {}
+
+fn read_file(path: &Path) -> Result<File, Error> {
+    let to_read_err = |source| Error::Read {
+        path: path.to_owned(),
+        source,
+    };
+    check_permissions(path, &fs::metadata(path).map_err(to_read_err)?);
+    let content = fs::read_to_string(path).map_err(to_read_err)?;
+    toml::from_str(&content).map_err(|source| Error::Parse {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+fn from_env(vars: &HashMap<String, String>, host: &str) -> Option<Token> {
+    ENV_TOKENS
+        .iter()
+        .find_map(|(token_var, host_var, default_host)| {
+            let token = vars.get(*token_var).filter(|token| !token.is_empty())?;
+            let token_host = vars
+                .get(*host_var)
+                .map_or_else(|| (*default_host).to_owned(), |value| host_of(value));
+            (token_host == host).then(|| {
+                log::debug!("Using the access token from {token_var} for host '{host}'.");
+                Token::new(token.clone())
+            })
+        })
+}
+
+fn from_file(settings: &Settings, host: &str) -> Result<Option<Token>, Error> {
+    let path = match &settings.credentials_file {
+        Some(path) => path.clone(),
+        None => match default_file() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(None),
+        },
+    };
+    let mut file = read_file(&path)?;
+    Ok(file.hosts.remove(host).map(|entry| {
+        log::debug!(
+            "Using the access token from '{}' for host '{host}'.",
+            path.display()
+        );
+        entry.token
+    }))
+}
+
+#[cfg(feature = "keyring")]
+fn from_keyring(host: &str) -> Result<Option<Token>, Error> {
+    match keyring::Entry::new(KEYRING_SERVICE, host)?.get_password() {
+        Ok(secret) => {
+            log::debug!("Using the access token from the OS keyring for host '{host}'.");
+            Ok(Some(Token::new(secret)))
+        }
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+#[allow(clippy::unnecessary_wraps)]
+const fn from_keyring(_host: &str) -> Result<Option<Token>, Error> {
+    Ok(None)
+}
+
+/// Looks up the access token for a host (e.g. "github.com" or "gitlab.company.com"),
+/// or a URL on that host.
+/// See the module documentation for where we look.
+///
+/// # Errors
+///
+/// If the credentials file could not be read or parsed,
+/// or accessing the OS keyring failed.
+pub fn token(
+    settings: &Settings,
+    vars: &HashMap<String, String>,
+    host_or_url: &str,
+) -> Result<Option<Token>, Error> {
+    let host = host_of(host_or_url);
+    if let Some(token) = from_env(vars, &host) {
+        return Ok(Some(token));
+    }
+    if let Some(token) = from_file(settings, &host)? {
+        return Ok(Some(token));
+    }
+    from_keyring(&host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_is_redacted() {
+        let token = Token::new("ghp_secret".to_owned());
+        assert_eq!(format!("{token}"), REDACTED);
+        assert_eq!(format!("{token:?}"), REDACTED);
+        assert_eq!(token.secret(), "ghp_secret");
+    }
+
+    #[test]
+    fn test_from_env() {
+        let mut vars = HashMap::new();
+        vars.insert("GITHUB_TOKEN".to_owned(), "gh".to_owned());
+        vars.insert("CI_JOB_TOKEN".to_owned(), "job".to_owned());
+        vars.insert("CI_SERVER_HOST".to_owned(), "gitlab.company.com".to_owned());
+        assert_eq!(
+            from_env(&vars, "github.com"),
+            Some(Token::new("gh".to_owned()))
+        );
+        assert_eq!(from_env(&vars, "gitlab.com"), None);
+        assert_eq!(
+            from_env(&vars, "gitlab.company.com"),
+            Some(Token::new("job".to_owned()))
+        );
+        vars.insert("GITLAB_TOKEN".to_owned(), "gl".to_owned());
+        assert_eq!(
+            from_env(&vars, "gitlab.company.com"),
+            Some(Token::new("gl".to_owned()))
+        );
+        vars.insert(
+            "GITHUB_SERVER_URL".to_owned(),
+            "https://github.company.com/".to_owned(),
+        );
+        assert_eq!(from_env(&vars, "github.com"), None);
+        assert_eq!(
+            from_env(&vars, "github.company.com"),
+            Some(Token::new("gh".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_parse_file() {
+        let file: File = toml::from_str(
+            r#"
+[hosts."github.com"]
+token = "ghp_secret"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            file.hosts.get("github.com").map(|entry| &entry.token),
+            Some(&Token::new("ghp_secret".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,38 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::Path;
+
+use url::Url;
+
+use crate::environment::Environment;
+use crate::tools::git_hosting_provs::PublicSite;
+
+use super::Res;
+
+/// [Allura](https://allura.apache.org), OSS,
+/// e.g. on <https://sourceforge.net>.
+pub struct Forge;
+
+impl super::Forge for Forge {
+    fn pages_site(&self) -> PublicSite {
+        PublicSite::SourceForgeNet
+    }
+
+    /// The pages are hosted under one sub-domain per project,
+    /// e.g. `https://sourceforge.net/projects/xampp/`
+    /// -> `https://xampp.sourceforge.io`.
+    fn build_hosting_url(
+        &self,
+        _environment: &Environment,
+        web_url: Url,
+        public_site: PublicSite,
+    ) -> Res {
+        Ok(public_site.pages_suffix().and_then(|pages_domain| {
+            Path::new(web_url.path())
+                .file_name()
+                .map(|proj_name| format!("https://{}.{pages_domain}", proj_name.to_string_lossy()))
+        }))
+    }
+}
@@ -0,0 +1,118 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use url::Url;
+
+use crate::tools::git::TransferProtocol;
+
+use super::Res;
+
+lazy_static! {
+    static ref R_WEB_PATH: Regex = Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/?$").unwrap();
+    static ref R_CLONE_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)(\.git)?$").unwrap();
+    static ref R_RAW_PREFIX_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/raw$").unwrap();
+    static ref R_FILE_PREFIX_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/src$").unwrap();
+    static ref R_DIR_PREFIX_PATH: Regex = (*R_FILE_PREFIX_PATH).clone();
+    static ref R_COMMIT_PREFIX_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/commits$").unwrap();
+    static ref R_ISSUES_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/issues$").unwrap();
+    static ref R_RELEASES_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/downloads$").unwrap();
+    static ref R_CI_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/(addon/)?pipelines(/home)?$").unwrap();
+}
+
+/// [BitBucket](https://bitbucket.org), proprietary software.
+pub struct Forge;
+
+impl super::Forge for Forge {
+    fn issues_url(&self, web_url: Url) -> Option<String> {
+        Some(super::sub_page_url(web_url, "issues"))
+    }
+
+    fn releases_url(&self, web_url: Url) -> Option<String> {
+        Some(super::sub_page_url(web_url, "downloads"))
+    }
+
+    fn ci_url(&self, web_url: Url) -> Option<String> {
+        Some(super::sub_page_url(web_url, "pipelines"))
+    }
+
+    fn raw_prefix_url(&self, web_url: Url) -> Res {
+        Ok(Some(super::path_suffix_url(web_url, "raw")))
+    }
+
+    fn versioned_file_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "src"))
+    }
+
+    fn versioned_dir_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "src"))
+    }
+
+    fn commit_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "commits"))
+    }
+
+    // NOTE BitBucket has no pages hosting,
+    //      it only supports one "page" repo per user, not per repo.
+
+    fn has_slug(&self) -> bool {
+        true
+    }
+
+    fn def_ssh_user(&self) -> &'static str {
+        "git@"
+    }
+
+    fn http_clone_url(&self, web_url: Url, _protocol: TransferProtocol) -> Option<String> {
+        Some(super::dot_git_clone_url(web_url))
+    }
+
+    fn web_url_from_clone_url(&self, https_clone_url: Url) -> Res {
+        super::strip_clone_url(https_clone_url)
+    }
+
+    fn web_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_WEB_PATH)
+    }
+
+    fn clone_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_CLONE_PATH)
+    }
+
+    fn raw_prefix_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_RAW_PREFIX_PATH)
+    }
+
+    fn versioned_file_prefix_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_FILE_PREFIX_PATH)
+    }
+
+    fn versioned_dir_prefix_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_DIR_PREFIX_PATH)
+    }
+
+    fn commit_prefix_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_COMMIT_PREFIX_PATH)
+    }
+
+    fn issues_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_ISSUES_PATH)
+    }
+
+    fn releases_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_RELEASES_PATH)
+    }
+
+    fn ci_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_CI_PATH)
+    }
+}
@@ -0,0 +1,50 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use url::Url;
+
+use crate::constants;
+use crate::tools::git::TransferProtocol;
+
+use super::Res;
+
+/// [Gerrit](https://www.gerritcodereview.com), OSS, with `Gitiles` as web UI,
+/// e.g. on <https://googlesource.com>.
+pub struct Forge;
+
+impl super::Forge for Forge {
+    fn versioned_file_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "+"))
+    }
+
+    fn versioned_dir_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "+"))
+    }
+
+    fn commit_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "+"))
+    }
+
+    fn has_slug(&self) -> bool {
+        true
+    }
+
+    fn http_clone_url(&self, web_url: Url, _protocol: TransferProtocol) -> Option<String> {
+        Some(web_url.to_string())
+    }
+
+    fn web_url_from_clone_url(&self, https_clone_url: Url) -> Res {
+        super::strip_clone_url(https_clone_url)
+    }
+
+    fn clone_path<'p>(&self, path_and_rest: &'p str) -> &'p str {
+        // NOTE Gerrit uses this path prefix for authenticated HTTP access
+        path_and_rest.strip_prefix("a/").unwrap_or(path_and_rest)
+    }
+
+    fn default_ssh_port(&self) -> Option<u16> {
+        // Gerrit runs its own SSH daemon, on a non-standard port
+        Some(constants::GERRIT_SSH_PORT)
+    }
+}
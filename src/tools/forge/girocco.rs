@@ -0,0 +1,28 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use url::Url;
+
+use crate::tools::git::TransferProtocol;
+
+use super::Res;
+
+/// [Girocco](https://repo.or.cz/girocco.git), OSS,
+/// e.g. on <https://repo.or.cz>.
+pub struct Forge;
+
+impl super::Forge for Forge {
+    fn supports_clone_url(&self, _protocol: TransferProtocol) -> bool {
+        true
+    }
+
+    fn http_clone_url(&self, web_url: Url, _protocol: TransferProtocol) -> Option<String> {
+        Some(web_url.to_string())
+    }
+
+    fn web_url_from_clone_url(&self, https_clone_url: Url) -> Res {
+        // Web-hosting and HTTP clone URL are exactly identical
+        Ok(Some(https_clone_url.to_string()))
+    }
+}
@@ -0,0 +1,27 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use url::Url;
+
+use crate::tools::git_hosting_provs::PublicSite;
+
+use super::Res;
+
+/// [Gitea](https://gitea.io), OSS,
+/// both on <https://codeberg.org> and self-hosted.
+pub struct Forge;
+
+impl super::Forge for Forge {
+    fn pages_site(&self) -> PublicSite {
+        PublicSite::CodeBergOrg
+    }
+
+    fn has_slug(&self) -> bool {
+        true
+    }
+
+    fn web_url_from_clone_url(&self, https_clone_url: Url) -> Res {
+        super::strip_clone_url(https_clone_url)
+    }
+}
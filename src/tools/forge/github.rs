@@ -0,0 +1,137 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use url::Url;
+
+use crate::constants;
+use crate::tools::git::TransferProtocol;
+use crate::tools::git_hosting_provs::PublicSite;
+use crate::value_conversions::Error;
+use crate::var::Key;
+
+use super::Res;
+
+lazy_static! {
+    static ref R_WEB_PATH: Regex = Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/?$").unwrap();
+    static ref R_CLONE_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)(\.git)?$").unwrap();
+    static ref R_RAW_PREFIX_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)$").unwrap();
+    static ref R_FILE_PREFIX_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/blob$").unwrap();
+    static ref R_DIR_PREFIX_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/tree$").unwrap();
+    static ref R_COMMIT_PREFIX_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/commit$").unwrap();
+    static ref R_ISSUES_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/issues$").unwrap();
+    static ref R_RELEASES_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/releases$").unwrap();
+    static ref R_CI_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/actions$").unwrap();
+}
+
+/// [GitHub](https://github.com), proprietary software.
+pub struct Forge;
+
+impl super::Forge for Forge {
+    fn issues_url(&self, web_url: Url) -> Option<String> {
+        Some(super::sub_page_url(web_url, "issues"))
+    }
+
+    fn releases_url(&self, web_url: Url) -> Option<String> {
+        Some(super::sub_page_url(web_url, "releases"))
+    }
+
+    fn ci_url(&self, web_url: Url) -> Option<String> {
+        Some(super::sub_page_url(web_url, "actions"))
+    }
+
+    // e.g. [https://raw.githubusercontent.com/hoijui/nim-ci]/master/.github/workflows/docker.yml
+    fn raw_prefix_url(&self, mut web_url: Url) -> Res {
+        let input = web_url.to_string();
+        web_url
+            .set_host(Some(constants::D_GIT_HUB_COM_RAW))
+            .map_err(|err| Error::BadInputValueErr {
+                key: Key::RepoRawVersionedPrefixUrl,
+                msg: format!(
+                    "Failed to parse '{}' host for URL",
+                    constants::D_GIT_HUB_COM_RAW
+                ),
+                input,
+                source: Box::new(err),
+            })?;
+        Ok(Some(web_url.to_string()))
+    }
+
+    fn versioned_file_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "blob"))
+    }
+
+    fn versioned_dir_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "tree"))
+    }
+
+    fn commit_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "commit"))
+    }
+
+    fn pages_site(&self) -> PublicSite {
+        PublicSite::GitHubCom
+    }
+
+    fn has_slug(&self) -> bool {
+        true
+    }
+
+    fn def_ssh_user(&self) -> &'static str {
+        "git@"
+    }
+
+    fn http_clone_url(&self, web_url: Url, _protocol: TransferProtocol) -> Option<String> {
+        Some(super::dot_git_clone_url(web_url))
+    }
+
+    fn web_url_from_clone_url(&self, https_clone_url: Url) -> Res {
+        super::strip_clone_url(https_clone_url)
+    }
+
+    fn web_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_WEB_PATH)
+    }
+
+    fn clone_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_CLONE_PATH)
+    }
+
+    fn raw_prefix_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_RAW_PREFIX_PATH)
+    }
+
+    fn versioned_file_prefix_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_FILE_PREFIX_PATH)
+    }
+
+    fn versioned_dir_prefix_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_DIR_PREFIX_PATH)
+    }
+
+    fn commit_prefix_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_COMMIT_PREFIX_PATH)
+    }
+
+    fn issues_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_ISSUES_PATH)
+    }
+
+    fn releases_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_RELEASES_PATH)
+    }
+
+    fn ci_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_CI_PATH)
+    }
+}
@@ -0,0 +1,166 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use url::Url;
+
+use crate::constants;
+use crate::environment::Environment;
+use crate::tools::git::TransferProtocol;
+use crate::tools::git_hosting_provs::PublicSite;
+
+use super::Res;
+
+lazy_static! {
+    static ref R_WEB_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)/?$").unwrap();
+    static ref R_CLONE_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)(\.git)?$").unwrap();
+    static ref R_RAW_PREFIX_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)/(-/)?raw$").unwrap();
+    static ref R_FILE_PREFIX_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)/(-/)?blob$")
+            .unwrap();
+    static ref R_DIR_PREFIX_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)/(-/)?tree$")
+            .unwrap();
+    static ref R_COMMIT_PREFIX_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)/(-/)?commit$")
+            .unwrap();
+    static ref R_ISSUES_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)/(-/)?issues$")
+            .unwrap();
+    static ref R_RELEASES_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)/(-/)?releases$")
+            .unwrap();
+    static ref R_CI_PATH: Regex =
+        Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)/(-/)?pipelines$")
+            .unwrap();
+}
+
+/// [GitLab](https://about.gitlab.com), OSS,
+/// both on <https://gitlab.com> and self-hosted.
+pub struct Forge;
+
+impl super::Forge for Forge {
+    // NOTE The "-/" is optional for most of these,
+    //      but it is what GitLab itself uses.
+    fn issues_url(&self, web_url: Url) -> Option<String> {
+        Some(super::sub_page_url(web_url, "-/issues"))
+    }
+
+    fn releases_url(&self, web_url: Url) -> Option<String> {
+        Some(super::sub_page_url(web_url, "-/releases"))
+    }
+
+    fn ci_url(&self, web_url: Url) -> Option<String> {
+        Some(super::sub_page_url(web_url, "-/pipelines"))
+    }
+
+    fn raw_prefix_url(&self, web_url: Url) -> Res {
+        Ok(Some(super::path_suffix_url(web_url, "-/raw")))
+    }
+
+    fn versioned_file_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "-/blob"))
+    }
+
+    fn versioned_dir_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "-/tree"))
+    }
+
+    fn commit_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "-/commit"))
+    }
+
+    fn pages_site(&self) -> PublicSite {
+        PublicSite::GitLabCom
+    }
+
+    fn build_hosting_url(
+        &self,
+        environment: &Environment,
+        mut web_url: Url,
+        public_site: PublicSite,
+    ) -> Res {
+        if let Some(pages_domain) = public_site.pages_suffix() {
+            return super::pages_url(web_url, public_site, pages_domain);
+        }
+        // A self-hosted GitLab instance with pages enabled
+        match environment
+            .vars
+            .get(constants::VAR_CI_PAGES_DOMAIN)
+            .filter(|pages_domain| !pages_domain.is_empty())
+        {
+            Some(pages_domain) => {
+                // NOTE The pages are not served under the base path
+                //      of the instance (if any).
+                let project_path = web_url.host_str().map(|host| {
+                    environment
+                        .settings
+                        .strip_hosting_base_path(host, web_url.path())
+                        .to_owned()
+                });
+                if let Some(project_path) = project_path {
+                    web_url.set_path(&project_path);
+                }
+                super::pages_url(web_url, public_site, pages_domain)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn has_slug(&self) -> bool {
+        true
+    }
+
+    fn def_ssh_user(&self) -> &'static str {
+        "git@"
+    }
+
+    fn http_clone_url(&self, web_url: Url, _protocol: TransferProtocol) -> Option<String> {
+        Some(super::dot_git_clone_url(web_url))
+    }
+
+    fn web_url_from_clone_url(&self, https_clone_url: Url) -> Res {
+        super::strip_clone_url(https_clone_url)
+    }
+
+    fn web_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_WEB_PATH)
+    }
+
+    fn clone_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_CLONE_PATH)
+    }
+
+    fn raw_prefix_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_RAW_PREFIX_PATH)
+    }
+
+    fn versioned_file_prefix_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_FILE_PREFIX_PATH)
+    }
+
+    fn versioned_dir_prefix_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_DIR_PREFIX_PATH)
+    }
+
+    fn commit_prefix_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_COMMIT_PREFIX_PATH)
+    }
+
+    fn issues_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_ISSUES_PATH)
+    }
+
+    fn releases_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_RELEASES_PATH)
+    }
+
+    fn ci_path_regex(&self) -> Option<&'static Regex> {
+        Some(&R_CI_PATH)
+    }
+}
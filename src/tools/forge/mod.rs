@@ -0,0 +1,334 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! The peculiarities of the different git hosting software (forges),
+//! like how their issues page URL or their SSH clone URL looks like.
+//!
+//! Each forge lives in its own module,
+//! so supporting a new one is (mostly) a matter of adding a single file.
+
+mod allura;
+mod bitbucket;
+mod gerrit;
+mod girocco;
+mod gitea;
+mod github;
+mod gitlab;
+pub mod radicle;
+mod rocketgit;
+mod sourcehut;
+mod unknown;
+
+use std::borrow::Cow;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use url::Url;
+
+use crate::environment::Environment;
+use crate::std_error;
+use crate::tools::git::TransferProtocol;
+use crate::tools::git_hosting_provs::{HostingType, PublicSite};
+use crate::value_conversions::{split_after_first_path_element, trim_char, Error};
+use crate::var::Key;
+
+type Res = Result<Option<String>, Error>;
+
+/// The forge specific parts of constructing, converting and validating
+/// the URLs of a repo.
+///
+/// The URL constructing methods take the repo web URL
+/// (e.g. `https://github.com/hoijui/projvar`),
+/// and return `None` if the forge has no such page,
+/// or we do not (yet) know how its URL looks like.
+///
+/// The `*_path_regex` methods return the regex
+/// that the path part of the respective URL has to match
+/// (with the hosting base path removed,
+/// see [`crate::settings::Settings::strip_hosting_base_path`]),
+/// or `None` if we can not check it.
+pub trait Forge: Sync {
+    /// See [`crate::value_conversions::web_url_to_issues_url`].
+    fn issues_url(&self, _web_url: Url) -> Option<String> {
+        None
+    }
+
+    /// See [`crate::value_conversions::web_url_to_releases_url`].
+    fn releases_url(&self, _web_url: Url) -> Option<String> {
+        None
+    }
+
+    /// See [`crate::value_conversions::web_url_to_ci_url`].
+    fn ci_url(&self, _web_url: Url) -> Option<String> {
+        None
+    }
+
+    /// See [`crate::value_conversions::web_url_to_raw_prefix_url`].
+    ///
+    /// # Errors
+    ///
+    /// If the URL could not be constructed.
+    fn raw_prefix_url(&self, _web_url: Url) -> Res {
+        Ok(None)
+    }
+
+    /// See [`crate::value_conversions::web_url_to_versioned_file_prefix_url`].
+    fn versioned_file_prefix_url(&self, _web_url: Url) -> Option<String> {
+        None
+    }
+
+    /// See [`crate::value_conversions::web_url_to_versioned_dir_prefix_url`].
+    fn versioned_dir_prefix_url(&self, _web_url: Url) -> Option<String> {
+        None
+    }
+
+    /// See [`crate::value_conversions::web_url_to_commit_prefix_url`].
+    fn commit_prefix_url(&self, _web_url: Url) -> Option<String> {
+        None
+    }
+
+    /// The public site that hosts the "pages" of the repos on this forge,
+    /// if any.
+    fn pages_site(&self) -> PublicSite {
+        PublicSite::Unknown
+    }
+
+    /// See [`crate::value_conversions::web_url_to_build_hosting_url`].
+    /// `public_site` is the site the web URL points to,
+    /// which is [`PublicSite::Unknown`] for a self-hosted instance.
+    ///
+    /// # Errors
+    ///
+    /// If the URL could not be constructed,
+    /// usually because the web URL has an unexpected format.
+    fn build_hosting_url(
+        &self,
+        _environment: &Environment,
+        web_url: Url,
+        public_site: PublicSite,
+    ) -> Res {
+        public_site.pages_suffix().map_or(Ok(None), |pages_domain| {
+            pages_url(web_url, public_site, pages_domain)
+        })
+    }
+
+    /// Whether the path of a web URL is the project slug,
+    /// see [`crate::value_conversions::web_url_to_slug`].
+    fn has_slug(&self) -> bool {
+        false
+    }
+
+    /// Whether repos may be cloned with the given protocol.
+    fn supports_clone_url(&self, protocol: TransferProtocol) -> bool {
+        match protocol {
+            TransferProtocol::Https | TransferProtocol::Ssh => true,
+            TransferProtocol::Git => false,
+        }
+    }
+
+    /// The user (including the trailing '@') to use in SSH clone URLs.
+    fn def_ssh_user(&self) -> &'static str {
+        ""
+    }
+
+    /// Constructs a clone URL from the web URL,
+    /// which is later converted to `protocol`,
+    /// see [`crate::value_conversions::web_url_to_clone_url`].
+    fn http_clone_url(&self, _web_url: Url, _protocol: TransferProtocol) -> Option<String> {
+        None
+    }
+
+    /// Constructs the web URL from an HTTPS clone URL,
+    /// see [`crate::value_conversions::clone_url_to_web_url`].
+    ///
+    /// # Errors
+    ///
+    /// If the URL could not be constructed.
+    fn web_url_from_clone_url(&self, _https_clone_url: Url) -> Res {
+        Ok(None)
+    }
+
+    /// The host to use in a clone URL with the given protocol.
+    fn clone_host<'h>(&self, host: &'h str, _protocol: TransferProtocol) -> Cow<'h, str> {
+        Cow::Borrowed(host)
+    }
+
+    /// The protocol independent path (and rest) of a clone URL.
+    fn clone_path<'p>(&self, path_and_rest: &'p str) -> &'p str {
+        path_and_rest
+    }
+
+    /// The separator between host (and port) and path in SSH clone URLs.
+    fn ssh_host_path_separator(&self) -> char {
+        // This is the (URL spec) compatible way
+        '/'
+    }
+
+    /// The port to use in SSH clone URLs, if none is given.
+    fn default_ssh_port(&self) -> Option<u16> {
+        None
+    }
+
+    /// See [`Forge`]; for the repo web URL.
+    fn web_path_regex(&self) -> Option<&'static Regex> {
+        None
+    }
+
+    /// See [`Forge`]; for the HTTPS, SSH and git clone URLs.
+    fn clone_path_regex(&self) -> Option<&'static Regex> {
+        None
+    }
+
+    /// See [`Forge`]; for the raw versioned prefix URL.
+    fn raw_prefix_path_regex(&self) -> Option<&'static Regex> {
+        None
+    }
+
+    /// See [`Forge`]; for the versioned file prefix URL.
+    fn versioned_file_prefix_path_regex(&self) -> Option<&'static Regex> {
+        None
+    }
+
+    /// See [`Forge`]; for the versioned directory prefix URL.
+    fn versioned_dir_prefix_path_regex(&self) -> Option<&'static Regex> {
+        None
+    }
+
+    /// See [`Forge`]; for the commit prefix URL.
+    fn commit_prefix_path_regex(&self) -> Option<&'static Regex> {
+        None
+    }
+
+    /// See [`Forge`]; for the issues URL.
+    fn issues_path_regex(&self) -> Option<&'static Regex> {
+        None
+    }
+
+    /// See [`Forge`]; for the releases URL.
+    fn releases_path_regex(&self) -> Option<&'static Regex> {
+        None
+    }
+
+    /// See [`Forge`]; for the CI pipelines URL.
+    fn ci_path_regex(&self) -> Option<&'static Regex> {
+        None
+    }
+}
+
+/// Returns the forge implementation for a hosting type.
+#[must_use]
+pub fn get(hosting_type: HostingType) -> &'static dyn Forge {
+    match hosting_type {
+        HostingType::GitHub => &github::Forge,
+        HostingType::GitLab => &gitlab::Forge,
+        HostingType::BitBucket => &bitbucket::Forge,
+        HostingType::SourceHut => &sourcehut::Forge,
+        HostingType::Gitea => &gitea::Forge,
+        HostingType::Girocco => &girocco::Forge,
+        HostingType::RocketGit => &rocketgit::Forge,
+        HostingType::Allura => &allura::Forge,
+        HostingType::Radicle => &radicle::Forge,
+        HostingType::Gerrit => &gerrit::Forge,
+        HostingType::Unknown => &unknown::Forge,
+    }
+}
+
+/// Appends a sub-page to the path of a repo web URL,
+/// e.g. `https://github.com/hoijui/projvar/` + "issues"
+/// -> `https://github.com/hoijui/projvar/issues`.
+fn sub_page_url(mut web_url: Url, sub_page: &str) -> String {
+    web_url.set_path(&format!("/{}/{sub_page}", trim_char(web_url.path(), '/')));
+    web_url.to_string()
+}
+
+/// Appends a suffix to the path of a repo web URL,
+/// e.g. `https://github.com/hoijui/projvar` + "blob"
+/// -> `https://github.com/hoijui/projvar/blob`.
+fn path_suffix_url(mut web_url: Url, suffix: &str) -> String {
+    web_url.set_path(&format!("{}/{suffix}", web_url.path()));
+    web_url.to_string()
+}
+
+/// Constructs an HTTPS clone URL from a web URL,
+/// by appending ".git" to it.
+fn dot_git_clone_url(mut web_url: Url) -> String {
+    lazy_static! {
+        static ref R_SLASH_AT_END: Regex = Regex::new(r"^(.+?)/?$").unwrap();
+    }
+    let path = R_SLASH_AT_END
+        .replace(web_url.path(), "$1.git")
+        .into_owned();
+    web_url.set_path(&path);
+    web_url.to_string()
+}
+
+/// Constructs a web URL from an HTTPS clone URL,
+/// by removing the ".git" suffix and the user (if any).
+fn strip_clone_url(mut https_clone_url: Url) -> Res {
+    let old_path = https_clone_url.path().to_owned();
+    https_clone_url.set_path(old_path.strip_suffix(".git").unwrap_or(&old_path));
+    https_clone_url
+        .set_username("")
+        .map_err(|_err| Error::BadInputValue {
+            key: Key::RepoWebUrl,
+            msg: "Failed to set username".to_owned(),
+            input: https_clone_url.to_string(),
+        })?;
+    Ok(Some(https_clone_url.to_string()))
+}
+
+/// Constructs a "pages" URL, where the first path element of the web URL
+/// (the user or group) becomes a sub-domain of `pages_domain`,
+/// e.g. `https://github.com/hoijui/projvar`
+/// -> `https://hoijui.github.io/projvar`.
+fn pages_url(mut web_url: Url, public_site: PublicSite, pages_domain: &str) -> Res {
+    let old_url = web_url.to_string();
+    let old_path = web_url.path().to_owned();
+    let (site_user, site_project) =
+        split_after_first_path_element(&old_url, &old_path, public_site)?;
+    web_url
+        .set_host(Some(&format!("{site_user}.{pages_domain}")))
+        .map_err(std_error::Error::from)?;
+    web_url.set_path(site_project);
+    Ok(Some(web_url.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn test_https_clone_urls_are_always_supported() {
+        for hosting_type in HostingType::iter() {
+            assert!(hosting_type
+                .forge()
+                .supports_clone_url(TransferProtocol::Https));
+        }
+    }
+
+    #[test]
+    fn test_sub_page_url() {
+        let web_url = Url::parse("https://github.com/hoijui/projvar/").unwrap();
+        assert_eq!(
+            sub_page_url(web_url, "issues"),
+            "https://github.com/hoijui/projvar/issues"
+        );
+        let web_url = Url::parse("https://github.com/hoijui/projvar").unwrap();
+        assert_eq!(
+            path_suffix_url(web_url, "blob"),
+            "https://github.com/hoijui/projvar/blob"
+        );
+    }
+
+    #[test]
+    fn test_pages_url() {
+        let web_url = Url::parse("https://gitlab.com/hoijui/sub-group/projvar").unwrap();
+        assert_eq!(
+            pages_url(web_url, PublicSite::GitLabCom, "gitlab.io").unwrap(),
+            Some("https://hoijui.gitlab.io/sub-group/projvar".to_owned())
+        );
+    }
+}
@@ -0,0 +1,111 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use url::Url;
+
+use crate::constants;
+use crate::tools::git::TransferProtocol;
+use crate::value_conversions::trim_char;
+
+use super::Res;
+
+/// [Radicle](https://radicle.xyz), OSS, peer-to-peer;
+/// repos are accessed through (public) seed nodes.
+pub struct Forge;
+
+/// Extracts the repository ID (without the `rad:` prefix)
+/// from a native Radicle clone URL,
+/// e.g. `rad://z3gqcJUoA1n9HaHKufZs5FCSGazv5` or `rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5`.
+#[must_use]
+pub fn native_rid(any_clone_url: &str) -> Option<&str> {
+    any_clone_url
+        .strip_prefix(constants::SCHEME_RADICLE)
+        .and_then(|rest| rest.strip_prefix("://").or_else(|| rest.strip_prefix(':')))
+        .map(|rid| trim_char(rid, '/'))
+        .filter(|rid| !rid.is_empty() && !rid.contains('/'))
+}
+
+/// Extracts the seed node (host, possibly with port)
+/// and the repository ID (without the `rad:` prefix)
+/// from either a Radicle web URL, e.g.
+/// `https://app.radicle.xyz/nodes/seed.radicle.garden/rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5`,
+/// or a Radicle HTTPS clone URL, e.g.
+/// `https://seed.radicle.garden/z3gqcJUoA1n9HaHKufZs5FCSGazv5.git`.
+fn seed_and_rid(url: &Url) -> Option<(String, String)> {
+    let segments = url
+        .path_segments()?
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    match segments.as_slice() {
+        ["nodes", seed, rid, ..] => rid
+            .strip_prefix(constants::RADICLE_RID_PREFIX)
+            .map(|rid| ((*seed).to_owned(), rid.to_owned())),
+        [rid] => {
+            let host = url.host_str()?;
+            let seed = url
+                .port()
+                .map_or_else(|| host.to_owned(), |port| format!("{host}:{port}"));
+            let rid = rid.trim_end_matches(".git");
+            let rid = rid
+                .strip_prefix(constants::RADICLE_RID_PREFIX)
+                .unwrap_or(rid);
+            Some((seed, rid.to_owned()))
+        }
+        _ => None,
+    }
+}
+
+fn web_url_of(seed: &str, rid: &str) -> String {
+    format!(
+        "https://{}/nodes/{seed}/{}{rid}",
+        constants::D_RADICLE_APP_XYZ,
+        constants::RADICLE_RID_PREFIX
+    )
+}
+
+/// The HTTPS clone URL of a repo on a seed node.
+#[must_use]
+pub fn clone_url(seed: &str, rid: &str) -> String {
+    format!("https://{seed}/{rid}.git")
+}
+
+impl super::Forge for Forge {
+    fn issues_url(&self, web_url: Url) -> Option<String> {
+        Some(super::sub_page_url(web_url, "issues"))
+    }
+
+    fn raw_prefix_url(&self, web_url: Url) -> Res {
+        Ok(seed_and_rid(&web_url).map(|(seed, rid)| {
+            format!("https://{seed}/raw/{}{rid}", constants::RADICLE_RID_PREFIX)
+        }))
+    }
+
+    fn versioned_file_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "tree"))
+    }
+
+    fn versioned_dir_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "tree"))
+    }
+
+    fn commit_prefix_url(&self, web_url: Url) -> Option<String> {
+        Some(super::path_suffix_url(web_url, "commits"))
+    }
+
+    fn supports_clone_url(&self, protocol: TransferProtocol) -> bool {
+        // NOTE Radicle uses its own protocol ("rad://...") for peer-to-peer access,
+        //      and seed nodes only serve HTTPS.
+        matches!(protocol, TransferProtocol::Https)
+    }
+
+    fn http_clone_url(&self, web_url: Url, protocol: TransferProtocol) -> Option<String> {
+        seed_and_rid(&web_url)
+            .filter(|_| matches!(protocol, TransferProtocol::Https))
+            .map(|(seed, rid)| clone_url(&seed, &rid))
+    }
+
+    fn web_url_from_clone_url(&self, https_clone_url: Url) -> Res {
+        Ok(seed_and_rid(&https_clone_url).map(|(seed, rid)| web_url_of(&seed, &rid)))
+    }
+}
@@ -0,0 +1,49 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::borrow::Cow;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use url::Url;
+
+use crate::tools::git::TransferProtocol;
+
+use super::Res;
+
+/// [RocketGit](https://rocketgit.com), OSS.
+pub struct Forge;
+
+impl super::Forge for Forge {
+    fn supports_clone_url(&self, _protocol: TransferProtocol) -> bool {
+        true
+    }
+
+    fn def_ssh_user(&self) -> &'static str {
+        "rocketgit@"
+    }
+
+    fn http_clone_url(&self, web_url: Url, _protocol: TransferProtocol) -> Option<String> {
+        Some(web_url.to_string())
+    }
+
+    fn web_url_from_clone_url(&self, https_clone_url: Url) -> Res {
+        // Web-hosting and HTTP clone URL are exactly identical
+        Ok(Some(https_clone_url.to_string()))
+    }
+
+    /// RocketGit uses a different sub-domain per protocol,
+    /// e.g. "ssh.rocketgit.com" for SSH.
+    fn clone_host<'h>(&self, host: &'h str, protocol: TransferProtocol) -> Cow<'h, str> {
+        lazy_static! {
+            static ref R_HOST_PREFIX: Regex = Regex::new(r"^(git|ssh)\.").unwrap();
+        }
+        let prefix = match protocol {
+            TransferProtocol::Git => "git.",
+            TransferProtocol::Https => "",
+            TransferProtocol::Ssh => "ssh.",
+        };
+        Cow::Owned(format!("{prefix}{}", R_HOST_PREFIX.replace(host, "")))
+    }
+}
@@ -0,0 +1,42 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use url::Url;
+
+use crate::tools::git::TransferProtocol;
+
+use super::Res;
+
+/// [SourceHut](https://sr.ht/~sircmpwn/sourcehut), OSS.
+pub struct Forge;
+
+impl super::Forge for Forge {
+    // NOTE SourceHut has pages support (<https://srht.site/>),
+    //      but only per-user, not per repo.
+    //      One could try to emulate per repo pages there,
+    //      but it would be cumbersome and is not standardized.
+
+    fn has_slug(&self) -> bool {
+        true
+    }
+
+    fn def_ssh_user(&self) -> &'static str {
+        "git@"
+    }
+
+    fn http_clone_url(&self, web_url: Url, _protocol: TransferProtocol) -> Option<String> {
+        Some(web_url.to_string())
+    }
+
+    fn web_url_from_clone_url(&self, https_clone_url: Url) -> Res {
+        // Web-hosting and HTTP clone URL are exactly identical
+        Ok(Some(https_clone_url.to_string()))
+    }
+
+    fn ssh_host_path_separator(&self) -> char {
+        // This is **not** URL spec compatible,
+        // but some/most hosters support this.
+        ':'
+    }
+}
@@ -0,0 +1,9 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+/// Any forge we do not know (yet),
+/// for which we can only do what works with all of them.
+pub struct Forge;
+
+impl super::Forge for Forge {}
@@ -3,17 +3,22 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use chrono::DateTime;
-use chrono::NaiveDateTime;
 use chrono::Utc;
 use git2::{self, Repository};
 use lazy_static::lazy_static;
 use regex::Regex;
+#[cfg(not(feature = "gix"))]
+use std::cell::OnceCell;
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::env;
+use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str;
 use thiserror::Error;
 
+use crate::value_conversions;
 use crate::var::Key;
 
 /// This enumerates all possible errors returned by this module.
@@ -34,6 +39,17 @@ impl From<&str> for Error {
     }
 }
 
+#[cfg(feature = "gix")]
+impl Error {
+    /// Wraps an error of the gitoxide backend.
+    pub(crate) fn gix(from: &impl std::fmt::Display, message: &str) -> Self {
+        Self {
+            from: git2::Error::from_str(&from.to_string()),
+            message: String::from(message),
+        }
+    }
+}
+
 /// The default date format.
 /// For formatting specifiers, see:
 /// <https://docs.rs/chrono/latest/chrono/format/strftime/index.html>
@@ -108,64 +124,236 @@ pub fn is_git_dirty_version(vers: &str) -> bool {
     R_DIRTY_VERSION.is_match(vers)
 }
 
-/// Returns true if the repo contains any tags.
-fn _has_tags(repo: &git2::Repository) -> bool {
-    let mut has_tags = false;
-    let _ = repo.tag_foreach(|_, _| {
-        has_tags = true;
-        false
-    });
-    has_tags
+/// Maps tagged commits to the (short) names of the tags pointing to them,
+/// sorted alphabetically.
+pub type TagMap = HashMap<git2::Oid, Vec<String>>;
+
+/// Creates the map of all tagged commits,
+/// with a single pass over all the tags.
+/// Annotated tags are peeled to the commit they point to,
+/// so they are treated the same as lightweight ones.
+#[cfg(not(feature = "gix"))]
+fn _tag_map(repo: &git2::Repository) -> Result<TagMap, Error> {
+    let mut tag_map = TagMap::new();
+    let references = repo.references_glob("refs/tags/*").map_err(|from| Error {
+        from,
+        message: String::from("Failed to list the tags"),
+    })?;
+    for reference in references {
+        let reference = reference.map_err(|from| Error {
+            from,
+            message: String::from("Failed to read a tag reference"),
+        })?;
+        let Some(name) = reference.shorthand() else {
+            log::warn!(
+                "Ignoring tag '{}', as its name is not valid UTF-8",
+                String::from_utf8_lossy(reference.shorthand_bytes())
+            );
+            continue;
+        };
+        match reference.peel_to_commit() {
+            Ok(commit) => tag_map
+                .entry(commit.id())
+                .or_default()
+                .push(name.to_owned()),
+            Err(err) => {
+                log::debug!("Ignoring tag '{name}', as it does not point to a commit: {err}");
+            }
+        }
+    }
+    for names in tag_map.values_mut() {
+        names.sort();
+    }
+    Ok(tag_map)
 }
 
 /// Returns the result of `git describe` with options:
-/// - "--tags"
-/// - "--dirty"
-/// - MISSING: "--always" (not possible)
+/// - `--tags`
+/// - `--dirty`
+/// - `--match <tag_pattern>`
+/// - MISSING: `--always` (not possible)
 ///   You should handle this case external to this function,
-///   by using a (shortened-)hash, if this function returns `Err`.
+///   by using a (shortened-)hash, if this function returns `None`.
 /// - MISSING: "--broken"
 ///   We might also want this,
 //    which is not possible with git2-rs,
 //    but it is really not important.
-fn _version(repo: &git2::Repository) -> Result<String, Error> {
-    repo.describe(
-        git2::DescribeOptions::new()
-            .pattern("*[0-9]*.[0-9]*.[0-9]*")
-            .describe_tags(),
-    )
-    .map_err(|from| Error {
-        from,
-        message: String::from("Failed to describe the HEAD revision version"),
-    })?
-    .format(Some(
-        git2::DescribeFormatOptions::new()
-            .always_use_long_format(false)
-            .dirty_suffix("-dirty"),
-    ))
-    .map_err(|from| Error {
-        from,
-        message: String::from("Failed to format the HEAD revision version"),
-    })
+#[cfg(not(feature = "gix"))]
+fn _version(repo: &git2::Repository, tag_pattern: &str) -> Result<Option<String>, Error> {
+    let mut describe_opts = git2::DescribeOptions::new();
+    describe_opts.pattern(tag_pattern).describe_tags();
+    let head_commit;
+    let describe_res = if repo.is_bare() {
+        // There is no working tree to describe (and to check for being dirty),
+        // so we describe the HEAD commit instead
+        match repo
+            .head()
+            .and_then(|head| head.peel(git2::ObjectType::Commit))
+        {
+            Ok(commit) => {
+                head_commit = commit;
+                head_commit.describe(&describe_opts)
+            }
+            Err(from) => Err(from),
+        }
+    } else {
+        repo.describe(&describe_opts)
+    };
+    let describe = match describe_res {
+        Ok(describe) => describe,
+        // No tag matching the pattern is reachable from HEAD
+        Err(from) if from.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(from) => {
+            return Err(Error {
+                from,
+                message: String::from("Failed to describe the HEAD revision version"),
+            })
+        }
+    };
+    describe
+        .format(Some(
+            git2::DescribeFormatOptions::new()
+                .always_use_long_format(false)
+                .dirty_suffix("-dirty"),
+        ))
+        .map(Some)
+        .map_err(|from| Error {
+            from,
+            message: String::from("Failed to format the HEAD revision version"),
+        })
 }
 
+/// A git repository.
+///
+/// This uses `libgit2` for everything,
+/// unless the "gix" feature is enabled,
+/// in which case the tag, describe and remote operations
+/// are performed with gitoxide,
+/// which is much faster on repos with many tags.
 pub struct Repo {
     repo: git2::Repository,
+    #[cfg(not(feature = "gix"))]
+    tag_map: OnceCell<TagMap>,
+    #[cfg(feature = "gix")]
+    gix: gix::Repository,
+}
+
+/// Opens the repo at `repo_root`, or the one given by `GIT_DIR`, if set.
+/// This respects the standard environment variables
+/// like `GIT_DIR` and `GIT_WORK_TREE`,
+/// and supports worktrees (where `.git` is a file pointing elsewhere).
+/// Unlike the git CLI tool, we do not search for a repo
+/// in the parent directories of `repo_root`,
+/// see [`discover`] for that.
+///
+/// NOTE `libgit2` only uses `GIT_DIR` if no path is given at all,
+///      which is what [`Repository::open_from_env`] does.
+fn open(repo_root: &Path) -> Result<Repository, git2::Error> {
+    if env::var_os("GIT_DIR").is_some() {
+        Repository::open_from_env()
+    } else {
+        Repository::open_ext(
+            repo_root,
+            git2::RepositoryOpenFlags::FROM_ENV | git2::RepositoryOpenFlags::NO_SEARCH,
+            std::iter::empty::<&std::ffi::OsStr>(),
+        )
+    }
+}
+
+/// Opens the repo at or above `dir`,
+/// just like the git CLI tool would.
+/// This respects `GIT_CEILING_DIRECTORIES`,
+/// but not `GIT_DIR`, as we are looking for a repo
+/// other than the one we run on.
+fn discover(dir: &Path) -> Result<Repository, git2::Error> {
+    let ceiling_dirs = env::var_os("GIT_CEILING_DIRECTORIES")
+        .map(|dirs| env::split_paths(&dirs).collect::<Vec<_>>())
+        .unwrap_or_default();
+    Repository::open_ext(dir, git2::RepositoryOpenFlags::empty(), ceiling_dirs)
+}
+
+/// Converts a git glob pattern (as used with "git describe --match")
+/// into an anchored regex.
+/// Supported are `*`, `?` and bracket expressions like `[0-9]` or `[!a-z]`.
+///
+/// # Errors
+///
+/// If the pattern contains an invalid bracket expression.
+pub fn glob_to_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    let mut r_str = String::with_capacity(pattern.len() + 8);
+    r_str.push('^');
+    let mut chars = pattern.chars();
+    while let Some(chr) = chars.next() {
+        match chr {
+            '*' => r_str.push_str(".*"),
+            '?' => r_str.push('.'),
+            '[' => {
+                r_str.push('[');
+                let mut first = true;
+                for chr in chars.by_ref() {
+                    match chr {
+                        ']' if !first => break,
+                        '!' | '^' if first => r_str.push('^'),
+                        '\\' | '[' | ']' | '&' | '~' => {
+                            r_str.push('\\');
+                            r_str.push(chr);
+                        }
+                        _ => r_str.push(chr),
+                    }
+                    first = false;
+                }
+                r_str.push(']');
+            }
+            _ => r_str.push_str(&regex::escape(&chr.to_string())),
+        }
+    }
+    r_str.push('$');
+    Regex::new(&r_str)
+}
+
+/// The name git gives the remote a repo was cloned from.
+const DEFAULT_REMOTE: &str = "origin";
+
+/// The section of the git config holding our settings,
+/// e.g. `git config projvar.keyPrefix OSH_`.
+pub const CONFIG_SECTION: &str = "projvar";
+
+/// Returns a snapshot of the git config that applies to the repo at `repo_root`
+/// (system, global and local level),
+/// or of only the system and global level ones,
+/// if there is no repo.
+#[must_use]
+pub fn config_snapshot(repo_root: &Path) -> Option<git2::Config> {
+    let config = match open(repo_root) {
+        Ok(repo) => repo.config(),
+        Err(_) => git2::Config::open_default(),
+    };
+    config
+        .and_then(|mut config| config.snapshot())
+        .map_err(|err| log::debug!("Failed to read the git config: {err}"))
+        .ok()
+}
+
+/// Returns the value of one of our settings from the git config,
+/// for example `"keyPrefix"` for `projvar.keyPrefix`,
+/// see [`CONFIG_SECTION`].
+#[must_use]
+pub fn config_setting(config: &git2::Config, name: &str) -> Option<String> {
+    config.get_string(&format!("{CONFIG_SECTION}.{name}")).ok()
 }
 
 impl TryFrom<Option<&str>> for Repo {
     type Error = git2::Error;
     fn try_from(repo_root: Option<&str>) -> Result<Self, Self::Error> {
-        let repo = Repository::open(repo_root.unwrap_or("."))?;
-        Ok(Self { repo })
+        Self::try_from(repo_root.map(Path::new))
     }
 }
 
 impl TryFrom<Option<&Path>> for Repo {
     type Error = git2::Error;
     fn try_from(repo_root: Option<&Path>) -> Result<Self, Self::Error> {
-        let repo = Repository::open(repo_root.unwrap_or_else(|| Path::new(".")))?;
-        Ok(Self { repo })
+        let repo = open(repo_root.unwrap_or_else(|| Path::new(".")))?;
+        Self::wrap(repo)
     }
 }
 
@@ -184,23 +372,64 @@ impl Repo {
     //     })
     // }
 
+    /// Wraps an already opened `libgit2` repo,
+    /// opening it with gitoxide as well, if the "gix" feature is enabled.
+    #[allow(clippy::unnecessary_wraps, clippy::missing_const_for_fn)]
+    fn wrap(repo: Repository) -> Result<Self, git2::Error> {
+        Ok(Self {
+            #[cfg(not(feature = "gix"))]
+            tag_map: OnceCell::new(),
+            #[cfg(feature = "gix")]
+            gix: super::git_gix::open(repo.path())?,
+            repo,
+        })
+    }
+
     #[must_use]
     pub const fn inner(&self) -> &git2::Repository {
         &self.repo
     }
 
-    /// Returns the path to the local repo.
+    /// Whether this is a bare repo,
+    /// which has no working tree,
+    /// as is common for mirrors on build servers.
+    #[must_use]
+    pub fn is_bare(&self) -> bool {
+        self.repo.is_bare()
+    }
+
+    /// Returns the git directory shared by all the worktrees of this repo,
+    /// which holds the refs, the config and the objects.
+    /// For the main worktree, this is the git directory itself;
+    /// for linked ones, it is read from their `commondir` file.
+    #[must_use]
+    pub fn common_dir(&self) -> PathBuf {
+        let git_dir = self.repo.path();
+        fs::read_to_string(git_dir.join("commondir")).map_or_else(
+            |_err| git_dir.to_path_buf(),
+            |common_dir| git_dir.join(common_dir.trim_end()),
+        )
+    }
+
+    /// Returns the path to the local repo,
+    /// which is the working directory for non-bare repos
+    /// (including worktrees),
+    /// and the git directory itself for bare repos
+    /// (e.g. "/srv/git/project.git"),
+    /// unless it is called ".git", in which case it is its parent.
     ///
     /// # Panics
     ///
     /// Should never happen
     #[must_use]
     pub fn local_path(&self) -> PathBuf {
+        if let Some(work_dir) = self.repo.workdir() {
+            return work_dir.canonicalize().unwrap(); // We want this to panic, as it should never happen
+        }
         let path = self.repo.path().canonicalize().unwrap(); // We want this to panic, as it should never happen
         match path.file_name() {
             Some(file_name) => {
-                if file_name.to_str().unwrap() == ".git" {
-                    // This panics if not valid UTF-8
+                if file_name == ".git" {
                     path.parent().unwrap().to_path_buf() // As we already know the parent is called ".git", this could never panic
                 } else {
                     // let path_str = path as &str;
@@ -216,14 +445,35 @@ impl Repo {
         }
     }
 
+    /// Returns the superproject of this repo,
+    /// if this repo is checked out as a git submodule of it.
+    #[must_use]
+    pub fn superproject(&self) -> Option<Self> {
+        let work_dir = self.repo.workdir()?.canonicalize().ok()?;
+        let parent = discover(work_dir.parent()?).ok()?;
+        let parent_work_dir = parent.workdir()?.canonicalize().ok()?;
+        let rel_path = work_dir.strip_prefix(&parent_work_dir).ok()?.to_path_buf();
+        let is_submodule = parent
+            .submodules()
+            .is_ok_and(|submodules| submodules.iter().any(|sm| sm.path() == rel_path));
+        if is_submodule {
+            Self::wrap(parent).ok()
+        } else {
+            None
+        }
+    }
+
     /// Returns the path to the local repo as string.
+    /// Parts of the path that are not valid UTF-8
+    /// are replaced with the Unicode replacement character (U+FFFD),
+    /// which gets flagged in [`crate::cleanup::conf_unicode`].
     ///
     /// # Panics
     ///
     /// Should never happen
     #[must_use]
     pub fn local_path_str(&self) -> String {
-        self.local_path().to_str().unwrap().to_owned() // Can never hapen, as we already know fro mwithin local_path(), that it is valid UTF-8
+        self.local_path().to_string_lossy().into_owned()
     }
 
     fn _branch(&self) -> Result<Option<git2::Branch>, Error> {
@@ -274,6 +524,53 @@ This may indicate either:
         ) //)
     }
 
+    /// Returns the abbreviated SHA of the currently checked-out commit,
+    /// if any.
+    /// Unlike "git rev-parse --short HEAD",
+    /// this always uses [`value_conversions::SHA_SHORT_LENGTH`] hex digits,
+    /// so it is consistent with the short SHAs we derive from full ones.
+    ///
+    /// # Errors
+    ///
+    /// If some git-related magic goes south,
+    /// or there is no commit.
+    pub fn sha_short(&self) -> Result<Option<String>, Error> {
+        Ok(self.sha()?.map(|sha| {
+            sha.chars()
+                .take(value_conversions::SHA_SHORT_LENGTH)
+                .collect()
+        }))
+    }
+
+    /// Whether the working tree has uncommitted changes
+    /// to tracked files (staged or not).
+    /// Untracked files are ignored, as with "git describe --dirty".
+    /// Bare repos have no working tree, and thus are never dirty.
+    ///
+    /// # Errors
+    ///
+    /// If some git-related magic goes south.
+    pub fn is_dirty(&self) -> Result<bool, Error> {
+        if self.is_bare() {
+            return Ok(false);
+        }
+        let mut status_opts = git2::StatusOptions::new();
+        status_opts
+            .include_untracked(false)
+            .include_ignored(false)
+            .exclude_submodules(true);
+        let statuses = self
+            .repo
+            .statuses(Some(&mut status_opts))
+            .map_err(|from| Error {
+                from,
+                message: String::from("Failed to get the working tree status"),
+            })?;
+        Ok(statuses
+            .iter()
+            .any(|entry| entry.status() != git2::Status::CURRENT))
+    }
+
     /// Returns the local name of the currently checked-out branch,
     /// if any.
     //
@@ -298,70 +595,50 @@ This may indicate either:
         })
     }
 
+    /// Returns the map of tagged commits,
+    /// which is built on first use.
+    #[cfg(not(feature = "gix"))]
+    fn tag_map(&self) -> Result<&TagMap, Error> {
+        if let Some(tag_map) = self.tag_map.get() {
+            return Ok(tag_map);
+        }
+        let tag_map = _tag_map(&self.repo)?;
+        Ok(self.tag_map.get_or_init(|| tag_map))
+    }
+
+    #[cfg(not(feature = "gix"))]
     fn _tag(&self) -> Result<Option<String>, Error> {
-        let head = self.repo.head().map_err(|from| Error {
-            from,
-            message: String::from("Failed to get repo HEAD for figuring out the tag"),
-        })?;
-        let head_oid = head
-            .resolve()
+        let head_oid = self
+            .repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
             .map_err(|from| Error {
                 from,
-                message: String::from("Failed resolve HEAD into a reference"),
+                message: String::from("Failed to peel HEAD to a commit for figuring out the tag"),
             })?
-            .target()
-            .ok_or_else(|| git2::Error::from_str("No OID for HEAD"))
-            .map_err(|from| Error {
-                from,
-                message: String::from("-"),
-            })?;
-        let mut tag = None;
-        let mut inner_err: Option<Result<Option<String>, Error>> = None;
-        self.repo
-            .tag_foreach(|_id, name| {
-                let name_str =
-                    String::from_utf8(name.to_vec()).expect("Failed stringifying tag name");
-                let cur_tag_res = self.repo.find_reference(&name_str).and_then(|git_ref| {
-                    git_ref.target().ok_or_else(|| {
-                        git2::Error::from_str("Failed to get tag reference target commit")
-                    })
-                });
-                let cur_tag = match cur_tag_res {
-                    Err(from) => {
-                        inner_err = Some(Err(Error {
-                            from,
-                            message: String::from("Failed fetching current tag reference"),
-                        }));
-                        return false;
-                    }
-                    Ok(cur_tag) => cur_tag,
-                };
-                if cur_tag == head_oid {
-                    tag = Some(name_str);
-                    false
-                } else {
-                    true
-                }
-            })
-            .map_err(|from| Error {
-                from,
-                message: String::from("Failed processing tags"),
-            })?;
-        match inner_err {
-            Some(err) => err,
-            None => Ok(tag),
-        }
+            .id();
+        Ok(self
+            .tag_map()?
+            .get(&head_oid)
+            .and_then(|names| names.first())
+            .cloned())
     }
 
-    /// Returns the name of the currently checked-out tag,
+    /// Returns the (short) name of the currently checked-out tag,
     /// if any tag points to the current HEAD.
+    /// If multiple ones do, the alphabetically first one is returned.
     //
     /// # Errors
     ///
     /// If some git-related magic goes south,
     /// or the tag name is not valid UTF-8.
+    #[allow(clippy::let_and_return)] // for the feature dependent choice of backend
     pub fn tag(&self) -> Result<Option<String>, Error> {
-        self._tag()
+        #[cfg(not(feature = "gix"))]
+        let tag = self._tag();
+        #[cfg(feature = "gix")]
+        let tag = super::git_gix::tag(&self.gix);
+        tag
     }
 
     fn _remote_tracking_branch(&self) -> Result<Option<git2::Branch>, Error> {
@@ -456,11 +733,115 @@ This may indicate either:
 
     /// Returns the clone URL of the main remote,
     /// if there is any.
+    /// This is the remote of the checked-out branch,
+    /// or for bare repos - which usually lack remote tracking branches -
+    /// the "origin" remote or the only remote, if there is just one.
     //
     /// # Errors
     ///
     /// If some git-related magic goes south.
     pub fn remote_clone_url(&self) -> Result<Option<String>, Error> {
+        #[cfg(not(feature = "gix"))]
+        let url = self._remote_clone_url()?;
+        #[cfg(feature = "gix")]
+        let url = super::git_gix::remote_clone_url(&self.gix)?;
+        if url.is_none() && self.is_bare() {
+            return self.default_remote_url();
+        }
+        Ok(url)
+    }
+
+    /// Returns the name of the "origin" remote,
+    /// or of the only remote, if there is just one.
+    fn default_remote_name(&self) -> Result<Option<String>, Error> {
+        let remotes = self.repo.remotes().map_err(|from| Error {
+            from,
+            message: String::from("Failed to list the remotes"),
+        })?;
+        let names: Vec<&str> = remotes.iter().flatten().collect();
+        Ok(if names.contains(&DEFAULT_REMOTE) {
+            Some(DEFAULT_REMOTE.to_owned())
+        } else if let [name] = names.as_slice() {
+            Some((*name).to_owned())
+        } else {
+            None
+        })
+    }
+
+    /// Returns the URL of the "origin" remote,
+    /// or of the only remote, if there is just one.
+    fn default_remote_url(&self) -> Result<Option<String>, Error> {
+        let Some(name) = self.default_remote_name()? else {
+            return Ok(None);
+        };
+        let remote = self.repo.find_remote(&name).map_err(|from| Error {
+            from,
+            message: format!("Failed to find remote '{name}'"),
+        })?;
+        Ok(remote.url().map(ToOwned::to_owned))
+    }
+
+    /// Returns the name of the remote pushes go to, if there is any.
+    /// This is the push remote configured for the checked-out branch
+    /// ("branch.<name>.pushRemote"), the default push remote
+    /// ("remote.pushDefault"), or else the main remote,
+    /// as used by [`Self::remote_clone_url`].
+    fn push_remote_name(&self) -> Result<Option<String>, Error> {
+        let config = self.repo.config().map_err(|from| Error {
+            from,
+            message: String::from("Failed to read the git config"),
+        })?;
+        let configured = self
+            .branch()?
+            .and_then(|branch| {
+                config
+                    .get_string(&format!("branch.{branch}.pushRemote"))
+                    .ok()
+            })
+            .or_else(|| config.get_string("remote.pushDefault").ok());
+        if configured.is_some() {
+            return Ok(configured);
+        }
+        let remote_name = self.remote_name()?;
+        if remote_name.is_none() && self.is_bare() {
+            return self.default_remote_name();
+        }
+        Ok(remote_name)
+    }
+
+    /// Returns the URL pushes go to, if there is any push remote
+    /// (see [`Self::push_remote_name`]).
+    /// This is the dedicated push URL of the remote ("remote.<name>.pushurl"),
+    /// if there is one, or its (fetch) URL otherwise,
+    /// which may differ from [`Self::remote_clone_url`],
+    /// e.g. if fetching is done anonymously through HTTPS,
+    /// but pushing through SSH.
+    ///
+    /// # Errors
+    ///
+    /// If some git-related magic goes south,
+    /// or the URL is not valid UTF-8.
+    pub fn remote_push_url(&self) -> Result<Option<String>, Error> {
+        let Some(remote_name) = self.push_remote_name()? else {
+            return Ok(None);
+        };
+        let remote = self.repo.find_remote(&remote_name).map_err(|from| Error {
+            from,
+            message: format!("Failed to find push remote '{remote_name}'"),
+        })?;
+        let url = remote
+            .pushurl_bytes()
+            .or_else(|| Some(remote.url_bytes()).filter(|url| !url.is_empty()));
+        url.map(|url| {
+            std::str::from_utf8(url)
+                .map(ToOwned::to_owned)
+                .map_err(|_err| Error::from("Remote push URL is not UTF-8 compatible"))
+        })
+        .transpose()
+    }
+
+    #[cfg(not(feature = "gix"))]
+    fn _remote_clone_url(&self) -> Result<Option<String>, Error> {
         Ok(if let Some(remote_name) = self.remote_name()? {
             Some(
                 self.repo
@@ -479,39 +860,103 @@ This may indicate either:
     }
 
     /// Returns the version of the current state of the repo.
-    /// This is basically the result of "git describe --tags --all <and-some-more...>".
+    /// This is basically the result of
+    /// `git describe --tags --dirty --match <tag_pattern>`,
+    /// which is the plain tag name if HEAD is tagged.
+    /// Only tags matching the (git glob) `tag_pattern` are considered.
     ///
+    /// Returns `None` if no matching tag is reachable from HEAD;
+    /// in that case, it is up to the caller to fall back to something else,
+    /// e.g. the short SHA.
     ///
     /// # Errors
     ///
     /// If some git-related magic goes south.
-    pub fn version(&self) -> Result<String, Error> {
-        if _has_tags(&self.repo) {
-            _version(&self.repo)
-        } else {
+    #[allow(clippy::let_and_return)] // for the feature dependent choice of backend
+    pub fn version(&self, tag_pattern: &str) -> Result<Option<String>, Error> {
+        #[cfg(not(feature = "gix"))]
+        let has_tags = !self.tag_map()?.is_empty();
+        #[cfg(feature = "gix")]
+        let has_tags = super::git_gix::has_tags(&self.gix);
+        if !has_tags {
             log::warn!(
                 "The git repository has no tags.
 Please consider adding at least a tag '0.1.0' to the first commit of the repo history; \
 for example with:
 git tag -a -m 'Release 0.1.0' 0.1.0 $(git rev-list --max-parents=0 HEAD)"
             );
-            match self.sha()? {
-                Some(sha_str) => Ok(sha_str),
-                None => Err(Error::from(
-                    "The repo has no tags, so we can not use git describe, \
-and there is no commit checked out either",
-                )),
-            }
+            return Ok(None);
         }
+        #[cfg(not(feature = "gix"))]
+        let version = _version(&self.repo, tag_pattern);
+        #[cfg(feature = "gix")]
+        let version = super::git_gix::describe(&self.gix, tag_pattern, self.is_dirty()?);
+        version
+    }
+
+    /// Returns the paths of all the files tracked by git,
+    /// relative to the repo root and separated by '/',
+    /// leaving out submodules.
+    /// Bare repos have no working tree, and thus no files.
+    ///
+    /// # Errors
+    ///
+    /// If the index could not be read.
+    pub fn tracked_files(&self) -> Result<Vec<String>, Error> {
+        /// The file mode git uses for submodules
+        const MODE_SUBMODULE: u32 = 0o160_000;
+        if self.is_bare() {
+            return Ok(vec![]);
+        }
+        let index = self.repo.index().map_err(|from| Error {
+            from,
+            message: String::from("Failed to read the index"),
+        })?;
+        let mut files: Vec<String> = index
+            .iter()
+            .filter(|entry| entry.mode != MODE_SUBMODULE)
+            .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+            .collect();
+        // NOTE Files with merge conflicts appear multiple times
+        files.dedup();
+        Ok(files)
+    }
+
+    /// Returns the number of commits in the currently checked out history
+    /// (=> HEAD), including HEAD itself.
+    /// This is the same as "git rev-list --count HEAD".
+    ///
+    /// # Errors
+    ///
+    /// If some git-related magic goes south.
+    pub fn commit_count(&self) -> Result<usize, Error> {
+        let mut rev_walk = self.repo.revwalk().map_err(|from| Error {
+            from,
+            message: String::from("Failed to create a rev-walk for counting commits"),
+        })?;
+        rev_walk.push_head().map_err(|from| Error {
+            from,
+            message: String::from("Failed to push HEAD to the rev-walk for counting commits"),
+        })?;
+        let mut count = 0;
+        for oid in rev_walk {
+            oid.map_err(|from| Error {
+                from,
+                message: String::from("Failed to walk the history for counting commits"),
+            })?;
+            count += 1;
+        }
+        Ok(count)
     }
 
     /// Returns the commit-time (not author-time)
-    /// of the last commit in the currently checked out history (=> HEAD)
+    /// of the last commit in the currently checked out history (=> HEAD).
+    /// See [`crate::settings::Settings::format_date`] for formatting it.
     ///
     /// # Errors
     ///
     /// If some git-related magic goes south.
-    pub fn commit_date(&self, date_format: &str) -> Result<String, Error> {
+    pub fn commit_date(&self) -> Result<DateTime<Utc>, Error> {
         let head = self.repo.head().map_err(|from| Error {
             from,
             message: String::from("Failed to get repo HEAD for figuring out the commit date"),
@@ -525,14 +970,9 @@ and there is no commit checked out either",
                 ),
             })?
             .time();
-        let commit_time_chrono = DateTime::<Utc>::from_utc(
-            NaiveDateTime::from_timestamp_opt(commit_time_git2.seconds(), 0).ok_or_else(|| {
-                Error::from("Failed to peal HEAD to commit for figuring out the commit date")
-            })?,
-            Utc,
-        );
-        Ok(commit_time_chrono.format(date_format).to_string())
-        // date.fromtimestamp(repo.head.ref.commit.committed_date).strftime(date_format)
+        DateTime::from_timestamp(commit_time_git2.seconds(), 0).ok_or_else(|| {
+            Error::from("Failed to peal HEAD to commit for figuring out the commit date")
+        })
     }
 }
 
@@ -0,0 +1,407 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! A minimal reader of git attributes (`.gitattributes` files).
+//!
+//! It is used to exclude files from detection (e.g. of licenses)
+//! that do not really belong to the project:
+//! those that are left out of source archives (`export-ignore`),
+//! and vendored third-party code (`linguist-vendored`),
+//! see <https://git-scm.com/docs/gitattributes>.
+//!
+//! Only what we need is supported;
+//! most notably, macro attributes are ignored.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+/// The name of the attributes files within the working tree.
+pub const FILE_NAME: &str = ".gitattributes";
+/// The attributes file with the highest precedence,
+/// relative to the project root.
+const FILE_INFO: &str = ".git/info/attributes";
+/// The attribute marking paths that are left out of source archives.
+const ATTR_EXPORT_IGNORE: &str = "export-ignore";
+/// The attribute marking paths that contain vendored third-party code,
+/// as used by GitHub (linguist).
+const ATTR_VENDORED: &str = "linguist-vendored";
+/// Names of directories that are considered vendored
+/// (as by linguist), unless their `linguist-vendored` attribute is unset.
+const DEFAULT_VENDORED_DIRS: &[&str] = &[
+    "node_modules",
+    "third-party",
+    "third_party",
+    "thirdparty",
+    "vendor",
+    "vendors",
+];
+
+/// The state of an attribute for a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum State {
+    /// Like "attr"
+    Set,
+    /// Like "-attr"
+    Unset,
+    /// Like "attr=value"
+    Value(String),
+    /// Like "!attr", which resets the attribute
+    Unspecified,
+}
+
+/// A single line of an attributes file.
+#[derive(Debug)]
+struct Rule {
+    /// The directory containing the attributes file,
+    /// relative to the project root, without trailing '/';
+    /// empty for the root.
+    base: String,
+    pattern: Regex,
+    /// Whether the pattern contains a '/',
+    /// in which case it is matched against the whole path
+    /// (relative to `base`), instead of only the file name.
+    anchored: bool,
+    /// Whether the pattern ends with a '/',
+    /// in which case it only matches directories.
+    dir_only: bool,
+    attrs: Vec<(String, State)>,
+}
+
+/// Converts an attributes file pattern into an anchored regex.
+/// Other than with [`super::git::glob_to_regex`],
+/// `*` and `?` do not match '/', while `**` does.
+pub(crate) fn pattern_to_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    let mut r_str = String::with_capacity(pattern.len() + 8);
+    r_str.push('^');
+    let mut chars = pattern.chars().peekable();
+    while let Some(chr) = chars.next() {
+        match chr {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    r_str.push_str("(?:.*/)?");
+                } else {
+                    r_str.push_str(".*");
+                }
+            }
+            '*' => r_str.push_str("[^/]*"),
+            '?' => r_str.push_str("[^/]"),
+            '[' => {
+                r_str.push('[');
+                let mut first = true;
+                for chr in chars.by_ref() {
+                    match chr {
+                        ']' if !first => break,
+                        '!' | '^' if first => r_str.push('^'),
+                        '\\' | '[' | ']' | '&' | '~' => {
+                            r_str.push('\\');
+                            r_str.push(chr);
+                        }
+                        _ => r_str.push(chr),
+                    }
+                    first = false;
+                }
+                r_str.push(']');
+            }
+            _ => r_str.push_str(&regex::escape(&chr.to_string())),
+        }
+    }
+    r_str.push('$');
+    Regex::new(&r_str)
+}
+
+fn parse_attr(token: &str) -> (String, State) {
+    if let Some(name) = token.strip_prefix('-') {
+        (name.to_owned(), State::Unset)
+    } else if let Some(name) = token.strip_prefix('!') {
+        (name.to_owned(), State::Unspecified)
+    } else if let Some((name, value)) = token.split_once('=') {
+        (name.to_owned(), State::Value(value.to_owned()))
+    } else {
+        (token.to_owned(), State::Set)
+    }
+}
+
+impl Rule {
+    /// Parses a single line of an attributes file,
+    /// returning `None` for empty lines, comments,
+    /// macro definitions and invalid patterns.
+    fn parse(line: &str, base: &str) -> Option<Self> {
+        let mut tokens = line.split_whitespace();
+        let pattern = tokens.next()?;
+        // NOTE Negative patterns are forbidden in attributes files
+        if pattern.starts_with('#') || pattern.starts_with('[') || pattern.starts_with('!') {
+            return None;
+        }
+        let dir_only = pattern.ends_with('/');
+        let pattern = pattern.trim_end_matches('/');
+        let anchored = pattern.contains('/');
+        let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+        let regex = pattern_to_regex(pattern)
+            .inspect_err(|err| {
+                log::warn!("Ignoring invalid git attributes pattern '{pattern}': {err}");
+            })
+            .ok()?;
+        Some(Self {
+            base: base.to_owned(),
+            pattern: regex,
+            anchored,
+            dir_only,
+            attrs: tokens.map(parse_attr).collect(),
+        })
+    }
+
+    fn matches(&self, rel_path: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let rel_path = if self.base.is_empty() {
+            Some(rel_path)
+        } else {
+            rel_path
+                .strip_prefix(&self.base)
+                .and_then(|path| path.strip_prefix('/'))
+        };
+        let Some(rel_path) = rel_path else {
+            return false;
+        };
+        if self.anchored {
+            self.pattern.is_match(rel_path)
+        } else {
+            let file_name = rel_path.rsplit('/').next().unwrap_or(rel_path);
+            self.pattern.is_match(file_name)
+        }
+    }
+}
+
+/// The attributes of the paths within a project.
+#[derive(Debug, Default)]
+pub struct Attributes {
+    /// The rules from the attributes files within the working tree,
+    /// from the lowest to the highest precedence
+    rules: Vec<Rule>,
+    /// The rules from [`FILE_INFO`],
+    /// which take precedence over all others
+    info_rules: Vec<Rule>,
+}
+
+impl Attributes {
+    /// Loads the attributes file in the project root
+    /// and the one in the git meta-data directory, if they exist.
+    /// The ones in sub-directories have to be added with [`Self::add_dir`].
+    #[must_use]
+    pub fn load(root: &Path) -> Self {
+        let mut attributes = Self::default();
+        attributes.add_dir(root, "");
+        if let Ok(content) = fs::read_to_string(root.join(FILE_INFO)) {
+            attributes.info_rules = Self::parse(&content, "");
+        }
+        attributes
+    }
+
+    fn parse(content: &str, base: &str) -> Vec<Rule> {
+        content
+            .lines()
+            .filter_map(|line| Rule::parse(line, base))
+            .collect()
+    }
+
+    /// Adds the rules of the attributes file in the given directory
+    /// (relative to the project root; empty for the root itself),
+    /// if there is one.
+    pub fn add_dir(&mut self, root: &Path, rel_dir: &str) {
+        if let Ok(content) = fs::read_to_string(root.join(rel_dir).join(FILE_NAME)) {
+            self.rules.extend(Self::parse(&content, rel_dir));
+        }
+    }
+
+    /// Returns the state of an attribute for a path
+    /// (relative to the project root, separated by '/'),
+    /// if it is specified.
+    #[must_use]
+    pub fn get(&self, rel_path: &str, is_dir: bool, attr: &str) -> Option<&State> {
+        // NOTE Later rules take precedence over earlier ones
+        self.rules
+            .iter()
+            .chain(&self.info_rules)
+            .rev()
+            .filter(|rule| rule.matches(rel_path, is_dir))
+            .flat_map(|rule| rule.attrs.iter().rev())
+            .find(|(name, _state)| name == attr)
+            .map(|(_name, state)| state)
+            .filter(|state| **state != State::Unspecified)
+    }
+
+    /// Whether the path is vendored third-party code,
+    /// either because it is marked as such through `linguist-vendored`,
+    /// or because it lies within a directory with a conventional name
+    /// like `vendor` or `third_party`.
+    fn is_vendored(&self, rel_path: &str, is_dir: bool) -> bool {
+        match self.get(rel_path, is_dir, ATTR_VENDORED) {
+            Some(State::Set) => true,
+            Some(State::Value(value)) => value == "true",
+            Some(State::Unset) => false,
+            Some(State::Unspecified) | None => {
+                let mut segments: Vec<&str> = rel_path.split('/').collect();
+                if !is_dir {
+                    segments.pop();
+                }
+                segments
+                    .iter()
+                    .any(|segment| DEFAULT_VENDORED_DIRS.contains(segment))
+            }
+        }
+    }
+
+    /// Whether the path (relative to the project root, separated by '/')
+    /// does not really belong to the project,
+    /// because it - or one of its parent directories -
+    /// is left out of source archives,
+    /// or because it is vendored third-party code.
+    #[must_use]
+    pub fn is_excluded(&self, rel_path: &str, is_dir: bool) -> bool {
+        let ancestors = rel_path
+            .match_indices('/')
+            .filter_map(|(idx, _)| rel_path.get(..idx))
+            .map(|dir| (dir, true));
+        let export_ignored = ancestors
+            .chain([(rel_path, is_dir)])
+            .any(|(path, is_dir)| self.get(path, is_dir, ATTR_EXPORT_IGNORE) == Some(&State::Set));
+        export_ignored || self.is_vendored(rel_path, is_dir)
+    }
+}
+
+/// Finds the files whose names are accepted by the filter.
+///
+/// They are searched for in the project root and its sub-directories,
+/// down to a depth of `max_depth` (0 meaning only the root),
+/// leaving out the ones that are excluded by their git attributes
+/// (see [`Attributes::is_excluded`]).
+///
+/// # Errors
+///
+/// If a directory could not be read.
+pub fn find_files(
+    root: &Path,
+    max_depth: usize,
+    accept: impl Fn(&str) -> bool,
+) -> io::Result<Vec<PathBuf>> {
+    let mut attributes = Attributes::load(root);
+    let mut found = vec![];
+    let mut dirs = vec![(String::new(), 0)];
+    while let Some((rel_dir, depth)) = dirs.pop() {
+        if !rel_dir.is_empty() {
+            attributes.add_dir(root, &rel_dir);
+        }
+        for entry in fs::read_dir(root.join(&rel_dir))? {
+            let entry = entry?;
+            let Some(file_name) = entry.file_name().to_str().map(ToOwned::to_owned) else {
+                continue;
+            };
+            let rel_path = if rel_dir.is_empty() {
+                file_name.clone()
+            } else {
+                format!("{rel_dir}/{file_name}")
+            };
+            let path = entry.path();
+            if path.is_dir() {
+                // NOTE We do not follow symlinks to directories,
+                //      to not end up in a loop.
+                if depth < max_depth
+                    && file_name != ".git"
+                    && !entry.file_type()?.is_symlink()
+                    && !attributes.is_excluded(&rel_path, true)
+                {
+                    dirs.push((rel_path, depth + 1));
+                }
+            } else if accept(&file_name) {
+                if attributes.is_excluded(&rel_path, false) {
+                    log::debug!("Ignoring '{rel_path}', as it is excluded by git attributes");
+                } else {
+                    found.push(path);
+                }
+            } else {
+                // not a file we are looking for
+            }
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes(content: &str) -> Attributes {
+        Attributes {
+            rules: Attributes::parse(content, ""),
+            info_rules: vec![],
+        }
+    }
+
+    #[test]
+    fn test_pattern_to_regex() {
+        let regex = pattern_to_regex("*.txt").unwrap();
+        assert!(regex.is_match("LICENSE.txt"));
+        assert!(!regex.is_match("dir/LICENSE.txt"));
+        let regex = pattern_to_regex("third_party/**").unwrap();
+        assert!(regex.is_match("third_party/lib/LICENSE"));
+        let regex = pattern_to_regex("**/LICENSE").unwrap();
+        assert!(regex.is_match("LICENSE"));
+        assert!(regex.is_match("a/b/LICENSE"));
+    }
+
+    #[test]
+    fn test_get() {
+        let attributes = attributes(
+            "# comment\n\
+            *.txt text\n\
+            /docs export-ignore\n\
+            LICENSE-* -text eol=lf\n\
+            LICENSE-MIT !text\n",
+        );
+        assert_eq!(attributes.get("a.txt", false, "text"), Some(&State::Set));
+        assert_eq!(
+            attributes.get("sub/a.txt", false, "text"),
+            Some(&State::Set)
+        );
+        assert_eq!(
+            attributes.get("LICENSE-APACHE", false, "text"),
+            Some(&State::Unset)
+        );
+        assert_eq!(
+            attributes.get("LICENSE-APACHE", false, "eol"),
+            Some(&State::Value("lf".to_owned()))
+        );
+        assert_eq!(attributes.get("LICENSE-MIT", false, "text"), None);
+        assert_eq!(
+            attributes.get("docs", true, ATTR_EXPORT_IGNORE),
+            Some(&State::Set)
+        );
+        assert_eq!(attributes.get("sub/docs", true, ATTR_EXPORT_IGNORE), None);
+    }
+
+    #[test]
+    fn test_is_excluded() {
+        let attributes = attributes(
+            "/LICENSE-THIRD-PARTY export-ignore\n\
+            /ext export-ignore\n\
+            /libs/** linguist-vendored\n\
+            /vendor/ours/** -linguist-vendored\n",
+        );
+        assert!(!attributes.is_excluded("LICENSE", false));
+        assert!(attributes.is_excluded("LICENSE-THIRD-PARTY", false));
+        assert!(attributes.is_excluded("ext/LICENSE", false));
+        assert!(attributes.is_excluded("libs/foo/LICENSE", false));
+        assert!(attributes.is_excluded("third_party/foo/LICENSE", false));
+        assert!(attributes.is_excluded("vendor/foo/LICENSE", false));
+        assert!(!attributes.is_excluded("vendor/ours/LICENSE", false));
+        assert!(!attributes.is_excluded("crates/core/LICENSE", false));
+    }
+}
@@ -10,6 +10,15 @@ pub struct PartsRef<'a> {
     pub protocol: Option<&'a str>,
     pub user: Option<&'a str>,
     pub host: &'a str,
+    /// Only set for URL spec compliant clone URLs,
+    /// as in the SCP like syntax ("user@host:path"),
+    /// the ':' separates the host from the path.
+    pub port: Option<&'a str>,
+    /// The base path of a self-hosted instance (e.g. "gitlab"),
+    /// if it was split off the start of the path
+    /// (see [`Self::with_base_path`]).
+    pub base_path: Option<&'a str>,
+    /// The path (after the base path, if any), including the rest of the URL.
     pub path_and_rest: &'a str,
 }
 
@@ -36,10 +45,28 @@ impl<'a> PartsRef<'a> {
     /// including non URL spec compliant ones -
     /// into a set of basic parts.
     ///
+    /// ```
+    /// # use projvar::tools::git_clone_url::PartsRef;
+    /// let parts = PartsRef::parse("ssh://git@gitlab.example.com:2222/group/project.git").unwrap();
+    /// assert_eq!(parts.protocol, Some("ssh"));
+    /// assert_eq!(parts.user, Some("git"));
+    /// assert_eq!(parts.host, "gitlab.example.com");
+    /// assert_eq!(parts.port, Some("2222"));
+    /// assert_eq!(parts.path_and_rest, "group/project.git");
+    /// // In the SCP like syntax, there is no port
+    /// let parts = PartsRef::parse("git@gitlab.example.com:2222/group/project.git").unwrap();
+    /// assert_eq!(parts.port, None);
+    /// assert_eq!(parts.path_and_rest, "2222/group/project.git");
+    /// let parts = PartsRef::parse("git+ssh://gitlab.example.com:2222/group/project.git").unwrap();
+    /// assert_eq!(parts.protocol, Some("git+ssh"));
+    /// assert_eq!(parts.port, Some("2222"));
+    /// ```
+    ///
     /// # Errors
     ///
     /// If our internal regex to parse a git clone URL
-    /// does not match the supplied string.
+    /// does not match the supplied string,
+    /// or it contains no path (e.g. "github.com:").
     pub fn parse<'b>(any_clone_url: &'b str) -> Result<Self, String>
     where
         'b: 'a,
@@ -49,25 +76,122 @@ impl<'a> PartsRef<'a> {
             // * git@github.com:hoijui/rust-project-scripts.git
             // * ssh://github.com/hoijui/rust-project-scripts.git
             // * https://github.com/hoijui/rust-project-scripts.git
-            static ref R_CLONE_URL: Regex = Regex::new(r"^((?P<protocol>[0-9a-zA-Z._-]+)://)?((?P<user>[0-9a-zA-Z._-]+)@)?(?P<host>[0-9a-zA-Z._-]+)([/:](?P<path_and_rest>.+)?)?$").unwrap();
+            // and the later two may also contain a port:
+            // * https://192.168.1.10:3000/hoijui/rust-project-scripts.git
+            // * ssh://git@192.168.1.10:2222/hoijui/rust-project-scripts.git
+            static ref R_CLONE_URL: Regex = Regex::new(r"^((?P<protocol>[0-9a-zA-Z.+_-]+)://)?((?P<user>[0-9a-zA-Z._-]+)@)?(?P<host>[0-9a-zA-Z._-]+)((?P<sep>[/:])(?P<path_and_rest>.+)?)?$").unwrap();
         }
 
         R_CLONE_URL
             .captures(any_clone_url.as_ref())
-            .map(|caps| {
+            .and_then(|caps| {
                 let_named_cap_opt!(caps, protocol);
                 let_named_cap_opt!(caps, user);
                 let_named_cap!(caps, host);
-                let_named_cap!(caps, path_and_rest);
-                Self {
+                let_named_cap_opt!(caps, sep);
+                // NOTE This is optional in the regex,
+                //      so we can not use `let_named_cap!` here.
+                let_named_cap_opt!(caps, path_and_rest);
+                let path_and_rest = path_and_rest?;
+                let (port, path_and_rest) = match (protocol, sep, path_and_rest.split_once('/')) {
+                    (Some(_), Some(":"), Some((port, rest)))
+                        if !port.is_empty() && port.chars().all(|chr| chr.is_ascii_digit()) =>
+                    {
+                        (Some(port), rest)
+                    }
+                    _ => (None, path_and_rest),
+                };
+                Some(Self {
                     protocol,
                     user,
                     host,
+                    port,
+                    base_path: None,
                     path_and_rest,
-                }
+                })
             })
             .ok_or_else(|| {
                 format!("Failed to parse as (any type of) git clone URL: '{any_clone_url}'")
             })
     }
+
+    /// Splits the base path of a self-hosted instance
+    /// (see [`crate::settings::Settings::hosting_base_path`])
+    /// off the start of [`Self::path_and_rest`] into [`Self::base_path`],
+    /// if it is there and followed by more path.
+    ///
+    /// ```
+    /// # use projvar::tools::git_clone_url::PartsRef;
+    /// let parts = PartsRef::parse("https://gitlab.example.com:8443/gitlab/group/project.git")
+    ///     .unwrap()
+    ///     .with_base_path(Some("gitlab"));
+    /// assert_eq!(parts.port, Some("8443"));
+    /// assert_eq!(parts.base_path, Some("gitlab"));
+    /// assert_eq!(parts.path_and_rest, "group/project.git");
+    /// // Not there
+    /// let parts = PartsRef::parse("https://gitlab.example.com/group/project.git")
+    ///     .unwrap()
+    ///     .with_base_path(Some("gitlab"));
+    /// assert_eq!(parts.base_path, None);
+    /// assert_eq!(parts.path_and_rest, "group/project.git");
+    /// ```
+    #[must_use]
+    pub fn with_base_path(self, base_path: Option<&'a str>) -> Self {
+        let Some(base_path) = base_path
+            .map(|base_path| base_path.trim_matches('/'))
+            .filter(|base_path| !base_path.is_empty())
+        else {
+            return self;
+        };
+        match self
+            .path_and_rest
+            .strip_prefix(base_path)
+            .and_then(|rest| rest.strip_prefix('/'))
+        {
+            Some(rest) if !rest.is_empty() => Self {
+                base_path: Some(base_path),
+                path_and_rest: rest,
+                ..self
+            },
+            _ => self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            PartsRef::parse("https://192.168.1.10:3000/hoijui/projvar.git"),
+            Ok(PartsRef {
+                protocol: Some("https"),
+                user: None,
+                host: "192.168.1.10",
+                port: Some("3000"),
+                base_path: None,
+                path_and_rest: "hoijui/projvar.git",
+            })
+        );
+        assert_eq!(
+            PartsRef::parse("git@github.com:hoijui/projvar.git"),
+            Ok(PartsRef {
+                protocol: None,
+                user: Some("git"),
+                host: "github.com",
+                port: None,
+                base_path: None,
+                path_and_rest: "hoijui/projvar.git",
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_without_path() {
+        assert!(PartsRef::parse("github.com").is_err());
+        assert!(PartsRef::parse("git@github.com:").is_err());
+        assert!(PartsRef::parse("https://github.com/").is_err());
+    }
 }
@@ -0,0 +1,173 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Implementations of the more expensive [`super::git::Repo`] operations
+//! (tags, describe and remotes) with [gitoxide](https://github.com/Byron/gitoxide),
+//! which is considerably faster then `libgit2`
+//! on repos with thousands of tags.
+//! This is only used if the "gix" feature is enabled.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use gix::prelude::ObjectIdExt;
+use gix::remote::Direction;
+use gix::ObjectId;
+
+use super::git::Error;
+
+/// Opens the repo at or above `repo_root`,
+/// respecting the standard git environment variables,
+/// just like the `libgit2` backend does.
+///
+/// # Errors
+///
+/// If there is no repo at or above `repo_root`.
+pub fn open(repo_root: &Path) -> Result<gix::Repository, git2::Error> {
+    gix::ThreadSafeRepository::discover_with_environment_overrides(repo_root)
+        .map(|repo| repo.to_thread_local())
+        .map_err(|err| git2::Error::from_str(&err.to_string()))
+}
+
+fn head_id(repo: &gix::Repository) -> Result<ObjectId, Error> {
+    repo.head_id()
+        .map(gix::Id::detach)
+        .map_err(|err| Error::gix(&err, "Failed to resolve HEAD to a commit"))
+}
+
+/// Returns the tags of the repo as (short name, target) pairs.
+/// Annotated tags are peeled to the object they point to,
+/// so they are treated the same as lightweight ones.
+fn tags(repo: &gix::Repository) -> Result<Vec<(String, ObjectId)>, Error> {
+    let references = repo
+        .references()
+        .map_err(|err| Error::gix(&err, "Failed to access the references"))?;
+    let mut tags = vec![];
+    for reference in references
+        .tags()
+        .map_err(|err| Error::gix(&err, "Failed to iterate over the tags"))?
+    {
+        let mut reference =
+            reference.map_err(|err| Error::gix(&err, "Failed to read a tag reference"))?;
+        let name = reference.name().shorten().to_string();
+        let target = reference
+            .peel_to_id_in_place()
+            .map_err(|err| Error::gix(&err, "Failed to peel a tag to its commit"))?
+            .detach();
+        tags.push((name, target));
+    }
+    Ok(tags)
+}
+
+/// Returns true if the repo contains any tags.
+pub fn has_tags(repo: &gix::Repository) -> bool {
+    repo.references()
+        .ok()
+        .and_then(|references| references.tags().ok().map(|mut tags| tags.next().is_some()))
+        .unwrap_or(false)
+}
+
+/// Returns the (short) name of a tag pointing to HEAD,
+/// if any.
+/// If multiple ones do, the alphabetically first one is returned.
+///
+/// # Errors
+///
+/// If some git-related magic goes south.
+pub fn tag(repo: &gix::Repository) -> Result<Option<String>, Error> {
+    let head_id = head_id(repo)?;
+    Ok(tags(repo)?
+        .into_iter()
+        .filter(|(_name, target)| *target == head_id)
+        .map(|(name, _target)| name)
+        .min())
+}
+
+/// Returns the equivalent of "git describe --tags --dirty --match <tag_pattern>",
+/// as the `libgit2` backend does,
+/// or `None` if no matching tag is reachable from HEAD.
+/// The history is walked breadth-first from HEAD,
+/// and the first commit with a tag is used.
+///
+/// # Errors
+///
+/// If some git-related magic goes south,
+/// or the tag pattern is invalid.
+pub fn describe(
+    repo: &gix::Repository,
+    tag_pattern: &str,
+    dirty: bool,
+) -> Result<Option<String>, Error> {
+    let r_tag_pattern = super::git::glob_to_regex(tag_pattern).map_err(|err| {
+        Error::gix(
+            &err,
+            &format!("Invalid version tag pattern '{tag_pattern}'"),
+        )
+    })?;
+    let head_id = head_id(repo)?;
+    let mut tagged_commits = HashMap::<ObjectId, String>::new();
+    let references = repo
+        .references()
+        .map_err(|err| Error::gix(&err, "Failed to access the references"))?;
+    for reference in references
+        .tags()
+        .map_err(|err| Error::gix(&err, "Failed to iterate over the tags"))?
+    {
+        let mut reference =
+            reference.map_err(|err| Error::gix(&err, "Failed to read a tag reference"))?;
+        let name = reference.name().shorten().to_string();
+        if !r_tag_pattern.is_match(&name) {
+            continue;
+        }
+        let commit_id = reference
+            .peel_to_id_in_place()
+            .map_err(|err| Error::gix(&err, "Failed to peel a tag to its commit"))?
+            .detach();
+        tagged_commits.entry(commit_id).or_insert(name);
+    }
+
+    let walk = repo
+        .rev_walk([head_id])
+        .all()
+        .map_err(|err| Error::gix(&err, "Failed to walk the history for describing HEAD"))?;
+    for (depth, info) in walk.enumerate() {
+        let info = info.map_err(|err| Error::gix(&err, "Failed to walk the history"))?;
+        if let Some(name) = tagged_commits.get(&info.id) {
+            let mut version = if depth == 0 {
+                name.clone()
+            } else {
+                format!("{name}-{depth}-g{}", head_id.attach(repo).shorten_or_id())
+            };
+            if dirty {
+                version.push_str("-dirty");
+            }
+            return Ok(Some(version));
+        }
+    }
+    Ok(None)
+}
+
+/// Returns the fetch URL of the remote of the checked-out branch,
+/// if there is any.
+///
+/// # Errors
+///
+/// If some git-related magic goes south.
+pub fn remote_clone_url(repo: &gix::Repository) -> Result<Option<String>, Error> {
+    let Some(head_name) = repo
+        .head_name()
+        .map_err(|err| Error::gix(&err, "Failed to get the name of HEAD"))?
+    else {
+        return Ok(None);
+    };
+    let Some(remote_name) = repo.branch_remote_name(head_name.shorten(), Direction::Fetch) else {
+        return Ok(None);
+    };
+    let remote = repo
+        .find_remote(remote_name.as_bstr())
+        .map_err(|err| Error::gix(&err, "Failed to find remote name for remote clone URL"))?;
+    Ok(remote
+        .url(Direction::Fetch)
+        .map(|url| url.to_bstring().to_string()))
+}
@@ -2,16 +2,19 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+#[cfg(feature = "cli")]
 use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 /// This deals with things related to different git hosting providers,
 /// both the actual hosters (github.com, gitlab.com, bitbucket.org, ...),
 /// as well as the software (gitlab, gitea, ...).
 use std::str;
-use strum_macros::{EnumString, IntoStaticStr, VariantNames};
+use strum_macros::{EnumIter, EnumString, IntoStaticStr, VariantNames};
 use url::Host;
 
 use crate::constants;
 
+use super::forge::{self, Forge};
 use super::git::TransferProtocol;
 
 #[derive(Debug, EnumString, VariantNames, IntoStaticStr, PartialEq, Eq, Clone, Copy)]
@@ -32,21 +35,62 @@ pub enum PublicSite {
     RocketGitCom,
     /// <https://rocketgit.com> - hosts only OS, software is OSS: `RocketGit`
     SourceForgeNet,
+    /// <https://app.radicle.xyz> - peer-to-peer, with public seed nodes, software is OSS: `Radicle`
+    RadicleXyz,
+    /// <https://googlesource.com> - hosts mostly OS, software is OSS: `Gerrit` with `Gitiles`
+    GoogleSourceCom,
     /// NOTE: The rust masters said, this is better then `Option<None>`!
     Unknown,
 }
 
 impl PublicSite {
+    /// The sites that host "pages"
+    /// (static web-sites, usually generated by CI),
+    /// see [`Self::pages_suffix`].
+    const PAGES_HOSTING_SITES: &'static [Self] = &[
+        Self::GitHubCom,
+        Self::GitLabCom,
+        Self::CodeBergOrg,
+        Self::SourceForgeNet,
+    ];
+
+    /// The domain suffix under which this site hosts "pages",
+    /// one sub-domain per user or project,
+    /// e.g. "github.io" for "hoijui.github.io".
+    #[must_use]
+    pub const fn pages_suffix(self) -> Option<&'static str> {
+        match self {
+            Self::GitHubCom => Some(constants::DS_GIT_HUB_IO_SUFIX),
+            Self::GitLabCom => Some(constants::DS_GIT_LAB_IO_SUFIX),
+            Self::CodeBergOrg => Some(constants::DS_CODE_BERG_PAGE),
+            Self::SourceForgeNet => Some(constants::DS_SOURCE_FORGE_IO),
+            // These do not have pages hosting, or not per repo
+            Self::BitBucketOrg
+            | Self::SourceHut
+            | Self::RepoOrCz
+            | Self::RocketGitCom
+            | Self::RadicleXyz
+            | Self::GoogleSourceCom
+            | Self::Unknown => None,
+        }
+    }
+
+    /// Evaluates the site from the domain of a "pages" URL,
+    /// e.g. "hoijui.github.io" -> [`Self::GitHubCom`].
     #[must_use]
     pub fn from_hosting_domain(host: &Host<&str>) -> Self {
         if let Host::Domain(domain) = host {
-            let domain_parts = domain.split('.').collect::<Vec<&str>>();
-            let main_domain = domain_parts[domain_parts.len() - 2..].join(".");
-            match main_domain.as_str() {
-                constants::DS_GIT_HUB_IO_SUFIX => Self::GitHubCom,
-                constants::DS_GIT_LAB_IO_SUFIX => Self::GitLabCom,
-                _ => Self::Unknown, // TODO implement the rest, where applicable (BitBucket does not have a hosting site, for example)
-            }
+            Self::PAGES_HOSTING_SITES
+                .iter()
+                .find(|site| {
+                    site.pages_suffix().is_some_and(|suffix| {
+                        domain
+                            .strip_suffix(suffix)
+                            .is_some_and(|sub| sub.ends_with('.'))
+                    })
+                })
+                .copied()
+                .unwrap_or_default()
         } else {
             Self::Unknown
         }
@@ -89,6 +133,18 @@ impl From<Host<&str>> for PublicSite {
             Host::Domain(constants::D_SOURCE_FORGE_NET | constants::DS_SOURCE_FORGE_IO) => {
                 Self::SourceForgeNet
             }
+            Host::Domain(
+                constants::D_RADICLE_APP_XYZ
+                | constants::D_RADICLE_SEED_XYZ
+                | constants::D_RADICLE_SEED_GARDEN,
+            ) => Self::RadicleXyz,
+            Host::Domain(domain)
+                if domain
+                    .strip_suffix(constants::DS_GOOGLE_SOURCE_COM)
+                    .is_some_and(|sub| sub.is_empty() || sub.ends_with('.')) =>
+            {
+                Self::GoogleSourceCom
+            }
             Host::Domain(_) | Host::Ipv4(_) | Host::Ipv6(_) => Self::Unknown,
         }
     }
@@ -100,7 +156,21 @@ impl From<Option<Host<&str>>> for PublicSite {
     }
 }
 
-#[derive(Debug, ValueEnum, EnumString, VariantNames, IntoStaticStr, PartialEq, Eq, Clone, Copy)]
+#[derive(
+    Debug,
+    EnumString,
+    VariantNames,
+    EnumIter,
+    IntoStaticStr,
+    PartialEq,
+    Eq,
+    Hash,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
 pub enum HostingType {
     /// <https://github.com> - proprietary
     GitHub,
@@ -118,6 +188,10 @@ pub enum HostingType {
     RocketGit,
     /// <https://allura.apache.org> - OSS
     Allura,
+    /// <https://radicle.xyz> - OSS - peer-to-peer, no central host
+    Radicle,
+    /// <https://www.gerritcodereview.com> - OSS - with `Gitiles` as web UI
+    Gerrit,
     /// NOTE: The rust masters said, this is better then `Option<None>`!
     Unknown,
 }
@@ -132,34 +206,27 @@ impl HostingType {
             | Self::Gitea
             | Self::Girocco
             | Self::RocketGit
-            | Self::Allura => true,
+            | Self::Allura
+            | Self::Radicle
+            | Self::Gerrit => true,
         }
     }
 
+    /// The peculiarities of this hosting type,
+    /// regarding the URLs of its repos.
     #[must_use]
-    pub const fn supports_clone_url(self, protocol: TransferProtocol) -> bool {
-        match protocol {
-            TransferProtocol::Https | TransferProtocol::Ssh => true,
-            TransferProtocol::Git => match self {
-                Self::Girocco | Self::RocketGit => true,
-                Self::GitHub
-                | Self::BitBucket
-                | Self::Unknown
-                | Self::GitLab
-                | Self::SourceHut
-                | Self::Gitea
-                | Self::Allura => false,
-            },
-        }
+    pub fn forge(self) -> &'static dyn Forge {
+        forge::get(self)
     }
 
     #[must_use]
-    pub const fn def_ssh_user(self) -> &'static str {
-        match self {
-            Self::GitHub | Self::GitLab | Self::BitBucket | Self::SourceHut => "git@",
-            Self::Gitea | Self::Girocco | Self::Allura | Self::Unknown => "",
-            Self::RocketGit => "rocketgit@",
-        }
+    pub fn supports_clone_url(self, protocol: TransferProtocol) -> bool {
+        self.forge().supports_clone_url(protocol)
+    }
+
+    #[must_use]
+    pub fn def_ssh_user(self) -> &'static str {
+        self.forge().def_ssh_user()
     }
 }
 
@@ -180,6 +247,8 @@ impl From<PublicSite> for HostingType {
             PublicSite::RocketGitCom => Self::RocketGit,
             PublicSite::CodeBergOrg => Self::Gitea,
             PublicSite::SourceForgeNet => Self::Allura,
+            PublicSite::RadicleXyz => Self::Radicle,
+            PublicSite::GoogleSourceCom => Self::Gerrit,
             PublicSite::Unknown => Self::Unknown,
         }
     }
@@ -0,0 +1,70 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use thiserror::Error;
+
+use super::credentials::Token;
+use super::net;
+use crate::settings::Settings;
+
+/// The base URL of the GitHub REST API,
+/// used if not overridden (e.g. by `GITHUB_API_URL` on GitHub Enterprise).
+pub const GIT_HUB_API_URL: &str = "https://api.github.com";
+
+/// This enumerates all possible errors returned by this module.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The request could not be sent, or failed on the network level.
+    #[error(transparent)]
+    Net(#[from] net::Error),
+
+    /// The request failed on the HTTP level.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+
+    /// The response was not valid JSON.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Fetches the URL under which the GitHub pages of a repo are published.
+///
+/// This respects a custom domain (CNAME), if one is configured.
+/// Returns `None` if the repo has no pages,
+/// or we lack the permissions to see them.
+///
+/// See: <https://docs.github.com/en/rest/pages/pages#get-a-apiname-pages-site>
+///
+/// # Errors
+///
+/// If network access is disabled,
+/// the request failed on the network level,
+/// or the response was not valid JSON.
+pub fn git_hub_pages_url(
+    settings: &Settings,
+    api_url: &str,
+    slug: &str,
+    token: Option<&Token>,
+) -> Result<Option<String>, Error> {
+    let url = format!("{}/repos/{slug}/pages", api_url.trim_end_matches('/'));
+    log::debug!("Querying GitHub pages settings from '{url}' ...");
+    let response = net::send(settings, |client| {
+        let request = client
+            .get(&url)
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json");
+        match token {
+            Some(token) => request.bearer_auth(token.secret()),
+            None => request,
+        }
+    })?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        log::debug!("No GitHub pages found for '{slug}'.");
+        return Ok(None);
+    }
+    let pages: serde_json::Value = serde_json::from_str(&response.error_for_status()?.text()?)?;
+    Ok(pages
+        .get("html_url")
+        .and_then(serde_json::Value::as_str)
+        .map(|html_url| html_url.trim_end_matches('/').to_owned()))
+}
@@ -2,6 +2,16 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+pub mod cmd;
+pub mod credentials;
+pub mod forge;
 pub mod git;
+pub mod git_attributes;
 pub mod git_clone_url;
+#[cfg(feature = "gix")]
+mod git_gix;
 pub mod git_hosting_provs;
+#[cfg(feature = "online")]
+pub mod hosting_api;
+#[cfg(feature = "online")]
+pub mod net;
@@ -0,0 +1,178 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Common network access functionality,
+//! so all online sources behave the same
+//! regarding permission (`--online`/`--offline`),
+//! time-outs, retries, proxies and TLS certificates.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::{Certificate, StatusCode};
+use thiserror::Error;
+use url::Url;
+
+use crate::settings::Settings;
+
+/// The time waited before the first retry;
+/// it doubles with each further retry.
+const BACKOFF_INITIAL: Duration = Duration::from_millis(500);
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// The environment variables configuring proxies,
+/// which are respected by the HTTP client.
+/// The lower-case variants are respected too.
+const PROXY_VARS: &[&str] = &["HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY", "NO_PROXY"];
+
+/// This enumerates all possible errors returned by this module.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Network access is not allowed,
+    /// see [`Settings::online`].
+    #[error("Network access is disabled; see --online and --offline")]
+    Offline,
+
+    /// The CA bundle could not be read.
+    #[error("Failed to read the CA bundle '{path}': {source}")]
+    CaBundleRead { path: PathBuf, source: io::Error },
+
+    /// The CA bundle contains invalid certificates.
+    #[error("Failed to parse the CA bundle '{path}': {source}")]
+    CaBundleParse {
+        path: PathBuf,
+        source: reqwest::Error,
+    },
+
+    /// The request failed on the network or HTTP level.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+/// Reads all the (PEM encoded) certificates from a CA bundle file.
+fn read_ca_bundle(path: &Path) -> Result<Vec<Certificate>, Error> {
+    let pem = fs::read(path).map_err(|source| Error::CaBundleRead {
+        path: path.to_owned(),
+        source,
+    })?;
+    Certificate::from_pem_bundle(&pem).map_err(|source| Error::CaBundleParse {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+/// Logs the proxy settings in use,
+/// without the passwords they might contain.
+fn log_proxy_vars() {
+    for var in PROXY_VARS {
+        let value = env::var(var).or_else(|_| env::var(var.to_lowercase()));
+        if let Ok(value) = value {
+            let value = match Url::parse(&value) {
+                Ok(mut url) if url.password().is_some() => {
+                    url.set_password(Some("***")).ok();
+                    url.to_string()
+                }
+                _ => value,
+            };
+            log::debug!("Using proxy setting {var}='{value}'");
+        }
+    }
+}
+
+/// Creates an HTTP client configured according to the settings.
+///
+/// Most notably, it uses the time-out,
+/// and trusts the certificates in [`Settings::ca_bundle`] -
+/// if any - in addition to the built-in ones.
+/// Proxies are configured through the common environment variables
+/// (`HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY`).
+///
+/// # Errors
+///
+/// If network access is disabled,
+/// the CA bundle could not be read,
+/// or the client could not be initialized.
+pub fn client(settings: &Settings) -> Result<Client, Error> {
+    if !settings.online {
+        return Err(Error::Offline);
+    }
+    log_proxy_vars();
+    let mut builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(settings.network_timeout);
+    if let Some(ca_bundle) = &settings.ca_bundle {
+        let certificates = read_ca_bundle(ca_bundle)?;
+        log::debug!(
+            "Trusting {} additional certificate(s) from '{}'",
+            certificates.len(),
+            ca_bundle.display()
+        );
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    Ok(builder.build()?)
+}
+
+/// Whether the failure is likely to be transient,
+/// so retrying makes sense.
+fn is_transient(err: &reqwest::Error) -> bool {
+    err.is_timeout() || err.is_connect() || err.is_request()
+}
+
+/// Whether the response status indicates a transient failure on the server side,
+/// so retrying makes sense.
+fn is_transient_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Sends the request created by `build`, retrying on transient failures.
+///
+/// It retries with exponential back-off
+/// on time-outs, connection problems, 5xx and 429 responses,
+/// up to [`Settings::network_retries`] times.
+/// If the last try still results in an error response,
+/// that response is returned;
+/// checking the status is up to the caller.
+///
+/// # Errors
+///
+/// If network access is disabled,
+/// or the request failed on the network level on the last try.
+pub fn send(
+    settings: &Settings,
+    build: impl Fn(&Client) -> RequestBuilder,
+) -> Result<Response, Error> {
+    let client = client(settings)?;
+    let mut backoff = BACKOFF_INITIAL;
+    let mut retries_left = settings.network_retries;
+    loop {
+        let result = build(&client).send();
+        let retry = match &result {
+            Ok(response) => is_transient_status(response.status()),
+            Err(err) => is_transient(err),
+        };
+        if !retry || retries_left == 0 {
+            return Ok(result?);
+        }
+        match &result {
+            Ok(response) => log::debug!(
+                "Request to '{}' failed with status {}; retrying in {:?} ...",
+                response.url(),
+                response.status(),
+                backoff
+            ),
+            Err(err) => log::debug!("Request failed ({err}); retrying in {backoff:?} ..."),
+        }
+        thread::sleep(backoff);
+        backoff *= 2;
+        retries_left -= 1;
+    }
+}
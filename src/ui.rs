@@ -0,0 +1,119 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Human-facing rendering of output meant for the terminal
+//! (as opposed to files, which are meant for machines or other tools).
+
+use std::env;
+use std::io::{self, IsTerminal};
+
+use crate::settings::{Settings, Verbosity};
+use crate::storage::Table;
+
+/// See <https://no-color.org/>.
+const VAR_NO_COLOR: &str = "NO_COLOR";
+
+const STYLE_HEADER: &str = "\x1b[1m";
+const STYLE_MISSING: &str = "\x1b[2m";
+const STYLE_RESET: &str = "\x1b[0m";
+
+/// How a missing value is represented,
+/// to distinguish it from an empty one (`""`).
+const MISSING: &str = "-";
+
+/// Whether to use colors when writing to the log (stderr),
+/// which is the case if it is a terminal,
+/// we are not quiet,
+/// and the user did not opt out by setting `NO_COLOR`.
+#[must_use]
+pub fn use_color(settings: &Settings) -> bool {
+    settings.verbosity != Verbosity::None
+        && env::var_os(VAR_NO_COLOR).is_none_or(|no_color| no_color.is_empty())
+        && io::stderr().is_terminal()
+}
+
+fn push_cell(line: &mut String, content: &str, width: usize, style: Option<&str>) {
+    if let Some(style) = style {
+        line.push_str(style);
+    }
+    line.push_str(content);
+    if style.is_some() {
+        line.push_str(STYLE_RESET);
+    }
+    line.push_str(&" ".repeat(width.saturating_sub(content.chars().count())));
+}
+
+/// Renders a table as aligned plain-text,
+/// with the columns separated by two spaces,
+/// optionally with the header in bold and missing values dimmed.
+#[must_use]
+pub fn render_table(table: &Table, color: bool) -> String {
+    let display = |cell: &Option<String>| match cell.as_deref() {
+        None => MISSING.to_owned(),
+        Some("") => "\"\"".to_owned(),
+        Some(value) => value.replace('\n', "\\n"),
+    };
+    let rows: Vec<Vec<String>> = table
+        .rows
+        .iter()
+        .map(|row| row.iter().map(display).collect())
+        .collect();
+    let mut widths: Vec<usize> = table
+        .header
+        .iter()
+        .map(|column| column.chars().count())
+        .collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut output = String::new();
+    for (index, (column, width)) in table.header.iter().zip(&widths).enumerate() {
+        if index > 0 {
+            output.push_str("  ");
+        }
+        push_cell(&mut output, column, *width, color.then_some(STYLE_HEADER));
+    }
+    output.truncate(output.trim_end_matches(' ').len());
+    output.push('\n');
+    for (row, raw_row) in rows.iter().zip(&table.rows) {
+        for (index, ((cell, raw_cell), width)) in row.iter().zip(raw_row).zip(&widths).enumerate() {
+            if index > 0 {
+                output.push_str("  ");
+            }
+            let style = (color && raw_cell.is_none()).then_some(STYLE_MISSING);
+            push_cell(&mut output, cell, *width, style);
+        }
+        // no trailing white-space
+        output.truncate(output.trim_end_matches(' ').len());
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_table() {
+        let table = Table {
+            header: vec!["Property".to_owned(), "Value".to_owned()],
+            rows: vec![
+                vec![Some("Name".to_owned()), Some("projvar".to_owned())],
+                vec![Some("VersionDescribe".to_owned()), None],
+                vec![Some("Licenses".to_owned()), Some(String::new())],
+            ],
+        };
+        assert_eq!(
+            render_table(&table, false),
+            "Property         Value\n\
+Name             projvar\n\
+VersionDescribe  -\n\
+Licenses         \"\"\n"
+        );
+    }
+}
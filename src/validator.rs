@@ -2,28 +2,49 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::cleanup;
+#[cfg(feature = "license-detection")]
 use crate::license;
 use crate::tools::git;
 use crate::tools::git::TransferProtocol;
 use crate::tools::git_hosting_provs::HostingType;
-use crate::var::{Confidence, Key};
+use crate::var::{AnyKey, ConfidenceScore, Key};
 use crate::{constants, environment::Environment};
-use chrono::{DateTime, NaiveDateTime};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
 use lazy_static::lazy_static;
 use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::Display;
+use std::str::FromStr;
 use thiserror::Error;
 use url::Url;
 
 pub type Result = std::result::Result<Validity, Error>;
 pub type Validator = fn(&mut Environment, &str) -> Result;
 
-// TODO Document this function! (hae... what does it do? why two confidences?)
-// TODO Maybe make this use a custom struct as return instead?
+/// How much we trust a value to be valid, judging by the result of its validation.
+///
+/// This is on the same scale as the confidence of the sources
+/// (see [`ConfidenceScore`]).
+/// Any valid value scores higher than any invalid one,
+/// so the validation result can be compared as a whole.
+///
+/// ```
+/// # use projvar::validator::{res_to_confidence, Error, Validity};
+/// let unknown = res_to_confidence(&Ok(Validity::Unknown));
+/// let almost = res_to_confidence(&Err(Error::AlmostUsableValue {
+///     msg: "typo".to_owned(),
+///     value: "1.0".to_owned(),
+/// }));
+/// assert!(res_to_confidence(&Ok(Validity::High { msg: None })) > unknown);
+/// assert!(unknown > almost);
+/// assert!(almost > res_to_confidence(&Ok(Validity::Missing)));
+/// ```
 #[must_use]
-pub const fn res_to_confidences(res: &Result) -> [Confidence; 2] {
+pub const fn res_to_confidence(res: &Result) -> ConfidenceScore {
     match &res {
-        Ok(validity) => [validity.confidence(), 0],
-        Err(error) => [0, error.confidence()],
+        Ok(validity) => validity.confidence(),
+        Err(error) => error.confidence(),
     }
 }
 
@@ -50,6 +71,12 @@ pub enum Validity {
     /// no source returned a (valid) value for it.
     Missing,
 
+    /// The value is valid, but it is a URL into a project
+    /// that is not publicly accessible,
+    /// so it requires authentication to be accessed;
+    /// see [`Environment::private_reason`].
+    RequiresAuth { msg: String },
+
     /// The evaluated value is usable, but with a grain of salt - be suspcious!
     Suboptimal {
         msg: String,
@@ -62,15 +89,17 @@ pub enum Validity {
 }
 
 impl Validity {
+    /// See [`res_to_confidence`].
     #[must_use]
-    pub const fn confidence(&self) -> Confidence {
+    pub const fn confidence(&self) -> ConfidenceScore {
         match self {
-            Self::High { msg: _ } => 250,
-            Self::Middle { msg: _ } => 230,
-            Self::Low { msg: _ } => 210,
-            Self::Missing => 0,
-            Self::Suboptimal { msg: _, source: _ } => 200,
-            Self::Unknown => 100,
+            Self::High { msg: _ } => ConfidenceScore::MAX,
+            Self::Middle { msg: _ } => ConfidenceScore::new(92),
+            Self::Low { msg: _ } => ConfidenceScore::new(84),
+            Self::RequiresAuth { msg: _ } => ConfidenceScore::new(82),
+            Self::Missing => ConfidenceScore::NONE,
+            Self::Suboptimal { msg: _, source: _ } => ConfidenceScore::new(80),
+            Self::Unknown => ConfidenceScore::new(40),
         }
     }
 
@@ -79,7 +108,10 @@ impl Validity {
     #[must_use]
     pub const fn is_good(&self) -> bool {
         match self {
-            Self::High { msg: _ } | Self::Middle { msg: _ } | Self::Low { msg: _ } => true,
+            Self::High { msg: _ }
+            | Self::Middle { msg: _ }
+            | Self::Low { msg: _ }
+            | Self::RequiresAuth { msg: _ } => true,
             Self::Missing | Self::Suboptimal { msg: _, source: _ } | Self::Unknown => false,
         }
     }
@@ -119,13 +151,97 @@ pub enum Error {
 }
 
 impl Error {
+    /// See [`res_to_confidence`];
+    /// this is always below the confidence of any valid value,
+    /// except [`Validity::Missing`].
     #[must_use]
-    pub const fn confidence(&self) -> Confidence {
+    pub const fn confidence(&self) -> ConfidenceScore {
+        match self {
+            Self::Missing { .. } => ConfidenceScore::new(12),
+            Self::AlmostUsableValue { .. } => ConfidenceScore::new(30),
+            Self::BadValue { .. } => ConfidenceScore::new(15),
+            Self::IO(_) => ConfidenceScore::new(9),
+        }
+    }
+}
+
+/// The prefix of a policy that denies all values matching its regex,
+/// see [`Policy`].
+pub const POLICY_PREFIX_DENY: &str = "deny:";
+/// The (optional) prefix of a policy that requires values to match its regex,
+/// see [`Policy`].
+pub const POLICY_PREFIX_ALLOW: &str = "allow:";
+
+/// A user supplied rule for the values of a key.
+///
+/// It is checked in addition to the built-in validator,
+/// e.g. to enforce an organizations naming or versioning policy.
+/// It is parsed from a regex,
+/// optionally prefixed with [`POLICY_PREFIX_ALLOW`] or [`POLICY_PREFIX_DENY`].
+#[derive(Debug, Clone)]
+pub enum Policy {
+    /// Values have to match the regex.
+    Allow(Regex),
+    /// Values must not match the regex.
+    Deny(Regex),
+}
+
+impl FromStr for Policy {
+    type Err = regex::Error;
+
+    fn from_str(policy: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(
+            if let Some(pattern) = policy.strip_prefix(POLICY_PREFIX_DENY) {
+                Self::Deny(Regex::new(pattern)?)
+            } else {
+                Self::Allow(Regex::new(
+                    policy.strip_prefix(POLICY_PREFIX_ALLOW).unwrap_or(policy),
+                )?)
+            },
+        )
+    }
+}
+
+impl Display for Policy {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Allow(regex) => write!(formatter, "{POLICY_PREFIX_ALLOW}{regex}"),
+            Self::Deny(regex) => write!(formatter, "{POLICY_PREFIX_DENY}{regex}"),
+        }
+    }
+}
+
+/// Serializes to the same string it is parsed from.
+impl Serialize for Policy {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Policy {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let policy = String::deserialize(deserializer)?;
+        Self::from_str(&policy).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Policy {
+    /// Checks whether `value` conforms to this policy.
+    ///
+    /// # Errors
+    ///
+    /// If it does not.
+    pub fn check(&self, value: &str) -> std::result::Result<(), Error> {
         match self {
-            Self::Missing { .. } => 40,
-            Self::AlmostUsableValue { .. } => 100,
-            Self::BadValue { .. } => 50,
-            Self::IO(_) => 30,
+            Self::Allow(regex) if !regex.is_match(value) => Err(Error::BadValue {
+                msg: format!("It does not match the configured pattern '{regex}'"),
+                value: value.to_owned(),
+            }),
+            Self::Deny(regex) if regex.is_match(value) => Err(Error::BadValue {
+                msg: format!("It matches the configured deny pattern '{regex}'"),
+                value: value.to_owned(),
+            }),
+            Self::Allow(_) | Self::Deny(_) => Ok(()),
         }
     }
 }
@@ -143,7 +259,6 @@ fn validate_version(environment: &mut Environment, value: &str) -> Result {
     lazy_static! {
         // The official SemVer regex as of September 2021, taken from
         // https://semver.org/#is-there-a-suggested-regular-expression-regex-to-check-a-semver-string
-        // TODO PRIO Think of what to do if we have a "v" prefix, as in "v1.2.3" -> best: remove it, but where.. a kind of pre-validator function?
         // TODO PRIO Use this create for semver checking: https://github.com/dtolnay/semver (does not need to be with a Regex!)
         static ref R_SEM_VERS_RELEASE: Regex = Regex::new(r"^(?P<major>0|[1-9]\d*)\.(?P<minor>0|[1-9]\d*)\.(?P<patch>0|[1-9]\d*)$").unwrap();
         static ref R_SEM_VERS: Regex = Regex::new(r"^(?P<major>0|[1-9]\d*)\.(?P<minor>0|[1-9]\d*)\.(?P<patch>0|[1-9]\d*)(?:-(?P<prerelease>(?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*)(?:\.(?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*))*))?(?:\+(?P<buildmetadata>[0-9a-zA-Z-]+(?:\.[0-9a-zA-Z-]+)*))?$").unwrap();
@@ -153,6 +268,14 @@ fn validate_version(environment: &mut Environment, value: &str) -> Result {
         static ref R_GIT_SHA_PREFIX: Regex = Regex::new(r"^g[0-9a-f]{7}").unwrap();
         static ref R_UNKNOWN_VERS: Regex = Regex::new(r"^($|#|//)").unwrap();
     }
+    // The sources clean up prefixes like "v" or "release-" (see [`cleanup::version`]),
+    // so such a prefix here probably comes from a user-supplied value;
+    // we validate what remains.
+    let stripped_value = cleanup::version(environment, value);
+    if let Some(stripped_value) = &stripped_value {
+        log::debug!("Validating version '{value}' without its prefix, as '{stripped_value}'");
+    }
+    let value = stripped_value.as_deref().unwrap_or(value);
     if R_SEM_VERS_RELEASE.is_match(value) {
         Ok(Validity::Low {
             msg: "This is a release version, \
@@ -254,43 +377,79 @@ but not a release-version, and not human-readable"
     }
 }
 
+fn validate_version_describe(environment: &mut Environment, value: &str) -> Result {
+    lazy_static! {
+        // "<tag>[-<distance>-g<SHA>][-dirty]" or "<SHA>[-dirty]"
+        static ref R_GIT_DESCRIBE: Regex = Regex::new(r"^[^~^:?\[\*\s]+?(-(0|[1-9]\d*)-g[0-9a-f]{4,64})?(-dirty)?$").unwrap();
+    }
+    check_empty(environment, value, "Version describe")?;
+    if !R_GIT_DESCRIBE.is_match(value) {
+        Err(Error::BadValue {
+            msg: "Not a valid git describe output".to_owned(),
+            value: value.to_owned(),
+        })
+    } else if git::is_git_dirty_version(value) {
+        Ok(Validity::Middle {
+            msg: "A git dirty version; you have uncommitted changes in your project".to_owned(),
+        })
+    } else {
+        Ok(Validity::High { msg: None })
+    }
+}
+
 fn validate_license(environment: &mut Environment, value: &str) -> Result {
     if value.is_empty() {
         missing(environment, Key::License)
     } else {
-        license::validate_spdx_expr(value).map_or_else(
-            |err| {
-                match err {
-                    license::Error::NoLicense => Ok(Validity::Suboptimal {
-                        msg: "Not a recognized SPDX license identifier".to_owned(),
-                        source: Some(Box::new(err)),
-                    }),
-                    license::Error::ParsingFailed(_) => Ok(Validity::Suboptimal {
-                        msg: "Not a valid SPDX license expression".to_owned(),
-                        source: Some(Box::new(err)),
-                    }),
-                    license::Error::NotApproved(_) => Ok(Validity::Low {
-                        // TODO We are loosing the detailed info here!
-                        msg: "Not only approved licenses".to_owned(),
-                    }),
-                }
-            },
-            |()| {
-                Ok(Validity::High {
-                    msg: Some("Consists of an SPDX license identifier".to_owned()),
-                })
-            },
-        )
+        validate_spdx_expr(value)
     }
 }
 
+#[cfg(feature = "license-detection")]
+fn validate_spdx_expr(value: &str) -> Result {
+    license::validate_spdx_expr(value).map_or_else(
+        |err| {
+            match err {
+                license::Error::NoLicense => Ok(Validity::Suboptimal {
+                    msg: "Not a recognized SPDX license identifier".to_owned(),
+                    source: Some(Box::new(err)),
+                }),
+                license::Error::ParsingFailed(_) => Ok(Validity::Suboptimal {
+                    msg: "Not a valid SPDX license expression".to_owned(),
+                    source: Some(Box::new(err)),
+                }),
+                license::Error::NotApproved(_) => Ok(Validity::Low {
+                    // TODO We are loosing the detailed info here!
+                    msg: "Not only approved licenses".to_owned(),
+                }),
+            }
+        },
+        |()| {
+            Ok(Validity::High {
+                msg: Some("Consists of an SPDX license identifier".to_owned()),
+            })
+        },
+    )
+}
+
+/// Without the SPDX license list,
+/// we have no way to check license expressions.
+#[cfg(not(feature = "license-detection"))]
+#[allow(clippy::unnecessary_wraps)]
+const fn validate_spdx_expr(_value: &str) -> Result {
+    Ok(Validity::Unknown)
+}
+
 fn validate_licenses(environment: &mut Environment, value: &str) -> Result {
     if value.is_empty() {
         missing(environment, Key::Licenses)
     } else {
         // TODO PRIO Implement SPDX expressions detection, not just (as is now) single identifiers; see: TODO
-        for license in value.split(',') {
-            let license = license.trim();
+        for license in value
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|license| !license.is_empty())
+        {
             let res = validate_license(environment, license);
             if let Err(err) = res {
                 return Ok(Validity::Suboptimal {
@@ -303,12 +462,24 @@ fn validate_licenses(environment: &mut Environment, value: &str) -> Result {
         }
         Ok(Validity::High {
             msg: Some(
-                "Consists of a list of SPDX license identifiers, separated by ','".to_owned(),
+                "Consists of a list of SPDX license identifiers, separated by ',' or line breaks"
+                    .to_owned(),
             ),
         })
     }
 }
 
+fn validate_licenses_compliance(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Licenses compliance")?;
+    match value.parse::<u8>() {
+        Ok(percentage) if percentage <= 100 => Ok(Validity::High { msg: None }),
+        Ok(_) | Err(_) => Err(Error::BadValue {
+            msg: "Not a percentage (an integer between 0 and 100)".to_owned(),
+            value: value.to_owned(),
+        }),
+    }
+}
+
 fn check_public_url(
     _environment: &mut Environment,
     value: &str,
@@ -389,15 +560,48 @@ fn eval_hosting_type_from_hosting_suffix(environment: &mut Environment, url: &Ur
     environment.settings.hosting_type_from_hosting_suffix(url)
 }
 
-fn check_url_path(value: &str, url_desc: &str, url: &Url, path_reg: Option<&Regex>) -> Result {
+/// Turns the validity of a URL that is meant to be accessed anonymously
+/// into [`Validity::RequiresAuth`],
+/// if the project is likely private.
+fn check_requires_auth(environment: &Environment, validity: Validity, url_desc: &str) -> Validity {
+    if !validity.is_good() {
+        return validity;
+    }
+    match environment.private_reason() {
+        Some(reason) => Validity::RequiresAuth {
+            msg: format!(
+                "The {url_desc} URL is likely not accessible anonymously, \
+                because the project seems to be private ({reason})"
+            ),
+        },
+        None => validity,
+    }
+}
+
+/// Checks the path of a URL against a regex,
+/// after removing the hosting base path (if any),
+/// see [`crate::settings::Settings::strip_hosting_base_path`].
+fn check_url_path(
+    environment: &Environment,
+    value: &str,
+    url_desc: &str,
+    url: &Url,
+    path_reg: Option<&Regex>,
+) -> Result {
     if let (Some(path_reg), Some(host)) = (path_reg, url.host().as_ref()) {
-        if path_reg.is_match(url.path()) {
+        let path = format!(
+            "/{}",
+            environment
+                .settings
+                .strip_hosting_base_path(&host.to_string(), url.path())
+        );
+        if path_reg.is_match(&path) {
             Ok(Validity::High {
                 msg: Some(format!(
                     r#"For {}, the path part of the {} URL ("{}") matches regex "{}""#,
                     host,
                     url_desc,
-                    url.path(),
+                    path,
                     path_reg.as_str()
                 )),
             })
@@ -407,7 +611,7 @@ fn check_url_path(value: &str, url_desc: &str, url: &Url, path_reg: Option<&Rege
                     r#"For {}, this path part of the {} URL is invalid: "{}"; it should match "{}""#,
                     host,
                     url_desc,
-                    url.path(),
+                    path,
                     path_reg.as_str()
                 ),
                 value: value.to_owned(),
@@ -449,31 +653,10 @@ fn check_url_host(value: &str, url_desc: &str, url: &Url, host_reg: Option<&Rege
 }
 
 fn validate_repo_web_url(environment: &mut Environment, value: &str) -> Result {
-    lazy_static! {
-        static ref R_GIT_HUB_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/?$").unwrap();
-        static ref R_GIT_LAB_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)/?$").unwrap();
-        static ref R_BIT_BUCKET_PATH: Regex = (*R_GIT_HUB_PATH).clone();
-    }
-
     let url = check_public_url(environment, value, false, false)?;
     let hosting_type = eval_hosting_type(environment, &url);
-    let host_reg: Option<&Regex> = match hosting_type {
-        HostingType::GitHub => Some(&R_GIT_HUB_PATH),
-        HostingType::GitLab => Some(&R_GIT_LAB_PATH),
-        HostingType::BitBucket => Some(&R_BIT_BUCKET_PATH),
-        _ => None, // TODO Implement the others
-    };
-    check_url_path(value, "versioned web", &url, host_reg)
-}
-
-lazy_static! {
-    static ref R_GIT_HUB_CLONE_PATH: Regex =
-        Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)(\.git)?$").unwrap();
-    static ref R_GIT_LAB_CLONE_PATH: Regex =
-        Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)(\.git)?$").unwrap();
-    static ref R_BIT_BUCKET_CLONE_PATH: Regex = (*R_GIT_HUB_CLONE_PATH).clone();
+    let path_reg = hosting_type.forge().web_path_regex();
+    check_url_path(environment, value, "versioned web", &url, path_reg)
 }
 
 /// Many possible formats, see:
@@ -516,13 +699,8 @@ fn validate_repo_clone_url_generic(
         });
     }
     let hosting_type = eval_hosting_type(environment, &url);
-    let host_reg: Option<&Regex> = match hosting_type {
-        HostingType::GitHub => Some(&R_GIT_HUB_CLONE_PATH),
-        HostingType::GitLab => Some(&R_GIT_LAB_CLONE_PATH),
-        HostingType::BitBucket => Some(&R_BIT_BUCKET_CLONE_PATH),
-        _ => None, // TODO Implement the others
-    };
-    check_url_path(value, "repo clone", &url, host_reg)
+    let path_reg = hosting_type.forge().clone_path_regex();
+    check_url_path(environment, value, "repo clone", &url, path_reg)
 }
 
 // * git://repo.or.cz/girocco.git
@@ -535,6 +713,15 @@ fn validate_repo_clone_url_http(environment: &mut Environment, value: &str) -> R
     validate_repo_clone_url_generic(environment, value, TransferProtocol::Https)
 }
 
+/// Push URLs usually require authentication,
+/// and often use a user other than the anonymous "git",
+/// so we only check that it is a valid git URL.
+// * git@github.com:hoijui/projvar.git
+// * https://hoijui@bitbucket.org/Aouatef/master_arbeit.git
+fn validate_repo_clone_url_push(environment: &mut Environment, value: &str) -> Result {
+    validate_repo_clone_url(environment, value)
+}
+
 // * git@bitbucket.org:Aouatef/master_arbeit.git
 // * ssh://bitbucket.org/Aouatef/master_arbeit.git
 fn validate_repo_clone_url_ssh(environment: &mut Environment, value: &str) -> Result {
@@ -569,13 +756,8 @@ fn validate_repo_clone_url_ssh(environment: &mut Environment, value: &str) -> Re
     };
 
     let hosting_type = eval_hosting_type(environment, &url);
-    let host_reg: Option<&Regex> = match hosting_type {
-        HostingType::GitHub => Some(&R_GIT_HUB_CLONE_PATH),
-        HostingType::GitLab => Some(&R_GIT_LAB_CLONE_PATH),
-        HostingType::BitBucket => Some(&R_BIT_BUCKET_CLONE_PATH),
-        _ => None, // TODO Implement the others
-    };
-    check_url_path(value, "repo clone ssh", &url, host_reg)
+    let path_reg = hosting_type.forge().clone_path_regex();
+    check_url_path(environment, value, "repo clone ssh", &url, path_reg)
 }
 
 /// See also `sources::try_construct_raw_prefix_url`.
@@ -584,139 +766,166 @@ fn validate_repo_clone_url_ssh(environment: &mut Environment, value: &str) -> Re
 // * https://gitlab.com/OSEGermany/osh-tool/raw/master/data/source_extension_formats.csv
 // * https://bitbucket.org/Aouatef/master_arbeit/raw/ae4a42a850b359a23da2483eb8f867f21c5382d4/procExData/import.sh
 fn validate_repo_raw_versioned_prefix_url(environment: &mut Environment, value: &str) -> Result {
-    lazy_static! {
-        static ref R_GIT_HUB_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)$").unwrap();
-        static ref R_GIT_LAB_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)/(-/)?raw$")
-                .unwrap();
-        static ref R_BIT_BUCKET_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/raw$").unwrap();
-    }
-
     let url = check_public_url(environment, value, false, false)?;
     let hosting_type = eval_hosting_type(environment, &url);
-    let host_reg: Option<&Regex> = match hosting_type {
-        HostingType::GitHub => Some(&R_GIT_HUB_PATH),
-        HostingType::GitLab => Some(&R_GIT_LAB_PATH),
-        HostingType::BitBucket => Some(&R_BIT_BUCKET_PATH),
-        _ => None, // TODO Implement the others
-    };
-    check_url_path(value, "raw versioned prefix", &url, host_reg)
+    let path_reg = hosting_type.forge().raw_prefix_path_regex();
+    check_url_path(environment, value, "raw versioned prefix", &url, path_reg)
+        .map(|validity| check_requires_auth(environment, validity, "raw versioned prefix"))
 }
 
 /// See also `sources::try_construct_file_prefix_url`.
 fn validate_repo_versioned_file_prefix_url(environment: &mut Environment, value: &str) -> Result {
-    lazy_static! {
-        static ref R_GIT_HUB_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/blob$").unwrap();
-        static ref R_GIT_LAB_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)/(-/)?blob$")
-                .unwrap();
-        static ref R_BIT_BUCKET_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/src$").unwrap();
-    }
-
     let url = check_public_url(environment, value, false, false)?;
     let hosting_type = eval_hosting_type(environment, &url);
-    let host_reg: Option<&Regex> = match hosting_type {
-        HostingType::GitHub => Some(&R_GIT_HUB_PATH),
-        HostingType::GitLab => Some(&R_GIT_LAB_PATH),
-        HostingType::BitBucket => Some(&R_BIT_BUCKET_PATH),
-        _ => None, // TODO Implement the others
-    };
-    check_url_path(value, "versioned file prefix", &url, host_reg)
+    let path_reg = hosting_type.forge().versioned_file_prefix_path_regex();
+    check_url_path(environment, value, "versioned file prefix", &url, path_reg)
 }
 
 /// See also `sources::try_construct_file_prefix_url`.
 fn validate_repo_versioned_dir_prefix_url(environment: &mut Environment, value: &str) -> Result {
-    lazy_static! {
-        static ref R_GIT_HUB_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/tree$").unwrap();
-        static ref R_GIT_LAB_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)/(-/)?tree$")
-                .unwrap();
-        static ref R_BIT_BUCKET_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/src$").unwrap();
-    }
-
     let url = check_public_url(environment, value, false, false)?;
     let hosting_type = eval_hosting_type(environment, &url);
-    let host_reg: Option<&Regex> = match hosting_type {
-        HostingType::GitHub => Some(&R_GIT_HUB_PATH),
-        HostingType::GitLab => Some(&R_GIT_LAB_PATH),
-        HostingType::BitBucket => Some(&R_BIT_BUCKET_PATH),
-        _ => None, // TODO Implement the others
-    };
-    check_url_path(value, "versioned dir prefix", &url, host_reg)
+    let path_reg = hosting_type.forge().versioned_dir_prefix_path_regex();
+    check_url_path(environment, value, "versioned dir prefix", &url, path_reg)
 }
 
 /// See also `sources::try_construct_commit_prefix_url`.
 fn validate_repo_commit_prefix_url(environment: &mut Environment, value: &str) -> Result {
-    lazy_static! {
-        static ref R_GIT_HUB_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/commit$").unwrap();
-        static ref R_GIT_LAB_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)/(-/)?commit$")
-                .unwrap();
-        static ref R_BIT_BUCKET_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/commits$").unwrap();
-    }
-
     let url = check_public_url(environment, value, false, false)?;
     let hosting_type = eval_hosting_type(environment, &url);
-    let host_reg: Option<&Regex> = match hosting_type {
-        HostingType::GitHub => Some(&R_GIT_HUB_PATH),
-        HostingType::GitLab => Some(&R_GIT_LAB_PATH),
-        HostingType::BitBucket => Some(&R_BIT_BUCKET_PATH),
-        _ => None, // TODO Implement the others
-    };
-    check_url_path(value, "commit prefix", &url, host_reg)
+    let path_reg = hosting_type.forge().commit_prefix_path_regex();
+    check_url_path(environment, value, "commit prefix", &url, path_reg)
 }
 
 fn validate_repo_issues_url(environment: &mut Environment, value: &str) -> Result {
-    lazy_static! {
-        static ref R_GIT_HUB_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/issues$").unwrap();
-        static ref R_GIT_LAB_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)/(-/)?issues$")
-                .unwrap();
-        static ref R_BIT_BUCKET_PATH: Regex =
-            Regex::new(r"^/(?P<user>[^/]+)/(?P<repo>[^/]+)/issues$").unwrap();
-    }
+    let url = check_public_url(environment, value, false, false)?;
+    let hosting_type = eval_hosting_type(environment, &url);
+    let path_reg = hosting_type.forge().issues_path_regex();
+    check_url_path(environment, value, "issues", &url, path_reg)
+}
 
+fn validate_repo_releases_url(environment: &mut Environment, value: &str) -> Result {
     let url = check_public_url(environment, value, false, false)?;
     let hosting_type = eval_hosting_type(environment, &url);
-    let host_reg: Option<&Regex> = match hosting_type {
-        HostingType::GitHub => Some(&R_GIT_HUB_PATH),
-        HostingType::GitLab => Some(&R_GIT_LAB_PATH),
-        HostingType::BitBucket => Some(&R_BIT_BUCKET_PATH),
-        _ => None, // TODO Implement the others
-    };
-    check_url_path(value, "issues", &url, host_reg)
+    let path_reg = hosting_type.forge().releases_path_regex();
+    check_url_path(environment, value, "releases", &url, path_reg)
 }
 
-fn validate_build_hosting_url(environment: &mut Environment, value: &str) -> Result {
+fn validate_repo_ci_url(environment: &mut Environment, value: &str) -> Result {
+    let url = check_public_url(environment, value, false, false)?;
+    let hosting_type = eval_hosting_type(environment, &url);
+    let path_reg = hosting_type.forge().ci_path_regex();
+    check_url_path(environment, value, "CI pipelines", &url, path_reg)
+}
+
+fn validate_repo_slug(environment: &mut Environment, value: &str) -> Result {
+    lazy_static! {
+        static ref R_SLUG: Regex =
+            Regex::new(r"^(?P<user>[^/]+)/((?P<structure>[^/]+)/)*(?P<repo>[^/]+)$").unwrap();
+    }
+
+    check_empty(environment, value, "Repo slug")?;
+    if R_SLUG.is_match(value) {
+        Ok(Validity::High {
+            msg: Some(format!("Matches regex '{}'", R_SLUG.as_str())),
+        })
+    } else {
+        Err(Error::BadValue {
+            msg: format!(
+                "Not a project slug (user(/group)*/project), does not match '{}'",
+                R_SLUG.as_str()
+            ),
+            value: value.to_owned(),
+        })
+    }
+}
+
+fn validate_repo_owner(environment: &mut Environment, value: &str) -> Result {
+    lazy_static! {
+        static ref R_OWNER: Regex = Regex::new(r"^[^/]+$").unwrap();
+    }
+
+    check_empty(environment, value, "Repo owner")?;
+    if R_OWNER.is_match(value) {
+        Ok(Validity::High {
+            msg: Some(format!("Matches regex '{}'", R_OWNER.as_str())),
+        })
+    } else {
+        Err(Error::BadValue {
+            msg: format!(
+                "Not a user or organization name, does not match '{}'",
+                R_OWNER.as_str()
+            ),
+            value: value.to_owned(),
+        })
+    }
+}
+
+fn validate_repo_namespace(environment: &mut Environment, value: &str) -> Result {
     lazy_static! {
-        static ref R_GIT_HUB_HOST: Regex = Regex::new(r"^(?P<user>[^/.]+)\.github\.io$").unwrap();
-        static ref R_GIT_LAB_HOST: Regex = Regex::new(r"^(?P<user>[^/.]+)\.gitlab\.io$").unwrap();
-        // NOTE BitBucket does not have this feature, it only supports one "page" repo per user, not per repo
+        static ref R_NAMESPACE: Regex =
+            Regex::new(r"^(?P<user>[^/]+)(/(?P<structure>[^/]+))*$").unwrap();
+    }
+
+    check_empty(environment, value, "Repo namespace")?;
+    if R_NAMESPACE.is_match(value) {
+        Ok(Validity::High {
+            msg: Some(format!("Matches regex '{}'", R_NAMESPACE.as_str())),
+        })
+    } else {
+        Err(Error::BadValue {
+            msg: format!(
+                "Not a namespace (user(/group)*), does not match '{}'",
+                R_NAMESPACE.as_str()
+            ),
+            value: value.to_owned(),
+        })
     }
+}
 
+fn validate_build_hosting_url(environment: &mut Environment, value: &str) -> Result {
     let url = check_public_url(environment, value, false, false)?;
-    let hosting_type = eval_hosting_type_from_hosting_suffix(environment, &url);
-    let host_reg: Option<&Regex> = match hosting_type {
-        HostingType::GitHub => Some(&R_GIT_HUB_HOST),
-        HostingType::GitLab => Some(&R_GIT_LAB_HOST),
-        _ => None, // TODO Implement the others (BitBucket does not have pages though, so skip it!)
+    // The pages domain of the (possibly self-hosted) GitLab instance we run on
+    let custom_pages_domain = environment
+        .vars
+        .get(constants::VAR_CI_PAGES_DOMAIN)
+        .filter(|pages_domain| !pages_domain.is_empty())
+        .cloned();
+    let on_custom_pages_domain = custom_pages_domain.as_ref().is_some_and(|pages_domain| {
+        url.host_str()
+            .and_then(|host| host.strip_suffix(pages_domain.as_str()))
+            .is_some_and(|sub| sub.ends_with('.'))
+    });
+    let hosting_type = if on_custom_pages_domain {
+        HostingType::GitLab
+    } else {
+        eval_hosting_type_from_hosting_suffix(environment, &url)
     };
-    check_url_host(value, "build hosting", &url, host_reg)
+    let pages_suffix = custom_pages_domain
+        .filter(|_| on_custom_pages_domain)
+        .or_else(|| {
+            hosting_type
+                .forge()
+                .pages_site()
+                .pages_suffix()
+                .map(ToOwned::to_owned)
+        });
+    let host_reg = pages_suffix.map(|suffix| {
+        Regex::new(&format!(r"^(?P<user>[^/.]+)\.{}$", regex::escape(&suffix)))
+            .expect("An escaped domain always makes for a valid regex")
+    });
+    check_url_host(value, "build hosting", &url, host_reg.as_ref())
+        .map(|validity| check_requires_auth(environment, validity, "build hosting"))
 }
 
 fn validate_name(environment: &mut Environment, value: &str) -> Result {
     check_empty(environment, value, "Project name (human-readable)")
 }
 
+fn validate_description(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Project description")
+}
+
 fn validate_name_machine_readable(environment: &mut Environment, value: &str) -> Result {
     lazy_static! {
         static ref R_MACHINE_READABLE: Regex = Regex::new(r"^[0-9a-zA-Z_-]+$").unwrap();
@@ -738,7 +947,28 @@ fn validate_name_machine_readable(environment: &mut Environment, value: &str) ->
     }
 }
 
-fn check_date(environment: &mut Environment, value: &str, date_desc: &str) -> Result {
+fn validate_name_slug(environment: &mut Environment, value: &str) -> Result {
+    lazy_static! {
+        static ref R_SLUG: Regex = Regex::new(r"^[0-9a-z]+(-[0-9a-z]+)*$").unwrap();
+    }
+
+    check_empty(environment, value, "Project name (slug)")?;
+    if R_SLUG.is_match(value) {
+        Ok(Validity::High {
+            msg: Some(format!("Matches regex '{}'", R_SLUG.as_str())),
+        })
+    } else {
+        Err(Error::BadValue {
+            msg: format!(
+                "Name is not a lower-case kebab-case slug, does not match '{}'",
+                R_SLUG.as_str()
+            ),
+            value: value.to_owned(),
+        })
+    }
+}
+
+fn check_date(environment: &mut Environment, value: &str, key: Key, date_desc: &str) -> Result {
     if value.is_empty() {
         return Err(Error::BadValue {
             // TODO Maybe replace with a call to missing(...) ?
@@ -747,34 +977,97 @@ fn check_date(environment: &mut Environment, value: &str, date_desc: &str) -> Re
         });
     }
 
-    let parse_err = NaiveDateTime::parse_from_str(value, &environment.settings.date_format)
+    let date_format = environment.settings.date_format_for(key).to_owned();
+    // NOTE This only succeeds if the date format contains an offset
+    let parsed_with_offset = DateTime::parse_from_str(value, &date_format);
+    let parse_err = NaiveDateTime::parse_from_str(value, &date_format)
         .err()
-        .and_then(|_err| DateTime::parse_from_str(value, &environment.settings.date_format).err());
+        // NOTE This succeeds for date formats without a time
+        .and_then(|_err| NaiveDate::parse_from_str(value, &date_format).err())
+        .and_then(|_err| parsed_with_offset.as_ref().err().copied());
+    let expected_offset = environment.settings.date_timezone.fixed_offset();
     if let Some(err) = parse_err {
-        // log::error!("XXX {}", NaiveDateTime::parse_from_str(value, &environment.settings.date_format).unwrap_err());
         Err(Error::BadValue {
             msg: format!(
                 r#"Not a {} date according to the date-format "{}": {}"#,
-                date_desc, environment.settings.date_format, err
+                date_desc, date_format, err
             ),
             value: value.to_owned(),
         })
+    } else if let (Ok(parsed), Some(expected_offset)) = (parsed_with_offset, expected_offset) {
+        if parsed.offset() == &expected_offset {
+            Ok(Validity::High {
+                msg: Some(format!(
+                    "Matches the date format '{}' and time zone '{}'",
+                    date_format, environment.settings.date_timezone
+                )),
+            })
+        } else {
+            Ok(Validity::Middle {
+                msg: format!(
+                    "The offset '{}' differs from the configured time zone '{}'",
+                    parsed.offset(),
+                    environment.settings.date_timezone
+                ),
+            })
+        }
     } else {
         Ok(Validity::High {
-            msg: Some(format!(
-                "Matches the date format '{}'",
-                environment.settings.date_format
-            )),
+            msg: Some(format!("Matches the date format '{date_format}'")),
         })
     }
 }
 
+fn validate_version_number_component(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Version number component")?;
+    match value.parse::<u64>() {
+        Ok(_) => Ok(Validity::High { msg: None }),
+        Err(err) => Err(Error::BadValue {
+            msg: format!("Not a non-negative integer: {err}"),
+            value: value.to_owned(),
+        }),
+    }
+}
+
+fn validate_version_prerelease(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Version pre-release")?;
+    match semver::Prerelease::new(value) {
+        Ok(_) => Ok(Validity::High { msg: None }),
+        Err(err) => Err(Error::BadValue {
+            msg: format!("Not a valid SemVer pre-release: {err}"),
+            value: value.to_owned(),
+        }),
+    }
+}
+
+fn validate_version_build_meta(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Version build metadata")?;
+    match semver::BuildMetadata::new(value) {
+        Ok(_) => Ok(Validity::High { msg: None }),
+        Err(err) => Err(Error::BadValue {
+            msg: format!("Not valid SemVer build metadata: {err}"),
+            value: value.to_owned(),
+        }),
+    }
+}
+
 fn validate_version_date(environment: &mut Environment, value: &str) -> Result {
-    check_date(environment, value, "version")
+    check_date(environment, value, Key::VersionDate, "version")
 }
 
 fn validate_build_date(environment: &mut Environment, value: &str) -> Result {
-    check_date(environment, value, "build")
+    check_date(environment, value, Key::BuildDate, "build")
+}
+
+fn validate_build_date_epoch(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Build date epoch")?;
+    match value.parse::<u64>() {
+        Ok(_) => Ok(Validity::High { msg: None }),
+        Err(err) => Err(Error::BadValue {
+            msg: format!("Not a valid Unix timestamp: {err}"),
+            value: value.to_owned(),
+        }),
+    }
 }
 
 fn validate_build_branch(environment: &mut Environment, value: &str) -> Result {
@@ -809,6 +1102,21 @@ fn validate_build_os_family(environment: &mut Environment, value: &str) -> Resul
     }
 }
 
+fn validate_version_control_system(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Version control system")?;
+    if constants::VALID_VCSS.contains(&value) {
+        Ok(Validity::High { msg: None })
+    } else {
+        Err(Error::BadValue {
+            msg: format!(
+                "Only these values are valid: {}",
+                constants::VALID_VCSS.join(", ")
+            ),
+            value: value.to_owned(),
+        })
+    }
+}
+
 fn validate_build_arch(environment: &mut Environment, value: &str) -> Result {
     check_empty(environment, value, "Build arch")?;
     if constants::VALID_ARCHS.contains(&value) {
@@ -829,71 +1137,323 @@ fn validate_build_arch(environment: &mut Environment, value: &str) -> Result {
     }
 }
 
-fn validate_build_number(environment: &mut Environment, value: &str) -> Result {
-    check_empty(environment, value, "Build number")?;
-    match value.parse::<i32>() {
-        Err(_err) => Ok(Validity::Suboptimal {
-            msg: "It is generally recommended and assumed that the build number is an integer (a positive, whole number)".to_owned(),
+fn validate_build_host_platform_triple(environment: &mut Environment, value: &str) -> Result {
+    lazy_static! {
+        // arch-vendor-os(-env), though some triples lack the vendor part,
+        // for example "aarch64-linux-android"
+        static ref R_PLATFORM_TRIPLE: Regex =
+            Regex::new(r"^[0-9a-z_]+(-[0-9a-z_.]+){1,3}$").unwrap();
+    }
+
+    check_empty(environment, value, "Build host platform triple")?;
+    if R_PLATFORM_TRIPLE.is_match(value) {
+        Ok(Validity::High {
+            msg: Some(format!("Matches regex '{}'", R_PLATFORM_TRIPLE.as_str())),
+        })
+    } else {
+        Err(Error::BadValue {
+            msg: format!(
+                "Not a platform triple, does not match '{}'",
+                R_PLATFORM_TRIPLE.as_str()
+            ),
+            value: value.to_owned(),
+        })
+    }
+}
+
+fn validate_build_host_kernel_version(environment: &mut Environment, value: &str) -> Result {
+    lazy_static! {
+        static ref R_KERNEL_VERSION: Regex = Regex::new(r"^[0-9]+(\.[0-9]+)+").unwrap();
+    }
+
+    check_empty(environment, value, "Build host kernel version")?;
+    if R_KERNEL_VERSION.is_match(value) {
+        Ok(Validity::High {
+            msg: Some(format!("Matches regex '{}'", R_KERNEL_VERSION.as_str())),
+        })
+    } else {
+        Ok(Validity::Suboptimal {
+            msg: format!(
+                "Kernel versions usually start with a numeric version, matching '{}'",
+                R_KERNEL_VERSION.as_str()
+            ),
             source: None,
-        }),
-        Ok(_int_value) => Ok(Validity::High { msg: Some("Is a build number (positive integer)".to_owned()) })
+        })
     }
 }
 
-fn validate_ci(environment: &mut Environment, value: &str) -> Result {
-    check_empty(environment, value, "CI")?;
-    match value {
-        "true" => Ok(Validity::High { msg: None }),
-        "false" => Ok(Validity::Middle {
-            msg: "Nothing wrong with that, but any 'true' value will get prefference over 'false'"
-                .to_owned(),
+fn validate_build_host_cpu_count(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Build host CPU count")?;
+    match value.parse::<u32>() {
+        Ok(cpu_count) if cpu_count > 0 => Ok(Validity::High {
+            msg: Some("Is a CPU count (positive integer)".to_owned()),
         }),
-        &_ => Err(Error::BadValue {
-            msg:
-                r"CI can be 'true', 'false' or be ommitted (None), which get interpreted as 'false'"
-                    .to_owned(),
+        _ => Err(Error::BadValue {
+            msg: "The CPU count has to be a positive integer".to_owned(),
             value: value.to_owned(),
         }),
     }
 }
 
-#[remain::check]
-#[must_use]
-pub fn get(key: Key) -> Validator {
-    // TODO This match could be written by a macro
-    #[remain::sorted]
-    match key {
-        Key::BuildArch => validate_build_arch,
+/// The lengths of full SHA-1 and SHA-256 commit hashes,
+/// the latter being used by git repos with `extensions.objectFormat = sha256`.
+const SHA_LENGTHS: [usize; 2] = [40, 64];
+/// The minimum length git abbreviates commit hashes to.
+const SHA_SHORT_MIN_LENGTH: usize = 4;
+
+fn check_hex(value: &str, part_desc: &str) -> Result {
+    if value.chars().all(|chr| chr.is_ascii_hexdigit()) {
+        Ok(Validity::High { msg: None })
+    } else {
+        Err(Error::BadValue {
+            msg: format!("{part_desc} has to consist of hex digits only"),
+            value: value.to_owned(),
+        })
+    }
+}
+
+fn validate_build_commit_sha(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Build commit SHA")?;
+    check_hex(value, "Build commit SHA")?;
+    if SHA_LENGTHS.contains(&value.len()) {
+        Ok(Validity::High { msg: None })
+    } else {
+        Err(Error::BadValue {
+            msg: format!(
+                "A full commit SHA has to be {} (SHA-1) or {} (SHA-256) hex digits long",
+                SHA_LENGTHS[0], SHA_LENGTHS[1]
+            ),
+            value: value.to_owned(),
+        })
+    }
+}
+
+fn validate_build_commit_sha_short(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Build commit short SHA")?;
+    check_hex(value, "Build commit short SHA")?;
+    if (SHA_SHORT_MIN_LENGTH..=SHA_LENGTHS[1]).contains(&value.len()) {
+        Ok(Validity::High { msg: None })
+    } else {
+        Err(Error::BadValue {
+            msg: format!(
+                "An abbreviated commit SHA has to be between {SHA_SHORT_MIN_LENGTH} and {} hex digits long",
+                SHA_LENGTHS[1]
+            ),
+            value: value.to_owned(),
+        })
+    }
+}
+
+fn validate_build_number(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Build number")?;
+    match value.parse::<i32>() {
+        Err(_err) => Ok(Validity::Suboptimal {
+            msg: "It is generally recommended and assumed that the build number is an integer (a positive, whole number)".to_owned(),
+            source: None,
+        }),
+        Ok(_int_value) => Ok(Validity::High { msg: Some("Is a build number (positive integer)".to_owned()) })
+    }
+}
+
+fn validate_pull_request_number(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Pull request number")?;
+    match value.parse::<u64>() {
+        Err(err) => Err(Error::BadValue {
+            msg: format!("Not a pull request number (a positive integer): {err}"),
+            value: value.to_owned(),
+        }),
+        Ok(_int_value) => Ok(Validity::High { msg: None }),
+    }
+}
+
+fn validate_pull_request_title(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Pull request title")
+}
+
+fn validate_pull_request_url(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Pull request URL")?;
+    check_public_url(environment, value, false, false)?;
+    Ok(Validity::High { msg: None })
+}
+
+fn validate_ci(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "CI")?;
+    match value {
+        "true" => Ok(Validity::High { msg: None }),
+        "false" => Ok(Validity::Middle {
+            msg: "Nothing wrong with that, but any 'true' value will get prefference over 'false'"
+                .to_owned(),
+        }),
+        &_ => Err(Error::BadValue {
+            msg:
+                r"CI can be 'true', 'false' or be ommitted (None), which get interpreted as 'false'"
+                    .to_owned(),
+            value: value.to_owned(),
+        }),
+    }
+}
+
+fn validate_build_dirty(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Build dirty")?;
+    match value {
+        "true" | "false" => Ok(Validity::High { msg: None }),
+        &_ => Err(Error::BadValue {
+            msg: r"Build dirty can only be 'true' or 'false'".to_owned(),
+            value: value.to_owned(),
+        }),
+    }
+}
+
+fn validate_doi(environment: &mut Environment, value: &str) -> Result {
+    lazy_static! {
+        // See <https://www.crossref.org/blog/dois-and-matching-regular-expressions/>
+        static ref R_DOI: Regex = Regex::new(r"^10\.\d{4,9}/\S+$").unwrap();
+    }
+
+    check_empty(environment, value, "DOI")?;
+    if R_DOI.is_match(value) {
+        Ok(Validity::High { msg: None })
+    } else {
+        Err(Error::BadValue {
+            msg: "A DOI has to look like '10.<registrant>/<suffix>', \
+                without any URL prefix, e.g. '10.5281/zenodo.1234567'"
+                .to_owned(),
+            value: value.to_owned(),
+        })
+    }
+}
+
+fn validate_funding_url(environment: &mut Environment, value: &str) -> Result {
+    check_empty(environment, value, "Funding URL")?;
+    check_public_url(environment, value, false, false)?;
+    Ok(Validity::High { msg: None })
+}
+
+/// Accepts any value without judgement,
+/// used for dynamic keys registered without a validator.
+///
+/// # Errors
+///
+/// Never.
+#[allow(clippy::unnecessary_wraps)]
+pub const fn validate_any(_environment: &mut Environment, _value: &str) -> Result {
+    Ok(Validity::Unknown)
+}
+
+/// The built-in keys whose values may span multiple lines;
+/// all others have to be single-line.
+/// Dynamic keys may always span multiple lines.
+pub const MULTILINE_KEYS: &[Key] = &[Key::Description, Key::Licenses];
+
+/// Rejects values spanning multiple lines for the keys that do not allow it,
+/// see [`MULTILINE_KEYS`].
+fn check_single_line(key: AnyKey, value: &str) -> std::result::Result<(), Error> {
+    let single_line = key
+        .builtin()
+        .is_some_and(|key| !MULTILINE_KEYS.contains(&key));
+    if single_line && value.contains(['\n', '\r']) {
+        Err(Error::BadValue {
+            msg: "Contains a line break, but has to be a single line".to_owned(),
+            value: value.to_owned(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the validator for `key`.
+///
+/// It rejects line breaks in single-line values (see [`MULTILINE_KEYS`]),
+/// applies the built-in validator (see [`AnyKey::validator`]),
+/// followed by the user supplied policy for the key, if any
+/// (see [`crate::settings::Settings::validators`]).
+pub fn get(key: impl Into<AnyKey>) -> impl Fn(&mut Environment, &str) -> Result {
+    let key = key.into();
+    let builtin = key.validator();
+    move |environment: &mut Environment, value: &str| {
+        check_single_line(key, value)?;
+        let validity = builtin(environment, value)?;
+        if let Some(policy) = environment.settings.validators.get(key.name()) {
+            policy.check(value)?;
+        }
+        Ok(validity)
+    }
+}
+
+/// Returns the built-in validator for `key`.
+#[remain::check]
+#[must_use]
+// NOTE Keys share validators, but we keep the arms sorted by key
+#[allow(clippy::match_same_arms)]
+pub fn get_builtin(key: Key) -> Validator {
+    // TODO This match could be written by a macro
+    #[remain::sorted]
+    match key {
+        Key::BuildArch => validate_build_arch,
         Key::BuildBranch => validate_build_branch,
+        Key::BuildCommitSha => validate_build_commit_sha,
+        Key::BuildCommitShaShort => validate_build_commit_sha_short,
         Key::BuildDate => validate_build_date,
+        Key::BuildDateEpoch => validate_build_date_epoch,
+        Key::BuildDirty => validate_build_dirty,
+        Key::BuildHostCpuCount => validate_build_host_cpu_count,
         Key::BuildHostingUrl => validate_build_hosting_url,
+        Key::BuildHostKernelVersion => validate_build_host_kernel_version,
+        Key::BuildHostPlatformTriple => validate_build_host_platform_triple,
         Key::BuildNumber => validate_build_number,
         Key::BuildOs => validate_build_os,
         Key::BuildOsFamily => validate_build_os_family,
         Key::BuildTag => validate_build_tag,
         Key::Ci => validate_ci,
+        Key::Description => validate_description,
+        Key::Doi => validate_doi,
+        Key::FundingUrl => validate_funding_url,
         Key::License => validate_license,
         Key::Licenses => validate_licenses,
+        Key::LicensesCompliance => validate_licenses_compliance,
         Key::Name => validate_name,
         Key::NameMachineReadable => validate_name_machine_readable,
+        Key::NameSlug => validate_name_slug,
+        Key::PullRequestNumber => validate_pull_request_number,
+        Key::PullRequestSourceBranch => validate_build_branch,
+        Key::PullRequestTargetBranch => validate_build_branch,
+        Key::PullRequestTitle => validate_pull_request_title,
+        Key::PullRequestUrl => validate_pull_request_url,
+        Key::RepoCiUrl => validate_repo_ci_url,
         Key::RepoCloneUrl => validate_repo_clone_url,
         Key::RepoCloneUrlGit => validate_repo_clone_url_git,
         Key::RepoCloneUrlHttp => validate_repo_clone_url_http,
+        Key::RepoCloneUrlPush => validate_repo_clone_url_push,
         Key::RepoCloneUrlSsh => validate_repo_clone_url_ssh,
         Key::RepoCommitPrefixUrl => validate_repo_commit_prefix_url,
+        Key::RepoDefaultBranch => validate_build_branch,
+        Key::RepoForkOfUrl => validate_repo_web_url,
+        Key::RepoForkUrl => validate_repo_web_url,
         Key::RepoIssuesUrl => validate_repo_issues_url,
+        Key::RepoNamespace => validate_repo_namespace,
+        Key::RepoOwner => validate_repo_owner,
         Key::RepoRawVersionedPrefixUrl => validate_repo_raw_versioned_prefix_url,
+        Key::RepoReleasesUrl => validate_repo_releases_url,
+        Key::RepoSlug => validate_repo_slug,
         Key::RepoVersionedDirPrefixUrl => validate_repo_versioned_dir_prefix_url,
         Key::RepoVersionedFilePrefixUrl => validate_repo_versioned_file_prefix_url,
         Key::RepoWebUrl => validate_repo_web_url,
         Key::Version => validate_version,
+        Key::VersionBuildMeta => validate_version_build_meta,
+        Key::VersionControlSystem => validate_version_control_system,
         Key::VersionDate => validate_version_date,
+        Key::VersionDescribe => validate_version_describe,
+        Key::VersionMajor | Key::VersionMinor | Key::VersionPatch => {
+            validate_version_number_component
+        }
+        Key::VersionPrerelease => validate_version_prerelease,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::var::DynamicKey;
 
     // %Y-%m-%d %H:%M:%S\"", value: "2021-09-21 06:27:37
     // #[test]
@@ -1040,6 +1600,69 @@ mod tests {
         // TODO Add some more bad cases. producing various different errors
     }
 
+    #[test]
+    fn test_validate_version_strip_prefix() {
+        fn is_release(res: Result) -> bool {
+            matches!(&res, Ok(Validity::Low { msg }) if msg.starts_with("This is a release version"))
+        }
+
+        let mut environment = Environment::stub();
+        assert!(is_release(validate_version(&mut environment, "0.1.19")));
+        assert!(is_release(validate_version(&mut environment, "v0.1.19")));
+        assert!(is_release(validate_version(&mut environment, "V. 0.1.19")));
+        assert!(!is_release(validate_version(
+            &mut environment,
+            "release-0.1.19"
+        )));
+        environment.settings.version_strip_prefixes = vec!["v".to_owned(), "release-".to_owned()];
+        assert!(is_release(validate_version(
+            &mut environment,
+            "release-0.1.19"
+        )));
+        assert!(is_middle(validate_version(
+            &mut environment,
+            "Release-0.1.19-12-gad8f844"
+        )));
+        assert_eq!(
+            cleanup::version(&mut environment, "release-0.1.19"),
+            Some("0.1.19".to_owned())
+        );
+        assert_eq!(cleanup::version(&mut environment, "0.1.19"), None);
+    }
+
+    #[test]
+    fn test_validate_version_components() {
+        let mut environment = Environment::stub();
+        assert!(is_high(validate_version_number_component(
+            &mut environment,
+            "0"
+        )));
+        assert!(is_high(validate_version_number_component(
+            &mut environment,
+            "12"
+        )));
+        assert!(is_bad_value(validate_version_number_component(
+            &mut environment,
+            "-1"
+        )));
+        assert!(is_high(validate_version_prerelease(
+            &mut environment,
+            "rc.1"
+        )));
+        assert!(is_bad_value(validate_version_prerelease(
+            &mut environment,
+            "rc..1"
+        )));
+        assert!(is_high(validate_version_build_meta(
+            &mut environment,
+            "build.5"
+        )));
+        assert!(is_bad_value(validate_version_build_meta(
+            &mut environment,
+            "build+5"
+        )));
+    }
+
     #[test]
     fn test_validate_license() {
         let mut environment = Environment::stub();
@@ -1073,6 +1696,21 @@ mod tests {
         // todo!(); // TODO Add some more bad cases; Producing different errors
     }
 
+    #[test]
+    fn test_validate_multiline() {
+        let mut environment = Environment::stub();
+        assert!(is_high(get(Key::Licenses)(
+            &mut environment,
+            "GPL-3.0-or-later,\nCC0-1.0"
+        )));
+        assert!(matches!(
+            get(Key::Name)(&mut environment, "My\nProject"),
+            Err(Error::BadValue { .. })
+        ));
+        let description = DynamicKey::create("Description", "A description", None);
+        assert!(get(AnyKey::Dynamic(description))(&mut environment, "Line 1\nLine 2").is_ok());
+    }
+
     #[test]
     fn test_validate_repo_versioned_dir_prefix_url() -> std::result::Result<(), Error> {
         let mut environment = Environment::stub();
@@ -1083,4 +1721,381 @@ mod tests {
         )?;
         Ok(())
     }
+
+    #[test]
+    fn test_validate_repo_releases_and_ci_url() {
+        let mut environment = Environment::stub();
+        assert!(is_high(validate_repo_releases_url(
+            &mut environment,
+            "https://github.com/hoijui/projvar/releases"
+        )));
+        assert!(is_high(validate_repo_releases_url(
+            &mut environment,
+            "https://gitlab.com/hoijui/some-group/projvar/-/releases"
+        )));
+        assert!(validate_repo_releases_url(
+            &mut environment,
+            "https://github.com/hoijui/projvar/issues"
+        )
+        .is_err());
+        assert!(is_high(validate_repo_ci_url(
+            &mut environment,
+            "https://github.com/hoijui/projvar/actions"
+        )));
+        assert!(is_high(validate_repo_ci_url(
+            &mut environment,
+            "https://gitlab.com/openflexure/openflexure-microscope/-/pipelines"
+        )));
+        assert!(
+            validate_repo_ci_url(&mut environment, "https://github.com/hoijui/projvar").is_err()
+        );
+    }
+
+    #[test]
+    fn test_validate_repo_slug() {
+        let mut environment = Environment::stub();
+
+        assert!(is_high(validate_repo_slug(
+            &mut environment,
+            "hoijui/projvar"
+        )));
+        assert!(is_high(validate_repo_slug(
+            &mut environment,
+            "OSEGermany/sub-group/OHS-3105"
+        )));
+        assert!(is_bad_value(validate_repo_slug(
+            &mut environment,
+            "projvar"
+        )));
+        assert!(is_bad_value(validate_repo_slug(
+            &mut environment,
+            "/hoijui/projvar/"
+        )));
+        assert!(is_bad_value(validate_repo_slug(&mut environment, "")));
+    }
+
+    #[test]
+    fn test_validate_name_slug() {
+        let mut environment = Environment::stub();
+
+        assert!(is_high(validate_name_slug(&mut environment, "projvar")));
+        assert!(is_high(validate_name_slug(
+            &mut environment,
+            "kuhlschrank-steuerung-2"
+        )));
+        assert!(is_bad_value(validate_name_slug(
+            &mut environment,
+            "Hello-World"
+        )));
+        assert!(is_bad_value(validate_name_slug(
+            &mut environment,
+            "hello_world"
+        )));
+        assert!(is_bad_value(validate_name_slug(
+            &mut environment,
+            "hello--world"
+        )));
+        assert!(is_bad_value(validate_name_slug(&mut environment, "-hello")));
+        assert!(is_bad_value(validate_name_slug(&mut environment, "")));
+    }
+
+    #[test]
+    fn test_validate_repo_owner_and_namespace() {
+        let mut environment = Environment::stub();
+
+        assert!(is_high(validate_repo_owner(&mut environment, "hoijui")));
+        assert!(is_bad_value(validate_repo_owner(
+            &mut environment,
+            "hoijui/projvar"
+        )));
+        assert!(is_bad_value(validate_repo_owner(&mut environment, "")));
+
+        assert!(is_high(validate_repo_namespace(&mut environment, "hoijui")));
+        assert!(is_high(validate_repo_namespace(
+            &mut environment,
+            "OSEGermany/sub-group"
+        )));
+        assert!(is_bad_value(validate_repo_namespace(
+            &mut environment,
+            "OSEGermany/"
+        )));
+    }
+
+    #[test]
+    fn test_validate_date_timezone() -> std::result::Result<(), String> {
+        let mut environment = Environment::stub();
+        environment.settings.date_format = "%Y-%m-%d %H:%M:%S %:z".to_owned();
+        assert!(is_high(validate_build_date(
+            &mut environment,
+            "2021-09-30 20:59:05 +00:00"
+        )));
+        assert!(is_middle(validate_build_date(
+            &mut environment,
+            "2021-09-30 22:59:05 +02:00"
+        )));
+        environment.settings.date_timezone = "+02:00".parse()?;
+        assert!(is_high(validate_build_date(
+            &mut environment,
+            "2021-09-30 22:59:05 +02:00"
+        )));
+        environment.settings.date_timezone = "local".parse()?;
+        assert!(is_high(validate_build_date(
+            &mut environment,
+            "2021-09-30 22:59:05 +02:00"
+        )));
+        assert!(is_bad_value(validate_build_date(
+            &mut environment,
+            "2021-09-30 22:59:05"
+        )));
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_date_format_per_key() {
+        let mut environment = Environment::stub();
+        environment.settings.build_date_format = Some("%Y-%m-%d".to_owned());
+        assert!(is_high(validate_build_date(&mut environment, "2021-09-30")));
+        assert!(is_bad_value(validate_version_date(
+            &mut environment,
+            "2021-09-30"
+        )));
+        assert!(is_high(validate_version_date(
+            &mut environment,
+            "2021-09-30 20:59:05"
+        )));
+    }
+
+    #[test]
+    fn test_validate_build_date_epoch() {
+        let mut environment = Environment::stub();
+        assert!(is_high(validate_build_date_epoch(
+            &mut environment,
+            "1633035545"
+        )));
+        assert!(is_bad_value(validate_build_date_epoch(
+            &mut environment,
+            "2021-09-30"
+        )));
+        assert!(is_bad_value(validate_build_date_epoch(
+            &mut environment,
+            ""
+        )));
+    }
+
+    #[test]
+    fn test_validate_build_host() {
+        let mut environment = Environment::stub();
+
+        assert!(is_high(validate_build_host_platform_triple(
+            &mut environment,
+            "x86_64-unknown-linux-gnu"
+        )));
+        assert!(is_high(validate_build_host_platform_triple(
+            &mut environment,
+            "aarch64-linux-android"
+        )));
+        assert!(is_bad_value(validate_build_host_platform_triple(
+            &mut environment,
+            "x86_64"
+        )));
+        assert!(is_bad_value(validate_build_host_platform_triple(
+            &mut environment,
+            ""
+        )));
+
+        assert!(is_high(validate_build_host_kernel_version(
+            &mut environment,
+            "6.1.0-18-amd64"
+        )));
+        assert!(is_suboptimal(validate_build_host_kernel_version(
+            &mut environment,
+            "unknown"
+        )));
+
+        assert!(is_high(validate_build_host_cpu_count(
+            &mut environment,
+            "8"
+        )));
+        assert!(is_bad_value(validate_build_host_cpu_count(
+            &mut environment,
+            "0"
+        )));
+        assert!(is_bad_value(validate_build_host_cpu_count(
+            &mut environment,
+            "many"
+        )));
+    }
+
+    #[test]
+    fn test_validate_build_commit_sha() {
+        let mut environment = Environment::stub();
+        assert!(is_high(validate_build_commit_sha(
+            &mut environment,
+            "9b5ff47e6e2b1e7ab7ac0fad3b8a13aa35b3b1a3"
+        )));
+        assert!(is_bad_value(validate_build_commit_sha(
+            &mut environment,
+            "9b5ff47"
+        )));
+        assert!(is_bad_value(validate_build_commit_sha(
+            &mut environment,
+            "9b5ff47e6e2b1e7ab7ac0fad3b8a13aa35b3b1ax"
+        )));
+        assert!(is_high(validate_build_commit_sha_short(
+            &mut environment,
+            "9b5ff47"
+        )));
+        assert!(is_bad_value(validate_build_commit_sha_short(
+            &mut environment,
+            "9b5"
+        )));
+        assert!(is_bad_value(validate_build_commit_sha_short(
+            &mut environment,
+            "master"
+        )));
+    }
+
+    #[test]
+    fn test_validate_version_describe() {
+        let mut environment = Environment::stub();
+        assert!(is_high(validate_version_describe(
+            &mut environment,
+            "v0.2.0-3-g9b5ff47"
+        )));
+        assert!(is_high(validate_version_describe(
+            &mut environment,
+            "9b5ff47"
+        )));
+        assert!(is_middle(validate_version_describe(
+            &mut environment,
+            "0.2.0-3-g9b5ff47-dirty"
+        )));
+        assert!(is_bad_value(validate_version_describe(
+            &mut environment,
+            "0.2.0 beta"
+        )));
+    }
+
+    #[test]
+    fn test_validate_custom_hosting_domain() -> std::result::Result<(), String> {
+        let mut environment = Environment::stub();
+        let issues_url = "http://192.168.1.10:3000/owner/repo/-/issues";
+        assert!(matches!(
+            validate_repo_issues_url(&mut environment, issues_url),
+            Ok(Validity::Unknown)
+        ));
+        let (domain, hosting_type) =
+            crate::settings::parse_hosting_domain("192.168.1.10:3000=gitlab")?;
+        environment
+            .settings
+            .hosting_domains
+            .insert(domain, hosting_type);
+        assert!(is_high(validate_repo_issues_url(
+            &mut environment,
+            issues_url
+        )));
+        assert_eq!(
+            crate::value_conversions::web_url_to_issues_url(
+                &environment,
+                "http://192.168.1.10:3000/owner/repo"
+            )
+            .map_err(|err| err.to_string())?,
+            Some(issues_url.to_owned())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_url_templates() -> std::result::Result<(), String> {
+        let mut environment = Environment::stub();
+        let web_url = "https://git.example.org/owner/repo";
+        let to_issues_url = |environment: &Environment| {
+            crate::value_conversions::web_url_to_issues_url(environment, web_url)
+                .map_err(|err| err.to_string())
+        };
+        assert_eq!(to_issues_url(&environment)?, None);
+        environment.settings.url_templates.insert(
+            HostingType::Unknown,
+            crate::config::UrlTemplates {
+                issues: Some("https://{host}/{slug}/tickets".to_owned()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            to_issues_url(&environment)?,
+            Some("https://git.example.org/owner/repo/tickets".to_owned())
+        );
+        environment.settings.url_templates.insert(
+            HostingType::Unknown,
+            crate::config::UrlTemplates {
+                issues: Some("https://tracker.example.org/{owner}/projects/{name}".to_owned()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            to_issues_url(&environment)?,
+            Some("https://tracker.example.org/owner/projects/repo".to_owned())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_build_hosting_url() {
+        let mut environment = Environment::stub();
+
+        for pages_url in [
+            "https://hoijui.github.io/kicad-text-injector",
+            "https://hoijui.gitlab.io/kicad-text-injector",
+            "https://hoijui.codeberg.page/kicad-text-injector",
+            "https://xampp.sourceforge.io",
+        ] {
+            assert!(is_high(validate_build_hosting_url(
+                &mut environment,
+                pages_url
+            )));
+        }
+        assert!(matches!(
+            validate_build_hosting_url(&mut environment, "https://a.b.github.io/repo"),
+            Err(Error::AlmostUsableValue { .. })
+        ));
+        let custom_pages_url = "https://group.pages.company.com/repo";
+        assert!(!is_high(validate_build_hosting_url(
+            &mut environment,
+            custom_pages_url
+        )));
+        environment.vars.insert(
+            constants::VAR_CI_PAGES_DOMAIN.to_owned(),
+            "pages.company.com".to_owned(),
+        );
+        assert!(is_high(validate_build_hosting_url(
+            &mut environment,
+            custom_pages_url
+        )));
+    }
+
+    #[test]
+    fn test_requires_auth() {
+        let mut environment = Environment::stub();
+        let raw_url = "https://gitlab.com/hoijui/kicad-text-injector/-/raw";
+        let pages_url = "https://hoijui.gitlab.io/kicad-text-injector";
+
+        environment.vars.insert(
+            constants::VAR_CI_PROJECT_VISIBILITY.to_owned(),
+            "public".to_owned(),
+        );
+        assert!(is_high(validate_repo_raw_versioned_prefix_url(
+            &mut environment,
+            raw_url
+        )));
+        environment.vars.insert(
+            constants::VAR_CI_PROJECT_VISIBILITY.to_owned(),
+            "private".to_owned(),
+        );
+        for res in [
+            validate_repo_raw_versioned_prefix_url(&mut environment, raw_url),
+            validate_build_hosting_url(&mut environment, pages_url),
+        ] {
+            assert!(matches!(res, Ok(Validity::RequiresAuth { .. })));
+        }
+    }
 }
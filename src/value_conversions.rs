@@ -3,14 +3,13 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use std::borrow::Cow;
-use std::ffi::OsStr;
-use std::path::PathBuf;
-use std::str::FromStr;
 
+use crate::config::UrlTemplates;
+use crate::tools::forge::radicle;
 use crate::tools::git::TransferProtocol;
 use crate::tools::git_clone_url;
 use crate::tools::git_hosting_provs::{HostingType, PublicSite};
-use chrono::DateTime;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
 use thiserror::Error;
 
 use lazy_static::lazy_static;
@@ -19,7 +18,7 @@ use url::Url;
 
 use crate::environment::Environment;
 use crate::var::Key;
-use crate::{constants, std_error};
+use crate::{cleanup, constants, std_error};
 
 type Res = Result<Option<String>, Error>;
 
@@ -84,19 +83,125 @@ pub fn slug_to_proj_name(slug: Option<&String>) -> Res {
             .transpose()
 }
 
+/// Extracts the owning user or organization from the project slug,
+/// which might be "user/project" or "user/group/sub-group/project".
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::slug_to_owner;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(
+///     slug_to_owner(&environment, "hoijui/projvar")?,
+///     Some("hoijui".to_owned())
+/// );
+/// assert_eq!(
+///     slug_to_owner(&environment, "OSEGermany/sub-group/OHS-3105")?,
+///     Some("OSEGermany".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// If the slug does not contain a '/'.
+pub fn slug_to_owner(_environment: &Environment, slug: &str) -> Res {
+    slug.split_once('/')
+        .map(|(owner, _rest)| Some(owner.to_owned()))
+        .ok_or_else(|| Error::BadInputValue {
+            key: Key::RepoOwner,
+            msg: r#"Failed splitting off the owner from the project slug, which is assumed to be "user/project" or "user/group/sub-group/project""#.to_owned(),
+            input: slug.to_owned(),
+        })
+}
+
+/// Extracts the namespace (everything but the project name) from the project slug,
+/// which might be "user/project" or "user/group/sub-group/project".
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::slug_to_namespace;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(
+///     slug_to_namespace(&environment, "hoijui/projvar")?,
+///     Some("hoijui".to_owned())
+/// );
+/// assert_eq!(
+///     slug_to_namespace(&environment, "OSEGermany/sub-group/OHS-3105")?,
+///     Some("OSEGermany/sub-group".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// If the slug does not contain a '/'.
+pub fn slug_to_namespace(_environment: &Environment, slug: &str) -> Res {
+    slug.rsplit_once('/')
+        .map(|(namespace, _proj_name)| Some(namespace.to_owned()))
+        .ok_or_else(|| Error::BadInputValue {
+            key: Key::RepoNamespace,
+            msg: r#"Failed splitting off the namespace from the project slug, which is assumed to be "user/project" or "user/group/sub-group/project""#.to_owned(),
+            input: slug.to_owned(),
+        })
+}
+
 /// Tries to construct the machine-readable project name
 /// from the human-readable one.
+/// It gets transliterated to ASCII (see [`crate::cleanup::ascii`]),
+/// and each run of remaining unsupported characters is replaced by a single '_',
+/// unless [`crate::settings::Settings::transliterate_names`] is disabled,
+/// in which case every unsupported character is replaced by a '_'.
 /// See also [`crate::validator::validate_name`].
 ///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::name_to_machine_readable_name;
+/// # use projvar::environment::Environment;
+/// let mut environment = Environment::stub();
+/// assert_eq!(
+///     name_to_machine_readable_name(&environment, "Kühlschrank-Steuerung")?,
+///     Some("Kuhlschrank-Steuerung".to_owned())
+/// );
+/// assert_eq!(
+///     name_to_machine_readable_name(&environment, "Große Straße: Karte")?,
+///     Some("Grosse_Strasse_Karte".to_owned())
+/// );
+/// environment.settings.transliterate_names = false;
+/// assert_eq!(
+///     name_to_machine_readable_name(&environment, "Kühlschrank-Steuerung")?,
+///     Some("K_hlschrank-Steuerung".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
 /// # Errors
 ///
 /// If the resulting name is empty.
-pub fn name_to_machine_readable_name(_environment: &Environment, human_name: &str) -> Res {
+pub fn name_to_machine_readable_name(environment: &Environment, human_name: &str) -> Res {
     lazy_static! {
         static ref R_BAD_CHAR: Regex = Regex::new(r"[^0-9a-zA-Z_-]").unwrap();
+        // NOTE This includes '_', so repeated ones get collapsed too
+        static ref R_BAD_CHARS: Regex = Regex::new(r"[^0-9a-zA-Z-]+").unwrap();
     }
 
-    let machine_name = R_BAD_CHAR.replace_all(human_name, "_");
+    let machine_name = if environment.settings.transliterate_names {
+        R_BAD_CHARS
+            .replace_all(&cleanup::ascii(human_name), "_")
+            .into_owned()
+    } else {
+        R_BAD_CHAR.replace_all(human_name, "_").into_owned()
+    };
     if machine_name.is_empty() {
         return Err(Error::BadInputValue {
             key: Key::NameMachineReadable,
@@ -104,7 +209,57 @@ pub fn name_to_machine_readable_name(_environment: &Environment, human_name: &st
             input: human_name.to_owned(),
         });
     }
-    Ok(Some(machine_name.into_owned()))
+    Ok(Some(machine_name))
+}
+
+/// Constructs the slug of the project name (see [`Key::NameSlug`]).
+///
+/// It is made from the human-readable or the machine-readable name.
+/// It gets transliterated to ASCII (see [`crate::cleanup::ascii`])
+/// and lower-cased,
+/// each run of remaining characters other than letters and digits
+/// is replaced by a single '-',
+/// and leading and trailing ones are removed.
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::name_to_slug;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(
+///     name_to_slug(&environment, "Kühlschrank-Steuerung")?,
+///     Some("kuhlschrank-steuerung".to_owned())
+/// );
+/// assert_eq!(
+///     name_to_slug(&environment, "The OHS_3105 (Draft)")?,
+///     Some("the-ohs-3105-draft".to_owned())
+/// );
+/// assert!(name_to_slug(&environment, "--").is_err());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// If the resulting slug is empty.
+pub fn name_to_slug(_environment: &Environment, name: &str) -> Res {
+    lazy_static! {
+        static ref R_NON_SLUG_CHARS: Regex = Regex::new(r"[^0-9a-z]+").unwrap();
+    }
+
+    let name_lower = cleanup::ascii(name).to_lowercase();
+    let slug = R_NON_SLUG_CHARS.replace_all(&name_lower, "-");
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        return Err(Error::BadInputValue {
+            key: Key::NameSlug,
+            msg: "The input resulted in an empty slug".to_owned(),
+            input: name.to_owned(),
+        });
+    }
+    Ok(Some(slug.to_owned()))
 }
 
 /// Tries to construct the machine-readable project name
@@ -130,6 +285,41 @@ pub fn web_url_to_machine_readable_name(_environment: &Environment, web_url: &st
     Ok(Some(machine_name.into_owned()))
 }
 
+/// Fills in the placeholders of a user supplied URL template,
+/// see [`crate::config::UrlTemplates`].
+// NOTE These placeholders are ours, not the ones of `format!`
+#[allow(clippy::literal_string_with_formatting_args)]
+fn apply_url_template(template: &str, web_url: &Url) -> String {
+    let host = web_url.host_str().unwrap_or_default();
+    let host = web_url
+        .port()
+        .map_or_else(|| host.to_owned(), |port| format!("{host}:{port}"));
+    let slug = trim_char(web_url.path(), '/');
+    let owner = slug.split('/').next().unwrap_or(slug);
+    let name = slug.rsplit('/').next().unwrap_or(slug);
+    template
+        .replace("{web_url}", web_url.as_str().trim_end_matches('/'))
+        .replace("{host}", &host)
+        .replace("{slug}", slug)
+        .replace("{owner}", owner)
+        .replace("{name}", name)
+}
+
+/// Constructs a URL from a user supplied template,
+/// if there is one for the hosting type of `web_url`.
+fn templated_url(
+    environment: &Environment,
+    web_url: &Url,
+    select: fn(&UrlTemplates) -> Option<&String>,
+) -> Option<String> {
+    environment
+        .settings
+        .url_templates
+        .get(&environment.settings.hosting_type(web_url))
+        .and_then(select)
+        .map(|template| apply_url_template(template, web_url))
+}
+
 fn web_url_match(
     _environment: &Environment,
     web_url: &str,
@@ -192,18 +382,107 @@ fn web_url_match(
 // * https://gitlab.opensourceecology.de/groups/verein/projekte/losh/-/issues
 // * https://bitbucket.org/Aouatef/master_arbeit/issues
 pub fn web_url_to_issues_url(environment: &Environment, web_url: &str) -> Res {
-    web_url_match(environment, web_url, Key::RepoIssuesUrl, &|mut url| {
-        Ok(match environment.settings.hosting_type(&url) {
-            HostingType::BitBucket | HostingType::GitHub => {
-                url.set_path(&format!("/{}/issues", trim_char(url.path(), '/')));
-                Some(url.to_string())
-            }
-            HostingType::GitLab => {
-                url.set_path(&format!("/{}/-/issues", trim_char(url.path(), '/')));
-                Some(url.to_string())
-            }
-            _ => None, // TODO Implement the others!
-        })
+    web_url_match(environment, web_url, Key::RepoIssuesUrl, &|url| {
+        if let Some(templated) =
+            templated_url(environment, &url, |templates| templates.issues.as_ref())
+        {
+            return Ok(Some(templated));
+        }
+        Ok(environment
+            .settings
+            .hosting_type(&url)
+            .forge()
+            .issues_url(url))
+    })
+}
+
+/// Tries to construct the releases page URL
+/// from the repo web URL property of a variable source.
+/// See also [`crate::validator::validate_repo_releases_url`].
+///
+/// NOTE: This currently only works for GitHub, GitLab and BitBucket!
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::web_url_to_releases_url;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(
+///     web_url_to_releases_url(&environment, "https://github.com/hoijui/projvar/")?,
+///     Some("https://github.com/hoijui/projvar/releases".to_owned())
+/// );
+/// assert_eq!(
+///     web_url_to_releases_url(&environment, "https://gitlab.com/hoijui/some-group/projvar")?,
+///     Some("https://gitlab.com/hoijui/some-group/projvar/-/releases".to_owned())
+/// );
+/// assert_eq!(
+///     web_url_to_releases_url(&environment, "https://bitbucket.org/hoijui/projvar")?,
+///     Some("https://bitbucket.org/hoijui/projvar/downloads".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// If an attempt to try fetching any required property returned an error.
+//
+// Real world releases URLs:
+// * https://github.com/OPEN-NEXT/LOSH-Krawler/releases
+// * https://gitlab.com/openflexure/openflexure-microscope/-/releases
+// * https://bitbucket.org/Aouatef/master_arbeit/downloads
+pub fn web_url_to_releases_url(environment: &Environment, web_url: &str) -> Res {
+    web_url_match(environment, web_url, Key::RepoReleasesUrl, &|url| {
+        Ok(environment
+            .settings
+            .hosting_type(&url)
+            .forge()
+            .releases_url(url))
+    })
+}
+
+/// Tries to construct the CI pipelines page URL
+/// from the repo web URL property of a variable source.
+/// See also [`crate::validator::validate_repo_ci_url`].
+///
+/// NOTE: This currently only works for GitHub, GitLab and BitBucket!
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::web_url_to_ci_url;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(
+///     web_url_to_ci_url(&environment, "https://github.com/hoijui/projvar/")?,
+///     Some("https://github.com/hoijui/projvar/actions".to_owned())
+/// );
+/// assert_eq!(
+///     web_url_to_ci_url(&environment, "https://gitlab.com/hoijui/some-group/projvar")?,
+///     Some("https://gitlab.com/hoijui/some-group/projvar/-/pipelines".to_owned())
+/// );
+/// assert_eq!(
+///     web_url_to_ci_url(&environment, "https://bitbucket.org/hoijui/projvar")?,
+///     Some("https://bitbucket.org/hoijui/projvar/pipelines".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// If an attempt to try fetching any required property returned an error.
+//
+// Real world CI URLs:
+// * https://github.com/OPEN-NEXT/LOSH-Krawler/actions
+// * https://gitlab.com/openflexure/openflexure-microscope/-/pipelines
+// * https://bitbucket.org/Aouatef/master_arbeit/pipelines
+pub fn web_url_to_ci_url(environment: &Environment, web_url: &str) -> Res {
+    web_url_match(environment, web_url, Key::RepoCiUrl, &|url| {
+        Ok(environment.settings.hosting_type(&url).forge().ci_url(url))
     })
 }
 
@@ -225,31 +504,17 @@ pub fn web_url_to_raw_prefix_url(environment: &Environment, web_url: &str) -> Re
         environment,
         web_url,
         Key::RepoRawVersionedPrefixUrl,
-        &|mut url| {
-            Ok(match environment.settings.hosting_type(&url) {
-                HostingType::GitHub => {
-                    url.set_host(Some(constants::D_GIT_HUB_COM_RAW))
-                        .map_err(|err| Error::BadInputValueErr {
-                            key: Key::RepoRawVersionedPrefixUrl,
-                            msg: format!(
-                                "Failed to parse '{}' host for URL",
-                                constants::D_GIT_HUB_COM_RAW
-                            ),
-                            input: web_url.to_owned(),
-                            source: Box::new(err),
-                        })?;
-                    Some(url.to_string())
-                }
-                HostingType::GitLab => {
-                    url.set_path(&format!("{}/-/raw", url.path()));
-                    Some(url.to_string())
-                }
-                HostingType::BitBucket => {
-                    url.set_path(&format!("{}/raw", url.path()));
-                    Some(url.to_string())
-                }
-                _ => None, // TODO Implement the others!
-            })
+        &|url| {
+            if let Some(templated) =
+                templated_url(environment, &url, |templates| templates.raw.as_ref())
+            {
+                return Ok(Some(templated));
+            }
+            environment
+                .settings
+                .hosting_type(&url)
+                .forge()
+                .raw_prefix_url(url)
         },
     )
 }
@@ -271,22 +536,17 @@ pub fn web_url_to_versioned_file_prefix_url(environment: &Environment, web_url:
         environment,
         web_url,
         Key::RepoVersionedFilePrefixUrl,
-        &|mut url| {
-            Ok(match environment.settings.hosting_type(&url) {
-                HostingType::GitHub => {
-                    url.set_path(&format!("{}/blob", url.path()));
-                    Some(url.to_string())
-                }
-                HostingType::GitLab => {
-                    url.set_path(&format!("{}/-/blob", url.path()));
-                    Some(url.to_string())
-                }
-                HostingType::BitBucket => {
-                    url.set_path(&format!("{}/src", url.path()));
-                    Some(url.to_string())
-                }
-                _ => None, // TODO Implement the others!
-            })
+        &|url| {
+            if let Some(templated) =
+                templated_url(environment, &url, |templates| templates.blob.as_ref())
+            {
+                return Ok(Some(templated));
+            }
+            Ok(environment
+                .settings
+                .hosting_type(&url)
+                .forge()
+                .versioned_file_prefix_url(url))
         },
     )
 }
@@ -308,22 +568,17 @@ pub fn web_url_to_versioned_dir_prefix_url(environment: &Environment, web_url: &
         environment,
         web_url,
         Key::RepoVersionedDirPrefixUrl,
-        &|mut url| {
-            Ok(match environment.settings.hosting_type(&url) {
-                HostingType::GitHub => {
-                    url.set_path(&format!("{}/tree", url.path()));
-                    Some(url.to_string())
-                }
-                HostingType::GitLab => {
-                    url.set_path(&format!("{}/-/tree", url.path()));
-                    Some(url.to_string())
-                }
-                HostingType::BitBucket => {
-                    url.set_path(&format!("{}/src", url.path()));
-                    Some(url.to_string())
-                }
-                _ => None, // TODO Implement the others!
-            })
+        &|url| {
+            if let Some(templated) =
+                templated_url(environment, &url, |templates| templates.tree.as_ref())
+            {
+                return Ok(Some(templated));
+            }
+            Ok(environment
+                .settings
+                .hosting_type(&url)
+                .forge()
+                .versioned_dir_prefix_url(url))
         },
     )
 }
@@ -341,28 +596,155 @@ pub fn web_url_to_versioned_dir_prefix_url(environment: &Environment, web_url: &
 // * [https://gitlab.com/OSEGermany/osh-tool/-/commit]/ae4a42a850b359a23da2483eb8f867f21c5382d4
 // * [https://bitbucket.org/Aouatef/master_arbeit/commits]/ae4a42a850b359a23da2483eb8f867f21c5382d4
 pub fn web_url_to_commit_prefix_url(environment: &Environment, web_url: &str) -> Res {
-    web_url_match(
-        environment,
-        web_url,
-        Key::RepoCommitPrefixUrl,
-        &|mut url| {
-            Ok(match environment.settings.hosting_type(&url) {
-                HostingType::GitHub => {
-                    url.set_path(&format!("{}/commit", url.path()));
-                    Some(url.to_string())
-                }
-                HostingType::GitLab => {
-                    url.set_path(&format!("{}/-/commit", url.path()));
-                    Some(url.to_string())
-                }
-                HostingType::BitBucket => {
-                    url.set_path(&format!("{}/commits", url.path()));
-                    Some(url.to_string())
-                }
-                _ => None, // TODO Implement the others!
-            })
-        },
-    )
+    web_url_match(environment, web_url, Key::RepoCommitPrefixUrl, &|url| {
+        if let Some(templated) =
+            templated_url(environment, &url, |templates| templates.commit.as_ref())
+        {
+            return Ok(Some(templated));
+        }
+        Ok(environment
+            .settings
+            .hosting_type(&url)
+            .forge()
+            .commit_prefix_url(url))
+    })
+}
+
+/// Tries to extract the project slug
+/// from the repo web URL property of a variable source.
+/// See also [`crate::validator::validate_repo_slug`].
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::web_url_to_slug;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(
+///     web_url_to_slug(&environment, "https://github.com/hoijui/kicad-text-injector/")?,
+///     Some("hoijui/kicad-text-injector".to_owned())
+/// );
+/// assert_eq!(
+///     web_url_to_slug(&environment, "https://gitlab.com/hoijui/sub-group/kicad-text-injector")?,
+///     Some("hoijui/sub-group/kicad-text-injector".to_owned())
+/// );
+/// assert_eq!(
+///     web_url_to_slug(&environment, "https://bitbucket.org/Aouatef/master_arbeit")?,
+///     Some("Aouatef/master_arbeit".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// A self-hosted instance served under a base path
+/// (see [`crate::settings::Settings::hosting_base_path`]):
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::tools::git_hosting_provs::HostingType;
+/// # use projvar::value_conversions::web_url_to_slug;
+/// # use projvar::environment::Environment;
+/// let mut environment = Environment::stub();
+/// environment.settings.hosting_domains.insert("gitlab.example.com".to_owned(), HostingType::GitLab);
+/// environment.settings.hosting_base_path = Some("gitlab".to_owned());
+/// assert_eq!(
+///     web_url_to_slug(&environment, "https://gitlab.example.com/gitlab/group/sub-group/project")?,
+///     Some("group/sub-group/project".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// If the web URL is not a valid URL.
+pub fn web_url_to_slug(environment: &Environment, web_url: &str) -> Res {
+    web_url_match(environment, web_url, Key::RepoSlug, &|url| {
+        if !environment.settings.hosting_type(&url).forge().has_slug() {
+            return Ok(None);
+        }
+        let path = url.host_str().map_or_else(
+            || url.path(),
+            |host| {
+                environment
+                    .settings
+                    .strip_hosting_base_path(host, url.path())
+            },
+        );
+        let slug = trim_char(path, '/');
+        Ok(if slug.contains('/') {
+            Some(slug.to_owned())
+        } else {
+            None
+        })
+    })
+}
+
+/// Extracts the owner (user or top-level group)
+/// from the web URL of the repo,
+/// via its slug (see [`web_url_to_slug`] and [`slug_to_owner`]).
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::web_url_to_owner;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(
+///     web_url_to_owner(&environment, "https://gitlab.com/OSEGermany/sub-group/OHS-3105")?,
+///     Some("OSEGermany".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// If the web URL is not a valid URL.
+pub fn web_url_to_owner(environment: &Environment, web_url: &str) -> Res {
+    web_url_to_slug(environment, web_url)?
+        .map_or(Ok(None), |slug| slug_to_owner(environment, &slug))
+}
+
+/// Extracts the namespace (everything but the project name)
+/// from the web URL of the repo,
+/// via its slug (see [`web_url_to_slug`] and [`slug_to_namespace`]).
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::web_url_to_namespace;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(
+///     web_url_to_namespace(&environment, "https://gitlab.com/OSEGermany/sub-group/OHS-3105")?,
+///     Some("OSEGermany/sub-group".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// If the web URL is not a valid URL.
+pub fn web_url_to_namespace(environment: &Environment, web_url: &str) -> Res {
+    web_url_to_slug(environment, web_url)?
+        .map_or(Ok(None), |slug| slug_to_namespace(environment, &slug))
+}
+
+/// Whether the scheme of a clone URL (if any)
+/// is one of the schemes of the given transfer protocol.
+/// URLs in the SCP like syntax ("user@host:path") have no scheme,
+/// and no port either.
+fn same_protocol(scheme: Option<&str>, protocol: TransferProtocol) -> bool {
+    match protocol {
+        TransferProtocol::Git => matches!(scheme, Some("git")),
+        TransferProtocol::Https => matches!(scheme, Some("http" | "https")),
+        TransferProtocol::Ssh => matches!(scheme, Some("ssh" | "git+ssh" | "ssh+git")),
+    }
 }
 
 /// Converts any kind of clone URL to an HTTP(S) or SSH one.
@@ -452,6 +834,10 @@ pub fn web_url_to_commit_prefix_url(environment: &Environment, web_url: &str) ->
 ///     Some("https://repo.or.cz/girocco.git".to_owned())
 /// );
 /// assert_eq!(
+///     clone_url_conversion("http://192.168.1.10:3000/hoijui/kicad-text-injector.git", &environment, TransferProtocol::Https)?,
+///     Some("https://192.168.1.10:3000/hoijui/kicad-text-injector.git".to_owned())
+/// );
+/// assert_eq!(
 ///     clone_url_conversion("git@github.com:hoijui/kicad-text-injector.git", &environment, TransferProtocol::Ssh)?,
 ///     Some("ssh://git@github.com/hoijui/kicad-text-injector.git".to_owned())
 /// );
@@ -543,16 +929,108 @@ pub fn web_url_to_commit_prefix_url(environment: &Environment, web_url: &str) ->
 ///     clone_url_conversion("git://repo.or.cz/girocco.git", &environment, TransferProtocol::Git)?,
 ///     Some("git://repo.or.cz/girocco.git".to_owned())
 /// );
+/// assert_eq!(
+///     clone_url_conversion("rad://z3gqcJUoA1n9HaHKufZs5FCSGazv5", &environment, TransferProtocol::Https)?,
+///     Some("https://seed.radicle.garden/z3gqcJUoA1n9HaHKufZs5FCSGazv5.git".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_conversion("rad://z3gqcJUoA1n9HaHKufZs5FCSGazv5", &environment, TransferProtocol::Ssh)?,
+///     None
+/// );
+/// assert_eq!(
+///     clone_url_conversion("https://gerrit.googlesource.com/a/gerrit", &environment, TransferProtocol::Ssh)?,
+///     Some("ssh://gerrit.googlesource.com:29418/gerrit".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_conversion("ssh://hoijui@gerrit.googlesource.com:29418/gerrit", &environment, TransferProtocol::Https)?,
+///     Some("https://gerrit.googlesource.com/gerrit".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_conversion("ssh://git@gitlab.example.com:2222/group/sub-group/project.git", &environment, TransferProtocol::Ssh)?,
+///     Some("ssh://git@gitlab.example.com:2222/group/sub-group/project.git".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_conversion("ssh://git@gitlab.example.com:2222/group/sub-group/project.git", &environment, TransferProtocol::Https)?,
+///     Some("https://gitlab.example.com/group/sub-group/project.git".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_conversion("git+ssh://git@gitlab.example.com:2222/group/sub-group/project.git", &environment, TransferProtocol::Ssh)?,
+///     Some("ssh://git@gitlab.example.com:2222/group/sub-group/project.git".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_conversion("git://git.example.com:9419/project.git", &environment, TransferProtocol::Git)?,
+///     Some("git://git.example.com:9419/project.git".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_conversion("git://git.example.com:9419/project.git", &environment, TransferProtocol::Https)?,
+///     Some("https://git.example.com/project.git".to_owned())
+/// );
 /// # Ok(())
 /// # }
 /// ```
-pub fn clone_url_conversion(
-    any_clone_url: &str,
-    environment: &Environment,
+///
+/// A self-hosted instance served under a base path
+/// (see [`crate::settings::Settings::hosting_base_path`]):
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::tools::git::TransferProtocol;
+/// # use projvar::tools::git_hosting_provs::HostingType;
+/// # use projvar::value_conversions::clone_url_conversion;
+/// # use projvar::environment::Environment;
+/// let mut environment = Environment::stub();
+/// environment.settings.hosting_domains.insert("gitlab.example.com".to_owned(), HostingType::GitLab);
+/// environment.settings.hosting_base_path = Some("gitlab".to_owned());
+/// assert_eq!(
+///     clone_url_conversion("https://gitlab.example.com/gitlab/group/sub-group/project.git", &environment, TransferProtocol::Ssh)?,
+///     Some("ssh://git@gitlab.example.com/group/sub-group/project.git".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_conversion("git@gitlab.example.com:group/sub-group/project.git", &environment, TransferProtocol::Https)?,
+///     Some("https://gitlab.example.com/gitlab/group/sub-group/project.git".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_conversion("ssh://git@gitlab.example.com:2222/group/sub-group/project.git", &environment, TransferProtocol::Https)?,
+///     Some("https://gitlab.example.com/gitlab/group/sub-group/project.git".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_conversion("https://gitlab.example.com:8443/gitlab/group/sub-group/project.git", &environment, TransferProtocol::Https)?,
+///     Some("https://gitlab.example.com:8443/gitlab/group/sub-group/project.git".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_conversion("https://gitlab.example.com:8443/gitlab/group/sub-group/project.git", &environment, TransferProtocol::Ssh)?,
+///     Some("ssh://git@gitlab.example.com/group/sub-group/project.git".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_conversion("https://gitlab.example.com/gitlab/group/sub-group/project.git", &environment, TransferProtocol::Git)?,
+///     Some("git://gitlab.example.com/group/sub-group/project.git".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_conversion("ssh://git@gitlab.example.com:2222/group/sub-group/project.git", &environment, TransferProtocol::Ssh)?,
+///     Some("ssh://git@gitlab.example.com:2222/group/sub-group/project.git".to_owned())
+/// );
+/// // the base path does not apply to the public hosting sites
+/// assert_eq!(
+///     clone_url_conversion("git@gitlab.com:group/sub-group/project.git", &environment, TransferProtocol::Https)?,
+///     Some("https://gitlab.com/group/sub-group/project.git".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn clone_url_conversion(
+    any_clone_url: &str,
+    environment: &Environment,
     protocol: TransferProtocol,
 ) -> Res {
-    lazy_static! {
-        static ref R_HOST_PREFIX: Regex = Regex::new(r"^(git|ssh)\.").unwrap(); // TODO This is RocketGit specific -> ranme and move to constants?
+    if let Some(rid) = radicle::native_rid(any_clone_url) {
+        // NOTE Without further information,
+        //      we can only assume the repo to be available on the default seed node.
+        return Ok(match protocol {
+            TransferProtocol::Https => {
+                Some(radicle::clone_url(constants::D_RADICLE_SEED_GARDEN, rid))
+            }
+            TransferProtocol::Git | TransferProtocol::Ssh => None,
+        });
     }
 
     let clone_url_parts = git_clone_url::PartsRef::parse(any_clone_url).map_err(|err_str| {
@@ -565,27 +1043,27 @@ pub fn clone_url_conversion(
             input: any_clone_url.to_owned(),
         }
     })?;
+    let port = clone_url_parts
+        .port
+        .and_then(|port_str| port_str.parse::<u16>().ok());
     let hosting_type = environment
         .settings
-        .hosting_type_from_host(clone_url_parts.host);
+        .hosting_type_from_host(clone_url_parts.host, port);
 
-    let host = if matches!(hosting_type, HostingType::RocketGit) {
-        let prefix = match protocol {
-            TransferProtocol::Git => "git.",
-            TransferProtocol::Https => "",
-            TransferProtocol::Ssh => "ssh.",
-        };
-        Cow::Owned(format!(
-            "{prefix}{}",
-            R_HOST_PREFIX.replace(clone_url_parts.host, "")
-        ))
-    } else {
-        Cow::Borrowed(clone_url_parts.host)
-    };
+    let forge = hosting_type.forge();
+
+    let host = forge.clone_host(clone_url_parts.host, protocol);
     let user_opt = clone_url_parts.user;
+    // NOTE SCP like clone URLs ("user@host:path") are SSH ones too
+    let from_ssh = clone_url_parts.protocol.is_none()
+        || same_protocol(clone_url_parts.protocol, TransferProtocol::Ssh);
     let user_at = if matches!(protocol, TransferProtocol::Ssh) {
-        // use the default user for the given hosting-type
-        Cow::Borrowed(hosting_type.def_ssh_user())
+        // use the default user for the given hosting-type,
+        // or if it has none, the one of the SSH clone URL (if any)
+        match (forge.def_ssh_user(), user_opt) {
+            ("", Some(user)) if from_ssh && !user.is_empty() => Cow::Owned(format!("{user}@")),
+            (def_ssh_user, _) => Cow::Borrowed(def_ssh_user),
+        }
     } else if let Some(user) = user_opt {
         if user == "git" {
             Cow::Borrowed("git@")
@@ -601,23 +1079,54 @@ pub fn clone_url_conversion(
         Cow::Borrowed("")
     };
 
-    let path_and_rest = clone_url_parts.path_and_rest;
+    // NOTE The base path of a self-hosted instance (if any)
+    //      is part of web and HTTP(S) clone URLs, but not of SSH and git ones.
+    let from_web = matches!(clone_url_parts.protocol, Some("http" | "https"));
+    let base_path_setting = environment
+        .settings
+        .hosting_base_path_for(clone_url_parts.host);
+    let clone_url_parts = if from_web {
+        clone_url_parts.with_base_path(base_path_setting)
+    } else {
+        clone_url_parts
+    };
+    let path_and_rest = forge.clone_path(clone_url_parts.path_and_rest);
+    let base_path = if from_web {
+        clone_url_parts.base_path
+    } else {
+        base_path_setting
+    };
+    let path_and_rest = match (protocol, base_path) {
+        (TransferProtocol::Https, Some(base_path)) => {
+            Cow::Owned(format!("{base_path}/{path_and_rest}"))
+        }
+        _ => Cow::Borrowed(path_and_rest),
+    };
     let scheme = protocol.scheme_str();
     Ok(Some(match protocol {
         TransferProtocol::Https | TransferProtocol::Git => {
-            format!("{scheme}://{host}/{path_and_rest}",)
+            // NOTE We only preserve the port between URLs of the same protocol,
+            //      as the port of any other protocol is a totally different one.
+            let port_part = clone_url_parts
+                .port
+                .filter(|_| same_protocol(clone_url_parts.protocol, protocol))
+                .map(|port| format!(":{port}"))
+                .unwrap_or_default();
+            format!("{scheme}://{host}{port_part}/{path_and_rest}")
         }
         TransferProtocol::Ssh => {
-            let host_path_sep = if host == constants::D_GIT_SOURCE_HUT {
-                // This is **not** URL spec compatible,
-                // but some/most hosters support this.
-                ':'
-            } else {
-                // This is the (URL spec) compatible way
-                '/'
-            };
+            let host_path_sep = forge.ssh_host_path_separator();
+            // NOTE We only preserve the port between SSH URLs,
+            //      as e.g. self-hosted instances often use a custom SSH port.
+            let port_part = clone_url_parts
+                .port
+                .filter(|_| same_protocol(clone_url_parts.protocol, protocol))
+                .map(ToOwned::to_owned)
+                .or_else(|| forge.default_ssh_port().map(|port| port.to_string()))
+                .map(|port| format!(":{port}"))
+                .unwrap_or_default();
             format!(
-                "{scheme}://{user}{host}{host_path_sep}{path_and_rest}",
+                "{scheme}://{user}{host}{port_part}{host_path_sep}{path_and_rest}",
                 // "{scheme}://{host}/{path_and_rest}", // anonymized (without user)
                 user = user_at.to_lowercase(),
                 path_and_rest = path_and_rest,
@@ -678,21 +1187,18 @@ pub fn clone_url_conversion_option(
 ///     trim_char("*hoijui/kicad-text-injector/", '*'),
 ///     "hoijui/kicad-text-injector/"
 /// );
+/// assert_eq!(trim_char("/grüße/", '/'), "grüße");
+/// assert_eq!(trim_char("ühoijui/ü", 'ü'), "hoijui/");
+/// assert_eq!(trim_char("«hoijui»", '»'), "«hoijui");
+/// assert_eq!(trim_char("€", '€'), "");
+/// assert_eq!(trim_char("€", '/'), "€");
 /// # Ok(())
 /// # }
 /// ```
 #[must_use]
 pub fn trim_char(input: &'_ str, chr: char) -> &'_ str {
-    let output = if input.starts_with(chr) {
-        &input[1..]
-    } else {
-        input
-    };
-    if output.ends_with(chr) {
-        &output[..output.len() - 1]
-    } else {
-        output
-    }
+    let output = input.strip_prefix(chr).unwrap_or(input);
+    output.strip_suffix(chr).unwrap_or(output)
 }
 
 /// Converts a common git repo web-host URL
@@ -760,18 +1266,6 @@ pub fn split_after_first_path_element<'t>(
         })
 }
 
-macro_rules! build_hostify_url {
-    ($url:ident, $web_url:ident, $public_site:ident, $suffix:ident) => {{
-        let old_path = $url.path().to_owned();
-        let (site_user, site_project) =
-            split_after_first_path_element($web_url, &old_path, $public_site)?;
-        $url.set_host(Some(&format!("{site_user}.{}", constants::$suffix)))
-            .map_err(std_error::Error::from)?;
-        $url.set_path(site_project);
-        Some($url.to_string())
-    }};
-}
-
 /// Converts a common git repo web-host URL
 /// into the URL of where to find hosted CI output
 /// (commonly known as "pages" URL).
@@ -809,6 +1303,27 @@ macro_rules! build_hostify_url {
 /// # }
 /// ```
 ///
+/// A self-hosted GitLab instance served under a base path
+/// (see [`crate::settings::Settings::hosting_base_path`]),
+/// with a project in a nested sub-group:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::tools::git_hosting_provs::HostingType;
+/// # use projvar::value_conversions::web_url_to_build_hosting_url;
+/// # use projvar::environment::Environment;
+/// let mut environment = Environment::stub();
+/// environment.settings.hosting_domains.insert("gitlab.example.com".to_owned(), HostingType::GitLab);
+/// environment.settings.hosting_base_path = Some("gitlab".to_owned());
+/// environment.vars.insert("CI_PAGES_DOMAIN".to_owned(), "pages.example.com".to_owned());
+/// assert_eq!(
+///     web_url_to_build_hosting_url(&environment, "https://gitlab.example.com/gitlab/group/sub-group/sub-sub-group/project")?,
+///     Some("https://group.pages.example.com/sub-group/sub-sub-group/project".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
 /// # Errors
 ///
 /// Failed fetching/generating the Web URL.
@@ -818,35 +1333,22 @@ macro_rules! build_hostify_url {
 // <https://osegermany.gitlab.io/OHS-3105/>
 // <https://hoijui.github.io/escher/>
 pub fn web_url_to_build_hosting_url(environment: &Environment, web_url: &str) -> Res {
-    web_url_match(
-        environment,
-        web_url,
-        Key::RepoCommitPrefixUrl,
-        &|mut url| {
-            let public_site = PublicSite::from(url.host());
-            Ok(match public_site {
-                PublicSite::GitHubCom => {
-                    build_hostify_url!(url, web_url, public_site, DS_GIT_HUB_IO_SUFIX)
-                }
-                PublicSite::GitLabCom => {
-                    build_hostify_url!(url, web_url, public_site, DS_GIT_LAB_IO_SUFIX)
-                }
-                PublicSite::CodeBergOrg => {
-                    build_hostify_url!(url, web_url, public_site, DS_CODE_BERG_PAGE)
-                }
-                PublicSite::SourceForgeNet => {
-                    let url_path = PathBuf::from_str(url.path())?;
-                    let proj_name_opt = url_path.file_name().map(OsStr::to_string_lossy);
-                    proj_name_opt.map(|proj_name| format!("https://{proj_name}.{}", constants::DS_SOURCE_FORGE_IO))
-                }
-                PublicSite::BitBucketOrg // has no pages hosting
-                | PublicSite::SourceHut // has pages support (<https://srht.site/>), but only per-user, not per repo. One could try to emulate per repo pages there, but it would be cumbersome and is not standardized.
-                | PublicSite::RepoOrCz // has no pages hosting
-                | PublicSite::RocketGitCom // has no pages hosting
-                | PublicSite::Unknown => None,
-            })
-        },
-    )
+    web_url_match(environment, web_url, Key::RepoCommitPrefixUrl, &|url| {
+        if let Some(templated) =
+            templated_url(environment, &url, |templates| templates.pages.as_ref())
+        {
+            return Ok(Some(templated));
+        }
+        let public_site = PublicSite::from(url.host());
+        let hosting_type = if public_site == PublicSite::Unknown {
+            environment.settings.hosting_type(&url)
+        } else {
+            HostingType::from(public_site)
+        };
+        hosting_type
+            .forge()
+            .build_hosting_url(environment, url, public_site)
+    })
 }
 
 /// Converts a common web hosting URL (HTTPS)
@@ -924,6 +1426,38 @@ pub fn web_url_to_build_hosting_url(environment: &Environment, web_url: &str) ->
 ///     web_url_to_clone_url(&environment, "https://repo.or.cz/girocco.git", TransferProtocol::Git)?,
 ///     Some("git://repo.or.cz/girocco.git".to_owned())
 /// );
+/// assert_eq!(
+///     web_url_to_clone_url(&environment, "https://app.radicle.xyz/nodes/seed.radicle.garden/rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5", TransferProtocol::Https)?,
+///     Some("https://seed.radicle.garden/z3gqcJUoA1n9HaHKufZs5FCSGazv5.git".to_owned())
+/// );
+/// assert_eq!(
+///     web_url_to_clone_url(&environment, "https://gerrit.googlesource.com/gerrit", TransferProtocol::Https)?,
+///     Some("https://gerrit.googlesource.com/gerrit".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// A self-hosted instance served under a base path
+/// (see [`crate::settings::Settings::hosting_base_path`]):
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::tools::git::TransferProtocol;
+/// # use projvar::tools::git_hosting_provs::HostingType;
+/// # use projvar::value_conversions::web_url_to_clone_url;
+/// # use projvar::environment::Environment;
+/// let mut environment = Environment::stub();
+/// environment.settings.hosting_domains.insert("gitlab.example.com".to_owned(), HostingType::GitLab);
+/// environment.settings.hosting_base_path = Some("gitlab".to_owned());
+/// assert_eq!(
+///     web_url_to_clone_url(&environment, "https://gitlab.example.com/gitlab/group/sub-group/project", TransferProtocol::Https)?,
+///     Some("https://gitlab.example.com/gitlab/group/sub-group/project.git".to_owned())
+/// );
+/// assert_eq!(
+///     web_url_to_clone_url(&environment, "https://gitlab.example.com/gitlab/group/sub-group/project", TransferProtocol::Ssh)?,
+///     Some("ssh://git@gitlab.example.com/group/sub-group/project.git".to_owned())
+/// );
 /// # Ok(())
 /// # }
 /// ```
@@ -937,22 +1471,13 @@ pub fn web_url_to_clone_url(
     web_url: &str,
     protocol: TransferProtocol,
 ) -> Res {
-    lazy_static! {
-        static ref R_SLASH_AT_END: Regex = Regex::new(r"^(.+?)/?$").unwrap();
-    }
     let key = protocol.to_clone_url_key();
-    let http_clone_url = web_url_match(environment, web_url, key, &|mut url| {
-        Ok(match environment.settings.hosting_type(&url) {
-            HostingType::GitHub | HostingType::GitLab | HostingType::BitBucket => {
-                let path = R_SLASH_AT_END.replace(url.path(), "$1.git").into_owned();
-                url.set_path(&path);
-                Some(url.to_string())
-            }
-            HostingType::SourceHut | HostingType::RocketGit | HostingType::Girocco => {
-                Some(url.to_string())
-            }
-            _ => None, // TODO Implement the others!
-        })
+    let http_clone_url = web_url_match(environment, web_url, key, &|url| {
+        Ok(environment
+            .settings
+            .hosting_type(&url)
+            .forge()
+            .http_clone_url(url, protocol))
     })?;
     clone_url_conversion_option(http_clone_url.as_ref(), environment, protocol)
 }
@@ -1027,60 +1552,518 @@ pub fn web_url_to_clone_url(
 ///     clone_url_to_web_url(&environment, "ssh://repo.or.cz/girocco.git")?,
 ///     Some("https://repo.or.cz/girocco.git".to_owned())
 /// );
+/// assert_eq!(
+///     clone_url_to_web_url(&environment, "rad://z3gqcJUoA1n9HaHKufZs5FCSGazv5")?,
+///     Some("https://app.radicle.xyz/nodes/seed.radicle.garden/rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_to_web_url(&environment, "https://seed.radicle.xyz/z3gqcJUoA1n9HaHKufZs5FCSGazv5.git")?,
+///     Some("https://app.radicle.xyz/nodes/seed.radicle.xyz/rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_to_web_url(&environment, "ssh://hoijui@gerrit.googlesource.com:29418/gerrit")?,
+///     Some("https://gerrit.googlesource.com/gerrit".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_to_web_url(&environment, "https://gerrit.googlesource.com/a/gerrit.git")?,
+///     Some("https://gerrit.googlesource.com/gerrit".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// A self-hosted instance served under a base path
+/// (see [`crate::settings::Settings::hosting_base_path`]):
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::tools::git_hosting_provs::HostingType;
+/// # use projvar::value_conversions::clone_url_to_web_url;
+/// # use projvar::environment::Environment;
+/// let mut environment = Environment::stub();
+/// environment.settings.hosting_domains.insert("gitlab.example.com".to_owned(), HostingType::GitLab);
+/// environment.settings.hosting_base_path = Some("gitlab".to_owned());
+/// assert_eq!(
+///     clone_url_to_web_url(&environment, "ssh://git@gitlab.example.com:2222/group/sub-group/project.git")?,
+///     Some("https://gitlab.example.com/gitlab/group/sub-group/project".to_owned())
+/// );
+/// assert_eq!(
+///     clone_url_to_web_url(&environment, "https://gitlab.example.com/gitlab/group/sub-group/project.git")?,
+///     Some("https://gitlab.example.com/gitlab/group/sub-group/project".to_owned())
+/// );
 /// # Ok(())
 /// # }
 /// ```
 pub fn clone_url_to_web_url(environment: &Environment, any_clone_url: &str) -> Res {
-    lazy_static! {
-        static ref R_DOT_GIT_SUFFIX: Regex = Regex::new(r"\.git$").unwrap();
+    match clone_url_conversion(any_clone_url, environment, TransferProtocol::Https)? {
+        Some(https_clone_url) => match Url::parse(&https_clone_url) {
+            Err(err) => Err(Error::BadInputValueErr {
+                key: Key::RepoWebUrl,
+                msg: "Not a valid URL".to_owned(),
+                input: https_clone_url.clone(),
+                source: Box::new(err),
+            }),
+            Ok(url) => environment
+                .settings
+                .hosting_type(&url)
+                .forge()
+                .web_url_from_clone_url(url),
+        },
+        None => Ok(None),
     }
+}
 
-    match clone_url_conversion(any_clone_url, environment, TransferProtocol::Https)? {
-        Some(https_clone_url) => {
-            match Url::parse(&https_clone_url) {
-                Err(err) => Err(Error::BadInputValueErr {
-                    key: Key::RepoWebUrl,
-                    msg: "Not a valid URL".to_owned(),
-                    input: https_clone_url.clone(),
-                    source: Box::new(err),
-                }),
-                Ok(mut url) => {
-                    Ok(match environment.settings.hosting_type(&url) {
-                        HostingType::GitHub
-                        | HostingType::GitLab
-                        | HostingType::BitBucket
-                        | HostingType::Gitea => {
-                            let old_path = url.path().to_owned();
-                            url.set_path(R_DOT_GIT_SUFFIX.replace(&old_path, "").as_ref());
-                            url.set_username("").map_err(|_err| Error::BadInputValue {
-                                key: Key::RepoWebUrl,
-                                msg: "Failed to set username".to_owned(),
-                                input: any_clone_url.to_owned(),
-                            })?;
-                            Some(url.to_string())
-                        }
-                        HostingType::Girocco | HostingType::SourceHut | HostingType::RocketGit => {
-                            Some(https_clone_url)
-                        } // Web-hosting and HTTP clone URL are exactly identical
-                        _ => None, // TODO Implement the others!
-                    })
-                }
-            }
+/// Date formats (as `strftime` patterns) including a time-zone offset,
+/// as found in CI supplied values.
+const DATE_FORMATS_WITH_OFFSET: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S %z",
+    "%Y-%m-%d %H:%M:%S%.f %z",
+    "%Y-%m-%dT%H:%M:%S%z",
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    // git default, e.g. "Thu Sep 30 20:59:05 2021 +0200"
+    "%a %b %e %H:%M:%S %Y %z",
+];
+
+/// Date formats (as `strftime` patterns) without a time-zone,
+/// as found in CI supplied values;
+/// UTC is assumed for them.
+const DATE_FORMATS_NAIVE: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    // old Jenkins BUILD_ID, e.g. "2021-09-30_20-59-05"
+    "%Y-%m-%d_%H-%M-%S",
+];
+
+/// Time-zone names that may follow a date without offset,
+/// and that are equivalent to UTC.
+const UTC_NAMES: &[&str] = &[" UTC", " GMT", "Z"];
+
+/// Unix timestamps with at least this many digits
+/// are taken to be in milliseconds, not seconds.
+/// Ten to the power of 11 seconds is in the year 5138,
+/// while as milliseconds, it is in 1973.
+const EPOCH_MILLIS_MIN_DIGITS: usize = 12;
+
+/// Parses a Unix timestamp in seconds or milliseconds
+/// (see [`EPOCH_MILLIS_MIN_DIGITS`]).
+fn parse_epoch(epoch: &str) -> Option<DateTime<FixedOffset>> {
+    let digits = epoch.strip_prefix('-').unwrap_or(epoch);
+    if digits.is_empty() || !digits.chars().all(|chr| chr.is_ascii_digit()) {
+        return None;
+    }
+    let number = epoch.parse::<i64>().ok()?;
+    let date = if digits.len() >= EPOCH_MILLIS_MIN_DIGITS {
+        DateTime::from_timestamp_millis(number)
+    } else {
+        DateTime::from_timestamp(number, 0)
+    };
+    date.map(|date| date.fixed_offset())
+}
+
+/// Parses a date in any of the formats commonly found in CI supplied values.
+///
+/// These are RFC 3339 (ISO 8601), RFC 2822, Unix timestamps in seconds or milliseconds,
+/// and some common `strftime` patterns (see [`DATE_FORMATS_WITH_OFFSET`]
+/// and [`DATE_FORMATS_NAIVE`]).
+/// Dates without a time-zone are taken to be in UTC,
+/// dates without a time to be at midnight.
+#[must_use]
+pub fn parse_date(in_date: &str) -> Option<DateTime<FixedOffset>> {
+    let in_date = in_date.trim();
+    if let Some(date) = parse_epoch(in_date) {
+        return Some(date);
+    }
+    if let Ok(date) = DateTime::parse_from_rfc3339(in_date) {
+        return Some(date);
+    }
+    if let Ok(date) = DateTime::parse_from_rfc2822(in_date) {
+        return Some(date);
+    }
+    if let Some(date) = DATE_FORMATS_WITH_OFFSET
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(in_date, format).ok())
+    {
+        return Some(date);
+    }
+    let naive = UTC_NAMES
+        .iter()
+        .find_map(|utc_name| in_date.strip_suffix(utc_name))
+        .unwrap_or(in_date);
+    DATE_FORMATS_NAIVE
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(naive, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(naive, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .map(|date| date.and_utc().fixed_offset())
+}
+
+/// Converts a date in any of the formats commonly found in CI supplied values
+/// (see [`parse_date`])
+/// into the date format and time zone in our settings.
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::date_to_our_format;
+/// # use projvar::environment::Environment;
+/// # use projvar::var::Key;
+/// # let environment = Environment::stub();
+/// for in_date in [
+///     "2021-09-30T22:59:05+02:00",
+///     "Thu, 30 Sep 2021 20:59:05 +0000",
+///     "1633035545",
+///     "1633035545000",
+///     "2021-09-30 20:59:05 UTC",
+///     "2021-09-30 22:59:05 +0200",
+///     "2021-09-30_20-59-05",
+/// ] {
+///     assert_eq!(
+///         date_to_our_format(&environment, Key::VersionDate, in_date)?,
+///         Some("2021-09-30 20:59:05".to_owned())
+///     );
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// If `in_date` is in none of the supported formats.
+pub fn date_to_our_format(environment: &Environment, key: Key, in_date: &str) -> Res {
+    let date = parse_date(in_date).ok_or_else(|| Error::BadInputValue {
+        key,
+        msg: "Date is in none of the supported formats".to_owned(),
+        input: in_date.to_owned(),
+    })?;
+    Ok(Some(environment.settings.format_date(key, &date)))
+}
+
+/// Converts a Unix timestamp (seconds since 1970-01-01 UTC),
+/// as for example found in `SOURCE_DATE_EPOCH`,
+/// into the date format and time zone in our settings.
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::epoch_to_our_format;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(
+///     epoch_to_our_format(&environment, "1633035545")?,
+///     Some("2021-09-30 20:59:05".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// If `epoch` is not an integer,
+/// or it is out of the range of representable dates.
+pub fn epoch_to_our_format(environment: &Environment, epoch: &str) -> Res {
+    let secs = epoch
+        .trim()
+        .parse::<i64>()
+        .map_err(|err| Error::BadInputValueErr {
+            key: Key::BuildDate,
+            msg: "Failed to parse Unix timestamp".to_owned(),
+            input: epoch.to_owned(),
+            source: Box::new(err),
+        })?;
+    let date = DateTime::from_timestamp(secs, 0).ok_or_else(|| Error::BadInputValue {
+        key: Key::BuildDate,
+        msg: "Unix timestamp is out of the range of representable dates".to_owned(),
+        input: epoch.to_owned(),
+    })?;
+    Ok(Some(
+        environment.settings.format_date(Key::BuildDate, &date),
+    ))
+}
+
+/// Parses a date as generated by us,
+/// in the date format and time zone in our settings
+/// (see [`crate::settings::Settings::format_date`]),
+/// falling back to the formats supported by [`parse_date`].
+#[must_use]
+pub fn parse_our_date(
+    environment: &Environment,
+    key: Key,
+    in_date: &str,
+) -> Option<DateTime<FixedOffset>> {
+    let in_date = in_date.trim();
+    let date_format = environment.settings.date_format_for(key);
+    DateTime::parse_from_str(in_date, date_format)
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(in_date, date_format)
+                .ok()
+                .or_else(|| {
+                    NaiveDate::parse_from_str(in_date, date_format)
+                        .ok()
+                        .and_then(|date| date.and_hms_opt(0, 0, 0))
+                })
+                .and_then(|naive| environment.settings.date_timezone.from_naive(&naive))
+        })
+        .or_else(|| parse_date(in_date))
+}
+
+/// Converts a date into a Unix timestamp (seconds since 1970-01-01 UTC),
+/// as for example used in `SOURCE_DATE_EPOCH`.
+/// The date is expected in the format of the given key
+/// (see [`parse_our_date`]).
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::date_to_epoch;
+/// # use projvar::environment::Environment;
+/// # use projvar::var::Key;
+/// # let environment = Environment::stub();
+/// assert_eq!(
+///     date_to_epoch(&environment, "2021-09-30 20:59:05", Key::BuildDate)?,
+///     Some("1633035545".to_owned())
+/// );
+/// assert_eq!(
+///     date_to_epoch(&environment, "2021-09-30T22:59:05+02:00", Key::VersionDate)?,
+///     Some("1633035545".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// If `in_date` is in none of the supported formats.
+pub fn date_to_epoch(environment: &Environment, in_date: &str, key: Key) -> Res {
+    let date = parse_our_date(environment, key, in_date).ok_or_else(|| Error::BadInputValue {
+        key,
+        msg: "Date is in none of the supported formats".to_owned(),
+        input: in_date.to_owned(),
+    })?;
+    Ok(Some(date.timestamp().to_string()))
+}
+
+/// Tries to parse a version as SemVer,
+/// ignoring a leading 'v', as commonly used in git tags.
+/// If it can not be parsed, we return `None`,
+/// as non-SemVer versions (e.g. raw git SHAs) are perfectly legit.
+fn parse_sem_ver(version: &str) -> Option<semver::Version> {
+    let stripped = version.strip_prefix('v').unwrap_or(version);
+    match semver::Version::parse(stripped) {
+        Ok(sem_ver) => Some(sem_ver),
+        Err(err) => {
+            log::debug!("Version '{version}' is not SemVer, so we can not decompose it: {err}");
+            None
         }
-        None => Ok(None),
     }
 }
 
-/// Converts an ISO 8601 formatted date string
-/// into the date frmat in our settings.
+/// Extracts the major component from a SemVer version.
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::version_to_major;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(version_to_major(&environment, "1.2.3")?, Some("1".to_owned()));
+/// assert_eq!(version_to_major(&environment, "v10.2.3-rc1")?, Some("10".to_owned()));
+/// assert_eq!(version_to_major(&environment, "ffac537e6cbbf934b0")?, None);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Never, but we keep the signature in line with the other conversions.
+#[allow(clippy::unnecessary_wraps)]
+pub fn version_to_major(_environment: &Environment, version: &str) -> Res {
+    Ok(parse_sem_ver(version).map(|sem_ver| sem_ver.major.to_string()))
+}
+
+/// Extracts the minor component from a SemVer version.
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::version_to_minor;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(version_to_minor(&environment, "1.2.3")?, Some("2".to_owned()));
+/// assert_eq!(version_to_minor(&environment, "1.2")?, None);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Never, but we keep the signature in line with the other conversions.
+#[allow(clippy::unnecessary_wraps)]
+pub fn version_to_minor(_environment: &Environment, version: &str) -> Res {
+    Ok(parse_sem_ver(version).map(|sem_ver| sem_ver.minor.to_string()))
+}
+
+/// Extracts the patch component from a SemVer version.
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::version_to_patch;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(version_to_patch(&environment, "1.2.3+build5")?, Some("3".to_owned()));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Never, but we keep the signature in line with the other conversions.
+#[allow(clippy::unnecessary_wraps)]
+pub fn version_to_patch(_environment: &Environment, version: &str) -> Res {
+    Ok(parse_sem_ver(version).map(|sem_ver| sem_ver.patch.to_string()))
+}
+
+/// Extracts the pre-release component from a SemVer version,
+/// if it has one.
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::version_to_prerelease;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(
+///     version_to_prerelease(&environment, "1.2.3-rc1+build5")?,
+///     Some("rc1".to_owned())
+/// );
+/// assert_eq!(
+///     version_to_prerelease(&environment, "0.2.0-1-ga5387ac-dirty")?,
+///     Some("1-ga5387ac-dirty".to_owned())
+/// );
+/// assert_eq!(version_to_prerelease(&environment, "1.2.3")?, None);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Never, but we keep the signature in line with the other conversions.
+#[allow(clippy::unnecessary_wraps)]
+pub fn version_to_prerelease(_environment: &Environment, version: &str) -> Res {
+    Ok(parse_sem_ver(version)
+        .filter(|sem_ver| !sem_ver.pre.is_empty())
+        .map(|sem_ver| sem_ver.pre.to_string()))
+}
+
+/// Extracts the build metadata component from a SemVer version,
+/// if it has one.
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::version_to_build_meta;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(
+///     version_to_build_meta(&environment, "1.2.3-rc1+build5")?,
+///     Some("build5".to_owned())
+/// );
+/// assert_eq!(version_to_build_meta(&environment, "1.2.3-rc1")?, None);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Never, but we keep the signature in line with the other conversions.
+#[allow(clippy::unnecessary_wraps)]
+pub fn version_to_build_meta(_environment: &Environment, version: &str) -> Res {
+    Ok(parse_sem_ver(version)
+        .filter(|sem_ver| !sem_ver.build.is_empty())
+        .map(|sem_ver| sem_ver.build.to_string()))
+}
+
+/// The number of hex digits we abbreviate a commit SHA to,
+/// which is the git default for small to medium sized repos.
+pub const SHA_SHORT_LENGTH: usize = 7;
+
+/// Abbreviates a full commit SHA to [`SHA_SHORT_LENGTH`] hex digits.
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::sha_to_short_sha;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(
+///     sha_to_short_sha(&environment, "9b5ff47e6e2b1e7ab7ac0fad3b8a13aa35b3b1a3")?,
+///     Some("9b5ff47".to_owned())
+/// );
+/// assert!(sha_to_short_sha(&environment, "not-a-sha").is_err());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// If `sha` is not a hex string of at least [`SHA_SHORT_LENGTH`] digits.
+pub fn sha_to_short_sha(_environment: &Environment, sha: &str) -> Res {
+    if sha.len() < SHA_SHORT_LENGTH || !sha.chars().all(|chr| chr.is_ascii_hexdigit()) {
+        return Err(Error::BadInputValue {
+            key: Key::BuildCommitShaShort,
+            msg: format!(
+                "Not a commit SHA; expected a hex string of at least {SHA_SHORT_LENGTH} digits"
+            ),
+            input: sha.to_owned(),
+        });
+    }
+    Ok(sha.get(..SHA_SHORT_LENGTH).map(ToOwned::to_owned))
+}
+
+/// Figures out whether the working tree was dirty
+/// from the output of `git describe --dirty`.
+///
+/// for example:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use projvar::value_conversions::describe_to_dirty;
+/// # use projvar::environment::Environment;
+/// # let environment = Environment::stub();
+/// assert_eq!(
+///     describe_to_dirty(&environment, "v0.2.0-3-g9b5ff47-dirty")?,
+///     Some("true".to_owned())
+/// );
+/// assert_eq!(
+///     describe_to_dirty(&environment, "v0.2.0-3-g9b5ff47")?,
+///     Some("false".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
 ///
 /// # Errors
 ///
-/// If `in_date` is not a valid ISO 8601 date,
-/// or the date format in our settings is invalid.
-pub fn date_iso8601_to_our_format(environment: &Environment, in_date: &str) -> Res {
-    let parsed = DateTime::parse_from_rfc3339(in_date)?;
+/// Never, but we keep the signature in line with the other conversions.
+#[allow(clippy::unnecessary_wraps)]
+pub fn describe_to_dirty(_environment: &Environment, describe: &str) -> Res {
     Ok(Some(
-        parsed.format(&environment.settings.date_format).to_string(),
+        crate::tools::git::is_git_dirty_version(describe).to_string(),
     ))
 }
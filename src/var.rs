@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use cli_utils::BoxResult;
+use cli_utils::BoxError;
 use enum_map::Enum;
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -20,21 +20,130 @@ use thiserror::Error;
 
 use std::str::FromStr;
 
+use crate::constants;
 use crate::environment::Environment;
+use crate::validator::{self, Validator};
 
-/// Confidence in a concrete value returned by a call to [`crate::sources::VarSource::retrieve`].
-/// Higher is better.
+/// Confidence in a concrete value, or in the validity of a value.
+///
+/// The former is returned by a call to [`crate::sources::VarSource::retrieve`],
+/// the latter is judged by a [`Validator`].
+/// It ranges from [`Self::NONE`] to [`Self::MAX`]; higher is better.
 ///
 /// This is eventually used as one criteria to decide which value to prefer,
 /// if multiple ones are present for a single key.
-pub type Confidence = u8;
-pub const C_HIGH: Confidence = 75;
-pub const C_MIDDLE: Confidence = 50;
-pub const C_LOW: Confidence = 25;
+///
+/// ```
+/// # use projvar::var::ConfidenceScore;
+/// assert!(ConfidenceScore::HIGH > ConfidenceScore::MIDDLE);
+/// assert_eq!(ConfidenceScore::new(250), ConfidenceScore::MAX);
+/// assert_eq!(ConfidenceScore::HIGH.saturating_add(50), ConfidenceScore::MAX);
+/// assert_eq!(ConfidenceScore::LOW.saturating_sub(50), ConfidenceScore::NONE);
+/// assert_eq!(ConfidenceScore::HIGH.to_string(), "75 (high)");
+/// assert_eq!(ConfidenceScore::new(94).to_string(), "94");
+/// ```
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(from = "u8", into = "u8")]
+pub struct ConfidenceScore(u8);
+
+impl ConfidenceScore {
+    /// No confidence at all.
+    pub const NONE: Self = Self(0);
+    /// Rather a guess.
+    pub const LOW: Self = Self(25);
+    /// Usually right.
+    pub const MIDDLE: Self = Self(50);
+    /// As sure as it gets for a single source.
+    pub const HIGH: Self = Self(75);
+    /// Absolutely certain; there is nothing above this.
+    pub const MAX: Self = Self(100);
+
+    /// The named levels, from lowest to highest.
+    const LEVELS: [(Self, &'static str); 5] = [
+        (Self::NONE, "none"),
+        (Self::LOW, "low"),
+        (Self::MIDDLE, "middle"),
+        (Self::HIGH, "high"),
+        (Self::MAX, "max"),
+    ];
+
+    /// Creates a score, capped at [`Self::MAX`].
+    #[must_use]
+    pub const fn new(score: u8) -> Self {
+        if score > Self::MAX.0 {
+            Self::MAX
+        } else {
+            Self(score)
+        }
+    }
+
+    /// The raw score, between 0 and 100.
+    #[must_use]
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+
+    /// The name of the level, if this score is exactly one of the named ones.
+    #[must_use]
+    pub fn level_name(self) -> Option<&'static str> {
+        Self::LEVELS
+            .iter()
+            .find(|(level, _name)| *level == self)
+            .map(|(_level, name)| *name)
+    }
+
+    /// Raises the score, but never above [`Self::MAX`].
+    #[must_use]
+    pub const fn saturating_add(self, amount: u8) -> Self {
+        Self::new(self.0.saturating_add(amount))
+    }
+
+    /// Lowers the score, but never below [`Self::NONE`].
+    #[must_use]
+    pub const fn saturating_sub(self, amount: u8) -> Self {
+        Self(self.0.saturating_sub(amount))
+    }
+
+    /// Combines the confidences of two sources that agree on a value.
+    /// They are treated like the probabilities of independent events,
+    /// of which at least one has to occur:
+    /// `P(A or B) = P(A) + P(B) - P(A) * P(B)`.
+    /// Thus the result is higher than either of them,
+    /// but never exceeds [`Self::MAX`].
+    #[must_use]
+    pub fn corroborate(self, other: Self) -> Self {
+        let (conf_a, conf_b) = (u16::from(self.0), u16::from(other.0));
+        let max = u16::from(Self::MAX.0);
+        let combined = conf_a + conf_b - (conf_a * conf_b / max);
+        u8::try_from(combined.min(max)).map_or(Self::MAX, Self::new)
+    }
+}
+
+impl From<u8> for ConfidenceScore {
+    fn from(score: u8) -> Self {
+        Self::new(score)
+    }
+}
+
+impl From<ConfidenceScore> for u8 {
+    fn from(score: ConfidenceScore) -> Self {
+        score.0
+    }
+}
+
+impl Display for ConfidenceScore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.level_name() {
+            Some(name) => write!(f, "{} ({name})", self.0),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
 
 // #[derive(Clone)]
-// #[derive(Debug)]
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Variable {
     key: &'static str,
     pub description: &'static str,
@@ -43,14 +152,34 @@ pub struct Variable {
 }
 
 impl Variable {
+    /// Whether this is a standard variable, not one of ours,
+    /// which is why its key is used verbatim,
+    /// without our prefix or case conversion.
+    fn is_standard(&self) -> bool {
+        std::ptr::eq(self, &raw const VAR_SOURCE_DATE_EPOCH)
+    }
+
     #[must_use]
     pub fn key(&self, environment: &Environment) -> Cow<str> {
+        if self.is_standard() {
+            return Cow::Borrowed(self.key);
+        }
         match &environment.settings.key_prefix {
             Some(prefix) => Cow::Owned(prefix.clone() + self.key),
             None => Cow::Borrowed(self.key),
         }
     }
 
+    /// The key as written to the sinks,
+    /// which is [`Self::key`] converted to [`crate::settings::Settings::key_case`].
+    #[must_use]
+    pub fn key_out(&self, environment: &Environment) -> Cow<'_, str> {
+        if self.is_standard() {
+            return Cow::Borrowed(self.key);
+        }
+        environment.settings.key_case.apply(self.key(environment))
+    }
+
     /// The raw key, without prefix.
     /// NOTE You should probably use [`Self::key`] instead.
     #[must_use]
@@ -79,6 +208,144 @@ impl<'a> Default for &'a Variable {
     }
 }
 
+/// A project property that is not covered by [`Key`].
+///
+/// It is registered at runtime - usually by a library user -
+/// through [`Environment::register_dynamic_key`].
+/// Its values flow through the same storage, validation and sinks
+/// as the ones of the built-in keys.
+#[derive(Debug)]
+pub struct DynamicKey {
+    /// The name of the property in `"CamelCase"`, e.g. `"FirmwareVariant"`.
+    pub name: &'static str,
+    /// The associated variable meta-data;
+    /// its key is the name in `"UPPER_SNAKE_CASE"`.
+    pub variable: Variable,
+    /// Checks the primary value, if one was found.
+    /// If this is `None`, any value is accepted.
+    pub validator: Option<Validator>,
+}
+
+impl DynamicKey {
+    /// Creates a new dynamic key.
+    ///
+    /// Because keys get registered only once per run,
+    /// the key and its strings are leaked,
+    /// which allows to use them just like the built-in ones.
+    #[must_use]
+    pub fn create(name: &str, description: &str, validator: Option<Validator>) -> &'static Self {
+        let var_key = camel_to_upper_snake_case(name);
+        Box::leak(Box::new(Self {
+            name: Box::leak(name.to_owned().into_boxed_str()),
+            variable: Variable {
+                key: Box::leak(var_key.into_boxed_str()),
+                description: Box::leak(description.to_owned().into_boxed_str()),
+                default_required: false,
+            },
+            validator,
+        }))
+    }
+}
+
+/// Either a built-in or a dynamic key.
+///
+/// Built-in keys sort before dynamic ones;
+/// the later are sorted by name.
+#[derive(Debug, Clone, Copy)]
+pub enum AnyKey {
+    Builtin(Key),
+    Dynamic(&'static DynamicKey),
+}
+
+impl AnyKey {
+    /// The name of the key in `"CamelCase"`.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Builtin(key) => key.into(),
+            Self::Dynamic(key) => key.name,
+        }
+    }
+
+    #[must_use]
+    pub const fn builtin(self) -> Option<Key> {
+        match self {
+            Self::Builtin(key) => Some(key),
+            Self::Dynamic(_) => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn variable(self) -> &'static Variable {
+        match self {
+            Self::Builtin(key) => get(key),
+            Self::Dynamic(key) => &key.variable,
+        }
+    }
+
+    /// The built-in validator for values of this key,
+    /// or the one supplied on registration for dynamic keys.
+    /// NOTE You should probably use [`validator::get`] instead,
+    ///      which also applies the user supplied policies.
+    #[must_use]
+    pub fn validator(self) -> Validator {
+        match self {
+            Self::Builtin(key) => validator::get_builtin(key),
+            Self::Dynamic(key) => key.validator.unwrap_or(validator::validate_any),
+        }
+    }
+
+    fn sort_key(self) -> (bool, Option<Key>, &'static str) {
+        (self.builtin().is_none(), self.builtin(), self.name())
+    }
+}
+
+impl From<Key> for AnyKey {
+    fn from(key: Key) -> Self {
+        Self::Builtin(key)
+    }
+}
+
+impl Display for AnyKey {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "{}", self.name())
+    }
+}
+
+/// Serializes to the name of the key,
+/// so it may be used as the key of a map, e.g. in JSON.
+impl Serialize for AnyKey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl PartialEq for AnyKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.sort_key() == other.sort_key()
+    }
+}
+
+impl Eq for AnyKey {}
+
+impl std::hash::Hash for AnyKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.sort_key().hash(state);
+    }
+}
+
+impl PartialOrd for AnyKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AnyKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
 #[remain::sorted]
 // #[derive(Debug, EnumString, EnumIter, IntoStaticStr, PartialEq, Eq, Hash, Copy, Clone, Enum)]
 // #[derive(Debug, EnumString, EnumIter, IntoStaticStr, Hash, Enum, EnumSetType)]
@@ -102,30 +369,63 @@ impl<'a> Default for &'a Variable {
 pub enum Key {
     BuildArch,
     BuildBranch,
+    BuildCommitSha,
+    BuildCommitShaShort,
     BuildDate,
+    BuildDateEpoch,
+    BuildDirty,
+    BuildHostCpuCount,
     BuildHostingUrl,
+    BuildHostKernelVersion,
+    BuildHostPlatformTriple,
     // BuildIdent, // TODO This name is very bad, as it makes one think of BUILD_NUMBER; choose a different one! Maybe refunction it as well(?) -> `HumanVersion` (vs a machine-readable one like from git describe, which goes to `Version`), for example "Ubuntu 10.04 - UbsiDubsi"
     BuildNumber,
     BuildOs,
     BuildOsFamily,
     BuildTag,
     Ci,
+    Description,
+    Doi,
+    FundingUrl,
     License,
     Licenses,
+    LicensesCompliance,
     Name,
     NameMachineReadable,
+    NameSlug,
+    PullRequestNumber,
+    PullRequestSourceBranch,
+    PullRequestTargetBranch,
+    PullRequestTitle,
+    PullRequestUrl,
+    RepoCiUrl,
     RepoCloneUrl,
     RepoCloneUrlGit,
     RepoCloneUrlHttp,
+    RepoCloneUrlPush,
     RepoCloneUrlSsh,
     RepoCommitPrefixUrl,
+    RepoDefaultBranch,
+    RepoForkOfUrl,
+    RepoForkUrl,
     RepoIssuesUrl,
+    RepoNamespace,
+    RepoOwner,
     RepoRawVersionedPrefixUrl,
+    RepoReleasesUrl,
+    RepoSlug,
     RepoVersionedDirPrefixUrl,
     RepoVersionedFilePrefixUrl,
     RepoWebUrl,
     Version,
+    VersionBuildMeta,
+    VersionControlSystem,
     VersionDate,
+    VersionDescribe,
+    VersionMajor,
+    VersionMinor,
+    VersionPatch,
+    VersionPrerelease,
 }
 
 /// Converts a `"CamelCase"` string into an `"UPPER_SNAKE_CASE"` one.
@@ -165,9 +465,72 @@ pub fn camel_to_upper_snake_case(id: &str) -> String {
 }
 
 #[derive(Error, Debug)]
-#[error("Not a valid key name: '{name}'")]
+#[error("Not a valid key name: '{name}'{}", fmt_suggestions(.suggestions))]
 pub struct InvalidKey {
     name: String,
+    suggestions: Vec<String>,
+}
+
+fn fmt_suggestions(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [suggestion] => format!("; did you mean '{suggestion}'?"),
+        _ => format!("; did you mean one of '{}'?", suggestions.join("', '")),
+    }
+}
+
+impl InvalidKey {
+    /// Creates the error for an identifier that does not map to any key,
+    /// including the most similar valid identifiers
+    /// as suggestions.
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            suggestions: suggest_key_ids(name),
+        }
+    }
+
+    /// The valid key identifiers most similar to the invalid one,
+    /// the closest first;
+    /// see [`SUGGESTIONS_MAX`].
+    #[must_use]
+    pub fn suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("{msg}; key-value pairs have to be of the form \"key=value\": '{key_value}'")]
+pub struct InvalidKeyValue {
+    msg: &'static str,
+    key_value: String,
+}
+
+/// This enumerates all possible errors
+/// when parsing a file containing "KEY=VALUE" lines.
+#[derive(Error, Debug)]
+pub enum ParseError {
+    #[error(transparent)]
+    InvalidKeyValue(#[from] InvalidKeyValue),
+
+    /// A quoted value or a heredoc was not terminated before the end of the file.
+    #[error("The value of '{key}' is missing its closing quote or heredoc delimiter")]
+    Unterminated { key: String },
+
+    /// Represents all cases of `std::io::Error`.
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+
+    /// Represents all other cases of `std::error::Error`.
+    #[error(transparent)]
+    Other(#[from] BoxError),
+}
+
+#[derive(Error, Debug)]
+#[error("Key name or variable key is already in use: '{name}'")]
+pub struct KeyTaken {
+    pub name: String,
 }
 
 /// Converts an `"UPPER_SNAKE_CASE"` string into an `"CamelCase"` one.
@@ -226,10 +589,149 @@ impl Key {
                     key_prefix.replace(id, "").as_ref(),
                 ))
             })
-            .map_err(|_err| InvalidKey {
-                name: id.to_owned(),
+            .map_err(|_err| InvalidKey::new(id))
+    }
+
+    /// Like [`Self::from_name_or_var_key`],
+    /// but additionally supports simple glob patterns,
+    /// where `*` matches any number of characters
+    /// and `?` matches exactly one character,
+    /// for example `"REPO_*"` or `"Build*"`.
+    /// The pattern is matched against both the name of the `Key`
+    /// and its associated variable key (with and without prefix).
+    ///
+    /// # Errors
+    ///
+    /// If the given identifier is not a pattern
+    /// and could not be mapped to any `Key` variant,
+    /// or if it is a pattern and matches no `Key` at all.
+    pub fn from_name_or_var_key_glob(
+        key_prefix: &Regex,
+        id: &str,
+    ) -> Result<Vec<Self>, InvalidKey> {
+        if !is_glob(id) {
+            return Self::from_name_or_var_key(key_prefix, id).map(|key| vec![key]);
+        }
+        let r_pattern = glob_to_regex(id);
+        let r_pattern_unprefixed = glob_to_regex(key_prefix.replace(id, "").as_ref());
+        let keys: Vec<Self> = Self::iter()
+            .filter(|key| {
+                let name: &'static str = key.into();
+                let var_key = get(*key).key_raw();
+                r_pattern.is_match(name)
+                    || r_pattern.is_match(var_key)
+                    || r_pattern_unprefixed.is_match(var_key)
             })
+            .collect();
+        if keys.is_empty() {
+            return Err(InvalidKey {
+                name: id.to_owned(),
+                suggestions: vec![],
+            });
+        }
+        Ok(keys)
+    }
+}
+
+/// The maximum number of suggestions for an invalid key identifier.
+pub const SUGGESTIONS_MAX: usize = 3;
+
+/// The identifiers we suggest for invalid ones:
+/// the name of each key (e.g. `Version`),
+/// and its variable key with the default prefix (e.g. `PROJECT_VERSION`).
+fn key_ids() -> impl Iterator<Item = String> {
+    Key::iter().flat_map(|key| {
+        let name: &'static str = key.into();
+        [
+            name.to_owned(),
+            format!("{}{}", constants::DEFAULT_KEY_PREFIX, get(key).key_raw()),
+        ]
+    })
+}
+
+/// Returns up to [`SUGGESTIONS_MAX`] valid key identifiers
+/// closest to the given (invalid) one,
+/// by their edit distance (see [`strsim::levenshtein`]),
+/// the closest first.
+/// Candidates that differ in more then a third of the characters
+/// are not considered.
+fn suggest_key_ids(id: &str) -> Vec<String> {
+    let max_distance = (id.chars().count() / 3).max(1);
+    let mut candidates: Vec<(usize, String)> = key_ids()
+        .map(|candidate| (strsim::levenshtein(id, &candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    candidates.sort_by_key(|(distance, _)| *distance);
+    candidates
+        .into_iter()
+        .take(SUGGESTIONS_MAX)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Checks whether the given string identifies one or more keys.
+///
+/// This accepts what [`Key::from_name_or_var_key_glob`] does,
+/// for use as a CLI value parser.
+/// Because the key prefix is not known at this point,
+/// variable keys are accepted with the default prefix,
+/// without any prefix,
+/// or with any other prefix ending in '_';
+/// the final mapping to keys happens later,
+/// with the actual prefix.
+///
+/// ```
+/// # use projvar::var::parse_key_id;
+/// assert!(parse_key_id("PROJECT_VERSION").is_ok());
+/// assert!(parse_key_id("VersionDate").is_ok());
+/// assert!(parse_key_id("MY_REPO_*").is_ok());
+/// assert_eq!(
+///     parse_key_id("PROJECT_VERSON").unwrap_err().suggestions(),
+///     ["PROJECT_VERSION"]
+/// );
+/// ```
+///
+/// # Errors
+///
+/// If the given string does not identify any key.
+pub fn parse_key_id(id: &str) -> Result<String, InvalidKey> {
+    lazy_static! {
+        static ref R_DEFAULT_PREFIX: Regex =
+            Regex::new(&format!("^{}", constants::DEFAULT_KEY_PREFIX)).unwrap();
     }
+    let Err(first_err) = Key::from_name_or_var_key_glob(&R_DEFAULT_PREFIX, id) else {
+        return Ok(id.to_owned());
+    };
+    // custom prefixes might contain '_' themselves
+    let mut unprefixed = id;
+    while let Some((_prefix_part, rest)) = unprefixed.split_once('_') {
+        unprefixed = rest;
+        if Key::from_name_or_var_key_glob(&R_DEFAULT_PREFIX, unprefixed).is_ok() {
+            return Ok(id.to_owned());
+        }
+    }
+    Err(first_err)
+}
+
+/// Whether the given key identifier contains glob wildcards.
+fn is_glob(id: &str) -> bool {
+    id.contains(['*', '?'])
+}
+
+/// Converts a simple glob pattern (supporting only `*` and `?`)
+/// into an anchored regex.
+fn glob_to_regex(pattern: &str) -> Regex {
+    let mut r_str = String::with_capacity(pattern.len() + 8);
+    r_str.push('^');
+    for chr in pattern.chars() {
+        match chr {
+            '*' => r_str.push_str(".*"),
+            '?' => r_str.push('.'),
+            _ => r_str.push_str(&regex::escape(&chr.to_string())),
+        }
+    }
+    r_str.push('$');
+    Regex::new(&r_str).expect("All non-wildcard characters are escaped, so this is always valid")
 }
 
 // pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
@@ -240,43 +742,118 @@ impl Key {
 //     Ok(io::BufReader::new(file).lines())
 // }
 
-fn unquote(pot_quoted: &str) -> &str {
-    let len = pot_quoted.len();
-    if len > 1 {
-        let mut chars = pot_quoted.chars();
-        let first_char = chars.next().unwrap();
-        let last_char = chars.last().unwrap();
-        if (first_char == '"' && last_char == '"') || (first_char == '\'' && last_char == '\'') {
-            return &pot_quoted[1..len - 1];
+/// Parses the value part of a "KEY=VALUE" line.
+/// Values in double quotes may contain escaped `\`, `"`, `$` and `` ` ``,
+/// like in BASH;
+/// values in single quotes are taken literally;
+/// unquoted values are trimmed.
+/// Quoted values may span multiple lines.
+///
+/// Returns `None` if a quoted value is not terminated,
+/// which means it continues on the next line.
+fn parse_value(value: &str) -> Option<String> {
+    let value = value.trim_start();
+    let mut chars = value.chars();
+    match chars.next() {
+        Some('"') => {
+            let mut parsed = String::with_capacity(value.len());
+            while let Some(chr) = chars.next() {
+                match chr {
+                    '"' => return Some(parsed),
+                    '\\' => match chars.next()? {
+                        // an escaped line break continues the line
+                        '\n' => {}
+                        escaped @ ('\\' | '"' | '$' | '`') => parsed.push(escaped),
+                        other => {
+                            parsed.push('\\');
+                            parsed.push(other);
+                        }
+                    },
+                    other => parsed.push(other),
+                }
+            }
+            None
         }
+        Some('\'') => chars
+            .as_str()
+            .split_once('\'')
+            .map(|(quoted, _rest)| quoted.to_owned()),
+        _ => Some(value.trim_end().to_owned()),
     }
-    pot_quoted
+}
+
+/// Parses the start of a heredoc (`KEY<<DELIMITER`),
+/// as used for multi-line values e.g. in GitHub Actions `$GITHUB_ENV` files.
+fn parse_heredoc_start(line: &str) -> Option<(&str, &str)> {
+    let (key, delimiter) = line.split_once("<<")?;
+    let delimiter = delimiter.trim();
+    (!key.is_empty() && !key.contains('=') && !delimiter.is_empty()).then_some((key, delimiter))
 }
 
 /// Parses a file containing lines with strings of the from "KEY=VALUE".
 ///
 /// Empty lines and those starting with either "#" or "//" are ignored.
 ///
+/// Values may span multiple lines,
+/// either when quoted (see [`parse_value`]),
+/// or as a heredoc:
+///
+/// ```text
+/// KEY<<DELIMITER
+/// line 1
+/// line 2
+/// DELIMITER
+/// ```
+///
 /// # Errors
 ///
 /// If there is a problem with reading the file.
 ///
 /// If any line has a bad form, missing key and/or value.
-pub fn parse_vars_file_reader(mut reader: impl BufRead) -> BoxResult<HashMap<String, String>> {
+///
+/// If a quoted value or a heredoc is not terminated.
+pub fn parse_vars_file_reader(reader: impl BufRead) -> Result<HashMap<String, String>, ParseError> {
     lazy_static! {
         // Ignore empty lines and those starting with '#' or "//"
         static ref R_IGNORE_LINE: Regex = Regex::new(r"^($|#|//)").unwrap();
     }
     let mut vars = HashMap::<String, String>::new();
 
-    for line in cli_utils::lines_iterator(&mut reader, true) {
+    let mut lines = reader.lines();
+    while let Some(line) = lines.next() {
         let line = line?;
-        let line = line.trim();
-        if !R_IGNORE_LINE.is_match(line) {
-            let (key, value) = parse_key_value_str(line)?;
-            let value = unquote(&value);
-            vars.insert(key.clone(), value.to_owned());
+        // NOTE We may not trim the end yet,
+        //      as it might be part of a multi-line value.
+        let line = line.trim_start();
+        if R_IGNORE_LINE.is_match(line.trim_end()) {
+            continue;
         }
+        if let Some((key, delimiter)) = parse_heredoc_start(line.trim_end()) {
+            let mut value_lines = Vec::new();
+            loop {
+                let value_line = lines.next().ok_or_else(|| ParseError::Unterminated {
+                    key: key.to_owned(),
+                })??;
+                if value_line == delimiter {
+                    break;
+                }
+                value_lines.push(value_line);
+            }
+            vars.insert(key.to_owned(), value_lines.join("\n"));
+            continue;
+        }
+        let (key, mut raw_value) = parse_key_value_str(line)?;
+        let value = loop {
+            if let Some(value) = parse_value(&raw_value) {
+                break value;
+            }
+            let next_line = lines
+                .next()
+                .ok_or_else(|| ParseError::Unterminated { key: key.clone() })??;
+            raw_value.push('\n');
+            raw_value.push_str(&next_line);
+        };
+        vars.insert(key, value);
     }
     Ok(vars)
 }
@@ -303,14 +880,18 @@ pub fn parse_vars_file_reader(mut reader: impl BufRead) -> BoxResult<HashMap<Str
 /// # Errors
 ///
 /// If the string has a bad form, missing key and/or value.
-pub fn parse_key_value_str(key_value: &str) -> BoxResult<(String, String)> {
+pub fn parse_key_value_str(key_value: &str) -> Result<(String, String), InvalidKeyValue> {
+    let invalid = |msg| InvalidKeyValue {
+        msg,
+        key_value: key_value.to_owned(),
+    };
     let mut splitter = key_value.splitn(2, '=');
     let key = splitter
         .next()
-        .ok_or("Failed to parse key; key-value pairs have to be of the form \"key=value\"")?;
+        .ok_or_else(|| invalid("Failed to parse key"))?;
     let value = splitter
         .next()
-        .ok_or("Failed to parse value; key-value pairs have to be of the form \"key=value\"")?;
+        .ok_or_else(|| invalid("Failed to parse value"))?;
     Ok((key.to_owned(), value.to_owned()))
 }
 
@@ -326,6 +907,218 @@ pub fn is_key_value_str_valid(key_value: &str) -> Result<(), String> {
         .map_err(|_err| String::from("Not a valid key=value pair"))
 }
 
+/// This enumerates all possible errors
+/// when interpolating variable references in values,
+/// see [`interpolate_vars`].
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum InterpolationError {
+    #[error("The value of '{key}' references the unknown variable '{name}'")]
+    Unknown { key: String, name: String },
+
+    #[error("The value of '{key}' references itself through other variables")]
+    Cycle { key: String },
+
+    #[error(
+        "The value of '{key}' contains an unterminated variable reference ('${{' without '}}')"
+    )]
+    Unterminated { key: String },
+}
+
+/// Replaces each `${NAME}` in `value` with the result of `lookup(NAME)`,
+/// and each `$$` with a literal `$`.
+fn interpolate(
+    key: &str,
+    value: &str,
+    mut lookup: impl FnMut(&str) -> Result<String, InterpolationError>,
+) -> Result<String, InterpolationError> {
+    let mut interpolated = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(chr) = chars.next() {
+        if chr != '$' {
+            interpolated.push(chr);
+            continue;
+        }
+        match chars.peek() {
+            Some('$') => {
+                chars.next();
+                interpolated.push('$');
+            }
+            Some('{') => {
+                chars.next();
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(name_chr) => name.push(name_chr),
+                        None => {
+                            return Err(InterpolationError::Unterminated {
+                                key: key.to_owned(),
+                            })
+                        }
+                    }
+                }
+                interpolated.push_str(&lookup(&name)?);
+            }
+            _ => interpolated.push('$'),
+        }
+    }
+    Ok(interpolated)
+}
+
+struct Interpolator<'a> {
+    raw: &'a HashMap<String, String>,
+    known: &'a HashMap<String, String>,
+    resolved: HashMap<String, String>,
+    /// The keys currently being resolved, to detect cycles.
+    stack: Vec<String>,
+}
+
+impl Interpolator<'_> {
+    fn resolve(&mut self, key: &str) -> Result<String, InterpolationError> {
+        if let Some(value) = self.resolved.get(key) {
+            return Ok(value.clone());
+        }
+        if self.stack.iter().any(|on_stack| on_stack == key) {
+            return Err(InterpolationError::Cycle {
+                key: key.to_owned(),
+            });
+        }
+        let raw = self.raw;
+        let Some(raw_value) = raw.get(key) else {
+            return self
+                .known
+                .get(key)
+                .cloned()
+                .ok_or_else(|| InterpolationError::Unknown {
+                    key: self.stack.last().cloned().unwrap_or_default(),
+                    name: key.to_owned(),
+                });
+        };
+        self.stack.push(key.to_owned());
+        let value = interpolate(key, raw_value, |name| {
+            if name == key {
+                // A self-reference means the previous value,
+                // as in `PATH=${PATH}:/my/bin`
+                self.known
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| InterpolationError::Unknown {
+                        key: key.to_owned(),
+                        name: name.to_owned(),
+                    })
+            } else {
+                self.resolve(name)
+            }
+        })?;
+        self.stack.pop();
+        self.resolved.insert(key.to_owned(), value.clone());
+        Ok(value)
+    }
+}
+
+/// Resolves the `${NAME}` references in the values of `new_vars`.
+///
+/// They are resolved both against each other and against the `known` variables,
+/// with `new_vars` taking precedence.
+/// A value may reference its own key,
+/// which resolves to its value in `known`.
+/// `$$` results in a literal `$`.
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use projvar::var::interpolate_vars;
+/// let known = HashMap::from([("HOST".to_owned(), "example.com".to_owned())]);
+/// let new_vars = HashMap::from([
+///     ("BASE_URL".to_owned(), "https://${HOST}".to_owned()),
+///     ("DOCS_URL".to_owned(), "${BASE_URL}/docs".to_owned()),
+///     ("PRICE".to_owned(), "$$5".to_owned()),
+/// ]);
+/// let resolved = interpolate_vars(&new_vars, &known).unwrap();
+/// assert_eq!(resolved["DOCS_URL"], "https://example.com/docs");
+/// assert_eq!(resolved["PRICE"], "$5");
+/// ```
+///
+/// # Errors
+///
+/// If a referenced variable is unknown,
+/// a reference is not terminated,
+/// or references form a cycle.
+pub fn interpolate_vars(
+    new_vars: &HashMap<String, String>,
+    known: &HashMap<String, String>,
+) -> Result<HashMap<String, String>, InterpolationError> {
+    let mut interpolator = Interpolator {
+        raw: new_vars,
+        known,
+        resolved: HashMap::new(),
+        stack: vec![],
+    };
+    for key in new_vars.keys() {
+        interpolator.resolve(key)?;
+    }
+    Ok(interpolator.resolved)
+}
+
+/// The prefix of a variable name pattern that is a regex,
+/// rather than a glob, see [`parse_var_name_pattern`].
+pub const VAR_NAME_PATTERN_PREFIX_REGEX: &str = "regex:";
+
+/// Parses a pattern for the names of input variables.
+///
+/// It is either a simple glob (supporting only `*` and `?`),
+/// or - if prefixed with [`VAR_NAME_PATTERN_PREFIX_REGEX`] - a regex,
+/// which has to match the whole name.
+///
+/// ```
+/// # use projvar::var::parse_var_name_pattern;
+/// let glob = parse_var_name_pattern("CI_*").unwrap();
+/// assert!(glob.is_match("CI_COMMIT_SHA"));
+/// assert!(!glob.is_match("GITLAB_CI"));
+/// let regex = parse_var_name_pattern("regex:.*_(TOKEN|SECRET)").unwrap();
+/// assert!(regex.is_match("GITHUB_TOKEN"));
+/// assert!(!regex.is_match("GITHUB_TOKEN_FILE"));
+/// ```
+///
+/// # Errors
+///
+/// If the pattern is marked as a regex, but is not a valid one.
+pub fn parse_var_name_pattern(pattern: &str) -> Result<Regex, regex::Error> {
+    match pattern.strip_prefix(VAR_NAME_PATTERN_PREFIX_REGEX) {
+        Some(regex) => Regex::new(&format!("^(?:{regex})$")),
+        None => Ok(glob_to_regex(pattern)),
+    }
+}
+
+/// Filters input variables by name,
+/// e.g. to keep secrets out of the evaluation.
+#[derive(Debug, Clone, Default)]
+pub struct VarFilter {
+    /// If not empty, only variables matching any of these are accepted
+    pub allow: Vec<Regex>,
+    /// Variables matching any of these are never accepted
+    pub deny: Vec<Regex>,
+}
+
+impl VarFilter {
+    /// Whether a variable with the given name passes this filter.
+    #[must_use]
+    pub fn accepts(&self, name: &str) -> bool {
+        (self.allow.is_empty() || self.allow.iter().any(|allow| allow.is_match(name)))
+            && !self.deny.iter().any(|deny| deny.is_match(name))
+    }
+
+    /// Removes all the variables that do not pass this filter.
+    pub fn apply<S: ::std::hash::BuildHasher>(&self, vars: &mut HashMap<String, String, S>) {
+        vars.retain(|name, _value| {
+            let accepted = self.accepts(name);
+            if !accepted {
+                log::trace!("Ignoring the filtered out input variable '{name}'");
+            }
+            accepted
+        });
+    }
+}
+
 #[must_use]
 pub fn list_keys(environment: &Environment) -> String {
     static HEADER: &str = "| Default Required | Key | Description |\n";
@@ -349,6 +1142,14 @@ pub fn list_keys(environment: &Environment) -> String {
             var.description
         ));
     }
+    for dynamic_key in &environment.dynamic_keys {
+        let var = &dynamic_key.variable;
+        table.push_str(&format!(
+            "| [ ] | `{}` | {} |\n",
+            var.key(environment),
+            var.description
+        ));
+    }
     table.push('\n');
 
     log::trace!("Table size (in chars), estimated: {}", table_chars_estimate);
@@ -359,29 +1160,74 @@ pub fn list_keys(environment: &Environment) -> String {
 
 pub const KEY_BUILD_ARCH: &str = "BUILD_ARCH";
 pub const KEY_BUILD_BRANCH: &str = "BUILD_BRANCH";
+pub const KEY_BUILD_COMMIT_SHA: &str = "BUILD_COMMIT_SHA";
+pub const KEY_BUILD_COMMIT_SHA_SHORT: &str = "BUILD_COMMIT_SHA_SHORT";
 pub const KEY_BUILD_DATE: &str = "BUILD_DATE";
+pub const KEY_BUILD_DATE_EPOCH: &str = "BUILD_DATE_EPOCH";
+pub const KEY_BUILD_DIRTY: &str = "BUILD_DIRTY";
+pub const KEY_BUILD_HOST_CPU_COUNT: &str = "BUILD_HOST_CPU_COUNT";
+pub const KEY_BUILD_HOST_KERNEL_VERSION: &str = "BUILD_HOST_KERNEL_VERSION";
+pub const KEY_BUILD_HOST_PLATFORM_TRIPLE: &str = "BUILD_HOST_PLATFORM_TRIPLE";
 pub const KEY_BUILD_HOSTING_URL: &str = "BUILD_HOSTING_URL";
 pub const KEY_BUILD_NUMBER: &str = "BUILD_NUMBER";
 pub const KEY_BUILD_OS: &str = "BUILD_OS";
 pub const KEY_BUILD_OS_FAMILY: &str = "BUILD_OS_FAMILY";
 pub const KEY_BUILD_TAG: &str = "BUILD_TAG";
 pub const KEY_CI: &str = "CI";
+pub const KEY_DESCRIPTION: &str = "DESCRIPTION";
+pub const KEY_DOI: &str = "DOI";
+pub const KEY_FUNDING_URL: &str = "FUNDING_URL";
 pub const KEY_LICENSE: &str = "LICENSE";
 pub const KEY_LICENSES: &str = "LICENSES";
+pub const KEY_LICENSES_COMPLIANCE: &str = "LICENSES_COMPLIANCE";
 pub const KEY_NAME: &str = "NAME";
 pub const KEY_NAME_MACHINE_READABLE: &str = "NAME_MACHINE_READABLE";
+pub const KEY_NAME_SLUG: &str = "NAME_SLUG";
+pub const KEY_PULL_REQUEST_NUMBER: &str = "PULL_REQUEST_NUMBER";
+pub const KEY_PULL_REQUEST_SOURCE_BRANCH: &str = "PULL_REQUEST_SOURCE_BRANCH";
+pub const KEY_PULL_REQUEST_TARGET_BRANCH: &str = "PULL_REQUEST_TARGET_BRANCH";
+pub const KEY_PULL_REQUEST_TITLE: &str = "PULL_REQUEST_TITLE";
+pub const KEY_PULL_REQUEST_URL: &str = "PULL_REQUEST_URL";
+pub const KEY_REPO_CI_URL: &str = "REPO_CI_URL";
 pub const KEY_REPO_CLONE_URL: &str = "REPO_CLONE_URL";
 pub const KEY_REPO_CLONE_URL_HTTP: &str = "REPO_CLONE_URL_HTTP";
+pub const KEY_REPO_CLONE_URL_PUSH: &str = "REPO_CLONE_URL_PUSH";
 pub const KEY_REPO_CLONE_URL_SSH: &str = "REPO_CLONE_URL_SSH";
 pub const KEY_REPO_CLONE_URL_GIT: &str = "REPO_CLONE_URL_GIT";
 pub const KEY_REPO_COMMIT_PREFIX_URL: &str = "REPO_COMMIT_PREFIX_URL";
+pub const KEY_REPO_DEFAULT_BRANCH: &str = "REPO_DEFAULT_BRANCH";
+pub const KEY_REPO_FORK_OF_URL: &str = "REPO_FORK_OF_URL";
+pub const KEY_REPO_FORK_URL: &str = "REPO_FORK_URL";
 pub const KEY_REPO_ISSUES_URL: &str = "REPO_ISSUES_URL";
+pub const KEY_REPO_NAMESPACE: &str = "REPO_NAMESPACE";
+pub const KEY_REPO_OWNER: &str = "REPO_OWNER";
 pub const KEY_REPO_RAW_VERSIONED_PREFIX_URL: &str = "REPO_RAW_VERSIONED_PREFIX_URL";
+pub const KEY_REPO_RELEASES_URL: &str = "REPO_RELEASES_URL";
+pub const KEY_REPO_SLUG: &str = "REPO_SLUG";
 pub const KEY_REPO_VERSIONED_DIR_PREFIX_URL: &str = "REPO_VERSIONED_DIR_PREFIX_URL";
 pub const KEY_REPO_VERSIONED_FILE_PREFIX_URL: &str = "REPO_VERSIONED_FILE_PREFIX_URL";
 pub const KEY_REPO_WEB_URL: &str = "REPO_WEB_URL";
 pub const KEY_VERSION: &str = "VERSION";
+pub const KEY_VERSION_BUILD_META: &str = "VERSION_BUILD_META";
+pub const KEY_VERSION_CONTROL_SYSTEM: &str = "VERSION_CONTROL_SYSTEM";
 pub const KEY_VERSION_DATE: &str = "VERSION_DATE";
+pub const KEY_VERSION_DESCRIBE: &str = "VERSION_DESCRIBE";
+pub const KEY_VERSION_MAJOR: &str = "VERSION_MAJOR";
+pub const KEY_VERSION_MINOR: &str = "VERSION_MINOR";
+pub const KEY_VERSION_PATCH: &str = "VERSION_PATCH";
+pub const KEY_VERSION_PRERELEASE: &str = "VERSION_PRERELEASE";
+
+/// The de-facto standard variable for the date of reproducible builds.
+///
+/// See <https://reproducible-builds.org/specs/source-date-epoch/>.
+/// With [`crate::settings::Settings::source_date_epoch_out`],
+/// the value of [`Key::BuildDateEpoch`] is also written to it.
+pub static VAR_SOURCE_DATE_EPOCH: Variable = Variable {
+    key: crate::sources::env::SOURCE_DATE_EPOCH,
+    description: "Date of this build as a Unix timestamp, \
+        as used by the tools of the following build steps for reproducible builds",
+    default_required: false,
+};
 
 const VAR_BUILD_ARCH: Variable = Variable {
     key: KEY_BUILD_ARCH,
@@ -396,10 +1242,57 @@ const VAR_BUILD_BRANCH: Variable = Variable {
         \"develop\"",
     default_required: false,
 };
+const VAR_BUILD_COMMIT_SHA: Variable = Variable {
+    key: KEY_BUILD_COMMIT_SHA,
+    description: "The full SHA (hash) of the commit that is being built, \
+        for example: \"9b5ff47e6e2b1e7ab7ac0fad3b8a13aa35b3b1a3\"",
+    default_required: false,
+};
+const VAR_BUILD_COMMIT_SHA_SHORT: Variable = Variable {
+    key: KEY_BUILD_COMMIT_SHA_SHORT,
+    description: "The abbreviated SHA (hash) of the commit that is being built, \
+        for example: \"9b5ff47\"",
+    default_required: false,
+};
 const VAR_BUILD_DATE: Variable = Variable {
     key: KEY_BUILD_DATE,
     description: "Date of this build, for example: \
-        \"2021-12-31 23:59:59\" (see --date-format)",
+        \"2021-12-31 23:59:59\" (see --date-format and --build-date-format)",
+    default_required: false,
+};
+const VAR_BUILD_DATE_EPOCH: Variable = Variable {
+    key: KEY_BUILD_DATE_EPOCH,
+    description: "Date of this build as a Unix timestamp (seconds since 1970-01-01 UTC), \
+        for example: \"1633035545\"; \
+        honors SOURCE_DATE_EPOCH, for reproducible builds",
+    default_required: false,
+};
+const VAR_BUILD_DIRTY: Variable = Variable {
+    key: KEY_BUILD_DIRTY,
+    description: "Whether the working tree had uncommitted changes \
+        at the time of the build: \"true\" or \"false\"",
+    default_required: false,
+};
+const VAR_BUILD_HOST_CPU_COUNT: Variable = Variable {
+    key: KEY_BUILD_HOST_CPU_COUNT,
+    description: "The number of CPUs (logical cores) available on the machine we are building on, \
+        for example: \
+        \"8\"",
+    default_required: false,
+};
+const VAR_BUILD_HOST_KERNEL_VERSION: Variable = Variable {
+    key: KEY_BUILD_HOST_KERNEL_VERSION,
+    description: "The version of the OS kernel we are building on, \
+        for example: \
+        \"6.1.0-18-amd64\"",
+    default_required: false,
+};
+const VAR_BUILD_HOST_PLATFORM_TRIPLE: Variable = Variable {
+    key: KEY_BUILD_HOST_PLATFORM_TRIPLE,
+    description: "The target triple of the platform we are building on, \
+        for example: \
+        \"x86_64-unknown-linux-gnu\", \
+        \"aarch64-apple-darwin\"",
     default_required: false,
 };
 const VAR_BUILD_HOSTING_URL: Variable = Variable {
@@ -438,6 +1331,27 @@ const VAR_CI: Variable = Variable {
     description: "'true' if running on a CI/build-bot; unset otherwise.",
     default_required: false,
 };
+const VAR_DESCRIPTION: Variable = Variable {
+    key: KEY_DESCRIPTION,
+    description: "A short description of the project, \
+        for example: A tool to extract and validate project meta-data",
+    default_required: false,
+};
+const VAR_DOI: Variable = Variable {
+    key: KEY_DOI,
+    description: "The Digital Object Identifier (DOI) of the project, \
+        for example its Zenodo concept DOI: \
+        \"10.5281/zenodo.1234567\"",
+    default_required: false,
+};
+const VAR_FUNDING_URL: Variable = Variable {
+    key: KEY_FUNDING_URL,
+    description: "The URL of a web-page to donate to the project or sponsor it, \
+        for example: \
+        \"https://github.com/sponsors/hoijui\", \
+        \"https://opencollective.com/projvar\"",
+    default_required: false,
+};
 const VAR_LICENSE: Variable = Variable {
     key: KEY_LICENSE,
     description: "The main License identifier of the sources, \
@@ -455,6 +1369,15 @@ const VAR_LICENSES: Variable = Variable {
         Unlicense\"",
     default_required: true,
 };
+const VAR_LICENSES_COMPLIANCE: Variable = Variable {
+    key: KEY_LICENSES_COMPLIANCE,
+    description:
+        "The share of the projects files that come with copyright and licensing information, \
+        in percent, as required by the REUSE specification (<https://reuse.software>); \
+        100 means the project is REUSE compliant, \
+        e.g. '100' or '87'",
+    default_required: false,
+};
 const VAR_NAME: Variable = Variable {
     key: KEY_NAME,
     description: "The human focused name of the project.",
@@ -465,6 +1388,52 @@ const VAR_NAME_MACHINE_READABLE: Variable = Variable {
     description: "The machine readable name of the project.",
     default_required: true,
 };
+const VAR_NAME_SLUG: Variable = Variable {
+    key: KEY_NAME_SLUG,
+    description: "The project name as a slug; \
+        only lower-case ASCII letters, digits and single dashes (kebab-case), \
+        e.g. \"kuhlschrank-steuerung\". \
+        Unlike the machine-readable name, \
+        it is suitable for container names, URLs and artifact file names.",
+    default_required: false,
+};
+const VAR_PULL_REQUEST_NUMBER: Variable = Variable {
+    key: KEY_PULL_REQUEST_NUMBER,
+    description: "The number of the pull/merge request being built, \
+        if any; on GitLab, this is the project internal ID (IID), \
+        for example: 42",
+    default_required: false,
+};
+const VAR_PULL_REQUEST_SOURCE_BRANCH: Variable = Variable {
+    key: KEY_PULL_REQUEST_SOURCE_BRANCH,
+    description: "The branch the changes of the pull/merge request being built come from, \
+        if any, for example: fix-typo",
+    default_required: false,
+};
+const VAR_PULL_REQUEST_TARGET_BRANCH: Variable = Variable {
+    key: KEY_PULL_REQUEST_TARGET_BRANCH,
+    description: "The branch the pull/merge request being built is to be merged into, \
+        if any, for example: master",
+    default_required: false,
+};
+const VAR_PULL_REQUEST_TITLE: Variable = Variable {
+    key: KEY_PULL_REQUEST_TITLE,
+    description: "The title of the pull/merge request being built, \
+        if any, for example: Fix a typo in the README",
+    default_required: false,
+};
+const VAR_PULL_REQUEST_URL: Variable = Variable {
+    key: KEY_PULL_REQUEST_URL,
+    description: "The web URL of the pull/merge request being built, \
+        if any, for example: https://github.com/hoijui/projvar/pull/42",
+    default_required: false,
+};
+const VAR_REPO_CI_URL: Variable = Variable {
+    key: KEY_REPO_CI_URL,
+    description: "The repo CI pipelines page URL, for example: \
+        https://gitlab.com/openflexure/openflexure-microscope/-/pipelines",
+    default_required: false,
+};
 const VAR_REPO_CLONE_URL: Variable = Variable {
     key: KEY_REPO_CLONE_URL,
     description: "The original repo clone URL; \
@@ -480,6 +1449,16 @@ const VAR_REPO_CLONE_URL_HTTP: Variable = Variable {
         It is commonly used for anonymous fetch-only access.",
     default_required: false,
 };
+const VAR_REPO_CLONE_URL_PUSH: Variable = Variable {
+    key: KEY_REPO_CLONE_URL_PUSH,
+    description: "The repo URL to push to; \
+        may use any valid git URL scheme. \
+        This is the push URL of the push remote, if one is configured, \
+        the URL of the push remote otherwise. \
+        It is commonly used for authenticated write access, \
+        e.g. by release automation.",
+    default_required: false,
+};
 const VAR_REPO_CLONE_URL_SSH: Variable = Variable {
     key: KEY_REPO_CLONE_URL_SSH,
     description: "The repo clone URL, SSH version. \
@@ -504,12 +1483,51 @@ const VAR_REPO_COMMIT_PREFIX_URL: Variable = Variable {
         [https://github.com/hoijui/nim-ci/commit]/23f84b91]",
     default_required: true,
 };
+const VAR_REPO_DEFAULT_BRANCH: Variable = Variable {
+    key: KEY_REPO_DEFAULT_BRANCH,
+    description: "The default branch of the repo, \
+        for example: master",
+    default_required: false,
+};
+const VAR_REPO_FORK_OF_URL: Variable = Variable {
+    key: KEY_REPO_FORK_OF_URL,
+    description: "The web URL of the upstream repo, \
+        if the changes built come from a fork of it, \
+        for example when building a pull/merge request from a fork on CI: \
+        https://github.com/hoijui/projvar",
+    default_required: false,
+};
+const VAR_REPO_FORK_URL: Variable = Variable {
+    key: KEY_REPO_FORK_URL,
+    description: "The web URL of the fork the changes built come from, \
+        if they do not come from the project repo itself, \
+        for example when building a pull/merge request from a fork on CI: \
+        https://github.com/some-user/projvar",
+    default_required: false,
+};
 const VAR_REPO_ISSUES_URL: Variable = Variable {
     key: KEY_REPO_ISSUES_URL,
     description: "The repo issues URL, for example: \
         https://gitlab.com/openflexure/openflexure-microscope/issues",
     default_required: true,
 };
+const VAR_REPO_NAMESPACE: Variable = Variable {
+    key: KEY_REPO_NAMESPACE,
+    description: "The full namespace path of the project, \
+        which is the project slug without the project name, \
+        for example: \
+        \"hoijui\", \
+        \"OSEGermany/sub-group\"",
+    default_required: false,
+};
+const VAR_REPO_OWNER: Variable = Variable {
+    key: KEY_REPO_OWNER,
+    description: "The user or organization owning the project, \
+        for example: \
+        \"hoijui\", \
+        \"OSEGermany\"",
+    default_required: false,
+};
 const VAR_REPO_RAW_VERSIONED_PREFIX_URL: Variable = Variable {
     key: KEY_REPO_RAW_VERSIONED_PREFIX_URL,
     description: "The repo raw prefix URL. \
@@ -518,6 +1536,21 @@ const VAR_REPO_RAW_VERSIONED_PREFIX_URL: Variable = Variable {
         [https://raw.githubusercontent.com/hoijui/nim-ci]/master/.github/workflows/docker.yml]",
     default_required: true,
 };
+const VAR_REPO_RELEASES_URL: Variable = Variable {
+    key: KEY_REPO_RELEASES_URL,
+    description: "The repo releases page URL, for example: \
+        https://github.com/hoijui/projvar/releases",
+    default_required: false,
+};
+const VAR_REPO_SLUG: Variable = Variable {
+    key: KEY_REPO_SLUG,
+    description: "The project slug, consisting of the owning user or organization, \
+        optionally (sub-)groups, and the project name, \
+        for example: \
+        \"hoijui/projvar\", \
+        \"OSEGermany/sub-group/OHS-3105\"",
+    default_required: false,
+};
 const VAR_REPO_VERSIONED_DIR_PREFIX_URL: Variable = Variable {
     key: KEY_REPO_VERSIONED_DIR_PREFIX_URL,
     description: // TODO Elaborate the "Add ..." part
@@ -549,13 +1582,57 @@ const VAR_VERSION: Variable = Variable {
         \"0.2.0-1-ga5387ac-dirty\"",
     default_required: true,
 };
+const VAR_VERSION_BUILD_META: Variable = Variable {
+    key: KEY_VERSION_BUILD_META,
+    description: "The build metadata component of the SemVer version, \
+        for example: \"build5\" for \"1.2.3-rc1+build5\"",
+    default_required: false,
+};
+const VAR_VERSION_CONTROL_SYSTEM: Variable = Variable {
+    key: KEY_VERSION_CONTROL_SYSTEM,
+    description: "The version control system (SCM) the project is managed with; \
+        one of: \"git\", \"hg\", \"svn\", \"none\" \
+        (the later for example for exported tarballs)",
+    default_required: false,
+};
 const VAR_VERSION_DATE: Variable = Variable {
     key: KEY_VERSION_DATE,
     description: "Date this version was committed to source control, for example: \
         \"2021-12-31 23:59:59\" \
-        (see --date-format)",
+        (see --date-format and --version-date-format)",
     default_required: true,
 };
+const VAR_VERSION_DESCRIBE: Variable = Variable {
+    key: KEY_VERSION_DESCRIBE,
+    description: "The raw output of `git describe --tags --dirty`, \
+        for example: \"v0.2.0-3-g9b5ff47-dirty\"; \
+        see VERSION for a cleaned up variant",
+    default_required: false,
+};
+const VAR_VERSION_MAJOR: Variable = Variable {
+    key: KEY_VERSION_MAJOR,
+    description: "The major component of the SemVer version, \
+        for example: \"1\" for \"1.2.3-rc1+build5\"",
+    default_required: false,
+};
+const VAR_VERSION_MINOR: Variable = Variable {
+    key: KEY_VERSION_MINOR,
+    description: "The minor component of the SemVer version, \
+        for example: \"2\" for \"1.2.3-rc1+build5\"",
+    default_required: false,
+};
+const VAR_VERSION_PATCH: Variable = Variable {
+    key: KEY_VERSION_PATCH,
+    description: "The patch component of the SemVer version, \
+        for example: \"3\" for \"1.2.3-rc1+build5\"",
+    default_required: false,
+};
+const VAR_VERSION_PRERELEASE: Variable = Variable {
+    key: KEY_VERSION_PRERELEASE,
+    description: "The pre-release component of the SemVer version, \
+        for example: \"rc1\" for \"1.2.3-rc1+build5\"",
+    default_required: false,
+};
 
 /// Returns a reference to the variable settings associated with the given key.
 #[must_use]
@@ -565,29 +1642,62 @@ pub const fn get(key: Key) -> &'static Variable {
     match key {
         Key::BuildArch => &VAR_BUILD_ARCH,
         Key::BuildBranch => &VAR_BUILD_BRANCH,
+        Key::BuildCommitSha => &VAR_BUILD_COMMIT_SHA,
+        Key::BuildCommitShaShort => &VAR_BUILD_COMMIT_SHA_SHORT,
         Key::BuildDate => &VAR_BUILD_DATE,
+        Key::BuildDateEpoch => &VAR_BUILD_DATE_EPOCH,
+        Key::BuildDirty => &VAR_BUILD_DIRTY,
+        Key::BuildHostCpuCount => &VAR_BUILD_HOST_CPU_COUNT,
         Key::BuildHostingUrl => &VAR_BUILD_HOSTING_URL,
+        Key::BuildHostKernelVersion => &VAR_BUILD_HOST_KERNEL_VERSION,
+        Key::BuildHostPlatformTriple => &VAR_BUILD_HOST_PLATFORM_TRIPLE,
         Key::BuildNumber => &VAR_BUILD_NUMBER,
         Key::BuildOs => &VAR_BUILD_OS,
         Key::BuildOsFamily => &VAR_BUILD_OS_FAMILY,
         Key::BuildTag => &VAR_BUILD_TAG,
         Key::Ci => &VAR_CI,
+        Key::Description => &VAR_DESCRIPTION,
+        Key::Doi => &VAR_DOI,
+        Key::FundingUrl => &VAR_FUNDING_URL,
         Key::License => &VAR_LICENSE,
         Key::Licenses => &VAR_LICENSES,
+        Key::LicensesCompliance => &VAR_LICENSES_COMPLIANCE,
         Key::Name => &VAR_NAME,
         Key::NameMachineReadable => &VAR_NAME_MACHINE_READABLE,
+        Key::NameSlug => &VAR_NAME_SLUG,
+        Key::PullRequestNumber => &VAR_PULL_REQUEST_NUMBER,
+        Key::PullRequestSourceBranch => &VAR_PULL_REQUEST_SOURCE_BRANCH,
+        Key::PullRequestTargetBranch => &VAR_PULL_REQUEST_TARGET_BRANCH,
+        Key::PullRequestTitle => &VAR_PULL_REQUEST_TITLE,
+        Key::PullRequestUrl => &VAR_PULL_REQUEST_URL,
+        Key::RepoCiUrl => &VAR_REPO_CI_URL,
         Key::RepoCloneUrl => &VAR_REPO_CLONE_URL,
         Key::RepoCloneUrlGit => &VAR_REPO_CLONE_URL_GIT,
         Key::RepoCloneUrlHttp => &VAR_REPO_CLONE_URL_HTTP,
+        Key::RepoCloneUrlPush => &VAR_REPO_CLONE_URL_PUSH,
         Key::RepoCloneUrlSsh => &VAR_REPO_CLONE_URL_SSH,
         Key::RepoCommitPrefixUrl => &VAR_REPO_COMMIT_PREFIX_URL,
+        Key::RepoDefaultBranch => &VAR_REPO_DEFAULT_BRANCH,
+        Key::RepoForkOfUrl => &VAR_REPO_FORK_OF_URL,
+        Key::RepoForkUrl => &VAR_REPO_FORK_URL,
         Key::RepoIssuesUrl => &VAR_REPO_ISSUES_URL,
+        Key::RepoNamespace => &VAR_REPO_NAMESPACE,
+        Key::RepoOwner => &VAR_REPO_OWNER,
         Key::RepoRawVersionedPrefixUrl => &VAR_REPO_RAW_VERSIONED_PREFIX_URL,
+        Key::RepoReleasesUrl => &VAR_REPO_RELEASES_URL,
+        Key::RepoSlug => &VAR_REPO_SLUG,
         Key::RepoVersionedDirPrefixUrl => &VAR_REPO_VERSIONED_DIR_PREFIX_URL,
         Key::RepoVersionedFilePrefixUrl => &VAR_REPO_VERSIONED_FILE_PREFIX_URL,
         Key::RepoWebUrl => &VAR_REPO_WEB_URL,
         Key::Version => &VAR_VERSION,
+        Key::VersionBuildMeta => &VAR_VERSION_BUILD_META,
+        Key::VersionControlSystem => &VAR_VERSION_CONTROL_SYSTEM,
         Key::VersionDate => &VAR_VERSION_DATE,
+        Key::VersionDescribe => &VAR_VERSION_DESCRIBE,
+        Key::VersionMajor => &VAR_VERSION_MAJOR,
+        Key::VersionMinor => &VAR_VERSION_MINOR,
+        Key::VersionPatch => &VAR_VERSION_PATCH,
+        Key::VersionPrerelease => &VAR_VERSION_PRERELEASE,
     }
 }
 
@@ -616,6 +1726,19 @@ pub fn default_keys() -> &'static HashSet<Key> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use cli_utils::BoxResult;
+
+    #[test]
+    fn test_corroborate() {
+        let high = ConfidenceScore::HIGH;
+        let middle = ConfidenceScore::MIDDLE;
+        let low = ConfidenceScore::LOW;
+        assert_eq!(high.corroborate(high), ConfidenceScore::new(94));
+        assert_eq!(middle.corroborate(low), ConfidenceScore::new(63));
+        assert_eq!(low.corroborate(ConfidenceScore::NONE), low);
+        assert_eq!(ConfidenceScore::MAX.corroborate(low), ConfidenceScore::MAX);
+        assert_eq!(high.corroborate(middle), middle.corroborate(high));
+    }
 
     #[test]
     fn test_camel_to_upper_snake_case() -> BoxResult<()> {
@@ -670,4 +1793,204 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_from_name_or_var_key_glob() -> BoxResult<()> {
+        let r_prefix_none = Regex::new("^").unwrap();
+        let r_prefix_project = Regex::new("^PROJECT_").unwrap();
+
+        assert_eq!(
+            Key::from_name_or_var_key_glob(&r_prefix_project, "VERSION")?,
+            vec![Key::Version]
+        );
+        let versions = vec![
+            Key::Version,
+            Key::VersionBuildMeta,
+            Key::VersionControlSystem,
+            Key::VersionDate,
+            Key::VersionDescribe,
+            Key::VersionMajor,
+            Key::VersionMinor,
+            Key::VersionPatch,
+            Key::VersionPrerelease,
+        ];
+        assert_eq!(
+            Key::from_name_or_var_key_glob(&r_prefix_none, "Version*")?,
+            versions
+        );
+        assert_eq!(
+            Key::from_name_or_var_key_glob(&r_prefix_none, "VERSION*")?,
+            versions
+        );
+        assert_eq!(
+            Key::from_name_or_var_key_glob(&r_prefix_project, "PROJECT_VERSION*")?,
+            versions
+        );
+        assert_eq!(
+            Key::from_name_or_var_key_glob(&r_prefix_none, "REPO_CLONE_URL_???")?,
+            vec![Key::RepoCloneUrlGit, Key::RepoCloneUrlSsh]
+        );
+        let builds = Key::from_name_or_var_key_glob(&r_prefix_none, "Build*")?;
+        assert!(builds.contains(&Key::BuildArch));
+        assert!(builds.contains(&Key::BuildTag));
+        assert!(!builds.contains(&Key::Version));
+
+        assert!(Key::from_name_or_var_key_glob(&r_prefix_none, "NOT_A_KEY*").is_err());
+        assert!(Key::from_name_or_var_key_glob(&r_prefix_none, "PROJECT_VERSION*").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_key_id() {
+        assert!(parse_key_id("Version").is_ok());
+        assert!(parse_key_id("VERSION").is_ok());
+        assert!(parse_key_id("PROJECT_VERSION").is_ok());
+        assert!(parse_key_id("MY_PREFIX_REPO_WEB_URL").is_ok());
+        assert!(parse_key_id("Build*").is_ok());
+        assert!(parse_key_id("NOT_A_KEY*").is_err());
+        assert!(parse_key_id("PROJECT_").is_err());
+
+        assert_eq!(
+            parse_key_id("PROJECT_VERSON").unwrap_err().suggestions(),
+            ["PROJECT_VERSION"]
+        );
+        assert_eq!(
+            parse_key_id("RepoWebUlr").unwrap_err().suggestions(),
+            ["RepoWebUrl"]
+        );
+        let err = parse_key_id("RepoCloneUrlHtp").unwrap_err();
+        assert_eq!(err.suggestions().len(), SUGGESTIONS_MAX);
+        assert_eq!(
+            err.suggestions().first().map(String::as_str),
+            Some("RepoCloneUrlHttp")
+        );
+        assert!(parse_key_id("%%%").unwrap_err().suggestions().is_empty());
+        assert_eq!(
+            parse_key_id("PROJECT_VERSON").unwrap_err().to_string(),
+            "Not a valid key name: 'PROJECT_VERSON'; did you mean 'PROJECT_VERSION'?"
+        );
+    }
+
+    #[test]
+    fn test_interpolate_vars() {
+        let known: HashMap<String, String> = [("PATH", "/bin"), ("HOST", "example.com")]
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect();
+        let interpolate_one = |key: &str, value: &str| {
+            let new_vars = HashMap::from([(key.to_owned(), value.to_owned())]);
+            interpolate_vars(&new_vars, &known).map(|mut resolved| resolved.remove(key).unwrap())
+        };
+
+        assert_eq!(interpolate_one("A", "plain"), Ok("plain".to_owned()));
+        assert_eq!(
+            interpolate_one("A", "https://${HOST}/"),
+            Ok("https://example.com/".to_owned())
+        );
+        assert_eq!(
+            interpolate_one("PATH", "${PATH}:/my/bin"),
+            Ok("/bin:/my/bin".to_owned())
+        );
+        assert_eq!(interpolate_one("A", "$$5 $x"), Ok("$5 $x".to_owned()));
+        assert_eq!(
+            interpolate_one("A", "${NOPE}"),
+            Err(InterpolationError::Unknown {
+                key: "A".to_owned(),
+                name: "NOPE".to_owned()
+            })
+        );
+        assert_eq!(
+            interpolate_one("A", "${HOST"),
+            Err(InterpolationError::Unterminated {
+                key: "A".to_owned()
+            })
+        );
+
+        let cyclic = HashMap::from([
+            ("A".to_owned(), "${B}".to_owned()),
+            ("B".to_owned(), "${A}".to_owned()),
+        ]);
+        assert!(matches!(
+            interpolate_vars(&cyclic, &known),
+            Err(InterpolationError::Cycle { .. })
+        ));
+    }
+
+    #[test]
+    fn test_var_filter() {
+        let mut vars: HashMap<String, String> = [
+            "CI",
+            "CI_COMMIT_SHA",
+            "CI_JOB_TOKEN",
+            "GITHUB_TOKEN",
+            "PATH",
+        ]
+        .into_iter()
+        .map(|name| (name.to_owned(), String::new()))
+        .collect();
+        let filter = VarFilter {
+            allow: vec![
+                parse_var_name_pattern("CI*").unwrap(),
+                parse_var_name_pattern("GITHUB_*").unwrap(),
+            ],
+            deny: vec![parse_var_name_pattern("regex:.*_TOKEN").unwrap()],
+        };
+        filter.apply(&mut vars);
+        let mut names: Vec<&str> = vars.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        assert_eq!(names, ["CI", "CI_COMMIT_SHA"]);
+        assert!(VarFilter::default().accepts("PATH"));
+    }
+
+    #[test]
+    fn test_parse_vars_file_reader() -> BoxResult<()> {
+        let content = r#"# a comment
+PLAIN = spaced
+DOUBLE="a \"b\" \\c \$d \e"
+SINGLE='it "is" \ literal'
+MULTI="line 1
+  line 2 "
+MULTI_SINGLE='l1
+
+l3'
+HEREDOC<<EOF
+line 1
+"line 2"
+EOF
+EMPTY=
+"#;
+        let vars = parse_vars_file_reader(content.as_bytes())?;
+        assert_eq!(vars.get("PLAIN ").map(String::as_str), Some("spaced"));
+        assert_eq!(
+            vars.get("DOUBLE").map(String::as_str),
+            Some(r#"a "b" \c $d \e"#)
+        );
+        assert_eq!(
+            vars.get("SINGLE").map(String::as_str),
+            Some(r#"it "is" \ literal"#)
+        );
+        assert_eq!(
+            vars.get("MULTI").map(String::as_str),
+            Some("line 1\n  line 2 ")
+        );
+        assert_eq!(
+            vars.get("MULTI_SINGLE").map(String::as_str),
+            Some("l1\n\nl3")
+        );
+        assert_eq!(
+            vars.get("HEREDOC").map(String::as_str),
+            Some("line 1\n\"line 2\"")
+        );
+        assert_eq!(vars.get("EMPTY").map(String::as_str), Some(""));
+        assert_eq!(vars.len(), 7);
+
+        for unterminated in ["A=\"open\nB=x\n", "A<<EOF\nx\n"] {
+            assert!(matches!(
+                parse_vars_file_reader(unterminated.as_bytes()),
+                Err(ParseError::Unterminated { key }) if key == "A"
+            ));
+        }
+        Ok(())
+    }
 }
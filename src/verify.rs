@@ -0,0 +1,167 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Verifies the values of URL keys by sending HTTP HEAD requests to them,
+//! which catches wrong derivations
+//! (e.g. a pages URL for a project without pages)
+//! before they end up in published documents,
+//! see [`crate::settings::Settings::verify_urls`].
+
+use std::fmt;
+
+use reqwest::StatusCode;
+
+use crate::environment::Environment;
+use crate::tools::net;
+use crate::var::{ConfidenceScore, Key};
+
+/// The keys whose values are URLs of web pages.
+/// URL prefixes and clone URLs are not included,
+/// because they do not point to a page by themselves.
+pub const KEYS: &[Key] = &[
+    Key::BuildHostingUrl,
+    Key::FundingUrl,
+    Key::PullRequestUrl,
+    Key::RepoCiUrl,
+    Key::RepoForkOfUrl,
+    Key::RepoForkUrl,
+    Key::RepoIssuesUrl,
+    Key::RepoReleasesUrl,
+    Key::RepoWebUrl,
+];
+
+/// What happens to a value, based on the response to the HEAD request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// The URL exists; the value is kept as it is.
+    Confirmed,
+    /// The server does not support HEAD requests,
+    /// so we can not tell; the value is kept as it is.
+    Unverifiable,
+    /// The URL could not be confirmed,
+    /// e.g. because it requires authentication, or the server failed;
+    /// the value is kept, but with low confidence.
+    Downgraded,
+    /// The URL does not exist; the value is discarded.
+    Discarded,
+}
+
+impl Verdict {
+    /// Judges a value by the status of the response to the HEAD request.
+    #[must_use]
+    pub fn of(status: StatusCode) -> Self {
+        match status {
+            StatusCode::NOT_FOUND | StatusCode::GONE => Self::Discarded,
+            StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED => Self::Unverifiable,
+            _ if status.is_success() || status.is_redirection() => Self::Confirmed,
+            _ => Self::Downgraded,
+        }
+    }
+
+    /// Whether the value was changed because of the verification.
+    #[must_use]
+    pub const fn is_failure(self) -> bool {
+        matches!(self, Self::Downgraded | Self::Discarded)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Confirmed => "confirmed",
+            Self::Unverifiable => "HEAD requests not supported; kept as is",
+            Self::Downgraded => "confidence lowered",
+            Self::Discarded => "value discarded",
+        })
+    }
+}
+
+/// The result of verifying the value of a single key.
+pub struct Verification {
+    pub key: Key,
+    /// The HTTP status (e.g. "404 Not Found"),
+    /// or the error message if the request failed
+    pub status: String,
+    pub verdict: Verdict,
+}
+
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}; {}", self.status, self.verdict)
+    }
+}
+
+/// Sends a HEAD request to the value of each of the [`KEYS`] that has one,
+/// discarding the values of URLs that do not exist,
+/// and lowering the confidence in the ones that could not be confirmed.
+///
+/// Returns `None` if verification is disabled,
+/// or network access is not allowed.
+pub fn run(environment: &mut Environment) -> Option<Vec<Verification>> {
+    if !environment.settings.verify_urls {
+        return None;
+    }
+    if !environment.settings.online {
+        log::warn!("Not verifying URLs, because network access is disabled; see --online");
+        return None;
+    }
+    let mut verifications = vec![];
+    for &key in KEYS {
+        let Some((_confidence, url)) = environment.output.get(key).cloned() else {
+            continue;
+        };
+        let (status, verdict) =
+            match net::send(&environment.settings, |client| client.head(url.as_str())) {
+                Ok(response) => (
+                    response.status().to_string(),
+                    Verdict::of(response.status()),
+                ),
+                Err(err) => (err.to_string(), Verdict::Downgraded),
+            };
+        let name: &str = key.into();
+        log::debug!("Verified {name}='{url}': {status}");
+        match verdict {
+            Verdict::Confirmed | Verdict::Unverifiable => (),
+            Verdict::Downgraded => {
+                log::warn!("Could not confirm {name}='{url}' ({status}); lowering its confidence");
+                environment.output.downgrade(key, ConfidenceScore::LOW);
+            }
+            Verdict::Discarded => {
+                log::warn!("Discarding {name}='{url}', because it does not exist ({status})");
+                environment.output.remove(key);
+            }
+        }
+        verifications.push(Verification {
+            key,
+            status,
+            verdict,
+        });
+    }
+    Some(verifications)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verdict() {
+        assert_eq!(Verdict::of(StatusCode::OK), Verdict::Confirmed);
+        assert_eq!(
+            Verdict::of(StatusCode::MOVED_PERMANENTLY),
+            Verdict::Confirmed
+        );
+        assert_eq!(Verdict::of(StatusCode::NOT_FOUND), Verdict::Discarded);
+        assert_eq!(Verdict::of(StatusCode::GONE), Verdict::Discarded);
+        assert_eq!(
+            Verdict::of(StatusCode::METHOD_NOT_ALLOWED),
+            Verdict::Unverifiable
+        );
+        assert_eq!(Verdict::of(StatusCode::UNAUTHORIZED), Verdict::Downgraded);
+        assert_eq!(
+            Verdict::of(StatusCode::INTERNAL_SERVER_ERROR),
+            Verdict::Downgraded
+        );
+    }
+}
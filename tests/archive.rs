@@ -0,0 +1,73 @@
+// SPDX-FileCopyrightText: 2021 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+mod common;
+
+use std::collections::HashMap;
+use std::fs;
+
+use cli_utils::BoxResult;
+use common::StrMatcher;
+
+const CARGO_TOML: &str = r#"[package]
+name = "my-crate"
+version = "0.1.0"
+repository = "https://github.com/my-user/my-crate"
+"#;
+
+const GIT_ARCHIVAL: &str = "node: 9b5ff47e6e2b1e7ab7ac0fad3b8a13aa35b3b1a3
+node-date: 2021-09-30T22:59:05+02:00
+describe-name: 0.2.0-3-g9b5ff47
+ref-names: HEAD -> master
+";
+
+fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)>> {
+    Ok(vec![
+        (
+            "PROJECT_BUILD_BRANCH",
+            (Box::new(&"master" as &'static dyn StrMatcher), true),
+        ),
+        ("PROJECT_NAME", (Box::new(&"my-crate"), true)),
+        (
+            "PROJECT_REPO_WEB_URL",
+            (Box::new(&"https://github.com/my-user/my-crate"), true),
+        ),
+        ("PROJECT_VERSION", (Box::new(&"0.2.0-3-g9b5ff47"), true)),
+        ("PROJECT_VERSION_CONTROL_SYSTEM", (Box::new(&"none"), true)),
+        (
+            "PROJECT_VERSION_DATE",
+            (Box::new(&"2021-09-30 20:59:05"), true),
+        ),
+        (
+            "PROJECT_VERSION_DESCRIBE",
+            (Box::new(&"0.2.0-3-g9b5ff47"), true),
+        ),
+    ]
+    .into_iter()
+    .collect())
+}
+
+#[test]
+fn archive() -> BoxResult<()> {
+    let tmp_proj_dir = assert_fs::TempDir::new()?;
+    fs::write(tmp_proj_dir.join("Cargo.toml"), CARGO_TOML)?;
+    fs::write(tmp_proj_dir.join(".git_archival.txt"), GIT_ARCHIVAL)?;
+    common::projvar_test(
+        &expected_pats()?,
+        &[
+            "--fail",
+            "--only-required",
+            "--none",
+            "-RPROJECT_BUILD_BRANCH",
+            "-RPROJECT_NAME",
+            "-RPROJECT_REPO_WEB_URL",
+            "-RPROJECT_VERSION",
+            "-RPROJECT_VERSION_CONTROL_SYSTEM",
+            "-RPROJECT_VERSION_DATE",
+            "-RPROJECT_VERSION_DESCRIBE",
+        ],
+        tmp_proj_dir.path(),
+        HashMap::<String, String>::new(),
+    )
+}
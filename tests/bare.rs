@@ -0,0 +1,51 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+mod common;
+mod repo_creation;
+
+use std::collections::HashMap;
+
+use cli_utils::BoxResult;
+use common::StrMatcher;
+
+use crate::repo_creation::create_repo;
+
+#[test]
+fn bare() -> BoxResult<()> {
+    let repo_dir = create_repo!(crate::repo_creation::bare::create, "repo_creation/bare.rs")?;
+    let expected_pats: HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)> = vec![
+        (
+            "PROJECT_BUILD_BRANCH",
+            (
+                Box::new(&"master" as &'static dyn StrMatcher) as Box<&'static dyn StrMatcher>,
+                true,
+            ),
+        ),
+        ("PROJECT_BUILD_TAG", (Box::new(&"0.2.0"), true)),
+        ("PROJECT_VERSION", (Box::new(&"0.2.0"), true)),
+        (
+            "PROJECT_REPO_WEB_URL",
+            (Box::new(&"https://github.com/hoijui/projvar"), true),
+        ),
+        ("PROJECT_VERSION_CONTROL_SYSTEM", (Box::new(&"git"), true)),
+    ]
+    .into_iter()
+    .collect();
+    common::projvar_test(
+        &expected_pats,
+        &[
+            "--fail",
+            "--only-required",
+            "--none",
+            "-RPROJECT_BUILD_BRANCH",
+            "-RPROJECT_BUILD_TAG",
+            "-RPROJECT_VERSION",
+            "-RPROJECT_REPO_WEB_URL",
+            "-RPROJECT_VERSION_CONTROL_SYSTEM",
+        ],
+        &repo_dir.join("project.git"),
+        HashMap::<String, String>::new(),
+    )
+}
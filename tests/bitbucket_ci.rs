@@ -10,6 +10,7 @@ use cli_utils::BoxResult;
 use common::StrMatcher;
 
 const CI: &str = "true";
+const BITBUCKET_BUILD_NUMBER: &str = "17";
 const BITBUCKET_COMMIT: &str = "ffac537e6cbbf934b08745a378932722df287a53";
 const BITBUCKET_WORKSPACE: [&str; 2] = ["my-org", "my-user"];
 const BITBUCKET_REPO_SLUG: &str = "my-user/my-proj";
@@ -24,7 +25,7 @@ const BITBUCKET_BRANCH: [Option<&str>; 5] = [
 ];
 const BITBUCKET_TAG: [Option<&str>; 4] =
     [Some("v0.1.0"), Some("0.1.0"), Some("-Some_Thing-"), None];
-const BITBUCKET_PR_ID: fn() -> String = common::random_uuid;
+const BITBUCKET_PR_ID: &str = "42";
 const BITBUCKET_PR_DESTINATION_BRANCH: [Option<&str>; 5] = [
     Some("master"),
     Some("main"),
@@ -40,6 +41,7 @@ const BITBUCKET_PROJECT_UUID: &str = "123e4567-e89b-12d3-a456-426614174001";
 fn setup() -> BoxResult<HashMap<&'static str, String>> {
     Ok(HashMap::from([
         ("CI", CI.to_owned()),
+        ("BITBUCKET_BUILD_NUMBER", BITBUCKET_BUILD_NUMBER.to_owned()),
         ("BITBUCKET_COMMIT", BITBUCKET_COMMIT.to_owned()),
         ("BITBUCKET_WORKSPACE", BITBUCKET_WORKSPACE[0].to_owned()),
         ("BITBUCKET_REPO_SLUG", BITBUCKET_REPO_SLUG.to_owned()),
@@ -50,7 +52,7 @@ fn setup() -> BoxResult<HashMap<&'static str, String>> {
         ),
         ("BITBUCKET_BRANCH", BITBUCKET_BRANCH[0].unwrap().to_owned()),
         ("BITBUCKET_TAG", BITBUCKET_TAG[0].unwrap().to_owned()),
-        ("BITBUCKET_PR_ID", BITBUCKET_PR_ID()),
+        ("BITBUCKET_PR_ID", BITBUCKET_PR_ID.to_owned()),
         (
             "BITBUCKET_PR_DESTINATION_BRANCH",
             BITBUCKET_PR_DESTINATION_BRANCH[0].unwrap().to_owned(),
@@ -80,6 +82,15 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
                 true,
             ),
         ),
+        (
+            "PROJECT_BUILD_COMMIT_SHA",
+            (Box::new(&"ffac537e6cbbf934b08745a378932722df287a53"), true),
+        ),
+        (
+            "PROJECT_BUILD_COMMIT_SHA_SHORT",
+            (Box::new(&"ffac537"), true),
+        ),
+        ("PROJECT_BUILD_NUMBER", (Box::new(&"17"), true)),
         ("PROJECT_BUILD_TAG", (Box::new(&"v0.1.0"), true)),
         ("PROJECT_CI", (Box::new(&"true"), true)),
         ("PROJECT_NAME", (Box::new(&"my-project-group"), true)),
@@ -87,6 +98,14 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
             "PROJECT_NAME_MACHINE_READABLE",
             (Box::new(&"my-project-group"), true),
         ),
+        ("PROJECT_NAME_SLUG", (Box::new(&"my-project-group"), true)),
+        (
+            "PROJECT_REPO_CI_URL",
+            (
+                Box::new(&"https://bitbucket.org/my-user/my-proj/pipelines"),
+                true,
+            ),
+        ),
         (
             "PROJECT_REPO_CLONE_URL",
             (Box::new(&"git@bitbucket.org:my-user/my-proj.git"), true),
@@ -95,6 +114,13 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
             "PROJECT_REPO_CLONE_URL_HTTP",
             (Box::new(&"https://bitbucket.org/my-user/my-proj"), true),
         ),
+        (
+            "PROJECT_REPO_CLONE_URL_PUSH",
+            (
+                Box::new(&"ssh://git@bitbucket.org/my-user/my-proj.git"),
+                true,
+            ),
+        ),
         (
             "PROJECT_REPO_CLONE_URL_SSH",
             (
@@ -128,10 +154,40 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
             "PROJECT_REPO_VERSIONED_FILE_PREFIX_URL",
             (Box::new(&"https://bitbucket.org/my-user/my-proj/src"), true),
         ),
+        ("PROJECT_PULL_REQUEST_NUMBER", (Box::new(&"42"), true)),
+        (
+            "PROJECT_PULL_REQUEST_SOURCE_BRANCH",
+            (Box::new(&"master"), true),
+        ),
+        (
+            "PROJECT_PULL_REQUEST_TARGET_BRANCH",
+            (Box::new(&"master"), true),
+        ),
+        (
+            "PROJECT_PULL_REQUEST_URL",
+            (
+                Box::new(&"https://bitbucket.org/my-user/my-proj/pull-requests/42"),
+                true,
+            ),
+        ),
+        ("PROJECT_REPO_NAMESPACE", (Box::new(&"my-user"), true)),
+        ("PROJECT_REPO_OWNER", (Box::new(&"my-user"), true)),
+        (
+            "PROJECT_REPO_RELEASES_URL",
+            (
+                Box::new(&"https://bitbucket.org/my-user/my-proj/downloads"),
+                true,
+            ),
+        ),
+        ("PROJECT_REPO_SLUG", (Box::new(&"my-user/my-proj"), true)),
         (
             "PROJECT_REPO_WEB_URL",
             (Box::new(&"https://bitbucket.org/my-user/my-proj"), true),
         ),
+        ("PROJECT_VERSION_MAJOR", (Box::new(&"0"), true)),
+        ("PROJECT_VERSION_MINOR", (Box::new(&"1"), true)),
+        ("PROJECT_VERSION_PATCH", (Box::new(&"0"), true)),
+        ("PROJECT_VERSION_CONTROL_SYSTEM", (Box::new(&"git"), true)),
         ("PROJECT_VERSION", (Box::new(&"0.1.0"), true)),
     ]
     .into_iter()
@@ -0,0 +1,151 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+mod common;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cli_utils::BoxResult;
+use common::StrMatcher;
+use regex::Regex;
+use serde::Deserialize;
+
+/// The directory containing the [`CiFixture`] data files,
+/// one per CI source (or scenario).
+const CI_FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/ci_fixtures");
+
+/// A black-box test case for a CI source,
+/// read from a TOML file in [`CI_FIXTURES_DIR`].
+/// Adding a test for a CI source requires only such a file.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct CiFixture {
+    /// The CLI arguments to run projvar with
+    #[serde(default = "CiFixture::default_args")]
+    args: Vec<String>,
+    /// Files to create in the (otherwise empty) project directory,
+    /// with their paths relative to it
+    #[serde(default)]
+    files: HashMap<String, String>,
+    /// The environment variables as set by the CI;
+    /// `{project_dir}` gets replaced with the path of the project directory
+    env: HashMap<String, String>,
+    /// The values projvar has to produce
+    #[serde(default)]
+    expected: HashMap<String, String>,
+    /// Regex patterns for the values projvar may or may not produce
+    #[serde(default)]
+    optional: HashMap<String, String>,
+}
+
+impl CiFixture {
+    fn default_args() -> Vec<String> {
+        vec!["--all".to_owned()]
+    }
+}
+
+/// The expected values for [`common::compare`],
+/// each with whether it is required.
+type ExpectedPats = HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)>;
+
+/// Leaks a value, to get a `'static` reference to it,
+/// as required by [`common::compare`].
+/// This is fine, as it is used only once per test.
+fn leak<T: 'static>(value: T) -> &'static T {
+    Box::leak(Box::new(value))
+}
+
+/// Generates the expected patterns for [`common::compare`]
+/// from exact (and required) values,
+/// and regex patterns for optional ones.
+///
+/// # Errors
+///
+/// If one of the optional patterns is not a valid regex.
+fn expected_pats_from(
+    expected: &HashMap<String, String>,
+    optional: &HashMap<String, String>,
+) -> BoxResult<ExpectedPats> {
+    let mut pats: ExpectedPats = HashMap::new();
+    for (key, value) in expected {
+        let value: &'static str = leak(value.clone()).as_str();
+        pats.insert(
+            leak(key.clone()).as_str(),
+            (Box::new(leak(value) as &'static dyn StrMatcher), true),
+        );
+    }
+    for (key, pattern) in optional {
+        let regex = leak(Regex::new(&format!("^(?:{pattern})$"))?);
+        pats.insert(
+            leak(key.clone()).as_str(),
+            (Box::new(regex as &'static dyn StrMatcher), false),
+        );
+    }
+    Ok(pats)
+}
+
+/// Runs projvar in an otherwise empty project directory,
+/// with the environment and expectations of a [`CiFixture`] file.
+///
+/// # Errors
+///
+/// If the fixture file can not be read or parsed,
+/// or if projvar does not produce the expected values.
+fn ci_fixture_test(fixture_file: &Path) -> BoxResult<()> {
+    let fixture: CiFixture = toml::from_str(&fs::read_to_string(fixture_file)?)?;
+    let tmp_proj_dir = assert_fs::TempDir::new()?;
+    let proj_dir = tmp_proj_dir.path();
+    for (file, content) in &fixture.files {
+        fs::write(proj_dir.join(file), content)?;
+    }
+    let proj_dir_str = proj_dir.display().to_string();
+    let envs = fixture
+        .env
+        .iter()
+        .map(|(key, value)| (key, value.replace("{project_dir}", &proj_dir_str)));
+    let args: Vec<&str> = fixture.args.iter().map(String::as_str).collect();
+    common::projvar_test(
+        &expected_pats_from(&fixture.expected, &fixture.optional)?,
+        &args,
+        proj_dir,
+        envs,
+    )
+}
+
+/// Lists all the [`CiFixture`] files in [`CI_FIXTURES_DIR`], sorted.
+///
+/// # Errors
+///
+/// If the directory can not be read.
+fn ci_fixture_files() -> BoxResult<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in fs::read_dir(CI_FIXTURES_DIR)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Runs all the CI fixtures in [`CI_FIXTURES_DIR`],
+/// reporting all the failing ones at once.
+#[test]
+fn ci_fixtures() -> BoxResult<()> {
+    let fixture_files = ci_fixture_files()?;
+    assert!(!fixture_files.is_empty(), "No CI fixtures found");
+    let failures: Vec<String> = fixture_files
+        .iter()
+        .filter_map(|fixture_file| {
+            ci_fixture_test(fixture_file)
+                .err()
+                .map(|err| format!("{}: {err}", fixture_file.display()))
+        })
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+    Ok(())
+}
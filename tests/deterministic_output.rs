@@ -0,0 +1,60 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+mod repo_creation;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use cli_utils::BoxResult;
+
+use crate::repo_creation::create_repo;
+
+/// Runs projvar on the given repo,
+/// and returns the content of the resulting output file.
+fn run(repo_dir: &Path, out_file_name: &str) -> BoxResult<Vec<u8>> {
+    let tmp_out_dir = assert_fs::TempDir::new()?;
+    let out_file = tmp_out_dir.path().join(out_file_name);
+    let mut cmd = Command::cargo_bin("projvar")?;
+    cmd.arg("--all").arg("-O").arg(&out_file);
+    cmd.current_dir(repo_dir);
+    cmd.env_clear();
+    // makes the build date reproducible
+    cmd.env("SOURCE_DATE_EPOCH", "1633035545");
+    cmd.assert().success();
+    Ok(fs::read(out_file)?)
+}
+
+fn assert_deterministic(out_file_name: &str) -> BoxResult<Vec<u8>> {
+    let repo_dir = create_repo!(
+        crate::repo_creation::default::create,
+        "repo_creation/default.rs"
+    )?;
+    let first = run(&repo_dir, out_file_name)?;
+    let second = run(&repo_dir, out_file_name)?;
+    assert!(!first.is_empty());
+    assert_eq!(first, second);
+    Ok(first)
+}
+
+#[test]
+fn deterministic_env_output() -> BoxResult<()> {
+    let output = String::from_utf8(assert_deterministic("projvars.env")?)?;
+    let keys: Vec<&str> = output
+        .lines()
+        .filter_map(|line| line.split_once('=').map(|(key, _value)| key))
+        .collect();
+    let mut sorted_keys = keys.clone();
+    sorted_keys.sort_unstable();
+    assert_eq!(keys, sorted_keys);
+    Ok(())
+}
+
+#[test]
+fn deterministic_json_output() -> BoxResult<()> {
+    assert_deterministic("projvars.json")?;
+    Ok(())
+}
@@ -42,14 +42,36 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
         ),
         ("PROJECT_BUILD_ARCH", (Box::new(&*R_NON_EMPTY), true)),
         ("PROJECT_BUILD_BRANCH", (Box::new(&*R_NON_EMPTY), false)),
+        ("PROJECT_BUILD_COMMIT_SHA", (Box::new(&*R_NON_EMPTY), true)),
+        (
+            "PROJECT_BUILD_COMMIT_SHA_SHORT",
+            (Box::new(&*R_NON_EMPTY), true),
+        ),
+        ("PROJECT_BUILD_DATE_EPOCH", (Box::new(&*R_NON_EMPTY), true)),
+        ("PROJECT_BUILD_DIRTY", (Box::new(&*R_BOOL), true)),
+        (
+            "PROJECT_BUILD_HOST_CPU_COUNT",
+            (Box::new(&*R_NON_EMPTY), true),
+        ),
+        (
+            "PROJECT_BUILD_HOST_KERNEL_VERSION",
+            (Box::new(&*R_NON_EMPTY), false),
+        ),
+        (
+            "PROJECT_BUILD_HOST_PLATFORM_TRIPLE",
+            (Box::new(&*R_NON_EMPTY), true),
+        ),
         (
             "PROJECT_BUILD_HOSTING_URL",
             (Box::new(&"https://hoijui.github.io/projvar"), true),
         ),
+        ("PROJECT_BUILD_NUMBER", (Box::new(&*R_NON_EMPTY), true)),
         ("PROJECT_BUILD_OS", (Box::new(&*R_NON_EMPTY), true)),
         ("PROJECT_BUILD_OS_FAMILY", (Box::new(&*R_NON_EMPTY), true)),
         ("PROJECT_BUILD_TAG", (Box::new(&*R_NON_EMPTY), false)),
         ("PROJECT_CI", (Box::new(&*R_BOOL), true)),
+        ("PROJECT_DOI", (Box::new(&*R_NON_EMPTY), false)),
+        ("PROJECT_FUNDING_URL", (Box::new(&*R_NON_EMPTY), false)),
         ("PROJECT_LICENSE", (Box::new(&"AGPL-3.0-only"), true)),
         (
             "PROJECT_LICENSES",
@@ -60,11 +82,20 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
             "PROJECT_NAME_MACHINE_READABLE",
             (Box::new(&"default_rs"), true),
         ),
+        ("PROJECT_NAME_SLUG", (Box::new(&"default-rs"), true)),
+        (
+            "PROJECT_REPO_CI_URL",
+            (Box::new(&"https://github.com/hoijui/projvar/actions"), true),
+        ),
         ("PROJECT_REPO_CLONE_URL", (Box::new(&*R_CLONE_URL), true)),
         (
             "PROJECT_REPO_CLONE_URL_HTTP",
             (Box::new(&*R_CLONE_URL_HTTP), true),
         ),
+        (
+            "PROJECT_REPO_CLONE_URL_PUSH",
+            (Box::new(&*R_CLONE_URL), true),
+        ),
         (
             "PROJECT_REPO_CLONE_URL_SSH",
             (Box::new(&*R_CLONE_URL_SSH), true),
@@ -84,6 +115,16 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
                 true,
             ),
         ),
+        ("PROJECT_REPO_NAMESPACE", (Box::new(&"hoijui"), true)),
+        ("PROJECT_REPO_OWNER", (Box::new(&"hoijui"), true)),
+        (
+            "PROJECT_REPO_RELEASES_URL",
+            (
+                Box::new(&"https://github.com/hoijui/projvar/releases"),
+                true,
+            ),
+        ),
+        ("PROJECT_REPO_SLUG", (Box::new(&"hoijui/projvar"), true)),
         (
             "PROJECT_REPO_VERSIONED_DIR_PREFIX_URL",
             (Box::new(&"https://github.com/hoijui/projvar/tree"), true),
@@ -96,7 +137,20 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
             "PROJECT_REPO_WEB_URL",
             (Box::new(&"https://github.com/hoijui/projvar"), true),
         ),
+        ("PROJECT_VERSION_CONTROL_SYSTEM", (Box::new(&"git"), true)),
         ("PROJECT_VERSION", (Box::new(&*R_NON_EMPTY), true)),
+        (
+            "PROJECT_VERSION_BUILD_META",
+            (Box::new(&*R_NON_EMPTY), false),
+        ),
+        ("PROJECT_VERSION_DESCRIBE", (Box::new(&*R_NON_EMPTY), true)),
+        ("PROJECT_VERSION_MAJOR", (Box::new(&*R_NON_EMPTY), false)),
+        ("PROJECT_VERSION_MINOR", (Box::new(&*R_NON_EMPTY), false)),
+        ("PROJECT_VERSION_PATCH", (Box::new(&*R_NON_EMPTY), false)),
+        (
+            "PROJECT_VERSION_PRERELEASE",
+            (Box::new(&*R_NON_EMPTY), false),
+        ),
         ("PROJECT_VERSION_DATE", (Box::new(&*R_DATE_TIME), true)),
     ]
     .into_iter()
@@ -9,6 +9,7 @@ use common::StrMatcher;
 use std::collections::HashMap;
 
 const CI: &str = "true";
+const GITHUB_ACTIONS: &str = "true";
 const GITHUB_ACTOR: &str = "octocat";
 const GITHUB_REPOSITORY: &str = "octocat/Hello-World";
 const GITHUB_SHA: &str = "ffac537e6cbbf934b08745a378932722df287a53";
@@ -35,6 +36,7 @@ const GITHUB_BASE_REF: [Option<&str>; 2] = [Some("base-branch"), None];
 fn setup() -> BoxResult<HashMap<&'static str, &'static str>> {
     Ok(HashMap::from([
         ("CI", CI),
+        ("GITHUB_ACTIONS", GITHUB_ACTIONS),
         ("GITHUB_ACTOR", GITHUB_ACTOR),
         ("GITHUB_REPOSITORY", GITHUB_REPOSITORY),
         ("GITHUB_SHA", GITHUB_SHA),
@@ -58,6 +60,14 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
                 true,
             ),
         ),
+        (
+            "PROJECT_BUILD_COMMIT_SHA",
+            (Box::new(&"ffac537e6cbbf934b08745a378932722df287a53"), true),
+        ),
+        (
+            "PROJECT_BUILD_COMMIT_SHA_SHORT",
+            (Box::new(&"ffac537"), true),
+        ),
         (
             "PROJECT_BUILD_HOSTING_URL",
             (Box::new(&"https://octocat.github.io/Hello-World"), true),
@@ -68,6 +78,14 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
             "PROJECT_NAME_MACHINE_READABLE",
             (Box::new(&"Hello-World"), true),
         ),
+        ("PROJECT_NAME_SLUG", (Box::new(&"hello-world"), true)),
+        (
+            "PROJECT_REPO_CI_URL",
+            (
+                Box::new(&"https://github.com/octocat/Hello-World/actions"),
+                true,
+            ),
+        ),
         (
             "PROJECT_REPO_CLONE_URL",
             (
@@ -82,6 +100,13 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
                 true,
             ),
         ),
+        (
+            "PROJECT_REPO_CLONE_URL_PUSH",
+            (
+                Box::new(&"ssh://git@github.com/octocat/Hello-World.git"),
+                true,
+            ),
+        ),
         (
             "PROJECT_REPO_CLONE_URL_SSH",
             (
@@ -124,10 +149,24 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
                 true,
             ),
         ),
+        ("PROJECT_REPO_NAMESPACE", (Box::new(&"octocat"), true)),
+        ("PROJECT_REPO_OWNER", (Box::new(&"octocat"), true)),
+        (
+            "PROJECT_REPO_RELEASES_URL",
+            (
+                Box::new(&"https://github.com/octocat/Hello-World/releases"),
+                true,
+            ),
+        ),
+        (
+            "PROJECT_REPO_SLUG",
+            (Box::new(&"octocat/Hello-World"), true),
+        ),
         (
             "PROJECT_REPO_WEB_URL",
             (Box::new(&"https://github.com/octocat/Hello-World"), true),
         ),
+        ("PROJECT_VERSION_CONTROL_SYSTEM", (Box::new(&"git"), true)),
         (
             "PROJECT_VERSION",
             (Box::new(&"ffac537e6cbbf934b08745a378932722df287a53"), true),
@@ -150,6 +150,15 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
                 true,
             ),
         ),
+        (
+            "PROJECT_BUILD_COMMIT_SHA",
+            (Box::new(&"ffac537e6cbbf934b08745a378932722df287a53"), true),
+        ),
+        (
+            "PROJECT_BUILD_COMMIT_SHA_SHORT",
+            (Box::new(&"ffac537e"), true),
+        ),
+        ("PROJECT_BUILD_DATE_EPOCH", (Box::new(&"1640244321"), true)),
         (
             "PROJECT_BUILD_HOSTING_URL",
             (Box::new(&"https://my-org.gitlab.io/my-proj"), true),
@@ -161,6 +170,14 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
             "PROJECT_NAME_MACHINE_READABLE",
             (Box::new(&"Project-1"), true),
         ),
+        ("PROJECT_NAME_SLUG", (Box::new(&"project-1"), true)),
+        (
+            "PROJECT_REPO_CLONE_URL_PUSH",
+            (
+                Box::new(&"ssh://git@gitlab.com/User-Name/Project-1.git"),
+                true,
+            ),
+        ),
         (
             "PROJECT_REPO_CLONE_URL_SSH",
             (
@@ -168,10 +185,28 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
                 true,
             ),
         ),
+        ("PROJECT_REPO_DEFAULT_BRANCH", (Box::new(&"master"), true)),
+        ("PROJECT_REPO_NAMESPACE", (Box::new(&"User-Name"), true)),
+        ("PROJECT_REPO_OWNER", (Box::new(&"user-name"), true)),
+        (
+            "PROJECT_REPO_RELEASES_URL",
+            (
+                Box::new(&"https://gitlab.com/User-Name/Project-1/-/releases"),
+                true,
+            ),
+        ),
+        (
+            "PROJECT_REPO_SLUG",
+            (Box::new(&"User-Name/Project-1"), true),
+        ),
         (
             "PROJECT_REPO_WEB_URL",
             (Box::new(&"https://gitlab.com/User-Name/Project-1"), true),
         ),
+        ("PROJECT_VERSION_MAJOR", (Box::new(&"0"), true)),
+        ("PROJECT_VERSION_MINOR", (Box::new(&"1"), true)),
+        ("PROJECT_VERSION_PATCH", (Box::new(&"0"), true)),
+        ("PROJECT_VERSION_CONTROL_SYSTEM", (Box::new(&"git"), true)),
         ("PROJECT_VERSION", (Box::new(&"0.1.0"), true)),
         (
             "PROJECT_VERSION_DATE",
@@ -198,6 +233,13 @@ fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatch
                 true,
             ),
         ),
+        (
+            "PROJECT_REPO_CI_URL",
+            (
+                Box::new(&"https://gitlab.com/User-Name/Project-1/-/pipelines"),
+                true,
+            ),
+        ),
         (
             "PROJECT_REPO_CLONE_URL",
             (
@@ -0,0 +1,55 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+mod repo_creation;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use cli_utils::BoxResult;
+
+use crate::repo_creation::create_repo;
+
+/// Runs projvar incrementally on the given repo,
+/// and returns the content of the resulting output file.
+fn run(repo_dir: &Path, state_file: &Path) -> BoxResult<String> {
+    let tmp_out_dir = assert_fs::TempDir::new()?;
+    let out_file = tmp_out_dir.path().join("projvars.env");
+    let mut cmd = Command::cargo_bin("projvar")?;
+    cmd.arg("--all")
+        .arg("-O")
+        .arg(&out_file)
+        .arg("--incremental")
+        .arg(state_file);
+    cmd.current_dir(repo_dir);
+    cmd.env_clear();
+    // makes the build date reproducible
+    cmd.env("SOURCE_DATE_EPOCH", "1633035545");
+    cmd.assert().success();
+    Ok(fs::read_to_string(out_file)?)
+}
+
+#[test]
+fn incremental_reuses_previous_state() -> BoxResult<()> {
+    let repo_dir = create_repo!(
+        crate::repo_creation::default::create,
+        "repo_creation/default.rs"
+    )?;
+    let state_dir = assert_fs::TempDir::new()?;
+    let state_file = state_dir.path().join("projvar-state.json");
+
+    let first = run(&repo_dir, &state_file)?;
+    assert!(state_file.is_file());
+    let second = run(&repo_dir, &state_file)?;
+    assert!(!first.is_empty());
+    assert_eq!(first, second);
+
+    // a corrupt state is ignored
+    fs::write(&state_file, "not JSON")?;
+    let third = run(&repo_dir, &state_file)?;
+    assert_eq!(first, third);
+    Ok(())
+}
@@ -0,0 +1,116 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::HashMap;
+
+mod common;
+
+use cli_utils::BoxResult;
+use common::StrMatcher;
+
+const BUILD_NUMBER: &str = "17";
+const GIT_COMMIT: &str = "ffac537e6cbbf934b08745a378932722df287a53";
+const GIT_URL: [&str; 2] = [
+    "git@github.com:my-user/my-proj.git",
+    "https://github.com/my-user/my-proj.git",
+];
+const JENKINS_URL: &str = "https://jenkins.example.com/";
+
+/// Runs projvar, requiring exactly the keys in `expected`.
+fn jenkins_test(
+    envs: &[(&'static str, &'static str)],
+    expected: &'static [(&'static str, &'static str)],
+) -> BoxResult<()> {
+    let tmp_proj_dir_empty = assert_fs::TempDir::new()?;
+    let require_args: Vec<String> = expected.iter().map(|(key, _)| format!("-R{key}")).collect();
+    let mut args = vec!["--fail", "--only-required", "--none"];
+    args.extend(require_args.iter().map(String::as_str));
+    let expected_pats: HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)> = expected
+        .iter()
+        .map(|(key, value)| (*key, (Box::new(value as &'static dyn StrMatcher), true)))
+        .collect();
+    common::projvar_test(
+        &expected_pats,
+        &args,
+        tmp_proj_dir_empty.path(),
+        envs.iter().copied(),
+    )
+}
+
+/// A free-style job, with only the Git plugin setting variables.
+#[test]
+fn jenkins_ci_git_plugin() -> BoxResult<()> {
+    jenkins_test(
+        &[
+            ("BUILD_NUMBER", BUILD_NUMBER),
+            ("GIT_BRANCH", "origin/develop"),
+            ("GIT_COMMIT", GIT_COMMIT),
+            ("GIT_URL", GIT_URL[0]),
+            ("JENKINS_URL", JENKINS_URL),
+        ],
+        &[
+            ("PROJECT_BUILD_BRANCH", "develop"),
+            ("PROJECT_BUILD_COMMIT_SHA", GIT_COMMIT),
+            ("PROJECT_BUILD_NUMBER", BUILD_NUMBER),
+            ("PROJECT_REPO_CLONE_URL", GIT_URL[0]),
+            ("PROJECT_REPO_SLUG", "my-user/my-proj"),
+            ("PROJECT_REPO_WEB_URL", "https://github.com/my-user/my-proj"),
+            ("PROJECT_VERSION_CONTROL_SYSTEM", "git"),
+        ],
+    )
+}
+
+/// A tag build in a multi-branch pipeline,
+/// where `BRANCH_NAME` holds the tag name.
+#[test]
+fn jenkins_ci_multibranch_tag() -> BoxResult<()> {
+    jenkins_test(
+        &[
+            ("BRANCH_NAME", "v0.1.0"),
+            ("BUILD_NUMBER", BUILD_NUMBER),
+            ("GIT_BRANCH", "v0.1.0"),
+            ("GIT_COMMIT", GIT_COMMIT),
+            ("GIT_URL", GIT_URL[1]),
+            ("JENKINS_URL", JENKINS_URL),
+            ("TAG_NAME", "v0.1.0"),
+        ],
+        &[
+            ("PROJECT_BUILD_TAG", "v0.1.0"),
+            ("PROJECT_REPO_WEB_URL", "https://github.com/my-user/my-proj"),
+            ("PROJECT_VERSION", "0.1.0"),
+        ],
+    )
+}
+
+/// A pull request build in a multi-branch pipeline,
+/// where `BRANCH_NAME` holds something like "PR-42".
+#[test]
+fn jenkins_ci_multibranch_pull_request() -> BoxResult<()> {
+    jenkins_test(
+        &[
+            ("BRANCH_NAME", "PR-42"),
+            ("BUILD_NUMBER", BUILD_NUMBER),
+            ("CHANGE_BRANCH", "fix-typo"),
+            ("CHANGE_ID", "42"),
+            ("CHANGE_TARGET", "master"),
+            ("CHANGE_TITLE", "Fix a typo in the README"),
+            ("CHANGE_URL", "https://github.com/my-user/my-proj/pull/42"),
+            ("GIT_BRANCH", "PR-42"),
+            ("GIT_COMMIT", GIT_COMMIT),
+            ("GIT_URL", GIT_URL[1]),
+            ("JENKINS_URL", JENKINS_URL),
+        ],
+        &[
+            ("PROJECT_PULL_REQUEST_NUMBER", "42"),
+            ("PROJECT_PULL_REQUEST_SOURCE_BRANCH", "fix-typo"),
+            ("PROJECT_PULL_REQUEST_TARGET_BRANCH", "master"),
+            ("PROJECT_PULL_REQUEST_TITLE", "Fix a typo in the README"),
+            (
+                "PROJECT_PULL_REQUEST_URL",
+                "https://github.com/my-user/my-proj/pull/42",
+            ),
+            ("PROJECT_REPO_WEB_URL", "https://github.com/my-user/my-proj"),
+        ],
+    )
+}
@@ -0,0 +1,66 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+mod common;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use cli_utils::BoxResult;
+use cmd_lib::run_cmd;
+use common::StrMatcher;
+
+/// Creates a workspace with REUSE license dirs
+/// in the root, in two crates, in vendored code
+/// and in a directory that is left out of source archives.
+/// It is a git repo, as the licenses are only looked for in repos.
+fn create_workspace(root: &Path) -> BoxResult<()> {
+    for license_file in [
+        "LICENSES/Apache-2.0.txt",
+        "crates/core/LICENSES/MIT.txt",
+        "crates/cli/LICENSES/Apache-2.0.txt",
+        "third_party/lib/LICENSES/GPL-3.0-only.txt",
+        "examples/LICENSES/CC0-1.0.txt",
+    ] {
+        let path = root.join(license_file);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, "")?;
+    }
+    fs::write(root.join(".gitattributes"), "/examples export-ignore\n")?;
+    run_cmd!(
+        cd "$root";
+        git init -q;
+        git config user.email "elui.alawi@email.com";
+        git config user.name "Joe Doe";
+        git add -A;
+        git commit -q -m "Initial commit";
+    )?;
+    Ok(())
+}
+
+fn licenses_test(extra_args: &[&str], expected: &'static dyn StrMatcher) -> BoxResult<()> {
+    let tmp_proj_dir = assert_fs::TempDir::new()?;
+    create_workspace(tmp_proj_dir.path())?;
+    let mut args = vec!["--fail", "--only-required", "--none", "-RLicenses"];
+    args.extend(extra_args);
+    let expected_pats: HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)> =
+        HashMap::from([("PROJECT_LICENSES", (Box::new(expected), true))]);
+    common::projvar_test(
+        &expected_pats,
+        &args,
+        tmp_proj_dir.path(),
+        HashMap::<String, String>::new(),
+    )
+}
+
+#[test]
+fn licenses_root_only() -> BoxResult<()> {
+    licenses_test(&[], &"Apache-2.0")
+}
+
+#[test]
+fn licenses_nested() -> BoxResult<()> {
+    licenses_test(&["--license-scan-depth", "2"], &"Apache-2.0, MIT")
+}
@@ -4,9 +4,11 @@
 
 mod common;
 
+use assert_cmd::prelude::*;
 use cli_utils::BoxResult;
 use common::StrMatcher;
 use std::collections::HashMap;
+use std::process::Command;
 
 fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)>> {
     Ok(vec![(
@@ -111,3 +113,122 @@ fn cli_arg_verbose() -> BoxResult<()> {
 fn cli_arg_defaults() -> BoxResult<()> {
     common::projvar_test_clean(&expected_pats()?, &[])
 }
+
+/// Runs projvar in an empty directory and clean environment,
+/// and returns its exit code.
+fn exit_code(args: &[&str]) -> BoxResult<Option<i32>> {
+    exit_code_with_envs(args, &[])
+}
+
+/// Like [`exit_code`], but with the given environment variables set.
+fn exit_code_with_envs(args: &[&str], envs: &[(&str, &str)]) -> BoxResult<Option<i32>> {
+    let tmp_proj_dir_empty = assert_fs::TempDir::new()?;
+    let output = Command::cargo_bin("projvar")?
+        .args(args)
+        .current_dir(tmp_proj_dir_empty.path())
+        .env_clear()
+        .envs(envs.iter().copied())
+        .output()?;
+    Ok(output.status.code())
+}
+
+#[test]
+fn exit_codes() -> BoxResult<()> {
+    assert_eq!(exit_code(&["--dry"])?, Some(0));
+    assert_eq!(exit_code(&["--help"])?, Some(0));
+    assert_eq!(
+        exit_code(&["--dry", "--none", "--fail", "-R", "PROJECT_REPO_WEB_URL"])?,
+        Some(2)
+    );
+    assert_eq!(exit_code(&["--dry", "--none", "--fail"])?, Some(0));
+    assert_eq!(exit_code(&["--no-such-option"])?, Some(64));
+    Ok(())
+}
+
+#[test]
+fn cli_arg_keep_going() -> BoxResult<()> {
+    let envs = [
+        // makes the env source fail to convert it to the build date
+        ("SOURCE_DATE_EPOCH", "not-a-number"),
+        // so we still end up with a valid value
+        ("PROJVAR_BUILD_DATE_EPOCH", "1633035545"),
+    ];
+    assert_eq!(exit_code_with_envs(&["--dry"], &envs)?, Some(1));
+    assert_eq!(
+        exit_code_with_envs(&["--dry", "--keep-going"], &envs)?,
+        Some(0)
+    );
+    Ok(())
+}
+
+#[test]
+fn cli_arg_max_length() -> BoxResult<()> {
+    let tmp_proj_dir_empty = assert_fs::TempDir::new()?;
+    let output = Command::cargo_bin("projvar")?
+        .args(["--dry", "--max-length", "Name=7", "-O", "vars.json"])
+        .current_dir(tmp_proj_dir_empty.path())
+        .env_clear()
+        .env("PROJECT_NAME", "projvar-extended")
+        .output()?;
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains(r#""NAME":"projvar""#));
+    let envs = [("PROJECT_NAME", "projvar-extended")];
+    assert_eq!(
+        exit_code_with_envs(&["--dry", "--max-length", "Name=7:error"], &envs)?,
+        Some(3)
+    );
+    assert_eq!(
+        exit_code_with_envs(&["--dry", "--max-length", "Name=7:warn"], &envs)?,
+        Some(0)
+    );
+    Ok(())
+}
+
+#[test]
+fn cli_arg_explain() -> BoxResult<()> {
+    let tmp_proj_dir_empty = assert_fs::TempDir::new()?;
+    let output = Command::cargo_bin("projvar")?
+        .args(["--explain", "PROJECT_REPO_WEB_URL"])
+        .current_dir(tmp_proj_dir_empty.path())
+        .env_clear()
+        .env("PROJECT_REPO_WEB_URL", "https://github.com/hoijui/projvar")
+        .output()?;
+    assert_eq!(output.status.code(), Some(0));
+    let log = String::from_utf8(output.stderr)?;
+    assert!(log.contains("Sources, from lowest to highest priority:"));
+    assert!(log.contains("reads: PROJECT_REPO_WEB_URL"));
+    assert!(log.contains("Result: 'https://github.com/hoijui/projvar'"));
+    Ok(())
+}
+
+#[test]
+fn cli_arg_dry() -> BoxResult<()> {
+    let tmp_proj_dir_empty = assert_fs::TempDir::new()?;
+    let output = Command::cargo_bin("projvar")?
+        .args(["--dry", "-O", "vars.json"])
+        .current_dir(tmp_proj_dir_empty.path())
+        .env_clear()
+        .output()?;
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("==> projvar::sinks::json::VarSink(file: vars.json) <=="));
+    assert!(stdout.contains(r#""CI":"false""#));
+    assert!(!tmp_proj_dir_empty.path().join("vars.json").exists());
+    Ok(())
+}
+
+#[test]
+fn cli_arg_dry_out() -> BoxResult<()> {
+    let tmp_proj_dir_empty = assert_fs::TempDir::new()?;
+    let output = Command::cargo_bin("projvar")?
+        .args(["--dry-out", "preview", "-O", "vars.env"])
+        .current_dir(tmp_proj_dir_empty.path())
+        .env_clear()
+        .output()?;
+    assert_eq!(output.status.code(), Some(0));
+    let preview = std::fs::read_to_string(tmp_proj_dir_empty.path().join("preview/vars.env"))?;
+    assert!(preview.contains("PROJECT_CI="));
+    assert!(!tmp_proj_dir_empty.path().join("vars.env").exists());
+    Ok(())
+}
@@ -0,0 +1,62 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+mod common;
+mod repo_creation;
+
+use std::collections::HashMap;
+
+use cli_utils::BoxResult;
+use common::StrMatcher;
+
+use crate::repo_creation::create_repo;
+
+#[test]
+fn overrides() -> BoxResult<()> {
+    let cwd = create_repo!(
+        crate::repo_creation::default::create,
+        "repo_creation/default.rs"
+    )?;
+    let envs: HashMap<&'static str, &'static str> = [
+        ("PROJVAR_VERSION", "9.8.7"),
+        (
+            "PROJVAR_REPO_WEB_URL",
+            "https://gitlab.com/some-group/some-proj",
+        ),
+        // The regular input variable is overridden too
+        ("PROJECT_VERSION", "1.2.3"),
+    ]
+    .into_iter()
+    .collect();
+    let expected_pats: HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)> = vec![
+        (
+            "PROJECT_VERSION",
+            (
+                Box::new(&"9.8.7" as &'static dyn StrMatcher) as Box<&'static dyn StrMatcher>,
+                true,
+            ),
+        ),
+        (
+            "PROJECT_REPO_WEB_URL",
+            (Box::new(&"https://gitlab.com/some-group/some-proj"), true),
+        ),
+        // derived from the overridden web URL
+        ("PROJECT_REPO_OWNER", (Box::new(&"some-group"), true)),
+    ]
+    .into_iter()
+    .collect();
+    common::projvar_test(
+        &expected_pats,
+        &[
+            "--fail",
+            "--only-required",
+            "--none",
+            "-RPROJECT_VERSION",
+            "-RPROJECT_REPO_WEB_URL",
+            "-RPROJECT_REPO_OWNER",
+        ],
+        &cwd,
+        envs,
+    )
+}
@@ -0,0 +1,48 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+mod common;
+mod repo_creation;
+
+use std::collections::HashMap;
+
+use cli_utils::BoxResult;
+use common::StrMatcher;
+
+use crate::repo_creation::create_repo;
+
+#[test]
+fn push_url() -> BoxResult<()> {
+    let repo_dir = create_repo!(
+        crate::repo_creation::push_url::create,
+        "repo_creation/push_url.rs"
+    )?;
+    let expected_pats: HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)> = vec![
+        (
+            "PROJECT_REPO_CLONE_URL",
+            (
+                Box::new(&"https://github.com/hoijui/projvar.git" as &'static dyn StrMatcher),
+                true,
+            ),
+        ),
+        (
+            "PROJECT_REPO_CLONE_URL_PUSH",
+            (Box::new(&"git@github.com:hoijui/projvar.git"), true),
+        ),
+    ]
+    .into_iter()
+    .collect();
+    common::projvar_test(
+        &expected_pats,
+        &[
+            "--fail",
+            "--only-required",
+            "--none",
+            "-RPROJECT_REPO_CLONE_URL",
+            "-RPROJECT_REPO_CLONE_URL_PUSH",
+        ],
+        &repo_dir,
+        HashMap::<String, String>::new(),
+    )
+}
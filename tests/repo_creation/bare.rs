@@ -0,0 +1,39 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::Path;
+
+use cmd_lib::run_cmd;
+
+use super::RepoCreationError;
+
+/// Creates the tags repo in the sub-dir "source",
+/// and a bare clone of it in the sub-dir "project.git",
+/// like a mirror on a build server.
+/// The bare clone has no remote tracking branches,
+/// and its "origin" remote is set to the public URL of the project.
+pub fn create(repo_dir: &Path) -> Result<(), RepoCreationError> {
+    let source_dir = repo_dir.join("source");
+    let bare_dir = repo_dir.join("project.git");
+    run_cmd! (
+        rm -Rf "$repo_dir";
+        mkdir -p "$repo_dir";
+    )
+    .map_err(|err| RepoCreationError::Initializing {
+        dir: repo_dir.display().to_string(),
+        source: err,
+    })?;
+    super::tags::create(&source_dir)?;
+    run_cmd! (
+        git clone --bare "$source_dir" "$bare_dir";
+        cd "$bare_dir";
+        git remote set-url origin "https://github.com/hoijui/projvar.git";
+    )
+    .map_err(|err| RepoCreationError::Initializing {
+        dir: repo_dir.display().to_string(),
+        source: err,
+    })?;
+
+    Ok(())
+}
@@ -2,9 +2,14 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+pub mod bare;
 pub mod default;
+pub mod push_url;
 pub mod sem_ver;
 pub mod sem_ver_pref;
+pub mod submodule;
+pub mod tags;
+pub mod worktree;
 
 #[derive(thiserror::Error, Debug)]
 pub enum RepoCreationError {
@@ -0,0 +1,26 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::Path;
+
+use cmd_lib::run_cmd;
+
+use super::RepoCreationError;
+
+/// Creates the default repo,
+/// and gives its "origin" remote a push URL
+/// that differs from its (fetch) URL.
+pub fn create(repo_dir: &Path) -> Result<(), RepoCreationError> {
+    super::default::create(repo_dir)?;
+    run_cmd! (
+        cd "$repo_dir";
+        git remote set-url --push origin "git@github.com:hoijui/projvar.git";
+    )
+    .map_err(|err| RepoCreationError::Initializing {
+        dir: repo_dir.display().to_string(),
+        source: err,
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::Path;
+
+use cmd_lib::run_cmd;
+
+use super::RepoCreationError;
+
+/// Creates the default repo in the sub-dir "super",
+/// and adds an other instance of it as a submodule under "super/sub".
+pub fn create(repo_dir: &Path) -> Result<(), RepoCreationError> {
+    let super_dir = repo_dir.join("super");
+    let sub_origin_dir = repo_dir.join("sub-origin");
+    run_cmd! (
+        rm -Rf "$repo_dir";
+        mkdir -p "$repo_dir";
+    )
+    .map_err(|err| RepoCreationError::Initializing {
+        dir: repo_dir.display().to_string(),
+        source: err,
+    })?;
+    super::default::create(&super_dir)?;
+    super::default::create(&sub_origin_dir)?;
+    run_cmd! (
+        cd "$super_dir";
+        // Local file-system clones are disabled for submodules by default
+        git -c "protocol.file.allow=always" submodule add "$sub_origin_dir" "sub";
+        git commit -m "Adds submodule";
+        // Local file-system paths are not supported as clone URLs,
+        // so we pretend the submodule was cloned from a hosting platform
+        cd "sub";
+        git remote set-url origin "https://github.com/hoijui/sub.git";
+    )
+    .map_err(|err| RepoCreationError::Initializing {
+        dir: repo_dir.display().to_string(),
+        source: err,
+    })?;
+
+    Ok(())
+}
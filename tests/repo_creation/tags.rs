@@ -0,0 +1,30 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::Path;
+
+use cmd_lib::run_cmd;
+
+use super::RepoCreationError;
+
+/// This creates a history with both lightweight and annotated tags,
+/// where HEAD is tagged with one of each.
+pub fn create(repo_dir: &Path) -> Result<(), RepoCreationError> {
+    super::default::create(repo_dir)?;
+    run_cmd! (
+        cd "$repo_dir";
+        git tag "0.1.0";
+        touch "d.txt";
+        git add "d.txt";
+        git commit -m "Second commit";
+        git tag -a -m "This is release 0.2.0" "0.2.0";
+        git tag "stable";
+    )
+    .map_err(|err| RepoCreationError::Initializing {
+        dir: repo_dir.display().to_string(),
+        source: err,
+    })?;
+
+    Ok(())
+}
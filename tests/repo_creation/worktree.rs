@@ -0,0 +1,38 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::Path;
+
+use cmd_lib::run_cmd;
+
+use super::RepoCreationError;
+
+/// Creates the default repo in the sub-dir "main",
+/// and a worktree of it in the sub-dir "worktree".
+/// In the latter, `.git` is a file pointing to the main repos git dir.
+pub fn create(repo_dir: &Path) -> Result<(), RepoCreationError> {
+    let main_dir = repo_dir.join("main");
+    run_cmd! (
+        rm -Rf "$repo_dir";
+        mkdir -p "$repo_dir";
+    )
+    .map_err(|err| RepoCreationError::Initializing {
+        dir: repo_dir.display().to_string(),
+        source: err,
+    })?;
+    super::default::create(&main_dir)?;
+    run_cmd! (
+        cd "$main_dir";
+        git worktree add -b "worktree-branch" "../worktree";
+        // Make the new branch track the same remote branch as master
+        git config "branch.worktree-branch.remote" "origin";
+        git config "branch.worktree-branch.merge" "refs/heads/master";
+    )
+    .map_err(|err| RepoCreationError::Initializing {
+        dir: repo_dir.display().to_string(),
+        source: err,
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+// SPDX-FileCopyrightText: 2021 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+mod common;
+mod repo_creation;
+
+use std::{collections::HashMap, path::PathBuf};
+
+use cli_utils::BoxResult;
+use common::StrMatcher;
+
+use crate::repo_creation::create_repo;
+
+fn setup() -> BoxResult<(PathBuf, HashMap<&'static str, &'static str>)> {
+    let repo_dir = create_repo!(
+        crate::repo_creation::default::create,
+        "repo_creation/default.rs"
+    )?;
+    let envs = vec![("SOURCE_DATE_EPOCH", "1633035545")];
+    Ok((repo_dir, envs.into_iter().collect()))
+}
+
+fn expected_pats() -> BoxResult<HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)>> {
+    Ok(vec![
+        (
+            "PROJECT_BUILD_DATE",
+            (
+                Box::new(&"2021-09-30 20:59:05" as &'static dyn StrMatcher),
+                true,
+            ),
+        ),
+        ("PROJECT_BUILD_DATE_EPOCH", (Box::new(&"1633035545"), true)),
+    ]
+    .into_iter()
+    .collect())
+}
+
+#[test]
+fn source_date_epoch() -> BoxResult<()> {
+    let (cwd, envs) = setup()?;
+    common::projvar_test(
+        &expected_pats()?,
+        &[
+            "--fail",
+            "--only-required",
+            "--none",
+            "-RPROJECT_BUILD_DATE",
+            "-RPROJECT_BUILD_DATE_EPOCH",
+        ],
+        &cwd,
+        envs,
+    )
+}
+
+/// The build date epoch is also written to the standard variable,
+/// without our key prefix and key case.
+#[test]
+fn source_date_epoch_out() -> BoxResult<()> {
+    let (cwd, envs) = setup()?;
+    let expected_pats: HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)> =
+        HashMap::from([
+            (
+                "project_build_date_epoch",
+                (Box::new(&"1633035545" as &'static dyn StrMatcher), true),
+            ),
+            (
+                "SOURCE_DATE_EPOCH",
+                (Box::new(&"1633035545" as &'static dyn StrMatcher), true),
+            ),
+        ]);
+    common::projvar_test(
+        &expected_pats,
+        &[
+            "--fail",
+            "--only-required",
+            "--none",
+            "-RPROJECT_BUILD_DATE_EPOCH",
+            "--key-case",
+            "lower-snake",
+            "--source-date-epoch-out",
+        ],
+        &cwd,
+        envs,
+    )
+}
@@ -0,0 +1,81 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+mod common;
+mod repo_creation;
+
+use std::{collections::HashMap, path::PathBuf};
+
+use cli_utils::BoxResult;
+use common::StrMatcher;
+
+use crate::repo_creation::create_repo;
+
+const ARGS: &[&str] = &[
+    "--fail",
+    "--only-required",
+    "--none",
+    "--stub-values",
+    "-RPROJECT_BUILD_DATE",
+    "-RPROJECT_BUILD_DATE_EPOCH",
+    "-RPROJECT_BUILD_NUMBER",
+    "-RPROJECT_BUILD_OS",
+    "-RPROJECT_BUILD_ARCH",
+];
+
+fn setup() -> BoxResult<PathBuf> {
+    Ok(create_repo!(
+        crate::repo_creation::default::create,
+        "repo_creation/default.rs"
+    )?)
+}
+
+#[test]
+fn stub_values() -> BoxResult<()> {
+    let cwd = setup()?;
+    let envs: HashMap<&'static str, &'static str> = [
+        ("SOURCE_DATE_EPOCH", "1633035545"),
+        // An explicit override still wins over the stub
+        ("PROJVAR_BUILD_NUMBER", "42"),
+    ]
+    .into_iter()
+    .collect();
+    let expected_pats: HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)> = vec![
+        (
+            "PROJECT_BUILD_DATE",
+            (
+                Box::new(&"2021-09-30 20:59:05" as &'static dyn StrMatcher),
+                true,
+            ),
+        ),
+        ("PROJECT_BUILD_DATE_EPOCH", (Box::new(&"1633035545"), true)),
+        ("PROJECT_BUILD_NUMBER", (Box::new(&"42"), true)),
+        ("PROJECT_BUILD_OS", (Box::new(&"linux"), true)),
+        ("PROJECT_BUILD_ARCH", (Box::new(&"x86_64"), true)),
+    ]
+    .into_iter()
+    .collect();
+    common::projvar_test(&expected_pats, ARGS, &cwd, envs)
+}
+
+#[test]
+fn stub_values_without_source_date_epoch() -> BoxResult<()> {
+    let cwd = setup()?;
+    let expected_pats: HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)> = vec![
+        (
+            "PROJECT_BUILD_DATE",
+            (
+                Box::new(&"1970-01-01 00:00:00" as &'static dyn StrMatcher),
+                true,
+            ),
+        ),
+        ("PROJECT_BUILD_DATE_EPOCH", (Box::new(&"0"), true)),
+        ("PROJECT_BUILD_NUMBER", (Box::new(&"1"), true)),
+        ("PROJECT_BUILD_OS", (Box::new(&"linux"), true)),
+        ("PROJECT_BUILD_ARCH", (Box::new(&"x86_64"), true)),
+    ]
+    .into_iter()
+    .collect();
+    common::projvar_test(&expected_pats, ARGS, &cwd, HashMap::<&str, &str>::new())
+}
@@ -0,0 +1,67 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+mod common;
+mod repo_creation;
+
+use std::{collections::HashMap, path::PathBuf};
+
+use cli_utils::BoxResult;
+use common::StrMatcher;
+
+use crate::repo_creation::create_repo;
+
+fn setup() -> BoxResult<PathBuf> {
+    Ok(create_repo!(
+        crate::repo_creation::submodule::create,
+        "repo_creation/submodule.rs"
+    )?)
+}
+
+#[test]
+fn submodule() -> BoxResult<()> {
+    let repo_dir = setup()?;
+    let expected = vec![(
+        "PROJECT_NAME",
+        (Box::new(&"sub" as &'static dyn StrMatcher), true),
+    )]
+    .into_iter()
+    .collect::<HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)>>();
+    common::projvar_test(
+        &expected,
+        &["--fail", "--only-required", "--none", "-RPROJECT_NAME"],
+        &repo_dir.join("super").join("sub"),
+        HashMap::<String, String>::new(),
+    )
+}
+
+#[test]
+fn parent_project() -> BoxResult<()> {
+    let repo_dir = setup()?;
+    let expected = vec![
+        (
+            "PROJECT_NAME",
+            (Box::new(&"super" as &'static dyn StrMatcher), true),
+        ),
+        (
+            "PROJECT_REPO_WEB_URL",
+            (Box::new(&"https://github.com/hoijui/projvar"), true),
+        ),
+    ]
+    .into_iter()
+    .collect::<HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)>>();
+    common::projvar_test(
+        &expected,
+        &[
+            "--fail",
+            "--only-required",
+            "--none",
+            "--parent-project",
+            "-RPROJECT_NAME",
+            "-RPROJECT_REPO_WEB_URL",
+        ],
+        &repo_dir.join("super").join("sub"),
+        HashMap::<String, String>::new(),
+    )
+}
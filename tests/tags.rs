@@ -0,0 +1,96 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+mod common;
+mod repo_creation;
+
+use std::collections::HashMap;
+
+use cli_utils::BoxResult;
+use common::StrMatcher;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::repo_creation::create_repo;
+
+lazy_static! {
+    static ref R_DESCRIBE_0_1_0: Regex = Regex::new(r"^0\.1\.0-1-g[0-9a-f]+$").unwrap();
+}
+
+const ARGS: &[&str] = &[
+    "--fail",
+    "--only-required",
+    "--none",
+    "-RPROJECT_BUILD_TAG",
+    "-RPROJECT_VERSION",
+];
+
+fn expected_pats(
+    tag: &'static &'static str,
+) -> HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)> {
+    vec![
+        (
+            "PROJECT_BUILD_TAG",
+            (Box::new(tag as &'static dyn StrMatcher), true),
+        ),
+        (
+            "PROJECT_VERSION",
+            (Box::new(tag as &'static dyn StrMatcher), true),
+        ),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn annotated_tag() -> BoxResult<()> {
+    let repo_dir = create_repo!(
+        crate::repo_creation::sem_ver::create,
+        "repo_creation/sem_ver.rs"
+    )?;
+    common::projvar_test(
+        &expected_pats(&"0.0.1"),
+        ARGS,
+        &repo_dir,
+        HashMap::<String, String>::new(),
+    )
+}
+
+#[test]
+fn lightweight_and_annotated_tags() -> BoxResult<()> {
+    let repo_dir = create_repo!(crate::repo_creation::tags::create, "repo_creation/tags.rs")?;
+    common::projvar_test(
+        &expected_pats(&"0.2.0"),
+        ARGS,
+        &repo_dir,
+        HashMap::<String, String>::new(),
+    )
+}
+
+#[test]
+fn version_tag_pattern() -> BoxResult<()> {
+    let repo_dir = create_repo!(crate::repo_creation::tags::create, "repo_creation/tags.rs")?;
+    let expected_pats = vec![(
+        "PROJECT_VERSION_DESCRIBE",
+        (
+            Box::new(&*R_DESCRIBE_0_1_0 as &'static dyn StrMatcher),
+            true,
+        ),
+    )]
+    .into_iter()
+    .collect();
+    common::projvar_test(
+        &expected_pats,
+        &[
+            "--fail",
+            "--only-required",
+            "--none",
+            "-RPROJECT_VERSION_DESCRIBE",
+            "--version-tag-pattern",
+            "0.1.*",
+        ],
+        &repo_dir,
+        HashMap::<String, String>::new(),
+    )
+}
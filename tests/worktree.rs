@@ -0,0 +1,76 @@
+// SPDX-FileCopyrightText: 2024 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+mod common;
+mod repo_creation;
+
+use std::{collections::HashMap, path::PathBuf};
+
+use cli_utils::BoxResult;
+use common::{StrMatcher, R_NON_EMPTY};
+
+use crate::repo_creation::create_repo;
+
+fn setup() -> BoxResult<PathBuf> {
+    Ok(create_repo!(
+        crate::repo_creation::worktree::create,
+        "repo_creation/worktree.rs"
+    )?)
+}
+
+fn expected_pats(
+    branch: &'static &'static str,
+) -> HashMap<&'static str, (Box<&'static dyn StrMatcher>, bool)> {
+    vec![
+        (
+            "PROJECT_BUILD_BRANCH",
+            (Box::new(branch as &'static dyn StrMatcher), true),
+        ),
+        (
+            "PROJECT_REPO_WEB_URL",
+            (Box::new(&"https://github.com/hoijui/projvar"), true),
+        ),
+        ("PROJECT_VERSION", (Box::new(&*R_NON_EMPTY), true)),
+        ("PROJECT_VERSION_CONTROL_SYSTEM", (Box::new(&"git"), true)),
+    ]
+    .into_iter()
+    .collect()
+}
+
+const ARGS: &[&str] = &[
+    "--fail",
+    "--only-required",
+    "--none",
+    "-RPROJECT_BUILD_BRANCH",
+    "-RPROJECT_REPO_WEB_URL",
+    "-RPROJECT_VERSION",
+    "-RPROJECT_VERSION_CONTROL_SYSTEM",
+];
+
+#[test]
+fn worktree() -> BoxResult<()> {
+    let repo_dir = setup()?;
+    common::projvar_test(
+        &expected_pats(&"worktree-branch"),
+        ARGS,
+        &repo_dir.join("worktree"),
+        HashMap::<String, String>::new(),
+    )
+}
+
+#[test]
+fn git_dir_env() -> BoxResult<()> {
+    let repo_dir = setup()?;
+    let main_dir = repo_dir.join("main");
+    let tmp_cwd = assert_fs::TempDir::new()?;
+    common::projvar_test(
+        &expected_pats(&"master"),
+        ARGS,
+        tmp_cwd.path(),
+        vec![
+            ("GIT_DIR", main_dir.join(".git").display().to_string()),
+            ("GIT_WORK_TREE", main_dir.display().to_string()),
+        ],
+    )
+}